@@ -65,6 +65,27 @@ fn main() {
         grid::js_types::JsRenderFill,
         grid::js_types::CellFormatSummary,
         grid::js_types::JsClipboard,
+        grid::js_types::JsRenderValidations,
+        grid::js_types::JsValidationFailure,
+        grid::sheet::validations::Validation,
+        grid::sheet::validations::ValidationRule,
+        grid::sheet::conditional_formats::ConditionalFormat,
+        grid::sheet::conditional_formats::ConditionalFormatRule,
+        grid::sheet::conditional_formats::ConditionalFormatStyle,
+        grid::sheet::conditional_formats::Comparison,
+        grid::js_types::JsRenderDataBar,
+        grid::sheet::sort::SortKey,
+        grid::sheet::sort::SortDirection,
+        sheet_offsets::offsets::OutlineGroup,
+        grid::sheet::comments::Comment,
+        grid::sheet::comments::CommentThread,
+        grid::js_types::JsRenderComment,
+        grid::js_types::JsUnsupportedExcelFeatures,
+        grid::defined_names::DefinedName,
+        grid::defined_names::NameValue,
+        controller::operations::import::CsvImportOptions,
+        controller::operations::import::CsvEncoding,
+        controller::operations::import::CsvPreview,
         ArraySize,
         Axis,
         Instant,
@@ -19,4 +19,13 @@ pub enum TransactionName {
     SheetDelete,
     DuplicateSheet,
     MoveCells,
+    ManipulateColumnRow,
+    MergeCells,
+    Validations,
+    ConditionalFormats,
+    SortRange,
+    HideColumnRow,
+    GroupColumnRow,
+    Comments,
+    DefinedNames,
 }
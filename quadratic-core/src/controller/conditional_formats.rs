@@ -0,0 +1,268 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use super::{active_transactions::pending_transaction::PendingTransaction, GridController};
+use crate::{
+    formulas::{parse_formula, Ctx},
+    grid::{
+        sheet::conditional_formats::{
+            ConditionalFormat, ConditionalFormatMatches, ConditionalFormatRule,
+        },
+        SheetId,
+    },
+    SheetRect,
+};
+
+impl GridController {
+    /// Evaluates a sheet's formula-based conditional formats and stores the
+    /// cells they match for rendering.
+    pub(crate) fn evaluate_conditional_format_formulas(&mut self, sheet_id: SheetId) {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return;
+        };
+        let ids: Vec<Uuid> = sheet
+            .conditional_formats
+            .iter()
+            .filter(|format| format.is_formula())
+            .map(|format| format.id)
+            .collect();
+        if let Some(sheet) = self.try_sheet_mut(sheet_id) {
+            sheet.conditional_format_matches.clear();
+        }
+        self.evaluate_conditional_formats_by_id(sheet_id, &ids);
+    }
+
+    /// Evaluates some of a sheet's formula-based conditional formats and
+    /// stores the cells they match and the cells they read.
+    fn evaluate_conditional_formats_by_id(&mut self, sheet_id: SheetId, ids: &[Uuid]) {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return;
+        };
+        let mut results = vec![];
+        for format in sheet.conditional_formats.iter() {
+            if !ids.contains(&format.id) {
+                continue;
+            }
+            let ConditionalFormatRule::Formula { formula } = &format.rule else {
+                continue;
+            };
+            let Ok(parsed) = parse_formula(formula, format.anchor()) else {
+                continue;
+            };
+            let Some(bounds) = sheet.selection_data_bounds(&format.selection) else {
+                continue;
+            };
+            let mut cells_accessed = HashSet::new();
+            let cells = bounds
+                .iter()
+                .filter(|pos| format.selection.pos_in_selection(*pos))
+                .filter(|pos| {
+                    let mut ctx = Ctx::new_for_cell_rule(self.grid(), pos.to_sheet_pos(sheet_id));
+                    let result = parsed
+                        .eval(&mut ctx, false)
+                        .ok()
+                        .and_then(|result| result.into_cell_value().ok())
+                        .and_then(|result| bool::try_from(&result).ok())
+                        .unwrap_or(false);
+                    cells_accessed.extend(ctx.cells_accessed);
+                    result
+                })
+                .collect();
+            results.push((
+                format.id,
+                ConditionalFormatMatches {
+                    cells,
+                    cells_accessed,
+                },
+            ));
+        }
+        if let Some(sheet) = self.try_sheet_mut(sheet_id) {
+            for id in ids {
+                sheet.conditional_format_matches.remove(id);
+            }
+            sheet.conditional_format_matches.extend(results);
+        }
+    }
+
+    /// Evaluates the formula-based conditional formats on every sheet.
+    pub(crate) fn evaluate_all_conditional_format_formulas(&mut self) {
+        for sheet_id in self.sheet_ids() {
+            self.evaluate_conditional_format_formulas(sheet_id);
+        }
+    }
+
+    /// Sends the cells and fills that a conditional format covers to the
+    /// client.
+    pub(crate) fn send_conditional_format(&self, sheet_id: SheetId, format: &ConditionalFormat) {
+        if !cfg!(target_family = "wasm") && !cfg!(test) {
+            return;
+        }
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return;
+        };
+        if let Some(bounds) = sheet.selection_data_bounds(&format.selection) {
+            self.send_render_cells(&bounds.to_sheet_rect(sheet_id));
+            if format.has_fill() {
+                self.send_fill_cells(&bounds.to_sheet_rect(sheet_id));
+            }
+        }
+    }
+
+    /// Re-evaluates the conditional formats that may depend on cells in
+    /// `sheet_rect` and re-sends the cells they cover.
+    ///
+    /// Formats depend on the cells in their own selection, and formula-based
+    /// formats also on the cells their formula read when last evaluated.
+    pub(crate) fn refresh_conditional_formats(
+        &mut self,
+        transaction: &PendingTransaction,
+        sheet_rect: &SheetRect,
+    ) {
+        for sheet_id in self.sheet_ids() {
+            let Some(sheet) = self.try_sheet(sheet_id) else {
+                continue;
+            };
+            let affected: Vec<ConditionalFormat> = sheet
+                .conditional_formats
+                .iter()
+                .filter(|format| {
+                    let in_selection = sheet_id == sheet_rect.sheet_id
+                        && sheet
+                            .selection_data_bounds(&format.selection)
+                            .is_some_and(|bounds| bounds.intersects((*sheet_rect).into()));
+                    let reads_changed_cells = sheet
+                        .conditional_format_matches
+                        .get(&format.id)
+                        .is_some_and(|matches| {
+                            matches
+                                .cells_accessed
+                                .iter()
+                                .any(|accessed| accessed.intersects(*sheet_rect))
+                        });
+                    in_selection || (format.is_formula() && reads_changed_cells)
+                })
+                .cloned()
+                .collect();
+            if affected.is_empty() {
+                continue;
+            }
+            let formula_ids: Vec<Uuid> = affected
+                .iter()
+                .filter(|format| format.is_formula())
+                .map(|format| format.id)
+                .collect();
+            if !formula_ids.is_empty() {
+                self.evaluate_conditional_formats_by_id(sheet_id, &formula_ids);
+            }
+            if !transaction.is_server() {
+                for format in affected.iter() {
+                    self.send_conditional_format(sheet_id, format);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grid::sheet::conditional_formats::ConditionalFormatStyle, selection::Selection, Pos, Rect,
+    };
+
+    #[test]
+    fn test_evaluate_conditional_format_formulas() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet = gc.try_sheet_mut(sheet_id).unwrap();
+        for y in 0..4 {
+            sheet.set_cell_value(Pos { x: 0, y }, crate::CellValue::Number(y.into()));
+        }
+        sheet.recalculate_bounds();
+
+        // odd numbers are bold
+        let format = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 0, 3), sheet_id),
+            ConditionalFormatRule::Formula {
+                formula: "MOD(A0, 2) = 1".into(),
+            },
+            ConditionalFormatStyle {
+                bold: Some(true),
+                ..Default::default()
+            },
+        );
+        sheet.set_conditional_format(format.clone(), None);
+        gc.evaluate_conditional_format_formulas(sheet_id);
+
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet
+                .conditional_format_matches
+                .get(&format.id)
+                .map(|matches| &matches.cells),
+            Some(&HashSet::from([Pos { x: 0, y: 1 }, Pos { x: 0, y: 3 }]))
+        );
+        let bold: Vec<_> = sheet
+            .get_render_cells(Rect::new(0, 0, 0, 3))
+            .into_iter()
+            .filter(|cell| cell.bold == Some(true))
+            .map(|cell| cell.y)
+            .collect();
+        assert_eq!(bold, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_refresh_conditional_formats() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for y in 0..4 {
+            gc.set_cell_value((0, y, sheet_id).into(), y.to_string(), None);
+        }
+        gc.set_cell_value((2, 0, sheet_id).into(), "1".into(), None);
+
+        // values greater than C0 are bold
+        let format = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 0, 3), sheet_id),
+            ConditionalFormatRule::Formula {
+                formula: "A0 > $C$0".into(),
+            },
+            ConditionalFormatStyle {
+                bold: Some(true),
+                ..Default::default()
+            },
+        );
+        gc.add_conditional_format(format.clone(), None);
+        let matches = |gc: &GridController| {
+            gc.sheet(sheet_id).conditional_format_matches[&format.id]
+                .cells
+                .clone()
+        };
+        assert_eq!(
+            matches(&gc),
+            HashSet::from([Pos { x: 0, y: 2 }, Pos { x: 0, y: 3 }])
+        );
+
+        // a cell that the formula does not read does not re-evaluate it
+        let sentinel = Pos { x: 10, y: 10 };
+        gc.sheet_mut(sheet_id)
+            .conditional_format_matches
+            .get_mut(&format.id)
+            .unwrap()
+            .cells
+            .insert(sentinel);
+        gc.set_cell_value((5, 5, sheet_id).into(), "100".into(), None);
+        assert!(matches(&gc).contains(&sentinel));
+
+        // changing a cell that it reads does
+        gc.set_cell_value((2, 0, sheet_id).into(), "2".into(), None);
+        assert_eq!(matches(&gc), HashSet::from([Pos { x: 0, y: 3 }]));
+
+        // as does changing a cell in its selection
+        gc.set_cell_value((0, 0, sheet_id).into(), "5".into(), None);
+        assert_eq!(
+            matches(&gc),
+            HashSet::from([Pos { x: 0, y: 0 }, Pos { x: 0, y: 3 }])
+        );
+    }
+}
@@ -2,7 +2,11 @@ use std::{self};
 
 use std::collections::HashSet;
 
-use crate::{SheetPos, SheetRect};
+use crate::{
+    formulas::find_names,
+    grid::{defined_names::NameValue, CodeCellLanguage},
+    CellValue, SheetPos, SheetRect,
+};
 
 use super::GridController;
 
@@ -27,6 +31,50 @@ impl GridController {
             Some(dependent_cells)
         }
     }
+
+    /// Searches all formulas in all sheets for cells that use the defined
+    /// name `name`, either directly or through the formulas of other names.
+    pub fn get_defined_name_dependent_code_cells(&self, name: &str) -> HashSet<SheetPos> {
+        let uses_any = |code: &str, names: &[String]| {
+            find_names(code)
+                .iter()
+                .any(|used| names.iter().any(|name| name.eq_ignore_ascii_case(used)))
+        };
+
+        // names whose formulas use `name`, however indirectly
+        let mut names = vec![name.to_string()];
+        loop {
+            let len = names.len();
+            for defined_name in self.grid.defined_names() {
+                if let NameValue::Formula(formula) = &defined_name.value {
+                    if !names
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(&defined_name.name))
+                        && uses_any(formula, &names)
+                    {
+                        names.push(defined_name.name.clone());
+                    }
+                }
+            }
+            if names.len() == len {
+                break;
+            }
+        }
+
+        let mut dependent_cells = HashSet::new();
+        for sheet in self.grid.sheets() {
+            for pos in sheet.code_runs.keys() {
+                if let Some(CellValue::Code(code_cell)) = sheet.cell_value(*pos) {
+                    if code_cell.language == CodeCellLanguage::Formula
+                        && uses_any(&code_cell.code, &names)
+                    {
+                        dependent_cells.insert(pos.to_sheet_pos(sheet.id));
+                    }
+                }
+            }
+        }
+        dependent_cells
+    }
 }
 
 #[cfg(test)]
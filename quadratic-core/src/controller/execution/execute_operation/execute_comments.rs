@@ -0,0 +1,175 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_comment(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        let sheet_pos = match &op {
+            Operation::AddComment { sheet_pos, .. }
+            | Operation::EditComment { sheet_pos, .. }
+            | Operation::ResolveComments { sheet_pos, .. }
+            | Operation::DeleteComment { sheet_pos, .. }
+            | Operation::SetCommentThread { sheet_pos, .. } => *sheet_pos,
+            _ => unreachable!("Expected a comment operation"),
+        };
+        let Some(sheet) = self.try_sheet_mut(sheet_pos.sheet_id) else {
+            // sheet may have been deleted
+            return;
+        };
+        let pos = sheet_pos.into();
+
+        let reverse = match op.clone() {
+            Operation::AddComment { comment, .. } => {
+                let comment_id = comment.id;
+                sheet.add_comment(pos, comment);
+                Operation::DeleteComment {
+                    sheet_pos,
+                    comment_id,
+                }
+            }
+            Operation::EditComment {
+                comment_id, text, ..
+            } => {
+                let Some(old_text) = sheet.edit_comment(pos, comment_id, text) else {
+                    return;
+                };
+                Operation::EditComment {
+                    sheet_pos,
+                    comment_id,
+                    text: old_text,
+                }
+            }
+            Operation::ResolveComments { resolved, .. } => {
+                let Some(old_resolved) = sheet.set_comments_resolved(pos, resolved) else {
+                    return;
+                };
+                Operation::ResolveComments {
+                    sheet_pos,
+                    resolved: old_resolved,
+                }
+            }
+            Operation::DeleteComment { comment_id, .. } => {
+                let Some(old_thread) = sheet.delete_comment(pos, comment_id) else {
+                    return;
+                };
+                Operation::SetCommentThread {
+                    sheet_pos,
+                    thread: Some(old_thread),
+                }
+            }
+            Operation::SetCommentThread { thread, .. } => {
+                let old_thread = sheet.set_comment_thread(pos, thread.clone());
+                if old_thread.is_none() && thread.is_none() {
+                    return;
+                }
+                Operation::SetCommentThread {
+                    sheet_pos,
+                    thread: old_thread,
+                }
+            }
+            _ => unreachable!("Expected a comment operation"),
+        };
+
+        transaction.forward_operations.push(op);
+        transaction.reverse_operations.insert(0, reverse);
+
+        if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
+            self.send_comments(sheet_pos.sheet_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{grid::sheet::comments::Comment, wasm_bindings::js::expect_js_call, Pos, SheetPos};
+
+    #[test]
+    #[serial]
+    fn test_execute_comments() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 1,
+            sheet_id,
+        };
+        let comment_id = gc.add_comment(sheet_pos, "ann".into(), "note".into(), None);
+        let reply_id = gc.add_comment(sheet_pos, "bob".into(), "reply".into(), None);
+        expect_js_call(
+            "jsSheetComments",
+            format!(
+                "{},{}",
+                sheet_id,
+                serde_json::to_string(&gc.sheet(sheet_id).get_render_comments()).unwrap()
+            ),
+            true,
+        );
+
+        gc.edit_comment(sheet_pos, reply_id, "edited".into(), None);
+        gc.resolve_comments(sheet_pos, true, None);
+        let thread = gc.sheet(sheet_id).comment_thread(sheet_pos.into()).unwrap();
+        assert_eq!(thread.comments[1].text, "edited");
+        assert!(thread.resolved);
+
+        gc.delete_comment(sheet_pos, comment_id, None);
+        assert!(gc.sheet(sheet_id).comments.is_empty());
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).comments.len(), 1);
+        gc.undo(None);
+        assert!(
+            !gc.sheet(sheet_id)
+                .comment_thread(sheet_pos.into())
+                .unwrap()
+                .resolved
+        );
+        gc.undo(None);
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id)
+                .comment_thread(sheet_pos.into())
+                .unwrap()
+                .comments
+                .len(),
+            1
+        );
+        gc.redo(None);
+        gc.redo(None);
+        gc.redo(None);
+        gc.redo(None);
+        assert!(gc.sheet(sheet_id).comments.is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_comment_operations_from_multiplayer() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = Pos { x: 0, y: 0 }.to_sheet_pos(sheet_id);
+        let comment = Comment::new("ann".into(), "note".into());
+        let operations = vec![
+            Operation::AddComment {
+                sheet_pos,
+                comment: comment.clone(),
+            },
+            Operation::ResolveComments {
+                sheet_pos,
+                resolved: true,
+            },
+        ];
+
+        // operations are sent to other clients as JSON
+        let operations: Vec<Operation> =
+            serde_json::from_str(&serde_json::to_string(&operations).unwrap()).unwrap();
+        gc.received_transaction(Uuid::new_v4(), 1, operations);
+
+        let thread = gc.sheet(sheet_id).comment_thread(sheet_pos.into()).unwrap();
+        assert_eq!(thread.comments, vec![comment]);
+        assert!(thread.resolved);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_conditional_format(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        let (sheet_id, old, reverse) = match op.clone() {
+            Operation::SetConditionalFormat {
+                sheet_id,
+                conditional_format,
+                index,
+            } => {
+                let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                    // sheet may have been deleted
+                    return;
+                };
+                let conditional_format_id = conditional_format.id;
+                match sheet.set_conditional_format(conditional_format, index) {
+                    Some(old) => (
+                        sheet_id,
+                        Some(old.clone()),
+                        Operation::SetConditionalFormat {
+                            sheet_id,
+                            conditional_format: old,
+                            index: None,
+                        },
+                    ),
+                    None => (
+                        sheet_id,
+                        None,
+                        Operation::RemoveConditionalFormat {
+                            sheet_id,
+                            conditional_format_id,
+                        },
+                    ),
+                }
+            }
+            Operation::RemoveConditionalFormat {
+                sheet_id,
+                conditional_format_id,
+            } => {
+                let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                    // sheet may have been deleted
+                    return;
+                };
+                let Some((index, old)) = sheet.remove_conditional_format(conditional_format_id)
+                else {
+                    return;
+                };
+                (
+                    sheet_id,
+                    Some(old.clone()),
+                    Operation::SetConditionalFormat {
+                        sheet_id,
+                        conditional_format: old,
+                        index: Some(index),
+                    },
+                )
+            }
+            _ => unreachable!(
+                "Expected Operation::SetConditionalFormat or Operation::RemoveConditionalFormat"
+            ),
+        };
+        self.evaluate_conditional_format_formulas(sheet_id);
+
+        transaction.forward_operations.push(op.clone());
+        transaction.reverse_operations.insert(0, reverse);
+
+        if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
+            // re-render the cells covered by both the old and new format
+            if let Some(old) = old {
+                self.send_conditional_format(sheet_id, &old);
+            }
+            if let Operation::SetConditionalFormat {
+                conditional_format, ..
+            } = &op
+            {
+                self.send_conditional_format(sheet_id, conditional_format);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::sheet::conditional_formats::{
+            ConditionalFormat, ConditionalFormatRule, ConditionalFormatStyle,
+        },
+        selection::Selection,
+        Rect,
+    };
+
+    #[test]
+    #[serial]
+    fn test_execute_conditional_format() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let first = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 1, 1), sheet_id),
+            ConditionalFormatRule::Duplicates,
+            ConditionalFormatStyle {
+                bold: Some(true),
+                ..Default::default()
+            },
+        );
+        let second = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 1, 1), sheet_id),
+            ConditionalFormatRule::DataBar {
+                color: "blue".into(),
+            },
+            ConditionalFormatStyle::default(),
+        );
+        gc.add_conditional_format(first.clone(), None);
+        gc.add_conditional_format(second.clone(), None);
+        gc.remove_conditional_format(sheet_id, first.id, None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats, vec![second.clone()]);
+
+        // undo restores the removed format's precedence
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).conditional_formats,
+            vec![first.clone(), second.clone()]
+        );
+
+        gc.undo(None);
+        gc.undo(None);
+        assert!(gc.sheet(sheet_id).conditional_formats.is_empty());
+
+        gc.redo(None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats, vec![first.clone()]);
+
+        // replacing a format is undoable
+        let mut replaced = first.clone();
+        replaced.rule = ConditionalFormatRule::TextContains { text: "a".into() };
+        gc.add_conditional_format(replaced.clone(), None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats, vec![replaced]);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats, vec![first]);
+    }
+}
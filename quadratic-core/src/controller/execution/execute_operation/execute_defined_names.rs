@@ -0,0 +1,136 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_set_defined_name(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetDefinedName { name, value } = op.clone() {
+            let old_value = self.grid.set_defined_name(&name, value.clone());
+            if old_value.is_none() && value.is_none() {
+                return;
+            }
+
+            transaction.forward_operations.push(op);
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetDefinedName {
+                    name: name.clone(),
+                    value: old_value,
+                },
+            );
+
+            // rerun the formulas that use the name. Undo and redo restore
+            // their results instead.
+            if transaction.is_user() {
+                for sheet_pos in self.get_defined_name_dependent_code_cells(&name) {
+                    let pending = transaction.operations.iter().any(|op| {
+                        matches!(op, Operation::ComputeCode { sheet_pos: pending } if *pending == sheet_pos)
+                    });
+                    if !pending {
+                        transaction
+                            .operations
+                            .push_back(Operation::ComputeCode { sheet_pos });
+                    }
+                }
+            }
+
+            if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
+                self.send_defined_names();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::{defined_names::NameValue, CodeCellLanguage, CodeRunResult},
+        wasm_bindings::js::expect_js_call,
+        CellValue, Pos, SheetPos, SheetRect,
+    };
+
+    #[test]
+    #[serial]
+    fn test_execute_set_defined_name() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id)
+            .test_set_values(0, 0, 1, 3, vec!["1", "2", "3"]);
+        gc.set_code_cell(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "Total + 1".into(),
+            None,
+        );
+        let display = |gc: &GridController| gc.sheet(sheet_id).display_value(Pos { x: 1, y: 0 });
+
+        // the formula reruns once both names are defined
+        gc.set_defined_name(
+            "Sales".into(),
+            Some(NameValue::Range(SheetRect::from_numbers(
+                0, 0, 1, 2, sheet_id,
+            ))),
+            None,
+        )
+        .unwrap();
+        gc.set_defined_name(
+            "Total".into(),
+            Some(NameValue::Formula("SUM(Sales)".into())),
+            None,
+        )
+        .unwrap();
+        expect_js_call(
+            "jsDefinedNames",
+            serde_json::to_string(gc.grid().defined_names()).unwrap(),
+            true,
+        );
+        assert_eq!(display(&gc), Some(CellValue::Number(4.into())));
+
+        // redefining a name used by another name reruns the formula
+        gc.set_defined_name(
+            "Sales".into(),
+            Some(NameValue::Range(SheetRect::from_numbers(
+                0, 0, 1, 3, sheet_id,
+            ))),
+            None,
+        )
+        .unwrap();
+        assert_eq!(display(&gc), Some(CellValue::Number(7.into())));
+
+        // changing a cell in the range reruns the formula
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 2,
+                sheet_id,
+            },
+            "10".into(),
+            None,
+        );
+        assert_eq!(display(&gc), Some(CellValue::Number(14.into())));
+
+        gc.undo(None);
+        assert_eq!(display(&gc), Some(CellValue::Number(7.into())));
+        gc.undo(None);
+        assert_eq!(display(&gc), Some(CellValue::Number(4.into())));
+        gc.redo(None);
+        assert_eq!(display(&gc), Some(CellValue::Number(7.into())));
+
+        gc.set_defined_name("sales".into(), None, None).unwrap();
+        assert!(gc.grid().defined_name("Sales").is_none());
+        let code_run = gc.sheet(sheet_id).code_run(Pos { x: 1, y: 0 }).unwrap();
+        assert!(matches!(code_run.result, CodeRunResult::Err(_)));
+    }
+}
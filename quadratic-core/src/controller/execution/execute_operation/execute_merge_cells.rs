@@ -0,0 +1,73 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_merge_cells(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        let (sheet_rect, reverse) = match op {
+            Operation::MergeCells { sheet_rect } => {
+                (sheet_rect, Operation::UnmergeCells { sheet_rect })
+            }
+            Operation::UnmergeCells { sheet_rect } => {
+                (sheet_rect, Operation::MergeCells { sheet_rect })
+            }
+            _ => unreachable!("Expected Operation::MergeCells or Operation::UnmergeCells"),
+        };
+        let Some(sheet) = self.try_sheet_mut(sheet_rect.sheet_id) else {
+            // sheet may have been deleted
+            return;
+        };
+        let changed = match op {
+            Operation::MergeCells { .. } => sheet.merge_cells(sheet_rect.into()),
+            _ => sheet.unmerge_cells(sheet_rect.into()),
+        };
+        if !changed {
+            return;
+        }
+
+        transaction.forward_operations.push(op);
+        transaction.reverse_operations.insert(0, reverse);
+        transaction.generate_thumbnail |= self.thumbnail_dirty_sheet_rect(&sheet_rect);
+
+        if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
+            self.send_render_cells(&sheet_rect);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{Rect, SheetRect};
+
+    #[test]
+    #[serial]
+    fn test_execute_merge_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_rect = SheetRect::from_numbers(0, 0, 2, 2, sheet_id);
+        gc.merge_cells(sheet_rect, None);
+        assert_eq!(gc.sheet(sheet_id).merged_cells, vec![Rect::new(0, 0, 1, 1)]);
+
+        gc.undo(None);
+        assert!(gc.sheet(sheet_id).merged_cells.is_empty());
+
+        gc.redo(None);
+        assert_eq!(gc.sheet(sheet_id).merged_cells, vec![Rect::new(0, 0, 1, 1)]);
+
+        // merging an overlapping region does nothing
+        let mut transaction = PendingTransaction::default();
+        let overlapping = SheetRect::from_numbers(1, 1, 2, 2, sheet_id);
+        gc.execute_merge_cells(
+            &mut transaction,
+            Operation::MergeCells {
+                sheet_rect: overlapping,
+            },
+        );
+        assert!(transaction.forward_operations.is_empty());
+        assert_eq!(gc.sheet(sheet_id).merged_cells, vec![Rect::new(0, 0, 1, 1)]);
+    }
+}
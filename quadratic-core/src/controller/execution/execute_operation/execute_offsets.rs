@@ -3,7 +3,9 @@ use crate::{
         active_transactions::pending_transaction::PendingTransaction,
         operations::operation::Operation, GridController,
     },
-    SheetPos,
+    grid::SheetId,
+    sheet_offsets::offsets::OutlineGroup,
+    SheetPos, SheetRect,
 };
 
 impl GridController {
@@ -114,11 +116,232 @@ impl GridController {
             }
         }
     }
+    pub fn execute_set_hidden(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        let (sheet_id, indices, hidden, is_column) = match op.clone() {
+            Operation::SetColumnsHidden {
+                sheet_id,
+                columns,
+                hidden,
+            } => (sheet_id, columns, hidden, true),
+            Operation::SetRowsHidden {
+                sheet_id,
+                rows,
+                hidden,
+            } => (sheet_id, rows, hidden, false),
+            _ => unreachable!("Expected Operation::SetColumnsHidden or Operation::SetRowsHidden"),
+        };
+        let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+            // sheet may have been deleted
+            return;
+        };
+
+        // only the columns/rows that changed are reversed
+        let mut changed = vec![];
+        let mut visibility_changed = vec![];
+        for index in indices {
+            let was_visible = if is_column {
+                !sheet.offsets.is_column_hidden(index)
+            } else {
+                !sheet.offsets.is_row_hidden(index)
+            };
+            let old = if is_column {
+                sheet.offsets.set_column_hidden(index, hidden)
+            } else {
+                sheet.offsets.set_row_hidden(index, hidden)
+            };
+            if old != hidden {
+                changed.push(index);
+            }
+            let is_visible = if is_column {
+                !sheet.offsets.is_column_hidden(index)
+            } else {
+                !sheet.offsets.is_row_hidden(index)
+            };
+            if was_visible != is_visible {
+                visibility_changed.push(index);
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+
+        transaction.forward_operations.push(op);
+        transaction.reverse_operations.insert(
+            0,
+            if is_column {
+                Operation::SetColumnsHidden {
+                    sheet_id,
+                    columns: changed,
+                    hidden: !hidden,
+                }
+            } else {
+                Operation::SetRowsHidden {
+                    sheet_id,
+                    rows: changed,
+                    hidden: !hidden,
+                }
+            },
+        );
+        self.send_visibility_changes(transaction, sheet_id, is_column, &visibility_changed);
+    }
+
+    pub fn execute_set_groups(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        let (sheet_id, groups, is_column) = match op.clone() {
+            Operation::SetColumnGroups { sheet_id, groups } => (sheet_id, groups, true),
+            Operation::SetRowGroups { sheet_id, groups } => (sheet_id, groups, false),
+            _ => unreachable!("Expected Operation::SetColumnGroups or Operation::SetRowGroups"),
+        };
+        let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+            // sheet may have been deleted
+            return;
+        };
+
+        let is_hidden = |sheet: &crate::grid::Sheet, index: i64| {
+            if is_column {
+                sheet.offsets.is_column_hidden(index)
+            } else {
+                sheet.offsets.is_row_hidden(index)
+            }
+        };
+
+        // only columns/rows in collapsed groups can change visibility
+        let collapsed_indices = |groups: &[OutlineGroup]| {
+            groups
+                .iter()
+                .filter(|group| group.collapsed)
+                .flat_map(|group| group.start..=group.end)
+                .collect::<Vec<_>>()
+        };
+        let old_groups = if is_column {
+            sheet.offsets.column_groups()
+        } else {
+            sheet.offsets.row_groups()
+        };
+        if old_groups == groups.as_slice() {
+            return;
+        }
+        let mut candidates = collapsed_indices(old_groups);
+        candidates.extend(collapsed_indices(&groups));
+        candidates.sort_unstable();
+        candidates.dedup();
+        let was_hidden: Vec<bool> = candidates
+            .iter()
+            .map(|&index| is_hidden(sheet, index))
+            .collect();
+
+        let old_groups = if is_column {
+            sheet.offsets.set_column_groups(groups)
+        } else {
+            sheet.offsets.set_row_groups(groups)
+        };
+        let visibility_changed: Vec<i64> = candidates
+            .into_iter()
+            .zip(was_hidden)
+            .filter(|&(index, was_hidden)| is_hidden(sheet, index) != was_hidden)
+            .map(|(index, _)| index)
+            .collect();
+
+        transaction.forward_operations.push(op);
+        transaction.reverse_operations.insert(
+            0,
+            if is_column {
+                Operation::SetColumnGroups {
+                    sheet_id,
+                    groups: old_groups,
+                }
+            } else {
+                Operation::SetRowGroups {
+                    sheet_id,
+                    groups: old_groups,
+                }
+            },
+        );
+        self.send_visibility_changes(transaction, sheet_id, is_column, &visibility_changed);
+    }
+
+    /// Updates the thumbnail and client after columns/rows were hidden or
+    /// shown.
+    fn send_visibility_changes(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        sheet_id: SheetId,
+        is_column: bool,
+        indices: &[i64],
+    ) {
+        if indices.is_empty() {
+            return;
+        }
+        if transaction.is_user_undo_redo() {
+            transaction.generate_thumbnail |= indices.iter().any(|&index| {
+                self.thumbnail_dirty_sheet_pos(if is_column {
+                    SheetPos {
+                        x: index,
+                        y: 0,
+                        sheet_id,
+                    }
+                } else {
+                    SheetPos {
+                        x: 0,
+                        y: index,
+                        sheet_id,
+                    }
+                })
+            });
+        }
+        if (!cfg!(target_family = "wasm") && !cfg!(test)) || transaction.is_server() {
+            return;
+        }
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return;
+        };
+        let mut render_rects = vec![];
+        for &index in indices {
+            if is_column {
+                crate::wasm_bindings::js::jsOffsetsModified(
+                    sheet_id.to_string(),
+                    Some(index),
+                    None,
+                    sheet.offsets.column_width(index),
+                );
+                if let Some((min, max)) = sheet.column_bounds(index, true) {
+                    render_rects.push(SheetRect::from_numbers(
+                        index,
+                        min,
+                        1,
+                        max - min + 1,
+                        sheet_id,
+                    ));
+                }
+            } else {
+                crate::wasm_bindings::js::jsOffsetsModified(
+                    sheet_id.to_string(),
+                    None,
+                    Some(index),
+                    sheet.offsets.row_height(index),
+                );
+                if let Some((min, max)) = sheet.row_bounds(index, true) {
+                    render_rects.push(SheetRect::from_numbers(
+                        min,
+                        index,
+                        max - min + 1,
+                        1,
+                        sheet_id,
+                    ));
+                }
+            }
+        }
+        render_rects
+            .iter()
+            .for_each(|sheet_rect| self.send_render_cells(sheet_rect));
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{controller::GridController, wasm_bindings::js::expect_js_call};
+    use crate::{
+        controller::GridController, sheet_offsets::offsets::OutlineGroup,
+        wasm_bindings::js::expect_js_call, Axis,
+    };
     use serial_test::serial;
 
     // also see tests in sheet_offsets.rs
@@ -180,4 +403,76 @@ mod tests {
             true,
         );
     }
+
+    #[test]
+    #[serial]
+    fn test_execute_set_hidden() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_columns_hidden(sheet_id, vec![1, 2], true, None);
+        let offsets = &gc.sheet(sheet_id).offsets;
+        assert!(offsets.is_column_hidden(1));
+        assert!(offsets.is_column_hidden(2));
+        assert_eq!(offsets.column_width(1), 0.0);
+        expect_js_call(
+            "jsOffsetsModified",
+            format!("{},{:?},{:?},{}", sheet_id, Some(2), None::<i64>, 0.0),
+            true,
+        );
+
+        // only the column that changed is reversed
+        gc.set_columns_hidden(sheet_id, vec![2, 3], false, None);
+        assert!(!gc.sheet(sheet_id).offsets.is_column_hidden(2));
+        gc.undo(None);
+        assert!(gc.sheet(sheet_id).offsets.is_column_hidden(2));
+        gc.undo(None);
+        assert!(!gc.sheet(sheet_id).offsets.is_column_hidden(1));
+        gc.redo(None);
+        assert!(gc.sheet(sheet_id).offsets.is_column_hidden(1));
+
+        gc.set_rows_hidden(sheet_id, vec![4], true, None);
+        assert_eq!(gc.sheet(sheet_id).offsets.row_from_y(0.0 + 4.0 * 21.0).0, 5);
+    }
+
+    #[test]
+    #[serial]
+    fn test_execute_set_groups() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id).test_set_value_number(0, 2, "1");
+
+        gc.group(sheet_id, Axis::Y, 1, 3, None).unwrap();
+        assert_eq!(
+            gc.sheet(sheet_id).offsets.row_groups(),
+            &[OutlineGroup::new(1, 3)]
+        );
+        assert_eq!(gc.sheet(sheet_id).offsets.row_outline_level(2), 1);
+
+        gc.set_group_collapsed(sheet_id, Axis::Y, 1, 3, true, None)
+            .unwrap();
+        assert!(gc.sheet(sheet_id).offsets.is_row_hidden(2));
+        assert!(gc
+            .sheet(sheet_id)
+            .get_render_cells(crate::Rect::new(0, 0, 0, 5))
+            .is_empty());
+        expect_js_call(
+            "jsOffsetsModified",
+            format!("{},{:?},{:?},{}", sheet_id, None::<i64>, Some(3), 0.0),
+            false,
+        );
+
+        gc.undo(None);
+        assert!(!gc.sheet(sheet_id).offsets.is_row_hidden(2));
+        assert_eq!(
+            gc.sheet(sheet_id)
+                .get_render_cells(crate::Rect::new(0, 0, 0, 5))
+                .len(),
+            1
+        );
+
+        gc.ungroup(sheet_id, Axis::Y, 1, 3, None).unwrap();
+        assert!(gc.sheet(sheet_id).offsets.row_groups().is_empty());
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).offsets.row_groups().len(), 1);
+    }
 }
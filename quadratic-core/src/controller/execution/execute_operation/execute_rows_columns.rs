@@ -0,0 +1,1103 @@
+use crate::{
+    cell_values::CellValues,
+    controller::{
+        active_transactions::pending_transaction::PendingTransaction,
+        operations::operation::Operation, GridController,
+    },
+    formulas::{adjust_cell_references, RefAdjust},
+    grid::{
+        formats::Formats, get_rect_borders, CodeCellLanguage, CodeRun, GridBounds, Sheet, SheetId,
+    },
+    selection::Selection,
+    Axis, CellValue, Pos, Rect, SheetPos, SheetRect,
+};
+
+impl GridController {
+    pub fn execute_insert_delete(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        let (sheet_id, adjust) = match op {
+            Operation::InsertColumn { sheet_id, column } => {
+                (sheet_id, RefAdjust::insert(Axis::X, column))
+            }
+            Operation::DeleteColumn { sheet_id, column } => {
+                (sheet_id, RefAdjust::delete(Axis::X, column))
+            }
+            Operation::InsertRow { sheet_id, row } => (sheet_id, RefAdjust::insert(Axis::Y, row)),
+            Operation::DeleteRow { sheet_id, row } => (sheet_id, RefAdjust::delete(Axis::Y, row)),
+            _ => unreachable!("Expected an insert or delete column/row operation"),
+        };
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            // sheet may have been deleted
+            return;
+        };
+        let old_bounds = sheet.bounds(false);
+        let old_offsets = sheet.offsets.export();
+
+        // The reverse operations run in order: first the column/row is
+        // deleted or inserted again, then anything that was lost is restored.
+        let mut reverse_operations = vec![Self::reverse_insert_delete(sheet_id, adjust)];
+        if adjust.is_delete() {
+            reverse_operations.extend(Self::restore_line_operations(
+                sheet,
+                adjust.axis,
+                adjust.start,
+            ));
+        }
+
+        // Formulas whose references change are restored to their original
+        // code, and every code run that is computed again is restored to its
+        // original output.
+        let formulas = self.adjusted_formulas(sheet_id, adjust);
+        let mut code_runs = self.adjust_cells_accessed(sheet_id, adjust);
+        for (old_sheet_pos, _) in &formulas {
+            let Some(sheet) = self.try_sheet(old_sheet_pos.sheet_id) else {
+                continue;
+            };
+            let pos = (*old_sheet_pos).into();
+            if let Some(value) = sheet.cell_value(pos) {
+                reverse_operations.push(Operation::SetCellValues {
+                    sheet_pos: *old_sheet_pos,
+                    values: CellValues::from(value),
+                });
+            }
+            if !code_runs
+                .iter()
+                .any(|(sheet_pos, _, _)| sheet_pos == old_sheet_pos)
+            {
+                if let Some((index, _, code_run)) = sheet.code_runs.get_full(&pos) {
+                    code_runs.push((*old_sheet_pos, index, code_run.clone()));
+                }
+            }
+        }
+        let mut to_compute = vec![];
+        for (old_sheet_pos, index, code_run) in code_runs {
+            reverse_operations.push(Operation::SetCodeRun {
+                sheet_pos: old_sheet_pos,
+                code_run: Some(code_run),
+                index,
+            });
+            to_compute.extend(Self::shifted_sheet_pos(old_sheet_pos, sheet_id, adjust));
+        }
+
+        let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+            return;
+        };
+        sheet.shift_cells(adjust);
+        let sheet_name = sheet.name.clone();
+
+        // validations and conditional formats on any sheet may refer to the
+        // sheet, and are restored to their original values and priorities
+        let mut changed_validations = vec![];
+        let mut changed_conditional_formats = vec![];
+        for other_sheet_id in self.grid.sheet_ids() {
+            let Some(sheet) = self.try_sheet_mut(other_sheet_id) else {
+                continue;
+            };
+            let changed = sheet.adjust_validations(sheet_id, &sheet_name, adjust);
+            if !changed.is_empty() {
+                changed_validations.push(other_sheet_id);
+            }
+            for (index, validation) in changed {
+                reverse_operations.push(Operation::SetValidation {
+                    sheet_id: other_sheet_id,
+                    validation,
+                    index: Some(index),
+                });
+            }
+            for (index, conditional_format) in
+                sheet.adjust_conditional_formats(sheet_id, &sheet_name, adjust)
+            {
+                reverse_operations.push(Operation::SetConditionalFormat {
+                    sheet_id: other_sheet_id,
+                    conditional_format: conditional_format.clone(),
+                    index: Some(index),
+                });
+                changed_conditional_formats.push((other_sheet_id, conditional_format));
+            }
+        }
+
+        // defined names that refer to the sheet are restored to their
+        // original values
+        let changed_names = self.grid.adjust_defined_names(sheet_id, adjust);
+        for (name, value) in &changed_names {
+            reverse_operations.push(Operation::SetDefinedName {
+                name: name.clone(),
+                value: Some(value.clone()),
+            });
+        }
+
+        for (old_sheet_pos, new_code) in formulas {
+            let Some(new_sheet_pos) = Self::shifted_sheet_pos(old_sheet_pos, sheet_id, adjust)
+            else {
+                continue;
+            };
+            if let Some(sheet) = self.try_sheet_mut(new_sheet_pos.sheet_id) {
+                sheet.set_cell_value(new_sheet_pos.into(), CellValue::Code(new_code));
+                if !to_compute.contains(&new_sheet_pos) {
+                    to_compute.push(new_sheet_pos);
+                }
+            }
+        }
+
+        // cells matching formula rules moved, and formulas may have changed
+        let mut evaluate_sheet_ids = vec![sheet_id];
+        for (other_sheet_id, _) in &changed_conditional_formats {
+            if !evaluate_sheet_ids.contains(other_sheet_id) {
+                evaluate_sheet_ids.push(*other_sheet_id);
+            }
+        }
+        for sheet_id in evaluate_sheet_ids {
+            self.evaluate_conditional_format_formulas(sheet_id);
+        }
+
+        transaction.forward_operations.push(op);
+        transaction
+            .reverse_operations
+            .splice(0..0, reverse_operations);
+
+        if transaction.is_user() {
+            for sheet_pos in to_compute {
+                if !transaction.operations.iter().any(|op| {
+                    matches!(op, Operation::ComputeCode { sheet_pos: pending } if *pending == sheet_pos)
+                }) {
+                    transaction
+                        .operations
+                        .push_back(Operation::ComputeCode { sheet_pos });
+                }
+            }
+            self.check_all_spills(transaction, sheet_id);
+
+            let thumbnail_pos = match adjust.axis {
+                Axis::X => Pos {
+                    x: adjust.start,
+                    y: 0,
+                },
+                Axis::Y => Pos {
+                    x: 0,
+                    y: adjust.start,
+                },
+            };
+            transaction.generate_thumbnail |=
+                self.thumbnail_dirty_sheet_pos(thumbnail_pos.to_sheet_pos(sheet_id));
+        }
+
+        if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
+            self.send_shifted_cells(sheet_id, adjust, old_bounds, old_offsets);
+            if !changed_names.is_empty() {
+                self.send_defined_names();
+            }
+            for sheet_id in changed_validations {
+                self.send_validations(sheet_id);
+            }
+            for (sheet_id, conditional_format) in &changed_conditional_formats {
+                self.send_conditional_format(*sheet_id, conditional_format);
+            }
+        }
+    }
+
+    /// Returns the operation that undoes inserting or deleting a column/row.
+    fn reverse_insert_delete(sheet_id: SheetId, adjust: RefAdjust) -> Operation {
+        let index = adjust.start;
+        match (adjust.axis, adjust.is_delete()) {
+            (Axis::X, false) => Operation::DeleteColumn {
+                sheet_id,
+                column: index,
+            },
+            (Axis::X, true) => Operation::InsertColumn {
+                sheet_id,
+                column: index,
+            },
+            (Axis::Y, false) => Operation::DeleteRow {
+                sheet_id,
+                row: index,
+            },
+            (Axis::Y, true) => Operation::InsertRow {
+                sheet_id,
+                row: index,
+            },
+        }
+    }
+
+    /// Returns the new position of a cell after a column/row is inserted or
+    /// deleted on the sheet `sheet_id`.
+    fn shifted_sheet_pos(
+        sheet_pos: SheetPos,
+        sheet_id: SheetId,
+        adjust: RefAdjust,
+    ) -> Option<SheetPos> {
+        if sheet_pos.sheet_id == sheet_id {
+            let pos = adjust.adjust_pos(sheet_pos.into())?;
+            Some(pos.to_sheet_pos(sheet_id))
+        } else {
+            Some(sheet_pos)
+        }
+    }
+
+    /// Returns operations that restore the contents of a column/row after it
+    /// is inserted again, for undoing its deletion.
+    fn restore_line_operations(sheet: &Sheet, axis: Axis, index: i64) -> Vec<Operation> {
+        let mut ops = vec![];
+
+        // Column/row formatting goes first because it clears the formatting
+        // of individual cells.
+        let (line_format, selection) = match axis {
+            Axis::X => (
+                sheet.try_format_column(index),
+                Selection {
+                    sheet_id: sheet.id,
+                    x: index,
+                    columns: Some(vec![index]),
+                    ..Default::default()
+                },
+            ),
+            Axis::Y => (
+                sheet.try_format_row(index),
+                Selection {
+                    sheet_id: sheet.id,
+                    y: index,
+                    rows: Some(vec![index]),
+                    ..Default::default()
+                },
+            ),
+        };
+        if let Some(format) = line_format {
+            ops.push(Operation::SetCellFormatsSelection {
+                selection,
+                formats: Formats::repeat(format.to_replace(), 1),
+            });
+        }
+
+        if let Some(rect) = sheet.line_bounds(axis, index) {
+            let mut values = CellValues::new(rect.width(), rect.height());
+            let mut formats = Formats::new();
+            for x in rect.x_range() {
+                for y in rect.y_range() {
+                    if let Some(value) = sheet.cell_value(Pos { x, y }) {
+                        values.set((x - rect.min.x) as u32, (y - rect.min.y) as u32, value);
+                    }
+                    let format = sheet.format_cell(x, y, false);
+                    formats.push(if format.is_default() {
+                        Default::default()
+                    } else {
+                        format.to_replace()
+                    });
+                }
+            }
+            if values.columns.iter().any(|column| !column.is_empty()) {
+                ops.push(Operation::SetCellValues {
+                    sheet_pos: rect.min.to_sheet_pos(sheet.id),
+                    values,
+                });
+            }
+            if formats.iter_values().any(|format| !format.is_default()) {
+                ops.push(Operation::SetCellFormatsSelection {
+                    selection: Selection::rect(rect, sheet.id),
+                    formats,
+                });
+            }
+            ops.push(Operation::SetBorders {
+                sheet_rect: rect.to_sheet_rect(sheet.id),
+                borders: get_rect_borders(sheet, &rect),
+            });
+        }
+
+        for (code_run_index, (pos, code_run)) in sheet.code_runs.iter().enumerate() {
+            let on_line = match axis {
+                Axis::X => pos.x == index,
+                Axis::Y => pos.y == index,
+            };
+            if on_line {
+                ops.push(Operation::SetCodeRun {
+                    sheet_pos: pos.to_sheet_pos(sheet.id),
+                    code_run: Some(code_run.clone()),
+                    index: code_run_index,
+                });
+            }
+        }
+
+        // Merged regions are lost if they are entirely in the column/row, and
+        // otherwise shrink, so inserting the column/row again only shifts
+        // those that started there.
+        let deleted = RefAdjust::delete(axis, index);
+        let inserted = RefAdjust::insert(axis, index);
+        for merged in sheet.merged_rects_intersecting(line_rect(axis, index)) {
+            let reinserted = deleted
+                .adjust_rect(merged)
+                .and_then(|rect| inserted.adjust_rect(rect));
+            if reinserted == Some(merged) {
+                continue;
+            }
+            if let Some(reinserted) = reinserted {
+                ops.push(Operation::UnmergeCells {
+                    sheet_rect: reinserted.to_sheet_rect(sheet.id),
+                });
+            }
+            ops.push(Operation::MergeCells {
+                sheet_rect: merged.to_sheet_rect(sheet.id),
+            });
+        }
+
+        for (pos, thread) in sheet.comments.iter() {
+            let on_line = match axis {
+                Axis::X => pos.x == index,
+                Axis::Y => pos.y == index,
+            };
+            if !on_line {
+                continue;
+            }
+            ops.push(Operation::SetCommentThread {
+                sheet_pos: pos.to_sheet_pos(sheet.id),
+                thread: Some(thread.clone()),
+            });
+        }
+
+        match axis {
+            Axis::X => {
+                let size = sheet.offsets.unhidden_column_width(index);
+                if size != crate::DEFAULT_COLUMN_WIDTH {
+                    ops.push(Operation::ResizeColumn {
+                        sheet_id: sheet.id,
+                        column: index,
+                        new_size: size,
+                        client_resized: false,
+                    });
+                }
+                if sheet.offsets.hidden_columns().any(|x| x == index) {
+                    ops.push(Operation::SetColumnsHidden {
+                        sheet_id: sheet.id,
+                        columns: vec![index],
+                        hidden: true,
+                    });
+                }
+                let groups = sheet.offsets.column_groups();
+                if groups.iter().any(|group| group.contains(index)) {
+                    ops.push(Operation::SetColumnGroups {
+                        sheet_id: sheet.id,
+                        groups: groups.to_vec(),
+                    });
+                }
+            }
+            Axis::Y => {
+                let size = sheet.offsets.unhidden_row_height(index);
+                if size != crate::DEFAULT_ROW_HEIGHT {
+                    ops.push(Operation::ResizeRow {
+                        sheet_id: sheet.id,
+                        row: index,
+                        new_size: size,
+                        client_resized: false,
+                    });
+                }
+                if sheet.offsets.hidden_rows().any(|y| y == index) {
+                    ops.push(Operation::SetRowsHidden {
+                        sheet_id: sheet.id,
+                        rows: vec![index],
+                        hidden: true,
+                    });
+                }
+                let groups = sheet.offsets.row_groups();
+                if groups.iter().any(|group| group.contains(index)) {
+                    ops.push(Operation::SetRowGroups {
+                        sheet_id: sheet.id,
+                        groups: groups.to_vec(),
+                    });
+                }
+            }
+        }
+
+        ops
+    }
+
+    /// Returns the formulas in every sheet whose cell references change when a
+    /// column/row is inserted or deleted on the sheet `sheet_id`, along with
+    /// their current position and new code. Formulas in deleted cells are
+    /// skipped.
+    fn adjusted_formulas(
+        &self,
+        sheet_id: SheetId,
+        adjust: RefAdjust,
+    ) -> Vec<(SheetPos, crate::CodeCellValue)> {
+        let Some(sheet_name) = self.try_sheet(sheet_id).map(|sheet| sheet.name.clone()) else {
+            return vec![];
+        };
+
+        let mut formulas = vec![];
+        for sheet in self.grid.sheets() {
+            let is_affected_sheet = |name: Option<&str>| match name {
+                None => sheet.id == sheet_id,
+                Some(name) => name == sheet_name,
+            };
+            for (&x, column) in &sheet.columns {
+                for (&y, value) in &column.values {
+                    let CellValue::Code(code_cell) = value else {
+                        continue;
+                    };
+                    if code_cell.language != CodeCellLanguage::Formula {
+                        continue;
+                    }
+                    let old_sheet_pos = Pos { x, y }.to_sheet_pos(sheet.id);
+                    let Some(new_sheet_pos) =
+                        Self::shifted_sheet_pos(old_sheet_pos, sheet_id, adjust)
+                    else {
+                        continue;
+                    };
+                    let code = adjust_cell_references(
+                        &code_cell.code,
+                        old_sheet_pos.into(),
+                        new_sheet_pos.into(),
+                        adjust,
+                        &is_affected_sheet,
+                    );
+                    if code != code_cell.code {
+                        formulas.push((
+                            old_sheet_pos,
+                            crate::CodeCellValue {
+                                language: code_cell.language,
+                                code,
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+        formulas
+    }
+
+    /// Adjusts the cells accessed by every code run when a column/row is
+    /// inserted or deleted on the sheet `sheet_id`. Returns the original
+    /// position, index and value of each code run that accessed cells which
+    /// were inserted or deleted, and so needs to be computed again.
+    fn adjust_cells_accessed(
+        &mut self,
+        sheet_id: SheetId,
+        adjust: RefAdjust,
+    ) -> Vec<(SheetPos, usize, CodeRun)> {
+        let mut changed_code_runs = vec![];
+        for sheet_id_of_code in self.sheet_ids() {
+            let Some(sheet) = self.try_sheet_mut(sheet_id_of_code) else {
+                continue;
+            };
+            for (index, (pos, code_run)) in sheet.code_runs.iter_mut().enumerate() {
+                let mut changed = false;
+                let cells_accessed = code_run
+                    .cells_accessed
+                    .iter()
+                    .filter_map(|&sheet_rect| {
+                        if sheet_rect.sheet_id != sheet_id {
+                            return Some(sheet_rect);
+                        }
+                        let rect = Rect::from(sheet_rect);
+                        let adjusted = adjust.adjust_rect(rect);
+                        // whole columns and rows are unbounded, so compare
+                        // sizes without overflowing
+                        let size = |r: Rect| {
+                            (r.max.x.wrapping_sub(r.min.x), r.max.y.wrapping_sub(r.min.y))
+                        };
+                        changed |=
+                            !matches!(adjusted, Some(adjusted) if size(adjusted) == size(rect));
+                        Some(adjusted?.to_sheet_rect(sheet_id))
+                    })
+                    .collect();
+                if changed {
+                    changed_code_runs.push((
+                        pos.to_sheet_pos(sheet_id_of_code),
+                        index,
+                        code_run.clone(),
+                    ));
+                }
+                code_run.cells_accessed = cells_accessed;
+            }
+        }
+        changed_code_runs
+    }
+
+    /// Sends everything that moved when a column/row was inserted or deleted
+    /// to the client.
+    fn send_shifted_cells(
+        &mut self,
+        sheet_id: SheetId,
+        adjust: RefAdjust,
+        old_bounds: GridBounds,
+        old_offsets: crate::sheet_offsets::OffsetWidthHeight,
+    ) {
+        self.send_updated_bounds(sheet_id);
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return;
+        };
+        self.send_comments(sheet_id);
+
+        // Everything after the inserted or deleted column/row moved, so
+        // render it again, including cells that are now empty.
+        if let GridBounds::NonEmpty(bounds) = GridBounds::merge(old_bounds, sheet.bounds(false)) {
+            let mut rect = bounds;
+            match adjust.axis {
+                Axis::X => rect.min.x = std::cmp::max(rect.min.x, adjust.start),
+                Axis::Y => rect.min.y = std::cmp::max(rect.min.y, adjust.start),
+            }
+            if rect.min.x <= rect.max.x && rect.min.y <= rect.max.y {
+                let sheet_rect: SheetRect = rect.to_sheet_rect(sheet_id);
+                self.send_render_cells(&sheet_rect);
+                self.send_fill_cells(&sheet_rect);
+            }
+        }
+        sheet.send_sheet_fills();
+
+        if let Ok(borders) = serde_json::to_string(&sheet.render_borders()) {
+            crate::wasm_bindings::js::jsSheetBorders(sheet_id.to_string(), borders);
+        }
+        if let Ok(code) = serde_json::to_string(&sheet.get_all_render_code_cells()) {
+            crate::wasm_bindings::js::jsSheetCodeCell(sheet_id.to_string(), code);
+        }
+        if let Ok(html) = serde_json::to_string(&sheet.get_html_output()) {
+            crate::wasm_bindings::js::jsHtmlOutput(html);
+        }
+
+        // Send the sizes of any columns/rows that changed.
+        let (old_sizes, new_sizes) = match adjust.axis {
+            Axis::X => (old_offsets.0, sheet.offsets.export().0),
+            Axis::Y => (old_offsets.1, sheet.offsets.export().1),
+        };
+        let mut indices: Vec<i64> = old_sizes
+            .iter()
+            .chain(&new_sizes)
+            .map(|&(index, _)| index)
+            .filter(|&index| index >= adjust.start)
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices.retain(|&index| {
+            let old_size = old_sizes
+                .iter()
+                .find(|&&(i, _)| i == index)
+                .map(|&(_, s)| s);
+            let new_size = new_sizes
+                .iter()
+                .find(|&&(i, _)| i == index)
+                .map(|&(_, s)| s);
+            old_size != new_size
+        });
+
+        // Hidden columns/rows moved too, so everything from the insertion or
+        // deletion to one past the last hidden one is resent.
+        let (hidden, groups) = match adjust.axis {
+            Axis::X => (
+                sheet.offsets.hidden_columns().max(),
+                sheet.offsets.column_groups(),
+            ),
+            Axis::Y => (
+                sheet.offsets.hidden_rows().max(),
+                sheet.offsets.row_groups(),
+            ),
+        };
+        let last_hidden = groups
+            .iter()
+            .filter(|group| group.collapsed)
+            .map(|group| group.end)
+            .chain(hidden)
+            .max();
+        if let Some(last) = last_hidden {
+            indices.extend(adjust.start..=last + 1);
+            indices.sort_unstable();
+            indices.dedup();
+        }
+
+        for index in indices {
+            let (column, row, size) = match adjust.axis {
+                Axis::X => (Some(index), None, sheet.offsets.column_width(index)),
+                Axis::Y => (None, Some(index), sheet.offsets.row_height(index)),
+            };
+            crate::wasm_bindings::js::jsOffsetsModified(sheet_id.to_string(), column, row, size);
+        }
+    }
+}
+
+/// Returns a rectangle covering an entire column or row.
+fn line_rect(axis: Axis, index: i64) -> Rect {
+    match axis {
+        Axis::X => Rect::new(index, i64::MIN, index, i64::MAX),
+        Axis::Y => Rect::new(i64::MIN, index, i64::MAX, index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bigdecimal::BigDecimal;
+    use serial_test::serial;
+
+    use super::*;
+    use crate::grid::{
+        defined_names::NameValue,
+        sheet::{
+            conditional_formats::{
+                ConditionalFormat, ConditionalFormatRule, ConditionalFormatStyle,
+            },
+            validations::{Validation, ValidationRule},
+        },
+    };
+
+    fn code_at(gc: &GridController, sheet_pos: SheetPos) -> String {
+        match gc.sheet(sheet_pos.sheet_id).cell_value(sheet_pos.into()) {
+            Some(CellValue::Code(code_cell)) => code_cell.code,
+            other => panic!("expected a code cell, got {other:?}"),
+        }
+    }
+
+    fn number(n: i32) -> Option<CellValue> {
+        Some(CellValue::Number(BigDecimal::from(n)))
+    }
+
+    #[test]
+    #[serial]
+    fn test_insert_column() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for x in 0..3 {
+            gc.set_cell_value(SheetPos { x, y: 0, sheet_id }, (x + 1).to_string(), None);
+        }
+        let formula_pos = SheetPos {
+            x: 0,
+            y: 2,
+            sheet_id,
+        };
+        gc.set_code_cell(
+            formula_pos,
+            CodeCellLanguage::Formula,
+            "SUM(A0:C0)".to_string(),
+            None,
+        );
+        let original_code = code_at(&gc, formula_pos);
+
+        gc.insert_column(sheet_id, 1, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 0 }), number(1));
+        assert_eq!(sheet.display_value(Pos { x: 1, y: 0 }), None);
+        assert_eq!(sheet.display_value(Pos { x: 2, y: 0 }), number(2));
+        assert_eq!(sheet.display_value(Pos { x: 3, y: 0 }), number(3));
+        assert_eq!(sheet.display_value(formula_pos.into()), number(6));
+        assert_ne!(code_at(&gc, formula_pos), original_code);
+
+        // the new column is included in the range
+        gc.set_cell_value(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            "10".to_string(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos.into()),
+            number(16)
+        );
+
+        gc.undo(None);
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 1, y: 0 }), number(2));
+        assert_eq!(sheet.display_value(Pos { x: 3, y: 0 }), None);
+        assert_eq!(sheet.display_value(formula_pos.into()), number(6));
+        assert_eq!(code_at(&gc, formula_pos), original_code);
+
+        gc.redo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 3, y: 0 }), number(3));
+        assert_eq!(sheet.display_value(formula_pos.into()), number(6));
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_row() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        for y in 0..3 {
+            gc.set_cell_value(SheetPos { x: 0, y, sheet_id }, (y + 1).to_string(), None);
+        }
+        gc.set_bold_selection(Selection::pos(0, 1, sheet_id), true, None)
+            .unwrap();
+        gc.commit_single_resize(sheet_id, None, Some(1), 50.0, None);
+        let formula_pos = SheetPos {
+            x: 1,
+            y: 3,
+            sheet_id,
+        };
+        gc.set_code_cell(
+            formula_pos,
+            CodeCellLanguage::Formula,
+            "SUM(A0:A2) + A1".to_string(),
+            None,
+        );
+        let original_code = code_at(&gc, formula_pos);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos.into()),
+            number(8)
+        );
+
+        gc.delete_row(sheet_id, 1, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 1 }), number(3));
+        assert_eq!(sheet.format_cell(0, 1, false).bold, None);
+        assert_eq!(sheet.offsets.row_height(1), crate::DEFAULT_ROW_HEIGHT);
+        let new_pos = SheetPos {
+            x: 1,
+            y: 2,
+            sheet_id,
+        };
+        assert!(code_at(&gc, new_pos).contains("#REF!"));
+        assert!(gc
+            .sheet(sheet_id)
+            .code_run(new_pos.into())
+            .and_then(|code_run| code_run.get_error())
+            .is_some());
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 1 }), number(2));
+        assert_eq!(sheet.format_cell(0, 1, false).bold, Some(true));
+        assert_eq!(sheet.offsets.row_height(1), 50.0);
+        assert_eq!(sheet.display_value(formula_pos.into()), number(8));
+        assert_eq!(code_at(&gc, formula_pos), original_code);
+
+        gc.redo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 1 }), number(3));
+        assert!(code_at(&gc, new_pos).contains("#REF!"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_column_on_other_sheet() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let other_id = gc.sheet_ids()[1];
+        let other_name = gc.sheet(other_id).name.clone();
+        gc.set_cell_value(
+            SheetPos {
+                x: 2,
+                y: 0,
+                sheet_id: other_id,
+            },
+            "5".to_string(),
+            None,
+        );
+        let formula_pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        gc.set_code_cell(
+            formula_pos,
+            CodeCellLanguage::Formula,
+            format!("'{other_name}'!C0 * 2"),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos.into()),
+            number(10)
+        );
+
+        gc.delete_column(other_id, 0, None);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos.into()),
+            number(10)
+        );
+        gc.set_cell_value(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id: other_id,
+            },
+            "7".to_string(),
+            None,
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos.into()),
+            number(14)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_column_with_merged_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.merge_cells(SheetRect::from_numbers(1, 0, 2, 2, sheet_id), None);
+        gc.merge_cells(SheetRect::from_numbers(4, 0, 1, 3, sheet_id), None);
+
+        gc.delete_column(sheet_id, 1, None);
+        assert_eq!(
+            gc.sheet(sheet_id).merged_cells,
+            vec![Rect::new(1, 0, 1, 1), Rect::new(3, 0, 3, 2)]
+        );
+
+        gc.delete_column(sheet_id, 3, None);
+        assert_eq!(gc.sheet(sheet_id).merged_cells, vec![Rect::new(1, 0, 1, 1)]);
+
+        gc.undo(None);
+        gc.undo(None);
+        let mut merged_cells = gc.sheet(sheet_id).merged_cells.clone();
+        merged_cells.sort_by_key(|rect| rect.min.x);
+        assert_eq!(
+            merged_cells,
+            vec![Rect::new(1, 0, 2, 1), Rect::new(4, 0, 4, 2)]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_delete_row_with_comments() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_comment(
+            Pos { x: 0, y: 1 }.to_sheet_pos(sheet_id),
+            "ann".into(),
+            "deleted".into(),
+            None,
+        );
+        gc.add_comment(
+            Pos { x: 0, y: 3 }.to_sheet_pos(sheet_id),
+            "ann".into(),
+            "moved".into(),
+            None,
+        );
+
+        gc.delete_row(sheet_id, 1, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.comments.len(), 1);
+        assert_eq!(
+            sheet.comment_thread(Pos { x: 0, y: 2 }).unwrap().comments[0].text,
+            "moved"
+        );
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.comment_thread(Pos { x: 0, y: 1 }).unwrap().comments[0].text,
+            "deleted"
+        );
+        assert_eq!(
+            sheet.comment_thread(Pos { x: 0, y: 3 }).unwrap().comments[0].text,
+            "moved"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_insert_delete_with_defined_names() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id)
+            .test_set_values(0, 0, 1, 3, vec!["1", "2", "3"]);
+        let sales = |rect: Rect| Some(NameValue::Range(rect.to_sheet_rect(sheet_id)));
+        gc.set_defined_name("Sales".into(), sales(Rect::new(0, 0, 0, 2)), None)
+            .unwrap();
+        gc.set_defined_name(
+            "Total".into(),
+            Some(NameValue::Formula("SUM(\"Sheet 1\"!$A$0:$A$2)".into())),
+            None,
+        )
+        .unwrap();
+        let formula_pos = SheetPos {
+            x: 2,
+            y: 0,
+            sheet_id,
+        };
+        gc.set_code_cell(
+            formula_pos,
+            CodeCellLanguage::Formula,
+            "SUM(Sales) + Total".into(),
+            None,
+        );
+        let name_value = |gc: &GridController, name: &str| {
+            gc.grid().defined_name(name).map(|name| name.value.clone())
+        };
+        let display = |gc: &GridController| gc.sheet(sheet_id).display_value(Pos { x: 2, y: 0 });
+        assert_eq!(display(&gc), number(12));
+
+        // the names grow to include an inserted row
+        gc.insert_row(sheet_id, 1, None);
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 1,
+                sheet_id,
+            },
+            "10".into(),
+            None,
+        );
+        assert_eq!(name_value(&gc, "Sales"), sales(Rect::new(0, 0, 0, 3)));
+        assert_eq!(
+            name_value(&gc, "Total"),
+            Some(NameValue::Formula("SUM(\"Sheet 1\"!$A$0:$A$3)".into()))
+        );
+        assert_eq!(display(&gc), number(32));
+
+        // and are `#REF!` once their range is deleted
+        gc.delete_column(sheet_id, 0, None);
+        assert_eq!(
+            name_value(&gc, "Sales"),
+            Some(NameValue::Formula("#REF!".into()))
+        );
+
+        gc.undo(None);
+        assert_eq!(name_value(&gc, "Sales"), sales(Rect::new(0, 0, 0, 3)));
+        gc.undo(None);
+        gc.undo(None);
+        assert_eq!(name_value(&gc, "Sales"), sales(Rect::new(0, 0, 0, 2)));
+        assert_eq!(
+            name_value(&gc, "Total"),
+            Some(NameValue::Formula("SUM(\"Sheet 1\"!$A$0:$A$2)".into()))
+        );
+        assert_eq!(display(&gc), number(12));
+
+        // deleting the sheet of a range makes it `#REF!` until undone
+        gc.delete_sheet(sheet_id, None);
+        assert_eq!(
+            name_value(&gc, "Sales"),
+            Some(NameValue::Formula("#REF!".into()))
+        );
+        gc.undo(None);
+        assert_eq!(name_value(&gc, "Sales"), sales(Rect::new(0, 0, 0, 2)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_insert_delete_with_validations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let list = Validation::new(
+            Selection::rect(Rect::new(0, 2, 0, 5), sheet_id),
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(3, 0, 1, 3, sheet_id),
+            },
+            None,
+        );
+        let custom = Validation::new(
+            Selection::rect(Rect::new(1, 1, 1, 1), sheet_id),
+            ValidationRule::Custom {
+                formula: "B1 > 0".into(),
+            },
+            None,
+        );
+        gc.add_validation(list.clone(), None);
+        gc.add_validation(custom.clone(), None);
+        let original = vec![list.clone(), custom.clone()];
+
+        gc.insert_row(sheet_id, 1, None);
+        let inserted = gc.sheet(sheet_id).validations.clone();
+        assert_eq!(
+            inserted[0].selection,
+            Selection::rect(Rect::new(0, 3, 0, 6), sheet_id)
+        );
+        assert_eq!(
+            inserted[0].rule,
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(3, 0, 1, 4, sheet_id),
+            }
+        );
+        assert_eq!(inserted[1].anchor(), Pos { x: 1, y: 2 });
+        assert_eq!(
+            inserted[1].rule,
+            ValidationRule::Custom {
+                formula: "B2 > 0".into(),
+            }
+        );
+
+        // deleting the cells of a validation removes it
+        gc.delete_row(sheet_id, 2, None);
+        let validations = &gc.sheet(sheet_id).validations;
+        assert_eq!(validations.len(), 1);
+        assert_eq!(
+            validations[0].selection,
+            Selection::rect(Rect::new(0, 2, 0, 5), sheet_id)
+        );
+
+        // and deleting its list removes it too
+        gc.delete_column(sheet_id, 3, None);
+        assert!(gc.sheet(sheet_id).validations.is_empty());
+
+        gc.undo(None);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).validations, inserted);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).validations, original);
+
+        gc.redo(None);
+        gc.redo(None);
+        assert_eq!(gc.sheet(sheet_id).validations.len(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn test_insert_delete_with_conditional_formats() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id)
+            .test_set_values(0, 0, 1, 4, vec!["1", "5", "2", "8"]);
+        gc.sheet_mut(sheet_id).recalculate_bounds();
+        let style = ConditionalFormatStyle {
+            bold: Some(true),
+            ..Default::default()
+        };
+        let top = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 0, 3), sheet_id),
+            ConditionalFormatRule::Top { count: 1 },
+            style.clone(),
+        );
+        let formula = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 1, 0, 3), sheet_id),
+            ConditionalFormatRule::Formula {
+                formula: "A1 > $A$2".into(),
+            },
+            style,
+        );
+        gc.add_conditional_format(top.clone(), None);
+        gc.add_conditional_format(formula.clone(), None);
+        let original = vec![top.clone(), formula.clone()];
+        let matches = |gc: &GridController| {
+            let mut matches: Vec<_> = gc.sheet(sheet_id).conditional_format_matches[&formula.id]
+                .cells
+                .iter()
+                .map(|pos| pos.y)
+                .collect();
+            matches.sort();
+            matches
+        };
+        assert_eq!(matches(&gc), vec![1, 3]);
+
+        // the anchor of the formula is deleted, so its references keep their
+        // offsets from the next row
+        gc.delete_row(sheet_id, 1, None);
+        let formats = gc.sheet(sheet_id).conditional_formats.clone();
+        assert_eq!(
+            formats[0].selection,
+            Selection::rect(Rect::new(0, 0, 0, 2), sheet_id)
+        );
+        assert_eq!(
+            formats[1].selection,
+            Selection::rect(Rect::new(0, 1, 0, 2), sheet_id)
+        );
+        assert_eq!(
+            formats[1].rule,
+            ConditionalFormatRule::Formula {
+                formula: "A1 > $A$1".into(),
+            }
+        );
+
+        gc.insert_column(sheet_id, 0, None);
+        assert_eq!(
+            gc.sheet(sheet_id).conditional_formats[0].selection,
+            Selection::rect(Rect::new(1, 0, 1, 2), sheet_id)
+        );
+
+        // deleting the cells of a format removes it
+        gc.delete_column(sheet_id, 1, None);
+        assert!(gc.sheet(sheet_id).conditional_formats.is_empty());
+
+        gc.undo(None);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats, formats);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).conditional_formats, original);
+        assert_eq!(matches(&gc), vec![1, 3]);
+    }
+}
@@ -47,6 +47,7 @@ impl GridController {
                 }
                 let sheet_id = sheet.id;
                 self.grid.add_sheet(Some(sheet));
+                self.evaluate_conditional_format_formulas(sheet_id);
 
                 self.send_add_sheet(sheet_id, transaction);
 
@@ -71,6 +72,25 @@ impl GridController {
                 return;
             };
 
+            // names that refer to the sheet are restored after it is added
+            // back
+            let changed_names = self.grid.remove_sheet_from_defined_names(sheet_id);
+            transaction.reverse_operations.splice(
+                0..0,
+                changed_names
+                    .iter()
+                    .map(|(name, value)| Operation::SetDefinedName {
+                        name: name.clone(),
+                        value: Some(value.clone()),
+                    }),
+            );
+            if (cfg!(target_family = "wasm") || cfg!(test))
+                && !transaction.is_server()
+                && !changed_names.is_empty()
+            {
+                self.send_defined_names();
+            }
+
             // create a sheet if we deleted the last one (only for user actions)
             if transaction.is_user() && self.sheet_ids().is_empty() {
                 let new_first_sheet_id = SheetId::new();
@@ -205,6 +225,44 @@ impl GridController {
         }
     }
 
+    pub(crate) fn execute_set_sheet_frozen_panes(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetSheetFrozenPanes {
+            sheet_id,
+            frozen_columns,
+            frozen_rows,
+        } = op
+        {
+            let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                // sheet may have been deleted
+                return;
+            };
+            let old_frozen_columns = std::mem::replace(&mut sheet.frozen_columns, frozen_columns);
+            let old_frozen_rows = std::mem::replace(&mut sheet.frozen_rows, frozen_rows);
+
+            transaction
+                .forward_operations
+                .push(Operation::SetSheetFrozenPanes {
+                    sheet_id,
+                    frozen_columns,
+                    frozen_rows,
+                });
+            transaction.reverse_operations.insert(
+                0,
+                Operation::SetSheetFrozenPanes {
+                    sheet_id,
+                    frozen_columns: old_frozen_columns,
+                    frozen_rows: old_frozen_rows,
+                },
+            );
+
+            self.send_sheet_info(sheet_id);
+        }
+    }
+
     pub(crate) fn execute_duplicate_sheet(
         &mut self,
         transaction: &mut PendingTransaction,
@@ -221,6 +279,14 @@ impl GridController {
             };
             let mut new_sheet = sheet.clone();
             new_sheet.id = new_sheet_id;
+            new_sheet
+                .validations
+                .iter_mut()
+                .for_each(|validation| validation.selection.sheet_id = new_sheet_id);
+            new_sheet
+                .conditional_formats
+                .iter_mut()
+                .for_each(|format| format.selection.sheet_id = new_sheet_id);
             let right = self.grid.next_sheet(sheet_id);
             let right_order = right.map(|right| right.order.clone());
             if let Ok(order) = key_between(&Some(sheet.order.clone()), &right_order) {
@@ -358,6 +424,28 @@ mod tests {
         );
     }
 
+    #[test]
+    #[serial]
+    fn test_set_sheet_frozen_panes() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_sheet_frozen_panes(sheet_id, 1, 2, None);
+        assert_eq!(gc.sheet(sheet_id).frozen_columns, 1);
+        assert_eq!(gc.sheet(sheet_id).frozen_rows, 2);
+        let sheet_info = SheetInfo::from(gc.sheet(sheet_id));
+        expect_js_call(
+            "jsSheetInfoUpdate",
+            serde_json::to_string(&sheet_info).unwrap(),
+            true,
+        );
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).frozen_columns, 0);
+        assert_eq!(gc.sheet(sheet_id).frozen_rows, 0);
+        gc.redo(None);
+        assert_eq!(gc.sheet(sheet_id).frozen_rows, 2);
+    }
+
     #[test]
     #[serial]
     fn test_sheet_reorder() {
@@ -0,0 +1,137 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub fn execute_validation(&mut self, transaction: &mut PendingTransaction, op: Operation) {
+        let (sheet_id, reverse) = match op.clone() {
+            Operation::SetValidation {
+                sheet_id,
+                validation,
+                index,
+            } => {
+                let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                    // sheet may have been deleted
+                    return;
+                };
+                let validation_id = validation.id;
+                let reverse = match sheet.set_validation(validation, index) {
+                    Some(old) => Operation::SetValidation {
+                        sheet_id,
+                        validation: old,
+                        index: None,
+                    },
+                    None => Operation::RemoveValidation {
+                        sheet_id,
+                        validation_id,
+                    },
+                };
+                (sheet_id, reverse)
+            }
+            Operation::RemoveValidation {
+                sheet_id,
+                validation_id,
+            } => {
+                let Some(sheet) = self.try_sheet_mut(sheet_id) else {
+                    // sheet may have been deleted
+                    return;
+                };
+                let Some((index, old)) = sheet.remove_validation(validation_id) else {
+                    return;
+                };
+                (
+                    sheet_id,
+                    Operation::SetValidation {
+                        sheet_id,
+                        validation: old,
+                        index: Some(index),
+                    },
+                )
+            }
+            _ => unreachable!("Expected Operation::SetValidation or Operation::RemoveValidation"),
+        };
+
+        transaction.forward_operations.push(op);
+        transaction.reverse_operations.insert(0, reverse);
+
+        if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
+            self.send_validations(sheet_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::sheet::validations::{Validation, ValidationRule},
+        selection::Selection,
+        wasm_bindings::js::{clear_js_calls, expect_js_call},
+        Rect,
+    };
+
+    #[test]
+    #[serial]
+    fn test_execute_validation() {
+        clear_js_calls();
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let first = Validation::new(
+            Selection::rect(Rect::new(0, 0, 1, 1), sheet_id),
+            ValidationRule::List {
+                values: vec!["a".into()],
+            },
+            None,
+        );
+        let second = Validation::new(
+            Selection::rect(Rect::new(0, 0, 1, 1), sheet_id),
+            ValidationRule::TextLength {
+                min: None,
+                max: Some(2),
+            },
+            None,
+        );
+        gc.add_validation(first.clone(), None);
+        gc.add_validation(second.clone(), None);
+        expect_js_call(
+            "jsSheetValidations",
+            format!(
+                "{},{}",
+                sheet_id,
+                serde_json::to_string(&gc.render_validations(sheet_id)).unwrap()
+            ),
+            false,
+        );
+
+        gc.remove_validation(sheet_id, first.id, None);
+        assert_eq!(gc.sheet(sheet_id).validations, vec![second.clone()]);
+
+        // undo restores the removed validation's priority
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).validations,
+            vec![first.clone(), second.clone()]
+        );
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).validations, vec![first.clone()]);
+
+        gc.redo(None);
+        gc.redo(None);
+        assert_eq!(gc.sheet(sheet_id).validations, vec![second]);
+
+        // removing a missing validation does nothing
+        let mut transaction = PendingTransaction::default();
+        gc.execute_validation(
+            &mut transaction,
+            Operation::RemoveValidation {
+                sheet_id,
+                validation_id: first.id,
+            },
+        );
+        assert!(transaction.forward_operations.is_empty());
+    }
+}
@@ -58,10 +58,17 @@ impl GridController {
                         );
                     }
                     transaction.generate_thumbnail |= self.thumbnail_dirty_sheet_rect(&sheet_rect);
+                    self.refresh_conditional_formats(transaction, &sheet_rect);
 
                     if !transaction.is_server() {
                         self.send_updated_bounds(sheet_rect.sheet_id);
                         self.send_render_cells(&sheet_rect);
+                        if self
+                            .try_sheet(sheet_rect.sheet_id)
+                            .is_some_and(|sheet| !sheet.validations.is_empty())
+                        {
+                            self.send_validations(sheet_rect.sheet_id);
+                        }
                     }
                 }
             }
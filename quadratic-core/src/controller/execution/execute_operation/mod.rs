@@ -4,11 +4,17 @@ use crate::controller::GridController;
 
 pub mod execute_borders;
 pub mod execute_code;
+pub mod execute_comments;
+pub mod execute_conditional_formats;
 pub mod execute_cursor;
+pub mod execute_defined_names;
 pub mod execute_formats;
+pub mod execute_merge_cells;
 pub mod execute_move_cells;
 pub mod execute_offsets;
+pub mod execute_rows_columns;
 pub mod execute_sheets;
+pub mod execute_validations;
 pub mod execute_values;
 
 impl GridController {
@@ -37,10 +43,41 @@ impl GridController {
                 Operation::ReorderSheet { .. } => self.execute_reorder_sheet(transaction, op),
                 Operation::SetSheetName { .. } => self.execute_set_sheet_name(transaction, op),
                 Operation::SetSheetColor { .. } => self.execute_set_sheet_color(transaction, op),
+                Operation::SetSheetFrozenPanes { .. } => {
+                    self.execute_set_sheet_frozen_panes(transaction, op);
+                }
                 Operation::DuplicateSheet { .. } => self.execute_duplicate_sheet(transaction, op),
 
                 Operation::ResizeColumn { .. } => self.execute_resize_column(transaction, op),
                 Operation::ResizeRow { .. } => self.execute_resize_row(transaction, op),
+                Operation::SetColumnsHidden { .. } | Operation::SetRowsHidden { .. } => {
+                    self.execute_set_hidden(transaction, op);
+                }
+                Operation::SetColumnGroups { .. } | Operation::SetRowGroups { .. } => {
+                    self.execute_set_groups(transaction, op);
+                }
+
+                Operation::InsertColumn { .. }
+                | Operation::DeleteColumn { .. }
+                | Operation::InsertRow { .. }
+                | Operation::DeleteRow { .. } => self.execute_insert_delete(transaction, op),
+
+                Operation::MergeCells { .. } | Operation::UnmergeCells { .. } => {
+                    self.execute_merge_cells(transaction, op);
+                }
+                Operation::SetValidation { .. } | Operation::RemoveValidation { .. } => {
+                    self.execute_validation(transaction, op);
+                }
+                Operation::SetConditionalFormat { .. }
+                | Operation::RemoveConditionalFormat { .. } => {
+                    self.execute_conditional_format(transaction, op);
+                }
+                Operation::AddComment { .. }
+                | Operation::EditComment { .. }
+                | Operation::ResolveComments { .. }
+                | Operation::DeleteComment { .. }
+                | Operation::SetCommentThread { .. } => self.execute_comment(transaction, op),
+                Operation::SetDefinedName { .. } => self.execute_set_defined_name(transaction, op),
 
                 Operation::SetCursor { .. } => self.execute_set_cursor(transaction, op),
                 Operation::SetCursorSelection { .. } => {
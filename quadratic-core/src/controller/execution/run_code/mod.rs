@@ -138,6 +138,7 @@ impl GridController {
             self.check_all_spills(transaction, sheet_pos.sheet_id);
         }
         transaction.generate_thumbnail |= self.thumbnail_dirty_sheet_rect(&sheet_rect);
+        self.refresh_conditional_formats(transaction, &sheet_rect);
 
         if (cfg!(target_family = "wasm") || cfg!(test)) && !transaction.is_server() {
             if let Some(sheet) = self.try_sheet(sheet_id) {
@@ -231,6 +231,88 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_offset_triggers_compute() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 5,
+                sheet_id,
+            },
+            "7".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "SUM(OFFSET(A0, 4, 0, 3))".into(),
+            None,
+        );
+
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(7.into()))
+        );
+
+        // A6 is within the range returned by `OFFSET` but was blank when the
+        // formula was first run.
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 6,
+                sheet_id,
+            },
+            "5".into(),
+            None,
+        );
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(12.into()))
+        );
+    }
+
+    #[test]
+    fn test_whole_column_triggers_compute() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = |x, y| SheetPos { x, y, sheet_id };
+
+        gc.set_cell_value(sheet_pos(0, 0), "1".into(), None);
+        gc.set_cell_value(sheet_pos(0, 1), "2".into(), None);
+        gc.set_code_cell(
+            sheet_pos(1, 0),
+            CodeCellLanguage::Formula,
+            "SUM(A:A)".into(),
+            None,
+        );
+        let display = |gc: &GridController| gc.sheet(sheet_id).display_value(Pos { x: 1, y: 0 });
+        assert_eq!(display(&gc), Some(CellValue::Number(3.into())));
+
+        // a row appended below the data is included
+        gc.set_cell_value(sheet_pos(0, 100), "4".into(), None);
+        assert_eq!(display(&gc), Some(CellValue::Number(7.into())));
+
+        gc.undo(None);
+        assert_eq!(display(&gc), Some(CellValue::Number(3.into())));
+        gc.redo(None);
+        assert_eq!(display(&gc), Some(CellValue::Number(7.into())));
+
+        // the reference is kept when rows are inserted and deleted
+        gc.insert_row(sheet_id, 1, None);
+        assert_eq!(display(&gc), Some(CellValue::Number(7.into())));
+        gc.delete_row(sheet_id, 101, None);
+        assert_eq!(display(&gc), Some(CellValue::Number(3.into())));
+    }
+
     #[test]
     fn test_js_code_result_to_code_cell_value_single() {
         let mut gc = GridController::test();
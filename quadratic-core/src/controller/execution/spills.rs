@@ -111,6 +111,9 @@ mod tests {
             italic: None,
             text_color: None,
             special: Some(JsRenderCellSpecial::SpillError),
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
         }]
     }
 
@@ -131,6 +134,9 @@ mod tests {
             italic: None,
             text_color: None,
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
         }]
     }
 
@@ -212,6 +218,52 @@ mod tests {
         assert!(!sheet.code_runs[0].spill_error);
     }
 
+    #[test]
+    fn test_check_spills_from_array_function() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 2,
+                sheet_id,
+            },
+            "blocking".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "SEQUENCE(3)".into(),
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.code_run(Pos { x: 0, y: 0 }).unwrap().spill_error);
+
+        // removing the blocking value lets the array spill
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 2,
+                sheet_id,
+            },
+            "".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(!sheet.code_run(Pos { x: 0, y: 0 }).unwrap().spill_error);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 2 }),
+            Some(CellValue::Number(3.into()))
+        );
+    }
+
     #[test]
     fn test_check_spills_by_code_run() {
         let mut gc = GridController::default();
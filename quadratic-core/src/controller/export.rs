@@ -5,21 +5,39 @@ use itertools::PeekingNext;
 use super::GridController;
 use crate::{selection::Selection, Pos};
 
+mod arrow;
+mod xlsx;
+
 impl GridController {
-    /// exports a CSV string from a selection on the grid.
+    /// exports a CSV string from a selection on the grid. If `skip_hidden`
+    /// is set, hidden columns and rows are left out.
     ///
     /// Returns a [`String`].
-    pub fn export_csv_selection(&self, selection: Selection) -> Result<String> {
+    pub fn export_csv_selection(&self, selection: Selection, skip_hidden: bool) -> Result<String> {
         let sheet = self
             .try_sheet(selection.sheet_id)
             .context("Sheet not found")?;
         let bounds = sheet.selection_bounds(&selection).context("No values")?;
-        let values = sheet.selection_sorted_vec(&selection, false);
         let mut writer = Writer::from_writer(vec![]);
+        let is_hidden = |pos: &Pos| {
+            skip_hidden
+                && (sheet.offsets.is_column_hidden(pos.x) || sheet.offsets.is_row_hidden(pos.y))
+        };
+        let values: Vec<_> = sheet
+            .selection_sorted_vec(&selection, false)
+            .into_iter()
+            .filter(|(pos, _)| !is_hidden(pos))
+            .collect();
         let mut iter = values.iter();
         for y in bounds.min.y..=bounds.max.y {
+            if skip_hidden && sheet.offsets.is_row_hidden(y) {
+                continue;
+            }
             let mut line = vec![];
             for x in bounds.min.x..=bounds.max.x {
+                if skip_hidden && sheet.offsets.is_column_hidden(x) {
+                    continue;
+                }
                 // we need to ignore unselected columns or rows
                 if selection.rects.is_some() || selection.pos_in_selection(Pos { x, y }) {
                     if let Some((_, value)) = iter.peeking_next(|(pos, _)| pos.x == x && pos.y == y)
@@ -70,9 +88,17 @@ mod tests {
             }
         }
 
-        let result = gc.export_csv_selection(selected).unwrap();
+        let result = gc.export_csv_selection(selected.clone(), false).unwrap();
         let expected = "1,2,3,4\n5,6,7,8\n9,10,11,12\n13,14,15,16\n";
 
         assert_eq!(&result, expected);
+
+        let sheet = gc.sheet_mut(sheet_id);
+        sheet.offsets.set_column_hidden(1, true);
+        sheet.offsets.set_row_hidden(2, true);
+        let result = gc.export_csv_selection(selected.clone(), true).unwrap();
+        assert_eq!(&result, "1,3,4\n5,7,8\n13,15,16\n");
+        let result = gc.export_csv_selection(selected, false).unwrap();
+        assert_eq!(&result, expected);
     }
 }
@@ -0,0 +1,198 @@
+//! Exports a selection as typed columns, for data tools that read Parquet or
+//! Arrow IPC files.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow_array::RecordBatch;
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{Field, Schema};
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+
+use crate::{
+    cell_values_to_arrow_array, controller::GridController, selection::Selection,
+    util::column_name, CellValue, IsBlank, Pos, Rect,
+};
+
+impl GridController {
+    /// Exports the values in a selection as a Parquet file. Each column of
+    /// the selection is a column in the file, typed by
+    /// [`cell_values_to_arrow_array`]. If `first_row_as_headers` is set, the
+    /// first row names the columns; otherwise they are named by their letter.
+    pub fn export_parquet_selection(
+        &self,
+        selection: &Selection,
+        first_row_as_headers: bool,
+    ) -> Result<Vec<u8>> {
+        let batch = self.selection_record_batch(selection, first_row_as_headers)?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut output = vec![];
+        let mut writer = ArrowWriter::try_new(&mut output, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(output)
+    }
+
+    /// Exports the values in a selection as an Arrow IPC file. See
+    /// [`GridController::export_parquet_selection`].
+    pub fn export_arrow_selection(
+        &self,
+        selection: &Selection,
+        first_row_as_headers: bool,
+    ) -> Result<Vec<u8>> {
+        let batch = self.selection_record_batch(selection, first_row_as_headers)?;
+        let mut output = vec![];
+        let mut writer = FileWriter::try_new(&mut output, &batch.schema())?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        Ok(output)
+    }
+
+    /// Returns the values in a selection as a record batch, trimmed to the
+    /// rows and columns that have values.
+    fn selection_record_batch(
+        &self,
+        selection: &Selection,
+        first_row_as_headers: bool,
+    ) -> Result<RecordBatch> {
+        let sheet = self
+            .try_sheet(selection.sheet_id)
+            .context("Sheet not found")?;
+        let bounds = sheet.selection_bounds(selection).context("No values")?;
+        let values: Vec<(Pos, CellValue)> = bounds
+            .iter()
+            .filter(|pos| selection.pos_in_selection(*pos))
+            .filter_map(|pos| Some((pos, sheet.display_value(pos)?)))
+            .filter(|(_, value)| !value.is_blank())
+            .collect();
+        let mut positions = values.iter().map(|(pos, _)| *pos);
+        let first = positions.next().context("No values")?;
+        let bounds = positions.fold(Rect::single_pos(first), |mut bounds, pos| {
+            bounds.extend_to(pos);
+            bounds
+        });
+
+        let width = bounds.width() as usize;
+        let height = bounds.height() as usize;
+        let mut columns: Vec<Vec<Option<CellValue>>> = vec![vec![None; height]; width];
+        for (pos, value) in values {
+            columns[(pos.x - bounds.min.x) as usize][(pos.y - bounds.min.y) as usize] = Some(value);
+        }
+
+        let mut fields = vec![];
+        let mut arrays = vec![];
+        for (x, mut column) in bounds.x_range().zip(columns) {
+            let name = if first_row_as_headers {
+                column
+                    .remove(0)
+                    .map(|header| header.to_string())
+                    .filter(|header| !header.is_empty())
+            } else {
+                None
+            };
+            let array = cell_values_to_arrow_array(&column);
+            fields.push(Field::new(
+                name.unwrap_or_else(|| column_name(x)),
+                array.data_type().clone(),
+                true,
+            ));
+            arrays.push(array);
+        }
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{cast::AsArray, types::Int64Type, Array};
+    use arrow_ipc::reader::FileReader;
+    use arrow_schema::DataType;
+    use bytes::Bytes;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::{grid::CodeCellLanguage, SheetPos};
+
+    fn test_grid() -> GridController {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id).test_set_values(
+            1,
+            1,
+            3,
+            3,
+            vec!["name", "count", "ok", "a", "1", "true", "b", "", "false"],
+        );
+        for (y, value) in [(2, "true"), (3, "false")] {
+            gc.set_cell_value(SheetPos { x: 3, y, sheet_id }, value.into(), None);
+        }
+        gc.set_code_cell(
+            SheetPos {
+                x: 2,
+                y: 4,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "SUM(C2:C3)".into(),
+            None,
+        );
+        gc
+    }
+
+    #[test]
+    fn test_export_parquet_selection() {
+        let gc = test_grid();
+        let selection = Selection::all(gc.sheet_ids()[0]);
+        let parquet = gc.export_parquet_selection(&selection, true).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(parquet))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        let batch = &batches[0];
+
+        let schema = batch.schema();
+        let fields: Vec<(&str, &DataType)> = schema
+            .fields()
+            .iter()
+            .map(|field| (field.name().as_str(), field.data_type()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("name", &DataType::Utf8),
+                ("count", &DataType::Int64),
+                ("ok", &DataType::Boolean),
+            ]
+        );
+        assert_eq!(batch.num_rows(), 3);
+        let counts = batch.column(1).as_primitive::<Int64Type>();
+        assert_eq!(counts.value(0), 1);
+        assert!(counts.is_null(1));
+        assert_eq!(counts.value(2), 1);
+        assert!(batch.column(2).is_null(2));
+    }
+
+    #[test]
+    fn test_export_arrow_selection() {
+        let gc = test_grid();
+        let selection = Selection::rect(Rect::new(1, 2, 2, 3), gc.sheet_ids()[0]);
+        let arrow = gc.export_arrow_selection(&selection, false).unwrap();
+        let reader = FileReader::try_new(std::io::Cursor::new(arrow), None).unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+        let batch = &batches[0];
+
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).name(), "B");
+        assert_eq!(schema.field(1).name(), "C");
+        assert_eq!(batch.num_rows(), 2);
+        let names = batch.column(0).as_string::<i32>();
+        assert_eq!(names.value(0), "a");
+        assert_eq!(names.value(1), "b");
+
+        let empty = Selection::rect(Rect::new(10, 10, 12, 12), gc.sheet_ids()[0]);
+        assert!(gc.export_arrow_selection(&empty, false).is_err());
+    }
+}
@@ -0,0 +1,962 @@
+//! Exports the grid as an Excel workbook. An XLSX file is a zip archive of
+//! XML parts: the workbook, one worksheet per sheet and a shared stylesheet.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Cursor, Write};
+
+use anyhow::{Context, Result};
+use indexmap::IndexSet;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    color::Rgba,
+    controller::GridController,
+    formulas::{to_excel_formula, EXCEL_MAX_COLUMNS, EXCEL_MAX_ROWS},
+    grid::{
+        formats::format::Format, CellAlign, CellBorderLine, CellBorders, CellWrap,
+        CodeCellLanguage, CodeRunResult, NumericFormatKind, Sheet,
+    },
+    selection::Selection,
+    sheet_offsets::offsets::MAX_OUTLINE_LEVEL,
+    util::column_name,
+    CellValue, Pos, Rect, RunErrorMsg, DEFAULT_COLUMN_WIDTH, DEFAULT_ROW_HEIGHT,
+};
+
+const SPREADSHEET_NS: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const PACKAGE_RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships";
+
+/// Excel limits sheet names to 31 characters.
+const MAX_SHEET_NAME_LENGTH: usize = 31;
+
+/// Excel measures column widths in characters of its default font, which are
+/// 7 pixels wide, plus 5 pixels of padding.
+const CHARACTER_WIDTH: f64 = 7.0;
+const COLUMN_PADDING: f64 = 5.0;
+
+/// Excel measures row heights in points.
+const POINTS_PER_PIXEL: f64 = 0.75;
+
+/// Excel stores dates as days since 1899-12-30. This is 1970-01-01.
+const UNIX_EPOCH_SERIAL: f64 = 25_569.0;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Custom number formats are numbered after Excel's built-in ones.
+const FIRST_CUSTOM_NUM_FMT_ID: usize = 164;
+
+impl GridController {
+    /// Exports every sheet as an Excel workbook, with cell values, formats,
+    /// borders, column widths and row heights. Formulas are written in
+    /// Excel's syntax where possible; otherwise only their values are kept.
+    pub fn export_xlsx(&self) -> Result<Vec<u8>> {
+        let sheets: Vec<_> = self
+            .grid
+            .sheets()
+            .iter()
+            .map(|sheet| (sheet, None))
+            .collect();
+        write_workbook(&sheets)
+    }
+
+    /// Exports the cells in a selection as an Excel workbook with a single
+    /// sheet. See [`GridController::export_xlsx`].
+    pub fn export_xlsx_selection(&self, selection: &Selection) -> Result<Vec<u8>> {
+        let sheet = self
+            .try_sheet(selection.sheet_id)
+            .context("Sheet not found")?;
+        write_workbook(&[(sheet, Some(selection))])
+    }
+}
+
+/// Writes a workbook with the given sheets, limiting each to a selection if
+/// there is one.
+fn write_workbook(sheets: &[(&Sheet, Option<&Selection>)]) -> Result<Vec<u8>> {
+    let names = excel_sheet_names(sheets.iter().map(|(sheet, _)| sheet.name.as_str()));
+    let sheets: Vec<ExcelSheet<'_>> = sheets
+        .iter()
+        .zip(names)
+        .map(|((sheet, selection), name)| ExcelSheet::new(sheet, name, *selection))
+        .collect();
+
+    let mut styles = Styles::new();
+    let worksheets: Vec<String> = sheets
+        .iter()
+        .map(|sheet| sheet.worksheet_xml(&sheets, &mut styles))
+        .collect();
+
+    let sheet_entries: String = sheets
+        .iter()
+        .enumerate()
+        .map(|(i, sheet)| {
+            format!(
+                r#"<sheet name="{}" sheetId="{n}" r:id="rId{n}"/>"#,
+                xml_escape(&sheet.name),
+                n = i + 1
+            )
+        })
+        .collect();
+    let workbook = format!(
+        r#"{XML_DECLARATION}<workbook xmlns="{SPREADSHEET_NS}" xmlns:r="{RELATIONSHIPS_NS}"><sheets>{sheet_entries}</sheets><calcPr calcId="0" fullCalcOnLoad="1"/></workbook>"#
+    );
+
+    let mut workbook_rels: String = (1..=sheets.len())
+        .map(|n| {
+            format!(
+                r#"<Relationship Id="rId{n}" Type="{RELATIONSHIPS_NS}/worksheet" Target="worksheets/sheet{n}.xml"/>"#
+            )
+        })
+        .collect();
+    workbook_rels.push_str(&format!(
+        r#"<Relationship Id="rId{}" Type="{RELATIONSHIPS_NS}/styles" Target="styles.xml"/>"#,
+        sheets.len() + 1
+    ));
+    let workbook_rels = format!(
+        r#"{XML_DECLARATION}<Relationships xmlns="{PACKAGE_RELATIONSHIPS_NS}">{workbook_rels}</Relationships>"#
+    );
+
+    let root_rels = format!(
+        r#"{XML_DECLARATION}<Relationships xmlns="{PACKAGE_RELATIONSHIPS_NS}"><Relationship Id="rId1" Type="{RELATIONSHIPS_NS}/officeDocument" Target="xl/workbook.xml"/></Relationships>"#
+    );
+
+    let worksheet_types: String = (1..=sheets.len())
+        .map(|n| {
+            format!(
+                r#"<Override PartName="/xl/worksheets/sheet{n}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            )
+        })
+        .collect();
+    let content_types = format!(
+        r#"{XML_DECLARATION}<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>{worksheet_types}</Types>"#
+    );
+
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut write_part = |path: &str, xml: &str| -> Result<()> {
+        zip.start_file(path, options)?;
+        zip.write_all(xml.as_bytes())?;
+        Ok(())
+    };
+    write_part("[Content_Types].xml", &content_types)?;
+    write_part("_rels/.rels", &root_rels)?;
+    write_part("xl/workbook.xml", &workbook)?;
+    write_part("xl/_rels/workbook.xml.rels", &workbook_rels)?;
+    write_part("xl/styles.xml", &styles.xml())?;
+    for (i, worksheet) in worksheets.iter().enumerate() {
+        write_part(&format!("xl/worksheets/sheet{}.xml", i + 1), worksheet)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+const XML_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+
+/// Returns names that Excel accepts for the sheets: without the characters
+/// `[]:*?/\`, at most 31 characters long and unique ignoring case.
+fn excel_sheet_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut used: Vec<String> = vec![];
+    for name in names {
+        let name: String = name
+            .trim_matches('\'')
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+            .take(MAX_SHEET_NAME_LENGTH)
+            .collect();
+        let name = if name.is_empty() {
+            "Sheet".into()
+        } else {
+            name
+        };
+        let is_used = |name: &str| used.iter().any(|used| used.eq_ignore_ascii_case(name));
+        let mut unique = name.clone();
+        let mut count = 1;
+        while is_used(&unique) {
+            count += 1;
+            let suffix = format!(" ({count})");
+            let prefix: String = name
+                .chars()
+                .take(MAX_SHEET_NAME_LENGTH - suffix.len())
+                .collect();
+            unique = format!("{prefix}{suffix}");
+        }
+        used.push(unique);
+    }
+    used
+}
+
+/// A sheet being written to the workbook.
+struct ExcelSheet<'a> {
+    sheet: &'a Sheet,
+    name: String,
+
+    /// Cells to write, by row and then column.
+    cells: BTreeMap<i64, BTreeSet<i64>>,
+
+    /// Rows and columns outside these bounds keep their default size.
+    bounds: Option<Rect>,
+
+    /// Added to a position in the sheet to get its 0-based column and 1-based
+    /// row in Excel.
+    offset: Pos,
+}
+
+impl<'a> ExcelSheet<'a> {
+    fn new(sheet: &'a Sheet, name: String, selection: Option<&Selection>) -> Self {
+        let mut positions = vec![];
+        for (&x, column) in &sheet.columns {
+            if let Some(range) = column.range(false) {
+                positions.extend(
+                    range
+                        .filter(|&y| column.has_anything_in_row(y))
+                        .map(|y| Pos { x, y }),
+                );
+            }
+        }
+        for (&x, column) in &sheet.borders().per_cell.borders {
+            positions.extend(column.values().map(|(y, _)| Pos { x, y }));
+        }
+        for (pos, code_run) in &sheet.code_runs {
+            positions.extend(code_run.output_rect(*pos, false).iter());
+        }
+        if let Some(selection) = selection {
+            positions.retain(|&pos| selection.pos_in_selection(pos));
+        }
+
+        let min = |coord: fn(&Pos) -> i64| positions.iter().map(coord).min();
+        let offset = Pos {
+            x: min(|pos| pos.x).map_or(0, |x| (-x).max(0)),
+            y: min(|pos| pos.y).map_or(1, |y| (1 - y).max(0)),
+        };
+        let mut cells: BTreeMap<i64, BTreeSet<i64>> = BTreeMap::new();
+        for pos in positions {
+            cells.entry(pos.y).or_default().insert(pos.x);
+        }
+        let bounds = match selection {
+            Some(selection) => sheet.selection_bounds(selection),
+            None => Some(Rect::new(i64::MIN, i64::MIN, i64::MAX, i64::MAX)),
+        };
+
+        ExcelSheet {
+            sheet,
+            name,
+            cells,
+            bounds,
+            offset,
+        }
+    }
+
+    fn excel_column(&self, x: i64) -> Option<i64> {
+        let column = x.checked_add(self.offset.x)?;
+        (0..EXCEL_MAX_COLUMNS).contains(&column).then_some(column)
+    }
+
+    fn excel_row(&self, y: i64) -> Option<i64> {
+        let row = y.checked_add(self.offset.y)?;
+        (1..=EXCEL_MAX_ROWS).contains(&row).then_some(row)
+    }
+
+    fn cell_name(&self, pos: Pos) -> Option<String> {
+        Some(format!(
+            "{}{}",
+            column_name(self.excel_column(pos.x)?),
+            self.excel_row(pos.y)?
+        ))
+    }
+
+    /// Returns the XML for the worksheet. `sheets` are all the sheets in the
+    /// workbook, which formulas may refer to.
+    fn worksheet_xml(&self, sheets: &[ExcelSheet<'_>], styles: &mut Styles) -> String {
+        let offsets = &self.sheet.offsets;
+        let (custom_widths, custom_heights) = offsets.export();
+        let column_in_bounds = |x: i64| {
+            self.bounds
+                .is_some_and(|bounds| (bounds.min.x..=bounds.max.x).contains(&x))
+        };
+        let row_in_bounds = |y: i64| {
+            self.bounds
+                .is_some_and(|bounds| (bounds.min.y..=bounds.max.y).contains(&y))
+        };
+
+        let columns: BTreeSet<i64> = custom_widths
+            .iter()
+            .map(|(x, _)| *x)
+            .chain(offsets.hidden_columns())
+            .filter(|&x| column_in_bounds(x))
+            .collect();
+        let mut cols_xml = String::new();
+        for x in columns {
+            let Some(column) = self.excel_column(x) else {
+                continue;
+            };
+            let width =
+                (offsets.unhidden_column_width(x) - COLUMN_PADDING).max(0.0) / CHARACTER_WIDTH;
+            cols_xml.push_str(&format!(
+                r#"<col min="{n}" max="{n}" width="{width:.2}" customWidth="1"{}{}/>"#,
+                hidden_attr(offsets.is_column_hidden(x)),
+                outline_attr(offsets.column_outline_level(x)),
+                n = column + 1,
+            ));
+        }
+
+        let mut rows: BTreeSet<i64> = self.cells.keys().copied().collect();
+        rows.extend(
+            custom_heights
+                .iter()
+                .map(|(y, _)| *y)
+                .chain(offsets.hidden_rows())
+                .filter(|&y| row_in_bounds(y)),
+        );
+        let mut rows_xml = String::new();
+        for y in rows {
+            let Some(row) = self.excel_row(y) else {
+                continue;
+            };
+            let height = offsets.unhidden_row_height(y);
+            let mut attrs = String::new();
+            if height != DEFAULT_ROW_HEIGHT {
+                attrs.push_str(&format!(
+                    r#" ht="{:.2}" customHeight="1""#,
+                    height * POINTS_PER_PIXEL
+                ));
+            }
+            attrs.push_str(hidden_attr(offsets.is_row_hidden(y)));
+            attrs.push_str(&outline_attr(offsets.row_outline_level(y)));
+
+            let cells: String = self
+                .cells
+                .get(&y)
+                .into_iter()
+                .flatten()
+                .filter_map(|&x| self.cell_xml(Pos { x, y }, sheets, styles))
+                .collect();
+            rows_xml.push_str(&format!(r#"<row r="{row}"{attrs}>{cells}</row>"#));
+        }
+
+        let merged: String = self
+            .sheet
+            .merged_cells
+            .iter()
+            .filter(|rect| {
+                column_in_bounds(rect.min.x)
+                    && column_in_bounds(rect.max.x)
+                    && row_in_bounds(rect.min.y)
+                    && row_in_bounds(rect.max.y)
+            })
+            .filter_map(|rect| {
+                Some(format!(
+                    r#"<mergeCell ref="{}:{}"/>"#,
+                    self.cell_name(rect.min)?,
+                    self.cell_name(rect.max)?
+                ))
+            })
+            .collect();
+
+        let mut xml = format!(
+            r#"{XML_DECLARATION}<worksheet xmlns="{SPREADSHEET_NS}" xmlns:r="{RELATIONSHIPS_NS}"><sheetFormatPr defaultColWidth="{:.2}" defaultRowHeight="{:.2}"/>"#,
+            (DEFAULT_COLUMN_WIDTH - COLUMN_PADDING) / CHARACTER_WIDTH,
+            DEFAULT_ROW_HEIGHT * POINTS_PER_PIXEL,
+        );
+        if !cols_xml.is_empty() {
+            xml.push_str(&format!("<cols>{cols_xml}</cols>"));
+        }
+        xml.push_str(&format!("<sheetData>{rows_xml}</sheetData>"));
+        if !merged.is_empty() {
+            xml.push_str(&format!("<mergeCells>{merged}</mergeCells>"));
+        }
+        xml.push_str("</worksheet>");
+        xml
+    }
+
+    /// Returns the XML for a cell, or `None` if Excel cannot hold it.
+    fn cell_xml(&self, pos: Pos, sheets: &[ExcelSheet<'_>], styles: &mut Styles) -> Option<String> {
+        let name = self.cell_name(pos)?;
+        let mut value = self.sheet.display_value(pos);
+
+        let mut formula = String::new();
+        if let Some(code_run) = self.sheet.code_runs.get(&pos) {
+            if code_run.spill_error {
+                value = Some(error_value(&RunErrorMsg::Spill));
+            } else if let CodeRunResult::Err(error) = &code_run.result {
+                value = Some(error_value(&error.msg));
+            }
+            if let Some(CellValue::Code(code)) = self.sheet.cell_value_ref(pos) {
+                if code.language == CodeCellLanguage::Formula {
+                    let excel_sheet = |sheet_name: Option<&str>| match sheet_name {
+                        None => Some((None, self.offset)),
+                        Some(sheet_name) => sheets
+                            .iter()
+                            .find(|sheet| sheet.sheet.name == sheet_name)
+                            .map(|sheet| (Some(sheet.name.clone()), sheet.offset)),
+                    };
+                    if let Some(excel_formula) = to_excel_formula(&code.code, pos, &excel_sheet) {
+                        let output = code_run.output_rect(pos, false);
+                        formula = if output.len() > 1 {
+                            format!(
+                                r#"<f t="array" ref="{name}:{}">{}</f>"#,
+                                self.cell_name(output.max)?,
+                                xml_escape(&excel_formula)
+                            )
+                        } else {
+                            format!("<f>{}</f>", xml_escape(&excel_formula))
+                        };
+                    }
+                }
+            }
+        }
+
+        let format = self.sheet.format_cell(pos.x, pos.y, true);
+        let borders = self
+            .sheet
+            .borders()
+            .per_cell
+            .borders
+            .get(&pos.x)
+            .and_then(|column| column.get(pos.y));
+        let (value_type, value_xml, number_format) = match value {
+            Some(CellValue::Number(n)) => {
+                let n = n.to_string().parse::<f64>().ok().filter(|n| n.is_finite());
+                (
+                    "",
+                    n.map(|n| format!("<v>{n}</v>")).unwrap_or_default(),
+                    number_format(&format),
+                )
+            }
+            Some(CellValue::Text(text)) if formula.is_empty() => (
+                r#" t="inlineStr""#,
+                format!(
+                    r#"<is><t xml:space="preserve">{}</t></is>"#,
+                    xml_escape(&text)
+                ),
+                None,
+            ),
+            Some(CellValue::Text(text)) => {
+                (r#" t="str""#, format!("<v>{}</v>", xml_escape(&text)), None)
+            }
+            Some(CellValue::Logical(b)) => (r#" t="b""#, format!("<v>{}</v>", b as u8), None),
+            Some(CellValue::Instant(instant)) => {
+                let days = instant.seconds / SECONDS_PER_DAY;
+                let number_format = if days.fract() == 0.0 {
+                    "yyyy-mm-dd"
+                } else {
+                    "yyyy-mm-dd hh:mm:ss"
+                };
+                (
+                    "",
+                    format!("<v>{}</v>", days + UNIX_EPOCH_SERIAL),
+                    Some(number_format.to_string()),
+                )
+            }
+            Some(CellValue::Duration(duration)) if duration.total_months() == Some(0) => (
+                "",
+                format!("<v>{}</v>", duration.seconds / SECONDS_PER_DAY),
+                Some("[h]:mm:ss".to_string()),
+            ),
+            Some(CellValue::Duration(duration)) => (
+                r#" t="inlineStr""#,
+                format!(r#"<is><t>{}</t></is>"#, xml_escape(&duration.to_string())),
+                None,
+            ),
+            Some(CellValue::Error(error)) => (
+                r#" t="e""#,
+                format!("<v>{}</v>", excel_error_code(&error.msg)),
+                None,
+            ),
+            Some(CellValue::Blank | CellValue::Html(_) | CellValue::Code(_)) | None => {
+                ("", String::new(), None)
+            }
+        };
+
+        let style = styles.cell_style(&format, borders.as_ref(), number_format);
+        let style = if style == 0 {
+            String::new()
+        } else {
+            format!(r#" s="{style}""#)
+        };
+        if formula.is_empty() && value_xml.is_empty() && style.is_empty() {
+            return None;
+        }
+        Some(format!(
+            r#"<c r="{name}"{style}{value_type}>{formula}{value_xml}</c>"#
+        ))
+    }
+}
+
+fn hidden_attr(hidden: bool) -> &'static str {
+    if hidden {
+        r#" hidden="1""#
+    } else {
+        ""
+    }
+}
+
+fn outline_attr(level: usize) -> String {
+    match level {
+        0 => String::new(),
+        level => format!(r#" outlineLevel="{}""#, level.min(MAX_OUTLINE_LEVEL)),
+    }
+}
+
+fn error_value(msg: &RunErrorMsg) -> CellValue {
+    CellValue::Error(Box::new(crate::RunError {
+        span: None,
+        msg: msg.clone(),
+    }))
+}
+
+/// Returns the Excel error closest to `msg`.
+fn excel_error_code(msg: &RunErrorMsg) -> &'static str {
+    match msg {
+        RunErrorMsg::DivideByZero => "#DIV/0!",
+        RunErrorMsg::BadCellReference | RunErrorMsg::Spill | RunErrorMsg::CircularReference => {
+            "#REF!"
+        }
+        RunErrorMsg::BadFunctionName => "#NAME?",
+        RunErrorMsg::NoMatch => "#N/A",
+        RunErrorMsg::Overflow
+        | RunErrorMsg::LambdaTooDeep
+        | RunErrorMsg::NotANumber
+        | RunErrorMsg::Infinity
+        | RunErrorMsg::NegativeExponent => "#NUM!",
+        _ => "#VALUE!",
+    }
+}
+
+/// Returns the Excel number format code for a cell's numeric format, if it
+/// has one.
+fn number_format(format: &Format) -> Option<String> {
+    let decimals = |default: i16| {
+        let decimals = format.numeric_decimals.unwrap_or(default).max(0) as usize;
+        if decimals == 0 {
+            String::new()
+        } else {
+            format!(".{}", "0".repeat(decimals))
+        }
+    };
+    let commas = format.numeric_commas == Some(true);
+    let integer = if commas { "#,##0" } else { "0" };
+    let numeric_format = format.numeric_format.as_ref();
+    match numeric_format.map(|numeric_format| numeric_format.kind) {
+        Some(NumericFormatKind::Currency) => {
+            let symbol = numeric_format
+                .and_then(|numeric_format| numeric_format.symbol.as_deref())
+                .unwrap_or("$");
+            let integer = if format.numeric_commas == Some(false) {
+                "0"
+            } else {
+                "#,##0"
+            };
+            Some(format!(
+                r#""{}"{integer}{}"#,
+                symbol.replace('"', ""),
+                decimals(2)
+            ))
+        }
+        Some(NumericFormatKind::Percentage) => Some(format!("{integer}{}%", decimals(0))),
+        Some(NumericFormatKind::Exponential) => Some(format!("0{}E+00", decimals(2))),
+        Some(NumericFormatKind::Number) | None => (format.numeric_decimals.is_some() || commas)
+            .then(|| format!("{integer}{}", decimals(0))),
+    }
+}
+
+/// Returns a color from `Format` as an Excel ARGB color.
+fn excel_color(color: &str) -> Option<String> {
+    let color = color.trim();
+    let rgba = if color.starts_with('#') {
+        if !color.is_ascii() || !matches!(color.len(), 7 | 9) {
+            return None;
+        }
+        Rgba::color_from_str(color).ok()?
+    } else if color.starts_with("rgb(") {
+        Rgba::from_css_str(color).ok()?
+    } else {
+        return None;
+    };
+    Some(rgba_to_argb(&rgba))
+}
+
+fn rgba_to_argb(rgba: &Rgba) -> String {
+    format!(
+        "{:02X}{:02X}{:02X}{:02X}",
+        rgba.alpha, rgba.red, rgba.green, rgba.blue
+    )
+}
+
+/// Escapes text for use in XML, dropping characters that XML cannot hold.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c < ' ' => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The workbook's stylesheet. Each distinct cell style is a "cell format"
+/// that refers to a font, fill, border and number format by index.
+struct Styles {
+    num_fmts: IndexSet<String>,
+    fonts: IndexSet<String>,
+    fills: IndexSet<String>,
+    borders: IndexSet<String>,
+    cell_xfs: IndexSet<String>,
+}
+
+impl Styles {
+    fn new() -> Self {
+        let font = |attrs: &str| {
+            format!(r#"<font>{attrs}<sz val="11"/><name val="Calibri"/><family val="2"/></font>"#)
+        };
+        Styles {
+            num_fmts: IndexSet::new(),
+            fonts: IndexSet::from([font("")]),
+            // Excel requires these two fills
+            fills: IndexSet::from([
+                r#"<fill><patternFill patternType="none"/></fill>"#.to_string(),
+                r#"<fill><patternFill patternType="gray125"/></fill>"#.to_string(),
+            ]),
+            borders: IndexSet::from([
+                "<border><left/><right/><top/><bottom/><diagonal/></border>".to_string()
+            ]),
+            cell_xfs: IndexSet::from([
+                r#"<xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/>"#.to_string(),
+            ]),
+        }
+    }
+
+    /// Returns the index of the style for a cell, adding it if needed.
+    fn cell_style(
+        &mut self,
+        format: &Format,
+        borders: Option<&CellBorders>,
+        number_format: Option<String>,
+    ) -> usize {
+        let num_fmt_id = number_format.map_or(0, |code| self.num_fmt_id(code));
+
+        let mut font = String::new();
+        if format.bold == Some(true) {
+            font.push_str("<b/>");
+        }
+        if format.italic == Some(true) {
+            font.push_str("<i/>");
+        }
+        font.push_str(r#"<sz val="11"/>"#);
+        if let Some(color) = format.text_color.as_deref().and_then(excel_color) {
+            font.push_str(&format!(r#"<color rgb="{color}"/>"#));
+        }
+        let font_id = self.fonts.insert_full(format!(
+            r#"<font>{font}<name val="Calibri"/><family val="2"/></font>"#
+        ));
+
+        let fill_id = match format.fill_color.as_deref().and_then(excel_color) {
+            Some(color) => self.fills.insert_full(format!(
+                r#"<fill><patternFill patternType="solid"><fgColor rgb="{color}"/><bgColor indexed="64"/></patternFill></fill>"#
+            )).0,
+            None => 0,
+        };
+
+        let border_id = match borders {
+            Some(borders) => {
+                // `CellBorders` are ordered left, top, right, bottom
+                let side = |name: &str, index: usize| match &borders.borders[index] {
+                    Some(style) => {
+                        let line = match style.line {
+                            CellBorderLine::Line1 => "thin",
+                            CellBorderLine::Line2 => "medium",
+                            CellBorderLine::Line3 => "thick",
+                            CellBorderLine::Dotted => "dotted",
+                            CellBorderLine::Dashed => "dashed",
+                            CellBorderLine::Double => "double",
+                        };
+                        format!(
+                            r#"<{name} style="{line}"><color rgb="{}"/></{name}>"#,
+                            rgba_to_argb(&style.color)
+                        )
+                    }
+                    None => format!("<{name}/>"),
+                };
+                let border = format!(
+                    "<border>{}{}{}{}<diagonal/></border>",
+                    side("left", 0),
+                    side("right", 2),
+                    side("top", 1),
+                    side("bottom", 3)
+                );
+                self.borders.insert_full(border).0
+            }
+            None => 0,
+        };
+
+        let mut alignment = String::new();
+        if let Some(align) = format.align {
+            let horizontal = match align {
+                CellAlign::Left => "left",
+                CellAlign::Center => "center",
+                CellAlign::Right => "right",
+            };
+            alignment.push_str(&format!(r#" horizontal="{horizontal}""#));
+        }
+        if format.wrap == Some(CellWrap::Wrap) {
+            alignment.push_str(r#" wrapText="1""#);
+        }
+
+        let mut xf = format!(
+            r#"<xf numFmtId="{num_fmt_id}" fontId="{}" fillId="{fill_id}" borderId="{border_id}" xfId="0""#,
+            font_id.0
+        );
+        for (applies, attr) in [
+            (num_fmt_id != 0, "applyNumberFormat"),
+            (font_id.0 != 0, "applyFont"),
+            (fill_id != 0, "applyFill"),
+            (border_id != 0, "applyBorder"),
+            (!alignment.is_empty(), "applyAlignment"),
+        ] {
+            if applies {
+                xf.push_str(&format!(r#" {attr}="1""#));
+            }
+        }
+        if alignment.is_empty() {
+            xf.push_str("/>");
+        } else {
+            xf.push_str(&format!("><alignment{alignment}/></xf>"));
+        }
+        self.cell_xfs.insert_full(xf).0
+    }
+
+    /// Returns the ID of a number format, adding it if it is not built into
+    /// Excel.
+    fn num_fmt_id(&mut self, code: String) -> usize {
+        match code.as_str() {
+            "0" => 1,
+            "0.00" => 2,
+            "#,##0" => 3,
+            "#,##0.00" => 4,
+            "0%" => 9,
+            "0.00%" => 10,
+            "0.00E+00" => 11,
+            _ => FIRST_CUSTOM_NUM_FMT_ID + self.num_fmts.insert_full(code).0,
+        }
+    }
+
+    fn xml(&self) -> String {
+        let list = |name: &str, items: &IndexSet<String>| {
+            format!(
+                r#"<{name} count="{}">{}</{name}>"#,
+                items.len(),
+                items.iter().map(String::as_str).collect::<String>()
+            )
+        };
+        let num_fmts: IndexSet<String> = self
+            .num_fmts
+            .iter()
+            .enumerate()
+            .map(|(i, code)| {
+                format!(
+                    r#"<numFmt numFmtId="{}" formatCode="{}"/>"#,
+                    FIRST_CUSTOM_NUM_FMT_ID + i,
+                    xml_escape(code)
+                )
+            })
+            .collect();
+        let num_fmts = if num_fmts.is_empty() {
+            String::new()
+        } else {
+            list("numFmts", &num_fmts)
+        };
+        format!(
+            r#"{XML_DECLARATION}<styleSheet xmlns="{SPREADSHEET_NS}">{num_fmts}{}{}{}<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>{}<cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#,
+            list("fonts", &self.fonts),
+            list("fills", &self.fills),
+            list("borders", &self.borders),
+            list("cellXfs", &self.cell_xfs),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use calamine::{Data, Reader, Xlsx};
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::{BorderSelection, BorderStyle, SheetId},
+        SheetPos, SheetRect,
+    };
+
+    fn read_part(xlsx: &[u8], path: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(xlsx)).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name(path)
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    fn set_value(gc: &mut GridController, sheet_id: SheetId, x: i64, y: i64, value: &str) {
+        gc.set_cell_value(SheetPos { x, y, sheet_id }, value.into(), None);
+    }
+
+    fn set_formula(gc: &mut GridController, sheet_id: SheetId, x: i64, y: i64, code: &str) {
+        gc.set_code_cell(
+            SheetPos { x, y, sheet_id },
+            CodeCellLanguage::Formula,
+            code.into(),
+            None,
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_export_xlsx() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        set_value(&mut gc, sheet_id, 0, 0, "1");
+        set_value(&mut gc, sheet_id, 0, 1, "hello");
+        set_formula(&mut gc, sheet_id, 1, 0, "A0 + 1");
+        set_formula(&mut gc, sheet_id, 1, 1, "SPLIT(A1, \"l\")");
+        set_formula(&mut gc, sheet_id, 3, 0, "{1, 2; 3, 4}");
+        let cell = SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id);
+        gc.set_cell_bold(cell, Some(true), None);
+        gc.set_currency(&cell, Some("$".into()), None);
+        gc.set_borders(
+            cell,
+            vec![BorderSelection::All],
+            Some(BorderStyle {
+                color: Rgba::default(),
+                line: CellBorderLine::Line1,
+            }),
+            None,
+        );
+        gc.set_cell_fill_color(
+            SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id),
+            Some("#ff0000".into()),
+            None,
+        );
+        gc.commit_single_resize(sheet_id, Some(0), None, 200.0, None);
+
+        gc.add_sheet(None);
+        let sheet_id_2 = gc.sheet_ids()[1];
+        set_formula(&mut gc, sheet_id_2, 0, 0, "'Sheet 1'!A0 * 2");
+
+        let xlsx = gc.export_xlsx().unwrap();
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(xlsx.clone())).unwrap();
+        assert_eq!(workbook.sheet_names(), vec!["Sheet 1", "Sheet 2"]);
+
+        // row 0 is the first row in Excel
+        let values = workbook.worksheet_range("Sheet 1").unwrap();
+        assert_eq!(values.get_value((0, 0)), Some(&Data::Float(1.0)));
+        assert_eq!(
+            values.get_value((1, 0)),
+            Some(&Data::String("hello".into()))
+        );
+        assert_eq!(values.get_value((0, 1)), Some(&Data::Float(2.0)));
+        assert_eq!(values.get_value((1, 1)), Some(&Data::String("he".into())));
+        assert_eq!(values.get_value((1, 4)), Some(&Data::Float(4.0)));
+
+        let formulas = workbook.worksheet_formula("Sheet 1").unwrap();
+        assert_eq!(formulas.get_value((0, 1)), Some(&"A1 + 1".to_string()));
+        assert_eq!(
+            formulas.get_value((0, 3)),
+            Some(&"{1, 2; 3, 4}".to_string())
+        );
+        let formulas = workbook.worksheet_formula("Sheet 2").unwrap();
+        assert_eq!(
+            formulas.get_value((0, 0)),
+            Some(&"'Sheet 1'!A1 * 2".to_string())
+        );
+
+        let worksheet = read_part(&xlsx, "xl/worksheets/sheet1.xml");
+        // Excel has no SPLIT, so only the values are kept
+        assert!(!worksheet.contains("SPLIT"));
+        assert!(worksheet.contains(r#"<col min="1" max="1" width="27.86" customWidth="1"/>"#));
+        assert!(worksheet.contains(r#"<f t="array" ref="D1:E2">"#));
+
+        let styles = read_part(&xlsx, "xl/styles.xml");
+        assert!(styles.contains(r#"formatCode="&quot;$&quot;#,##0.00""#));
+        assert!(styles.contains("<b/>"));
+        assert!(styles.contains(r#"<left style="thin"><color rgb="FF000000"/></left>"#));
+        assert!(styles.contains(r#"<fgColor rgb="FFFF0000"/>"#));
+    }
+
+    #[test]
+    #[serial]
+    fn test_export_xlsx_selection() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        set_value(&mut gc, sheet_id, -2, -1, "a");
+        set_value(&mut gc, sheet_id, -1, -1, "b");
+        set_value(&mut gc, sheet_id, -2, 0, "not exported");
+        set_formula(&mut gc, sheet_id, -1, 0, "nAn1 & \"c\"");
+        set_value(&mut gc, sheet_id, 5, 5, "not exported");
+
+        let selection = Selection {
+            rects: Some(vec![Rect::new(-2, -1, -1, -1), Rect::new(-1, 0, -1, 0)]),
+            ..Selection::rect(Rect::new(-2, -1, -1, -1), sheet_id)
+        };
+        let xlsx = gc.export_xlsx_selection(&selection).unwrap();
+        let mut workbook: Xlsx<_> = Xlsx::new(Cursor::new(xlsx)).unwrap();
+        let values = workbook.worksheet_range("Sheet 1").unwrap();
+        assert_eq!(values.get_size(), (2, 2));
+        assert_eq!(values.get_value((0, 0)), Some(&Data::String("a".into())));
+        assert_eq!(values.get_value((1, 0)), Some(&Data::Empty));
+        assert_eq!(values.get_value((1, 1)), Some(&Data::String("bc".into())));
+
+        // references move with the cells
+        let formulas = workbook.worksheet_formula("Sheet 1").unwrap();
+        assert_eq!(formulas.get_value((1, 1)), Some(&"B1 & \"c\"".to_string()));
+    }
+
+    #[test]
+    fn test_excel_sheet_names() {
+        let long = "x".repeat(40);
+        assert_eq!(
+            excel_sheet_names(["a/b", "A_B", "'quoted'", "", &long, &long].into_iter()),
+            vec![
+                "a_b".to_string(),
+                "A_B (2)".into(),
+                "quoted".into(),
+                "Sheet".into(),
+                "x".repeat(31),
+                format!("{} (2)", "x".repeat(27)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_number_format() {
+        let format = |kind: Option<NumericFormatKind>, decimals, commas| Format {
+            numeric_format: kind.map(|kind| crate::grid::NumericFormat { kind, symbol: None }),
+            numeric_decimals: decimals,
+            numeric_commas: commas,
+            ..Default::default()
+        };
+        assert_eq!(number_format(&format(None, None, None)), None);
+        assert_eq!(
+            number_format(&format(None, Some(2), Some(true))),
+            Some("#,##0.00".into())
+        );
+        assert_eq!(
+            number_format(&format(Some(NumericFormatKind::Currency), Some(0), None)),
+            Some(r##""$"#,##0"##.into())
+        );
+        assert_eq!(
+            number_format(&format(Some(NumericFormatKind::Percentage), Some(1), None)),
+            Some("0.0%".into())
+        );
+        assert_eq!(
+            number_format(&format(Some(NumericFormatKind::Exponential), None, None)),
+            Some("0.00E+00".into())
+        );
+    }
+}
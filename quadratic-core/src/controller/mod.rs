@@ -2,6 +2,7 @@ use self::{active_transactions::ActiveTransactions, transaction::Transaction};
 use crate::grid::Grid;
 use wasm_bindgen::prelude::*;
 pub mod active_transactions;
+pub mod conditional_formats;
 pub mod dependencies;
 pub mod execution;
 pub mod export;
@@ -15,6 +16,7 @@ pub mod transaction;
 pub mod transaction_summary;
 pub mod transaction_types;
 pub mod user_actions;
+pub mod validations;
 
 #[derive(Debug, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "js", wasm_bindgen)]
@@ -29,19 +31,23 @@ pub struct GridController {
 
 impl GridController {
     pub fn from_grid(grid: Grid, last_sequence_num: u64) -> Self {
-        GridController {
+        let mut gc = GridController {
             grid,
             transactions: ActiveTransactions::new(last_sequence_num),
             ..Default::default()
-        }
+        };
+        gc.evaluate_all_conditional_format_formulas();
+        gc
     }
 
     pub fn upgrade_grid(grid: Grid, last_sequence_num: u64) -> Self {
-        GridController {
+        let mut gc = GridController {
             grid,
             transactions: ActiveTransactions::new(last_sequence_num),
             ..Default::default()
-        }
+        };
+        gc.evaluate_all_conditional_format_formulas();
+        gc
     }
 
     pub fn grid(&self) -> &Grid {
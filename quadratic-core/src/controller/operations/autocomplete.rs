@@ -37,6 +37,7 @@ impl GridController {
         selection: Rect,
         range: Rect,
     ) -> Result<Vec<Operation>> {
+        let initial_selection = selection;
         let mut selection = selection;
         let mut operations = vec![];
         let mut initial_down_range: Option<Rect> = None;
@@ -146,9 +147,60 @@ impl GridController {
             operations.extend(ops);
         }
 
+        operations.extend(self.autocomplete_merged_cells(sheet_id, &initial_selection, &range));
+
         Ok(operations)
     }
 
+    /// Repeats the merged regions in the selection across range, the same way
+    /// the values and formats are repeated. Any other merged regions in either
+    /// are unmerged.
+    fn autocomplete_merged_cells(
+        &self,
+        sheet_id: SheetId,
+        selection: &Rect,
+        range: &Rect,
+    ) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return vec![];
+        };
+        let repeated = sheet
+            .merged_rects_within(*selection)
+            .filter(|merged| range.contains_rect(*merged))
+            .collect::<Vec<_>>();
+        let mut ops = sheet
+            .merged_rects_intersecting(selection.union(range))
+            .filter(|merged| !repeated.contains(merged))
+            .map(|merged| Operation::UnmergeCells {
+                sheet_rect: merged.to_sheet_rect(sheet_id),
+            })
+            .collect::<Vec<_>>();
+
+        // the number of whole copies of a merged region that fit in range
+        // before and after it
+        let copies = |min: i64, max: i64, range_min: i64, range_max: i64, step: i64| {
+            let first = -(min - range_min).div_euclid(step);
+            let last = (range_max - max).div_euclid(step);
+            first..=last
+        };
+        let (w, h) = (selection.width() as i64, selection.height() as i64);
+        for merged in repeated {
+            for i in copies(merged.min.x, merged.max.x, range.min.x, range.max.x, w) {
+                for j in copies(merged.min.y, merged.max.y, range.min.y, range.max.y, h) {
+                    if i == 0 && j == 0 {
+                        continue;
+                    }
+                    let mut copy = merged;
+                    copy.translate(i * w, j * h);
+                    ops.push(Operation::MergeCells {
+                        sheet_rect: copy.to_sheet_rect(sheet_id),
+                    });
+                }
+            }
+        }
+        ops
+    }
+
     /// Delete cell values and formats in a given range.
     fn shrink(&mut self, delete_range: SheetRect) -> Vec<Operation> {
         let mut ops = vec![];
@@ -16,7 +16,7 @@ const MAX_BIG_DECIMAL_SIZE: usize = 10000000;
 
 impl GridController {
     /// Convert string to a cell_value and generate necessary operations
-    pub(super) fn string_to_cell_value(
+    pub(crate) fn string_to_cell_value(
         &mut self,
         sheet_pos: SheetPos,
         value: &str,
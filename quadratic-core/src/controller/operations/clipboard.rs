@@ -7,10 +7,12 @@ use crate::{
     formulas::replace_internal_cell_references,
     grid::{
         formats::{format::Format, Formats},
-        generate_borders_full, BorderSelection, CellBorders, CodeCellLanguage,
+        generate_borders_full,
+        sheet::comments::CommentThread,
+        BorderSelection, CellBorders, CodeCellLanguage,
     },
     selection::Selection,
-    CellValue, Pos, SheetPos, SheetRect,
+    CellValue, Pos, Rect, SheetPos, SheetRect,
 };
 use anyhow::{Error, Result};
 use regex::Regex;
@@ -56,6 +58,14 @@ pub struct Clipboard {
     pub sheet_formats: ClipboardSheetFormats,
     pub borders: Vec<(i64, i64, Option<CellBorders>)>,
 
+    // merged regions relative to the origin
+    #[serde(default)]
+    pub merged_cells: Vec<Rect>,
+
+    // comment threads relative to the origin
+    #[serde(default)]
+    pub comments: Vec<(Pos, CommentThread)>,
+
     pub origin: ClipboardOrigin,
     pub selection: Option<Selection>,
 }
@@ -70,7 +80,31 @@ impl GridController {
             .ok_or("Unable to find Sheet")?;
 
         let (plain_text, html) = sheet.copy_to_clipboard(selection)?;
-        let operations = self.delete_values_and_formatting_operations(selection);
+        let mut operations = vec![];
+        let mut comment_ops = vec![];
+        if let Some(bounds) = sheet.selection_bounds(selection) {
+            operations.extend(
+                sheet
+                    .merged_rects_within(bounds)
+                    .filter(|merged| selection.pos_in_selection(merged.min))
+                    .map(|merged| Operation::UnmergeCells {
+                        sheet_rect: merged.to_sheet_rect(sheet.id),
+                    }),
+            );
+
+            // only the comment threads that were copied are removed
+            comment_ops.extend(
+                sheet
+                    .comment_threads_in_rect(bounds)
+                    .filter(|(pos, _)| selection.pos_in_selection(*pos))
+                    .map(|(pos, _)| Operation::SetCommentThread {
+                        sheet_pos: pos.to_sheet_pos(sheet.id),
+                        thread: None,
+                    }),
+            );
+        }
+        operations.extend(self.delete_values_and_formatting_operations(selection));
+        operations.extend(comment_ops);
         Ok((operations, plain_text, html))
     }
 
@@ -261,6 +295,29 @@ impl GridController {
                 formats,
             });
 
+            // replace any merged regions under the pasted cells
+            ops.extend(self.unmerge_cells_operations(sheet_rect));
+            ops.extend(clipboard.merged_cells.iter().map(|merged| {
+                let mut merged = *merged;
+                merged.translate(start_pos.x, start_pos.y);
+                Operation::MergeCells {
+                    sheet_rect: merged.to_sheet_rect(selection.sheet_id),
+                }
+            }));
+
+            // comments under the pasted cells are replaced
+            ops.extend(self.delete_comment_threads_operations(&Selection::sheet_rect(sheet_rect)));
+            ops.extend(clipboard.comments.iter().map(|(pos, thread)| {
+                Operation::SetCommentThread {
+                    sheet_pos: SheetPos {
+                        x: start_pos.x + pos.x,
+                        y: start_pos.y + pos.y,
+                        sheet_id: selection.sheet_id,
+                    },
+                    thread: Some(thread.clone()),
+                }
+            }));
+
             ops.extend(self.sheet_formats_operations(selection, &clipboard));
 
             if let Some(sheet) = self.try_sheet(selection.sheet_id) {
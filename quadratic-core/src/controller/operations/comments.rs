@@ -0,0 +1,65 @@
+use uuid::Uuid;
+
+use super::operation::Operation;
+use crate::{
+    controller::GridController, grid::sheet::comments::Comment, selection::Selection, SheetPos,
+};
+
+impl GridController {
+    /// Returns the operations to add a comment to a cell, either starting a
+    /// thread or replying to the existing one.
+    pub fn add_comment_operations(&self, sheet_pos: SheetPos, comment: Comment) -> Vec<Operation> {
+        vec![Operation::AddComment { sheet_pos, comment }]
+    }
+
+    pub fn edit_comment_operations(
+        &self,
+        sheet_pos: SheetPos,
+        comment_id: Uuid,
+        text: String,
+    ) -> Vec<Operation> {
+        vec![Operation::EditComment {
+            sheet_pos,
+            comment_id,
+            text,
+        }]
+    }
+
+    pub fn resolve_comments_operations(
+        &self,
+        sheet_pos: SheetPos,
+        resolved: bool,
+    ) -> Vec<Operation> {
+        vec![Operation::ResolveComments {
+            sheet_pos,
+            resolved,
+        }]
+    }
+
+    pub fn delete_comment_operations(
+        &self,
+        sheet_pos: SheetPos,
+        comment_id: Uuid,
+    ) -> Vec<Operation> {
+        vec![Operation::DeleteComment {
+            sheet_pos,
+            comment_id,
+        }]
+    }
+
+    /// Returns the operations to remove every comment thread in a selection.
+    pub fn delete_comment_threads_operations(&self, selection: &Selection) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(selection.sheet_id) else {
+            return vec![];
+        };
+        sheet
+            .comments
+            .keys()
+            .filter(|pos| selection.pos_in_selection(**pos))
+            .map(|pos| Operation::SetCommentThread {
+                sheet_pos: pos.to_sheet_pos(sheet.id),
+                thread: None,
+            })
+            .collect()
+    }
+}
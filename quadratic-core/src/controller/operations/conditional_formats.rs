@@ -0,0 +1,42 @@
+use uuid::Uuid;
+
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    formulas::parse_formula,
+    grid::{
+        sheet::conditional_formats::{ConditionalFormat, ConditionalFormatRule},
+        SheetId,
+    },
+};
+
+impl GridController {
+    /// Returns the operations to add a conditional format, or to replace the
+    /// format with the same id. Formulas that do not parse are ignored.
+    pub fn add_conditional_format_operations(
+        &self,
+        conditional_format: ConditionalFormat,
+    ) -> Vec<Operation> {
+        if let ConditionalFormatRule::Formula { formula } = &conditional_format.rule {
+            if parse_formula(formula, conditional_format.anchor()).is_err() {
+                return vec![];
+            }
+        }
+        vec![Operation::SetConditionalFormat {
+            sheet_id: conditional_format.selection.sheet_id,
+            conditional_format,
+            index: None,
+        }]
+    }
+
+    pub fn remove_conditional_format_operations(
+        &self,
+        sheet_id: SheetId,
+        conditional_format_id: Uuid,
+    ) -> Vec<Operation> {
+        vec![Operation::RemoveConditionalFormat {
+            sheet_id,
+            conditional_format_id,
+        }]
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    formulas::{is_valid_name, parse_formula},
+    grid::defined_names::NameValue,
+    Pos,
+};
+
+impl GridController {
+    /// Returns the operations to define `name` for the whole file, or to
+    /// remove it if `value` is `None`. Any existing name that differs only by
+    /// case is replaced.
+    pub fn set_defined_name_operations(
+        &self,
+        name: String,
+        value: Option<NameValue>,
+    ) -> Result<Vec<Operation>> {
+        if !is_valid_name(&name) {
+            bail!("\"{name}\" is not a valid name");
+        }
+        match &value {
+            Some(NameValue::Range(sheet_rect)) => {
+                self.try_sheet(sheet_rect.sheet_id)
+                    .ok_or_else(|| anyhow!("Sheet not found"))?;
+            }
+            Some(NameValue::Formula(formula)) => {
+                if let Err(e) = parse_formula(formula, Pos::ORIGIN) {
+                    bail!("Invalid formula for \"{name}\": {}", e.msg);
+                }
+            }
+            Some(NameValue::Constant(_)) => (),
+            None => {
+                if self.grid.defined_name(&name).is_none() {
+                    return Ok(vec![]);
+                }
+            }
+        }
+        Ok(vec![Operation::SetDefinedName { name, value }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{grid::SheetId, SheetRect};
+
+    #[test]
+    fn test_set_defined_name_operations() {
+        let gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let range = Some(NameValue::Range(SheetRect::from_numbers(
+            0, 0, 1, 1, sheet_id,
+        )));
+
+        let ops = gc
+            .set_defined_name_operations("Sales".into(), range.clone())
+            .unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::SetDefinedName {
+                name: "Sales".into(),
+                value: range.clone()
+            }]
+        );
+
+        assert!(gc
+            .set_defined_name_operations("A1".into(), range.clone())
+            .is_err());
+        assert!(gc
+            .set_defined_name_operations(
+                "Sales".into(),
+                Some(NameValue::Range(SheetRect::from_numbers(
+                    0,
+                    0,
+                    1,
+                    1,
+                    SheetId::new()
+                )))
+            )
+            .is_err());
+        assert!(gc
+            .set_defined_name_operations("Sales".into(), Some(NameValue::Formula("SUM(".into())))
+            .is_err());
+
+        // removing a name that does not exist does nothing
+        assert!(gc
+            .set_defined_name_operations("Sales".into(), None)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -1,20 +1,42 @@
-use std::io::Cursor;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    io::Cursor,
+};
 
 use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use itertools::Itertools;
 use lexicon_fractional_index::key_between;
 
 use crate::{
     cell_values::CellValues,
     controller::GridController,
-    grid::{file::sheet_schema::export_sheet, Sheet, SheetId},
-    CellValue, Pos, SheetPos,
+    formulas::{
+        from_excel_formula, parse_formula, replace_a1_notation, replace_internal_cell_references,
+        Ctx,
+    },
+    grid::{
+        file::sheet_schema::export_sheet, formatting::CellFmtArray, generate_borders,
+        js_types::JsUnsupportedExcelFeatures, set_rect_borders, BorderSelection, CodeCellLanguage,
+        CodeRun, CodeRunResult, Grid, Sheet, SheetId,
+    },
+    util::column_name,
+    ArraySize, CellValue, CodeCellValue, Duration, Pos, Rect, RunError, RunErrorMsg,
+    RunLengthEncoding, SheetPos, SheetRect,
 };
 use bytes::Bytes;
-use calamine::{Data as ExcelData, Reader as ExcelReader, Xlsx, XlsxError};
+use calamine::{CellErrorType, Data as ExcelData, Reader as ExcelReader, Xlsx, XlsxError};
+use excel::ExcelFile;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 
 use super::operation::Operation;
 
+pub use csv_options::{CsvEncoding, CsvImportOptions, CsvPreview};
+
+mod csv_options;
+mod excel;
+mod json;
+
 const IMPORT_LINES_PER_OPERATION: u32 = 10000;
 
 impl GridController {
@@ -25,33 +47,16 @@ impl GridController {
         file: &[u8],
         file_name: &str,
         insert_at: Pos,
+        options: &CsvImportOptions,
     ) -> Result<Vec<Operation>> {
         let error = |message: String| anyhow!("Error parsing CSV file {}: {}", file_name, message);
-        let file = match String::from_utf8_lossy(file) {
-            std::borrow::Cow::Borrowed(_) => file,
-            std::borrow::Cow::Owned(_) => {
-                if let Some(utf) = read_utf16(file) {
-                    return self.import_csv_operations(
-                        sheet_id,
-                        utf.as_bytes(),
-                        file_name,
-                        insert_at,
-                    );
-                }
-                file
-            }
-        };
+        let csv = options.decode(file).map_err(|e| error(e.to_string()))?;
+        let skipped_rows = options.skip_rows.unwrap_or(0);
 
         // first get the total number of lines so we can provide progress
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(false)
-            .from_reader(file);
-        let height = reader.records().count() as u32;
+        let height = csv.reader().records().count() as u32;
 
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(false)
-            .flexible(true)
-            .from_reader(file);
+        let mut reader = csv.reader();
 
         let width = reader.headers()?.len() as u32;
         if width == 0 {
@@ -65,16 +70,23 @@ impl GridController {
         let mut y: u32 = 0;
         for entry in reader.records() {
             match entry {
-                Err(e) => return Err(error(format!("line {}: {}", current_y + y + 1, e))),
+                Err(e) => {
+                    return Err(error(format!(
+                        "line {}: {}",
+                        skipped_rows + current_y + y + 1,
+                        e
+                    )))
+                }
                 Ok(record) => {
                     for (x, value) in record.iter().enumerate() {
-                        let (operations, cell_value) = self.string_to_cell_value(
+                        let (operations, cell_value) = self.csv_value_to_cell_value(
                             SheetPos {
                                 x: insert_at.x + x as i64,
                                 y: insert_at.y + current_y as i64 + y as i64,
                                 sheet_id,
                             },
                             value,
+                            options,
                         );
                         ops.extend(operations);
                         cell_values.set(x as u32, y, cell_value);
@@ -120,25 +132,49 @@ impl GridController {
             },
             values: cell_values,
         });
+
+        if options.header_row.unwrap_or(false) {
+            ops.push(Operation::SetCellFormats {
+                sheet_rect: SheetRect::from_numbers(
+                    insert_at.x,
+                    insert_at.y,
+                    width as i64,
+                    1,
+                    sheet_id,
+                ),
+                attr: CellFmtArray::Bold(RunLengthEncoding::repeat(Some(true), width as usize)),
+            });
+        }
         Ok(ops)
     }
 
-    /// Imports an Excel file into the grid.
+    /// Imports an Excel file into the grid. Also returns, for each sheet that
+    /// uses features that could not be imported, a description of them.
     pub fn import_excel_operations(
         &mut self,
         file: Vec<u8>,
         file_name: &str,
-    ) -> Result<Vec<Operation>> {
-        let mut ops = vec![] as Vec<Operation>;
+    ) -> Result<(Vec<Operation>, Vec<JsUnsupportedExcelFeatures>)> {
         let insert_at = Pos::default();
+        // Excel rows are counted from 1
+        let offset = Pos {
+            x: insert_at.x,
+            y: insert_at.y - 1,
+        };
         let error =
             |message: String| anyhow!("Error parsing Excel file {}: {}", file_name, message);
 
-        let cursor = Cursor::new(file);
+        let cursor = Cursor::new(file.as_slice());
         let mut workbook: Xlsx<_> =
             ExcelReader::new(cursor).map_err(|e: XlsxError| error(e.to_string()))?;
+        let mut excel_file = ExcelFile::open(&file).map_err(|e| error(e.to_string()))?;
         let sheets = workbook.sheet_names().to_owned();
 
+        // the sheets are built in a grid so that their formulas can be run
+        let mut grid = Grid::new_blank();
+        let mut formulas = vec![];
+        let mut unsupported = vec![];
+
         let mut order = key_between(&None, &None).unwrap_or("A0".to_string());
         for sheet_name in sheets {
             // add the sheet
@@ -148,55 +184,161 @@ impl GridController {
             let range = workbook
                 .worksheet_range(&sheet_name)
                 .map_err(|e: XlsxError| error(e.to_string()))?;
-
+            let excel_sheet = excel_file
+                .sheet(&sheet_name)
+                .map_err(|e| error(e.to_string()))?
+                .unwrap_or_default();
+            let mut features: Vec<String> = excel_sheet
+                .unsupported
+                .iter()
+                .map(|feature| feature.to_string())
+                .collect();
+
+            // the range starts at the first cell with a value
+            let (start_y, start_x) = range.start().unwrap_or_default();
             for (y, row) in range.rows().enumerate() {
                 for (x, col) in row.iter().enumerate() {
-                    let cell_value = match col {
-                        ExcelData::Empty => CellValue::Blank,
-                        ExcelData::String(value) => CellValue::Text(value.to_string()),
-                        ExcelData::DateTimeIso(ref value) => CellValue::Text(value.to_string()),
-                        ExcelData::DurationIso(ref value) => CellValue::Text(value.to_string()),
-                        ExcelData::Float(ref value) => {
-                            CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
-                        }
-                        // TODO(ddimaria): implement when implementing Instant
-                        // ExcelData::DateTime(ref value) => match value.is_datetime() {
-                        //     true => value.as_datetime().map_or_else(
-                        //         || CellValue::Blank,
-                        //         |v| CellValue::Instant(v.into()),
-                        //     ),
-                        //     false => CellValue::Text(value.to_string()),
-                        // },
-                        // TODO(ddimaria): remove when implementing Instant
-                        ExcelData::DateTime(ref value) => match value.is_datetime() {
-                            true => value.as_datetime().map_or_else(
-                                || CellValue::Blank,
-                                |v| CellValue::Text(v.to_string()),
-                            ),
-                            false => CellValue::Text(value.to_string()),
-                        },
-                        ExcelData::Int(ref value) => {
-                            CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
-                        }
-                        ExcelData::Error(_) => CellValue::Blank,
-                        ExcelData::Bool(value) => CellValue::Logical(*value),
-                    };
-
                     sheet.set_cell_value(
                         Pos {
-                            x: insert_at.x + x as i64,
-                            y: insert_at.y + y as i64,
+                            x: insert_at.x + start_x as i64 + x as i64,
+                            y: insert_at.y + start_y as i64 + y as i64,
                         },
-                        cell_value,
+                        excel_cell_value(col),
                     );
                 }
             }
-            ops.push(Operation::AddSheetSchema {
-                schema: export_sheet(&sheet),
-            });
+
+            // shared formulas are kept in internal notation, so they can be
+            // written for each cell that shares them
+            let mut shared_formulas: HashMap<u32, String> = HashMap::new();
+            let mut unconverted = vec![];
+            for cell in excel_sheet.cells {
+                let pos = Pos {
+                    x: cell.pos.x + offset.x,
+                    y: cell.pos.y + offset.y,
+                };
+                if let Some(style) = excel_file.styles.get(cell.style) {
+                    sheet.set_format_cell(pos, &style.format, false);
+                    let rect = Rect::single_pos(pos);
+                    let sides = [
+                        BorderSelection::Left,
+                        BorderSelection::Top,
+                        BorderSelection::Right,
+                        BorderSelection::Bottom,
+                    ];
+                    for (side, border) in sides.into_iter().zip(style.borders) {
+                        if let Some(border) = border {
+                            let borders = generate_borders(&sheet, &rect, vec![side], Some(border));
+                            set_rect_borders(&mut sheet, &rect, borders);
+                        }
+                    }
+                }
+
+                let Some(formula) = cell.formula else {
+                    continue;
+                };
+                let code = match formula.shared_index {
+                    Some(index) if formula.text.is_empty() => shared_formulas
+                        .get(&index)
+                        .map(|internal| replace_internal_cell_references(internal, pos)),
+                    shared_index => {
+                        let code = from_excel_formula(&formula.text, cell.pos, offset);
+                        if let (Some(index), Some(code)) = (shared_index, &code) {
+                            shared_formulas.insert(index, replace_a1_notation(code, pos));
+                        }
+                        code
+                    }
+                };
+                let Some(code) = code else {
+                    // the cached value is kept
+                    unconverted.push(cell.pos);
+                    continue;
+                };
+                if let Some(array) = formula.array {
+                    // the values Excel saved for the output are replaced
+                    // when the formula runs
+                    for output_pos in array.iter().filter(|output_pos| *output_pos != cell.pos) {
+                        sheet.set_cell_value(
+                            Pos {
+                                x: output_pos.x + offset.x,
+                                y: output_pos.y + offset.y,
+                            },
+                            CellValue::Blank,
+                        );
+                    }
+                }
+                sheet.set_cell_value(
+                    pos,
+                    CellValue::Code(CodeCellValue {
+                        language: CodeCellLanguage::Formula,
+                        code,
+                    }),
+                );
+                formulas.push(pos.to_sheet_pos(sheet.id));
+            }
+            if !unconverted.is_empty() {
+                features.push(unconverted_formulas_feature(&unconverted));
+            }
+
+            // Excel lists widths for ranges of columns that may go to the
+            // last column, so they stop at the last column that is used
+            let last_column = range
+                .end()
+                .map(|(_, x)| x as i64)
+                .into_iter()
+                .chain(excel_sheet.columns.iter().map(|column| column.min))
+                .max()
+                .unwrap_or_default();
+            for column in excel_sheet.columns {
+                for x in column.min..=column.max.min(last_column) {
+                    if let Some(width) = column.width {
+                        sheet.offsets.set_column_width(x + offset.x, width);
+                    }
+                    if column.hidden {
+                        sheet.offsets.set_column_hidden(x + offset.x, true);
+                    }
+                }
+            }
+            for row in excel_sheet.rows {
+                if let Some(height) = row.height {
+                    sheet.offsets.set_row_height(row.y + offset.y, height);
+                }
+                if row.hidden {
+                    sheet.offsets.set_row_hidden(row.y + offset.y, true);
+                }
+            }
+            for rect in excel_sheet.merged_cells {
+                sheet.merge_cells(Rect::new_span(
+                    Pos {
+                        x: rect.min.x + offset.x,
+                        y: rect.min.y + offset.y,
+                    },
+                    Pos {
+                        x: rect.max.x + offset.x,
+                        y: rect.max.y + offset.y,
+                    },
+                ));
+            }
+
+            if !features.is_empty() {
+                unsupported.push(JsUnsupportedExcelFeatures {
+                    sheet_name: sheet.name.clone(),
+                    features,
+                });
+            }
+            grid.add_sheet(Some(sheet));
         }
 
-        Ok(ops)
+        run_imported_formulas(&mut grid, &formulas);
+
+        let ops = grid
+            .sheets()
+            .iter()
+            .map(|sheet| Operation::AddSheetSchema {
+                schema: export_sheet(sheet),
+            })
+            .collect();
+        Ok((ops, unsupported))
     }
 
     /// Imports a Parquet file into the grid.
@@ -272,6 +414,173 @@ impl GridController {
     }
 }
 
+/// Converts a value read by calamine to a cell value.
+fn excel_cell_value(data: &ExcelData) -> CellValue {
+    match data {
+        ExcelData::Empty => CellValue::Blank,
+        ExcelData::String(value) => CellValue::Text(value.to_string()),
+        ExcelData::DateTimeIso(ref value) => CellValue::Text(value.to_string()),
+        ExcelData::DurationIso(ref value) => CellValue::Text(value.to_string()),
+        ExcelData::Float(ref value) => {
+            CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
+        }
+        ExcelData::DateTime(ref value) => {
+            if value.is_duration() {
+                value.as_duration().map_or(CellValue::Blank, |duration| {
+                    let seconds = duration.num_milliseconds() as f64 / 1000.0;
+                    CellValue::Duration(Duration::from_seconds(seconds))
+                })
+            } else {
+                value
+                    .as_datetime()
+                    .map_or(CellValue::Blank, |v| CellValue::Instant(v.into()))
+            }
+        }
+        ExcelData::Int(ref value) => {
+            CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
+        }
+        ExcelData::Error(error) => {
+            let msg = match error {
+                CellErrorType::Div0 => RunErrorMsg::DivideByZero,
+                CellErrorType::NA => RunErrorMsg::NoMatch,
+                CellErrorType::Name => RunErrorMsg::BadFunctionName,
+                CellErrorType::Ref => RunErrorMsg::BadCellReference,
+                CellErrorType::Num => RunErrorMsg::NotANumber,
+                _ => RunErrorMsg::InvalidArgument,
+            };
+            CellValue::Error(Box::new(RunError { span: None, msg }))
+        }
+        ExcelData::Bool(value) => CellValue::Logical(*value),
+    }
+}
+
+/// Describes the cells with formulas that could not be converted.
+fn unconverted_formulas_feature(cells: &[Pos]) -> String {
+    const MAX_CELLS_LISTED: usize = 10;
+
+    let mut names = cells
+        .iter()
+        .take(MAX_CELLS_LISTED)
+        .map(|pos| format!("{}{}", column_name(pos.x), pos.y))
+        .join(", ");
+    if cells.len() > MAX_CELLS_LISTED {
+        names.push_str(&format!(" and {} more", cells.len() - MAX_CELLS_LISTED));
+    }
+    format!("formulas in {names} (imported as values)")
+}
+
+/// Runs the formulas imported from an Excel file. A formula that reads from
+/// other formulas that have not run yet is run again after them.
+fn run_imported_formulas(grid: &mut Grid, formulas: &[SheetPos]) {
+    // formulas that have not run, by sheet and then by column and row, so
+    // that the ones in a range are found without visiting each cell (ranges
+    // may be whole columns or rows)
+    let mut not_run: HashMap<SheetId, BTreeSet<(i64, i64)>> = HashMap::new();
+    for sheet_pos in formulas {
+        not_run
+            .entry(sheet_pos.sheet_id)
+            .or_default()
+            .insert((sheet_pos.x, sheet_pos.y));
+    }
+    let mut waiting = HashSet::new();
+    for &first in formulas {
+        let mut stack = vec![first];
+        while let Some(&sheet_pos) = stack.last() {
+            let is_not_run = not_run
+                .get(&sheet_pos.sheet_id)
+                .is_some_and(|sheet_not_run| sheet_not_run.contains(&(sheet_pos.x, sheet_pos.y)));
+            if !is_not_run {
+                stack.pop();
+                continue;
+            }
+            let code_run = run_imported_formula(grid, sheet_pos);
+
+            // formulas that are waiting already read from this one, so they
+            // are not run first
+            let inputs: Vec<SheetPos> = code_run
+                .cells_accessed
+                .iter()
+                .flat_map(|rect| {
+                    let rows = rect.min.y..=rect.max.y;
+                    not_run
+                        .get(&rect.sheet_id)
+                        .into_iter()
+                        .flat_map(move |sheet_not_run| {
+                            sheet_not_run.range((rect.min.x, rect.min.y)..=(rect.max.x, rect.max.y))
+                        })
+                        .filter(move |(_, y)| rows.contains(y))
+                        .map(|&(x, y)| SheetPos::new(rect.sheet_id, x, y))
+                })
+                .filter(|input| *input != sheet_pos && !waiting.contains(input))
+                .unique()
+                .collect();
+
+            if inputs.is_empty() {
+                stack.pop();
+                waiting.remove(&sheet_pos);
+                if let Some(sheet_not_run) = not_run.get_mut(&sheet_pos.sheet_id) {
+                    sheet_not_run.remove(&(sheet_pos.x, sheet_pos.y));
+                }
+                if let Some(sheet) = grid.try_sheet_mut(sheet_pos.sheet_id) {
+                    sheet.set_code_run(sheet_pos.into(), Some(code_run));
+                }
+            } else {
+                waiting.insert(sheet_pos);
+                stack.extend(inputs);
+            }
+        }
+    }
+
+    for sheet_id in grid.sheet_ids() {
+        let Some(sheet) = grid.try_sheet_mut(sheet_id) else {
+            continue;
+        };
+        for index in 0..sheet.code_runs.len() {
+            let Some((&pos, code_run)) = sheet.code_runs.get_index(index) else {
+                continue;
+            };
+            let output: Rect = code_run
+                .output_sheet_rect(pos.to_sheet_pos(sheet.id), true)
+                .into();
+            let spill_error = !matches!(code_run.output_size(), ArraySize::_1X1)
+                && (sheet.has_cell_value_in_rect(&output, Some(pos))
+                    || sheet.has_code_cell_in_rect(&output, pos));
+            if let Some((_, code_run)) = sheet.code_runs.get_index_mut(index) {
+                code_run.spill_error = spill_error;
+            }
+        }
+    }
+}
+
+/// Runs a formula imported from an Excel file.
+fn run_imported_formula(grid: &Grid, sheet_pos: SheetPos) -> CodeRun {
+    let code = match grid
+        .try_sheet(sheet_pos.sheet_id)
+        .and_then(|sheet| sheet.cell_value(sheet_pos.into()))
+    {
+        Some(CellValue::Code(code_cell)) => code_cell.code,
+        _ => String::new(),
+    };
+    let mut ctx = Ctx::new(grid, sheet_pos);
+    let result =
+        parse_formula(&code, sheet_pos.into()).and_then(|parsed| parsed.eval(&mut ctx, false));
+    CodeRun {
+        std_out: None,
+        std_err: result.as_ref().err().map(|error| error.msg.to_string()),
+        formatted_code_string: None,
+        spill_error: false,
+        last_modified: Utc::now(),
+        cells_accessed: ctx.cells_accessed,
+        result: match result {
+            Ok(value) => CodeRunResult::Ok(value),
+            Err(error) => CodeRunResult::Err(error),
+        },
+        return_type: None,
+        line_number: None,
+        output_type: None,
+    }
+}
+
 fn read_utf16(bytes: &[u8]) -> Option<String> {
     if bytes.is_empty() && bytes.len() % 2 == 0 {
         return None;
@@ -321,7 +630,13 @@ mod test {
         const SIMPLE_CSV: &str =
             "city,region,country,population\nSouthborough,MA,United States,a lot of people";
 
-        let ops = gc.import_csv_operations(sheet_id, SIMPLE_CSV.as_bytes(), "smallpop.csv", pos);
+        let ops = gc.import_csv_operations(
+            sheet_id,
+            SIMPLE_CSV.as_bytes(),
+            "smallpop.csv",
+            pos,
+            &CsvImportOptions::default(),
+        );
         assert_eq!(ops.as_ref().unwrap().len(), 1);
         assert_eq!(
             ops.unwrap()[0],
@@ -352,7 +667,13 @@ mod test {
             csv.push_str(&format!("city{},MA,United States,{}\n", i, i * 1000));
         }
 
-        let ops = gc.import_csv_operations(sheet_id, csv.as_bytes(), "long.csv", pos);
+        let ops = gc.import_csv_operations(
+            sheet_id,
+            csv.as_bytes(),
+            "long.csv",
+            pos,
+            &CsvImportOptions::default(),
+        );
         assert_eq!(ops.as_ref().unwrap().len(), 3);
         let first_pos = match ops.as_ref().unwrap()[0] {
             Operation::SetCellValues { sheet_pos, .. } => sheet_pos,
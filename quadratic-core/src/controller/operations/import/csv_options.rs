@@ -0,0 +1,416 @@
+//! Options for importing CSV files, and the decoding and delimiter detection
+//! that they control.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use super::read_utf16;
+use crate::{
+    controller::{operations::operation::Operation, GridController},
+    CellValue, SheetPos,
+};
+
+/// Delimiters that are tried, in order of preference, when the delimiter is
+/// not given.
+const DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+
+/// Number of lines used to detect the delimiter.
+const DELIMITER_SAMPLE_LINES: usize = 50;
+
+#[derive(Default, Debug, Serialize, Deserialize, PartialEq, Clone, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct CsvImportOptions {
+    /// Character that separates fields. Detected from the file if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<char>,
+    /// Character that quotes fields. Defaults to `"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote: Option<char>,
+    /// Number of lines to skip at the start of the file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_rows: Option<u32>,
+    /// Whether the first row (after skipped lines) is a header, which is
+    /// made bold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_row: Option<bool>,
+    /// Whether to import every value as text, which keeps values such as
+    /// leading zeros as written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub all_text: Option<bool>,
+    /// Whether numbers are written with a decimal comma and `.` as the
+    /// thousands separator, e.g. `1.234,5`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimal_comma: Option<bool>,
+    /// Encoding of the file. Detected from the file if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<CsvEncoding>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum CsvEncoding {
+    Utf8,
+    Utf16,
+    Latin1,
+    Windows1252,
+}
+
+/// The first rows of a CSV file, as they would be imported with a set of
+/// options.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct CsvPreview {
+    pub delimiter: char,
+    pub encoding: CsvEncoding,
+    pub rows: Vec<Vec<String>>,
+    /// Whether the first row looks like a header, which is when all of its
+    /// values are text and a column below it has values that are not.
+    pub header_row: bool,
+}
+
+/// A CSV file that has been decoded, with its leading rows skipped.
+pub(super) struct DecodedCsv {
+    pub text: String,
+    pub encoding: CsvEncoding,
+    pub delimiter: char,
+    quote: char,
+}
+
+impl DecodedCsv {
+    /// Returns a reader for the records of the file.
+    pub fn reader(&self) -> csv::Reader<&[u8]> {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter as u8)
+            .quote(self.quote as u8)
+            .from_reader(self.text.as_bytes())
+    }
+}
+
+impl CsvEncoding {
+    /// Detects the encoding of a file: UTF-8 if it is valid UTF-8, UTF-16 if
+    /// it starts with a byte order mark or is mostly ASCII in UTF-16, and
+    /// otherwise Windows-1252, which can decode any file.
+    pub fn detect(bytes: &[u8]) -> CsvEncoding {
+        if std::str::from_utf8(bytes).is_ok() {
+            return CsvEncoding::Utf8;
+        }
+        let zeros = bytes.iter().filter(|&&byte| byte == 0).count();
+        if (bytes.starts_with(&[0xFF, 0xFE]) || zeros > bytes.len() / 4)
+            && read_utf16(bytes).is_some()
+        {
+            CsvEncoding::Utf16
+        } else {
+            CsvEncoding::Windows1252
+        }
+    }
+
+    /// Decodes a file in this encoding. Invalid characters are replaced.
+    pub fn decode(self, bytes: &[u8]) -> String {
+        let text = match self {
+            CsvEncoding::Utf8 => String::from_utf8_lossy(bytes),
+            CsvEncoding::Utf16 => match read_utf16(bytes) {
+                Some(text) => Cow::Owned(text),
+                None => String::from_utf8_lossy(bytes),
+            },
+            CsvEncoding::Latin1 => Cow::Owned(bytes.iter().map(|&byte| byte as char).collect()),
+            CsvEncoding::Windows1252 => {
+                encoding_rs::WINDOWS_1252
+                    .decode_without_bom_handling(bytes)
+                    .0
+            }
+        };
+        text.replace('\u{FEFF}', "")
+    }
+}
+
+impl CsvImportOptions {
+    /// Decodes a file, skips its leading rows, and finds its delimiter.
+    pub(super) fn decode(&self, file: &[u8]) -> Result<DecodedCsv> {
+        let encoding = self.encoding.unwrap_or_else(|| CsvEncoding::detect(file));
+        let mut text = encoding.decode(file);
+        let skip_rows = self.skip_rows.unwrap_or(0) as usize;
+        if skip_rows > 0 {
+            let start = text
+                .match_indices('\n')
+                .nth(skip_rows - 1)
+                .map_or(text.len(), |(index, _)| index + 1);
+            text.drain(..start);
+        }
+
+        let quote = self.quote.unwrap_or('"');
+        let delimiter = match self.delimiter {
+            Some(delimiter) => delimiter,
+            None => detect_delimiter(&text, quote),
+        };
+        if !delimiter.is_ascii() || !quote.is_ascii() {
+            bail!("the delimiter and quote must be ASCII characters");
+        }
+        Ok(DecodedCsv {
+            text,
+            encoding,
+            delimiter,
+            quote,
+        })
+    }
+
+    /// Returns up to `max_rows` rows of a file, parsed with these options.
+    pub fn preview(&self, file: &[u8], max_rows: usize) -> Result<CsvPreview> {
+        let csv = self.decode(file)?;
+        let rows = csv
+            .reader()
+            .records()
+            .take(max_rows)
+            .map(|record| Ok(record?.iter().map(|value| value.to_string()).collect()))
+            .collect::<Result<Vec<Vec<String>>>>()?;
+        let header_row = self.detect_header_row(&rows);
+        Ok(CsvPreview {
+            delimiter: csv.delimiter,
+            encoding: csv.encoding,
+            rows,
+            header_row,
+        })
+    }
+
+    /// Returns whether the first row looks like a header: every value in it
+    /// is text, and at least one of its columns has values below it that are
+    /// not text, such as numbers.
+    fn detect_header_row(&self, rows: &[Vec<String>]) -> bool {
+        let Some((first, rest)) = rows.split_first() else {
+            return false;
+        };
+        if first.iter().all(|value| value.is_empty())
+            || first.iter().any(|value| self.is_typed_value(value))
+        {
+            return false;
+        }
+        (0..first.len()).any(|column| {
+            !first[column].is_empty()
+                && rest
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .any(|value| self.is_typed_value(value))
+        })
+    }
+
+    /// Returns whether a value would be imported as something other than
+    /// text, such as a number or boolean.
+    fn is_typed_value(&self, value: &str) -> bool {
+        if self.decimal_comma.unwrap_or(false) && value.contains([',', '.']) {
+            return is_decimal_comma_number(value);
+        }
+        CellValue::unpack_currency(value).is_some()
+            || CellValue::unpack_boolean(value).is_some()
+            || BigDecimal::from_str(&CellValue::strip_commas(value)).is_ok()
+            || CellValue::unpack_percentage(value).is_some()
+    }
+}
+
+/// Returns whether `value` is a number written with a decimal comma: an
+/// optional sign, digits that may be split into groups of three by `.`, and
+/// an optional `,` followed by the decimals. Values such as dates
+/// (`01.02.2024`) and IP addresses do not match.
+fn is_decimal_comma_number(value: &str) -> bool {
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|byte| byte.is_ascii_digit());
+    let unsigned = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let (integer, decimals) = match unsigned.split_once(',') {
+        Some((integer, decimals)) => (integer, Some(decimals)),
+        None => (unsigned, None),
+    };
+    let mut groups = integer.split('.');
+    let first = groups.next().unwrap_or_default();
+    let mut groups = groups.peekable();
+    let valid_integer = if groups.peek().is_some() {
+        is_digits(first)
+            && first.len() <= 3
+            && groups.all(|group| group.len() == 3 && is_digits(group))
+    } else {
+        is_digits(first)
+    };
+    valid_integer && decimals.is_none_or(is_digits)
+}
+
+/// Finds the delimiter that splits the first lines of a file into the same
+/// number of fields most consistently. Delimiters inside quotes are ignored.
+/// Defaults to `,`.
+fn detect_delimiter(text: &str, quote: char) -> char {
+    // number of each delimiter in each line
+    let mut counts: Vec<[usize; DELIMITERS.len()]> = vec![[0; DELIMITERS.len()]];
+    let mut in_quotes = false;
+    for c in text.chars() {
+        if c == quote {
+            in_quotes = !in_quotes;
+        } else if c == '\n' && !in_quotes {
+            if counts.len() == DELIMITER_SAMPLE_LINES {
+                break;
+            }
+            counts.push([0; DELIMITERS.len()]);
+        } else if let Some(index) = DELIMITERS.iter().position(|&delimiter| delimiter == c) {
+            if !in_quotes {
+                if let Some(line) = counts.last_mut() {
+                    line[index] += 1;
+                }
+            }
+        }
+    }
+
+    let mut best = (0, DELIMITERS[0]);
+    for (index, &delimiter) in DELIMITERS.iter().enumerate() {
+        // the most common number of delimiters in a line that has any
+        let mut frequencies: Vec<(usize, usize)> = vec![];
+        for count in counts.iter().map(|line| line[index]).filter(|&n| n > 0) {
+            match frequencies.iter_mut().find(|(n, _)| *n == count) {
+                Some((_, frequency)) => *frequency += 1,
+                None => frequencies.push((count, 1)),
+            }
+        }
+        let consistent_lines = frequencies
+            .iter()
+            .map(|&(_, frequency)| frequency)
+            .max()
+            .unwrap_or(0);
+        if consistent_lines > best.0 {
+            best = (consistent_lines, delimiter);
+        }
+    }
+    best.1
+}
+
+impl GridController {
+    /// Converts a value read from a CSV file to a cell value, following the
+    /// import options. Returns any operations needed to format the value.
+    pub(super) fn csv_value_to_cell_value(
+        &mut self,
+        sheet_pos: SheetPos,
+        value: &str,
+        options: &CsvImportOptions,
+    ) -> (Vec<Operation>, CellValue) {
+        if options.all_text.unwrap_or(false) {
+            let cell_value = if value.is_empty() {
+                CellValue::Blank
+            } else {
+                CellValue::Text(value.to_string())
+            };
+            return (vec![], cell_value);
+        }
+        if options.decimal_comma.unwrap_or(false) && value.contains([',', '.']) {
+            // values that are not numbers, such as dates, are kept as written
+            if !is_decimal_comma_number(value) {
+                return (vec![], CellValue::Text(value.to_string()));
+            }
+            // swap the decimal and thousands separators
+            let swapped: String = value
+                .chars()
+                .map(|c| match c {
+                    ',' => '.',
+                    '.' => ',',
+                    c => c,
+                })
+                .collect();
+            return self.string_to_cell_value(sheet_pos, &swapped);
+        }
+        self.string_to_cell_value(sheet_pos, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a,b,c\n1,2,3", '"'), ',');
+        assert_eq!(detect_delimiter("a;b;c\n1,5;2,5;3", '"'), ';');
+        assert_eq!(detect_delimiter("a\tb\n\"1,2,3\"\t4", '"'), '\t');
+        assert_eq!(detect_delimiter("a|b|c\n1|2|3\n", '"'), '|');
+        assert_eq!(
+            detect_delimiter("title, with comma\na;b\n1;2\n3;4", '"'),
+            ';'
+        );
+        assert_eq!(detect_delimiter("one column", '"'), ',');
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(CsvEncoding::detect("naïve".as_bytes()), CsvEncoding::Utf8);
+        assert_eq!(
+            CsvEncoding::detect(&[0xFF, 0xFE, b'a', 0, b'b', 0]),
+            CsvEncoding::Utf16
+        );
+
+        // 0x80 is the euro sign in Windows-1252 and a control character in
+        // Latin-1
+        let bytes = [b'n', b'a', 0xEF, b'v', b'e', b' ', 0x80];
+        assert_eq!(CsvEncoding::detect(&bytes), CsvEncoding::Windows1252);
+        assert_eq!(CsvEncoding::Windows1252.decode(&bytes), "naïve €");
+        assert_eq!(CsvEncoding::Latin1.decode(&bytes), "naïve \u{80}");
+        assert_eq!(CsvEncoding::Utf8.decode("\u{FEFF}a,b".as_bytes()), "a,b");
+    }
+
+    #[test]
+    fn test_preview() {
+        let file = "Report\n\nname;code;amount\nab;007;1,5\n\"c;d\";008;2\nx;y;z";
+        let options = CsvImportOptions {
+            skip_rows: Some(2),
+            ..Default::default()
+        };
+        let preview = options.preview(file.as_bytes(), 3).unwrap();
+        assert_eq!(preview.delimiter, ';');
+        assert_eq!(preview.encoding, CsvEncoding::Utf8);
+        assert_eq!(
+            preview.rows,
+            vec![
+                vec!["name", "code", "amount"],
+                vec!["ab", "007", "1,5"],
+                vec!["c;d", "008", "2"],
+            ]
+        );
+        assert!(preview.header_row);
+        assert!(!options.preview(file.as_bytes(), 1).unwrap().header_row);
+
+        // no header when the first row has numbers, or every row is text
+        let preview = CsvImportOptions::default().preview(b"1,2\n3,4", 3).unwrap();
+        assert!(!preview.header_row);
+        let preview = CsvImportOptions::default().preview(b"a,b\nc,d", 3).unwrap();
+        assert!(!preview.header_row);
+
+        let options = CsvImportOptions {
+            delimiter: Some('é'),
+            ..Default::default()
+        };
+        assert!(options.preview(file.as_bytes(), 3).is_err());
+    }
+
+    #[test]
+    fn test_decimal_comma_values() {
+        assert!(is_decimal_comma_number("1,5"));
+        assert!(is_decimal_comma_number("-1.234.567,89"));
+        assert!(is_decimal_comma_number("+12"));
+        assert!(is_decimal_comma_number("1.234"));
+        assert!(!is_decimal_comma_number("01.02.2024"));
+        assert!(!is_decimal_comma_number("192.168.1.1"));
+        assert!(!is_decimal_comma_number("1234.567"));
+        assert!(!is_decimal_comma_number("1,2,3"));
+        assert!(!is_decimal_comma_number(",5"));
+        assert!(!is_decimal_comma_number("some, text"));
+
+        let mut gc = GridController::test();
+        let sheet_pos = SheetPos::new(gc.sheet_ids()[0], 0, 0);
+        let options = CsvImportOptions {
+            decimal_comma: Some(true),
+            ..Default::default()
+        };
+        let mut import = |value: &str| gc.csv_value_to_cell_value(sheet_pos, value, &options).1;
+        assert_eq!(
+            import("1.234,5"),
+            CellValue::Number(BigDecimal::from_str("1234.5").unwrap())
+        );
+        assert_eq!(import("01.02.2024"), CellValue::Text("01.02.2024".into()));
+        assert_eq!(import("192.168.1.1"), CellValue::Text("192.168.1.1".into()));
+    }
+}
@@ -0,0 +1,703 @@
+//! Reads the parts of an Excel file that calamine does not: formulas, styles,
+//! column widths, row heights and merged cells.
+//!
+//! Positions here are Excel's, with columns counted from 0 and rows counted
+//! from 1.
+
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+};
+
+use anyhow::{Context, Result};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use zip::ZipArchive;
+
+use crate::{
+    color::Rgba,
+    formulas::CellRef,
+    grid::{
+        formats::{format::Format, format_update::FormatUpdate},
+        BorderStyle, CellAlign, CellBorderLine, CellWrap, NumericFormat, NumericFormatKind,
+    },
+    Pos, Rect,
+};
+
+/// An Excel file opened for reading.
+pub(super) struct ExcelFile<'a> {
+    archive: ZipArchive<Cursor<&'a [u8]>>,
+    /// Path in the archive of each sheet, by name.
+    sheet_paths: HashMap<String, String>,
+    /// Cell styles, indexed by the `s` attribute of a cell.
+    pub styles: Vec<ExcelStyle>,
+}
+
+/// The format and borders of an Excel cell style.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct ExcelStyle {
+    pub format: FormatUpdate,
+    /// Ordered left, top, right, bottom, like `CellBorders`.
+    pub borders: [Option<BorderStyle>; 4],
+}
+
+/// The parts of a worksheet that calamine does not read.
+#[derive(Debug, Default)]
+pub(super) struct ExcelSheet {
+    /// Cells that have a style or a formula.
+    pub cells: Vec<ExcelCell>,
+    pub columns: Vec<ExcelColumn>,
+    pub rows: Vec<ExcelRow>,
+    pub merged_cells: Vec<Rect>,
+    /// Descriptions of the features used on the sheet that are not imported.
+    pub unsupported: Vec<&'static str>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ExcelCell {
+    pub pos: Pos,
+    pub style: usize,
+    pub formula: Option<ExcelFormula>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct ExcelFormula {
+    /// The formula, which is empty for cells that share the formula of
+    /// another cell.
+    pub text: String,
+    /// Index of the shared formula, if the formula is shared by a range of
+    /// cells.
+    pub shared_index: Option<u32>,
+    /// The cells an array formula spills into.
+    pub array: Option<Rect>,
+}
+
+/// A range of columns with the same width.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct ExcelColumn {
+    pub min: i64,
+    pub max: i64,
+    /// Width in pixels, if it was set.
+    pub width: Option<f64>,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct ExcelRow {
+    pub y: i64,
+    /// Height in pixels, if it was set.
+    pub height: Option<f64>,
+    pub hidden: bool,
+}
+
+/// Sheet elements that are not imported, with how they are reported.
+const UNSUPPORTED_ELEMENTS: &[(&str, &str)] = &[
+    ("conditionalFormatting", "conditional formatting"),
+    ("dataValidations", "data validation"),
+    ("hyperlinks", "hyperlinks"),
+    ("drawing", "charts, images and shapes"),
+    ("legacyDrawing", "comments"),
+    ("tableParts", "tables"),
+    ("autoFilter", "filters"),
+    ("sheetProtection", "sheet protection"),
+    ("sparklineGroups", "sparklines"),
+];
+
+impl<'a> ExcelFile<'a> {
+    pub fn open(file: &'a [u8]) -> Result<Self> {
+        let mut archive = ZipArchive::new(Cursor::new(file))?;
+
+        let relationships = read_part(&mut archive, "xl/_rels/workbook.xml.rels")?
+            .map(|xml| read_relationships(&xml))
+            .transpose()?
+            .unwrap_or_default();
+        let workbook = read_part(&mut archive, "xl/workbook.xml")?.context("missing workbook")?;
+        let mut sheet_paths = HashMap::new();
+        let mut reader = Reader::from_str(&workbook);
+        loop {
+            match reader.read_event()? {
+                Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+                    let path = attribute(&e, "id").and_then(|id| relationships.get(&id));
+                    if let (Some(name), Some(path)) = (attribute(&e, "name"), path) {
+                        sheet_paths.insert(name, path.clone());
+                    }
+                }
+                Event::Eof => break,
+                _ => (),
+            }
+        }
+
+        let styles = read_part(&mut archive, "xl/styles.xml")?
+            .map(|xml| read_styles(&xml))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(ExcelFile {
+            archive,
+            sheet_paths,
+            styles,
+        })
+    }
+
+    /// Reads a sheet, or returns `None` if the sheet is not in the file.
+    pub fn sheet(&mut self, name: &str) -> Result<Option<ExcelSheet>> {
+        let Some(path) = self.sheet_paths.get(name) else {
+            return Ok(None);
+        };
+        let Some(xml) = read_part(&mut self.archive, path)? else {
+            return Ok(None);
+        };
+
+        let mut sheet = ExcelSheet::default();
+        let mut reader = Reader::from_str(&xml);
+        let mut next_pos = Pos { x: 0, y: 1 };
+        let mut cell: Option<ExcelCell> = None;
+        let mut in_formula = false;
+        loop {
+            let event = reader.read_event()?;
+            let (e, is_empty) = match &event {
+                Event::Start(e) => (e, false),
+                Event::Empty(e) => (e, true),
+                Event::Text(text) if in_formula => {
+                    if let Some(formula) = cell.as_mut().and_then(|cell| cell.formula.as_mut()) {
+                        formula.text.push_str(&text.unescape()?);
+                    }
+                    continue;
+                }
+                Event::End(e) => {
+                    match e.local_name().as_ref() {
+                        b"f" => in_formula = false,
+                        b"c" => sheet.cells.extend(
+                            cell.take()
+                                .filter(|cell| cell.style != 0 || cell.formula.is_some()),
+                        ),
+                        _ => (),
+                    }
+                    continue;
+                }
+                Event::Eof => break,
+                _ => continue,
+            };
+
+            match e.local_name().as_ref() {
+                b"row" => {
+                    let row = attribute(e, "r")
+                        .and_then(|r| r.parse().ok())
+                        .unwrap_or(next_pos.y);
+                    next_pos = Pos { x: 0, y: row + 1 };
+                    let height = attribute(e, "ht").and_then(|ht| ht.parse::<f64>().ok());
+                    let custom_height = is_true(attribute(e, "customHeight"));
+                    let hidden = is_true(attribute(e, "hidden"));
+                    if custom_height || hidden {
+                        sheet.rows.push(ExcelRow {
+                            y: row,
+                            height: height.filter(|_| custom_height).map(|ht| ht / 0.75),
+                            hidden,
+                        });
+                    }
+                }
+                b"c" => {
+                    // cells without a position follow the previous one
+                    let pos = attribute(e, "r")
+                        .and_then(|r| parse_excel_cell(&r))
+                        .unwrap_or(Pos {
+                            x: next_pos.x,
+                            y: next_pos.y - 1,
+                        });
+                    next_pos = Pos {
+                        x: pos.x + 1,
+                        y: pos.y + 1,
+                    };
+                    let new_cell = ExcelCell {
+                        pos,
+                        style: attribute(e, "s").and_then(|s| s.parse().ok()).unwrap_or(0),
+                        formula: None,
+                    };
+                    if is_empty {
+                        sheet
+                            .cells
+                            .extend(Some(new_cell).filter(|cell| cell.style != 0));
+                    } else {
+                        cell = Some(new_cell);
+                    }
+                }
+                b"f" => {
+                    if let Some(cell) = cell.as_mut() {
+                        let kind = attribute(e, "t");
+                        let range = attribute(e, "ref").and_then(|r| parse_excel_range(&r));
+                        cell.formula = Some(ExcelFormula {
+                            text: String::new(),
+                            shared_index: attribute(e, "si")
+                                .filter(|_| kind.as_deref() == Some("shared"))
+                                .and_then(|si| si.parse().ok()),
+                            array: range.filter(|_| kind.as_deref() == Some("array")),
+                        });
+                        in_formula = !is_empty;
+                    }
+                }
+                b"col" => {
+                    let min = attribute(e, "min").and_then(|min| min.parse::<i64>().ok());
+                    let max = attribute(e, "max").and_then(|max| max.parse::<i64>().ok());
+                    let width = attribute(e, "width").and_then(|width| width.parse::<f64>().ok());
+                    let custom_width = is_true(attribute(e, "customWidth"));
+                    let hidden = is_true(attribute(e, "hidden"));
+                    if let (Some(min), Some(max)) = (min, max) {
+                        if custom_width || hidden {
+                            sheet.columns.push(ExcelColumn {
+                                min: min - 1,
+                                max: max - 1,
+                                width: width
+                                    .filter(|_| custom_width)
+                                    .map(|width| width * 7.0 + 5.0),
+                                hidden,
+                            });
+                        }
+                    }
+                }
+                b"mergeCell" => {
+                    sheet
+                        .merged_cells
+                        .extend(attribute(e, "ref").and_then(|r| parse_excel_range(&r)));
+                }
+                name => {
+                    for (element, feature) in UNSUPPORTED_ELEMENTS {
+                        if name == element.as_bytes() && !sheet.unsupported.contains(feature) {
+                            sheet.unsupported.push(feature);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Some(sheet))
+    }
+}
+
+/// Reads a file from the archive, or returns `None` if it does not exist.
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, path: &str) -> Result<Option<String>> {
+    let mut file = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)?;
+    Ok(Some(xml))
+}
+
+/// Reads the relationships of the workbook, returning the path in the
+/// archive of each target by ID.
+fn read_relationships(xml: &str) -> Result<HashMap<String, String>> {
+    let mut relationships = HashMap::new();
+    let mut reader = Reader::from_str(xml);
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+                if let (Some(id), Some(target)) = (attribute(&e, "Id"), attribute(&e, "Target")) {
+                    let path = match target.strip_prefix('/') {
+                        Some(path) => path.to_string(),
+                        None => format!("xl/{target}"),
+                    };
+                    relationships.insert(id, path);
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(relationships)
+}
+
+/// Reads the cell styles from `xl/styles.xml`.
+fn read_styles(xml: &str) -> Result<Vec<ExcelStyle>> {
+    #[derive(Default)]
+    struct Font {
+        bold: bool,
+        italic: bool,
+        color: Option<String>,
+    }
+
+    let mut number_formats = HashMap::new();
+    let mut fonts: Vec<Font> = vec![];
+    let mut fills: Vec<Option<String>> = vec![];
+    let mut borders: Vec<[Option<BorderStyle>; 4]> = vec![];
+    let mut styles = vec![];
+
+    // open elements in the list being read, outermost first, and the border
+    // side being read
+    let mut section = vec![];
+    let mut side = None;
+    let mut reader = Reader::from_str(xml);
+    loop {
+        let event = reader.read_event()?;
+        let (e, is_empty) = match &event {
+            Event::Start(e) => (e, false),
+            Event::Empty(e) => (e, true),
+            Event::End(e) => {
+                if section.last().map(|s: &Vec<u8>| s.as_slice()) == Some(e.local_name().as_ref()) {
+                    section.pop();
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        let name = e.local_name().as_ref().to_vec();
+        let parent = section.first().map(|s| s.as_slice());
+        match (parent, name.as_slice()) {
+            (Some(b"numFmts"), b"numFmt") => {
+                if let (Some(id), Some(code)) = (
+                    attribute(e, "numFmtId").and_then(|id| id.parse::<u32>().ok()),
+                    attribute(e, "formatCode"),
+                ) {
+                    number_formats.insert(id, code);
+                }
+            }
+            (Some(b"fonts"), b"font") => fonts.push(Font::default()),
+            (Some(b"fonts"), b"b" | b"i") => {
+                if let Some(font) = fonts.last_mut() {
+                    let value = !matches!(attribute(e, "val").as_deref(), Some("0" | "false"));
+                    match name.as_slice() {
+                        b"b" => font.bold = value,
+                        _ => font.italic = value,
+                    }
+                }
+            }
+            (Some(b"fonts"), b"color") => {
+                if let Some(font) = fonts.last_mut() {
+                    font.color = attribute(e, "rgb").and_then(|rgb| excel_color(&rgb));
+                }
+            }
+            (Some(b"fills"), b"fill") => fills.push(None),
+            // a fill without a solid pattern is left empty
+            (Some(b"fills"), b"patternFill")
+                if attribute(e, "patternType").as_deref() != Some("solid") =>
+            {
+                if let Some(fill) = fills.last_mut() {
+                    *fill = Some(String::new());
+                }
+            }
+            (Some(b"fills"), b"fgColor") => {
+                if let Some(fill) = fills.last_mut().filter(|fill| fill.is_none()) {
+                    *fill = attribute(e, "rgb").and_then(|rgb| excel_color(&rgb));
+                }
+            }
+            (Some(b"borders"), b"border") => borders.push(Default::default()),
+            (Some(b"borders"), b"left" | b"top" | b"right" | b"bottom") => {
+                let index = match name.as_slice() {
+                    b"left" => 0,
+                    b"top" => 1,
+                    b"right" => 2,
+                    _ => 3,
+                };
+                let line = attribute(e, "style").and_then(|style| border_line(&style));
+                if let (Some(border), Some(line)) = (borders.last_mut(), line) {
+                    border[index] = Some(BorderStyle {
+                        color: Rgba::default(),
+                        line,
+                    });
+                    side = Some(index);
+                } else {
+                    side = None;
+                }
+            }
+            (Some(b"borders"), b"color") => {
+                let color = attribute(e, "rgb")
+                    .and_then(|rgb| excel_color(&rgb))
+                    .and_then(|color| Rgba::color_from_str(&color).ok());
+                let style = side.and_then(|side| borders.last_mut()?[side].as_mut());
+                if let (Some(style), Some(color)) = (style, color) {
+                    style.color = color;
+                }
+            }
+            (Some(b"cellXfs"), b"xf") => {
+                let id = |name: &str| {
+                    attribute(e, name)
+                        .and_then(|id| id.parse::<usize>().ok())
+                        .unwrap_or(0)
+                };
+                let number_format = match id("numFmtId") as u32 {
+                    id if id >= 164 => number_formats.get(&id).map(|code| code.as_str()),
+                    id => builtin_number_format(id),
+                };
+                let mut format = number_format
+                    .and_then(parse_number_format)
+                    .unwrap_or_default();
+                if let Some(font) = fonts.get(id("fontId")) {
+                    format.bold = font.bold.then_some(true);
+                    format.italic = font.italic.then_some(true);
+                    format.text_color = font.color.clone();
+                }
+                format.fill_color = fills.get(id("fillId")).cloned().flatten();
+                format.fill_color = format.fill_color.filter(|fill| !fill.is_empty());
+                styles.push(ExcelStyle {
+                    format: format.into(),
+                    borders: borders.get(id("borderId")).cloned().unwrap_or_default(),
+                });
+            }
+            (Some(b"cellXfs"), b"alignment") => {
+                if let Some(style) = styles.last_mut() {
+                    style.format.align = match attribute(e, "horizontal").as_deref() {
+                        Some("left") => Some(Some(CellAlign::Left)),
+                        Some("center" | "centerContinuous") => Some(Some(CellAlign::Center)),
+                        Some("right") => Some(Some(CellAlign::Right)),
+                        _ => None,
+                    };
+                    if is_true(attribute(e, "wrapText")) {
+                        style.format.wrap = Some(Some(CellWrap::Wrap));
+                    }
+                }
+            }
+            (Some(b"borders"), b"diagonal" | b"vertical" | b"horizontal") => side = None,
+            _ => (),
+        }
+        if !is_empty && (parent.is_some() || is_section(&name)) {
+            section.push(name);
+        }
+    }
+    Ok(styles)
+}
+
+/// Returns whether an element in `xl/styles.xml` is a list that
+/// [`read_styles()`] reads.
+fn is_section(name: &[u8]) -> bool {
+    matches!(
+        name,
+        b"numFmts" | b"fonts" | b"fills" | b"borders" | b"cellXfs"
+    )
+}
+
+/// Returns the value of an attribute, ignoring any namespace prefix.
+fn attribute(e: &BytesStart<'_>, name: &str) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attr| {
+            let value = std::str::from_utf8(&attr.value).ok()?;
+            Some(quick_xml::escape::unescape(value).ok()?.into_owned())
+        })
+}
+
+/// Returns whether an Excel boolean attribute is set.
+fn is_true(value: Option<String>) -> bool {
+    matches!(value.as_deref(), Some("1" | "true"))
+}
+
+/// Parses an Excel cell name, such as `B3`.
+pub(super) fn parse_excel_cell(s: &str) -> Option<Pos> {
+    if s.contains('n') {
+        return None;
+    }
+    let cell_ref = CellRef::parse_a1(s, Pos::ORIGIN)?;
+    Some(Pos {
+        x: cell_ref.x.resolve_from(0),
+        y: cell_ref.y.resolve_from(0),
+    })
+}
+
+/// Parses an Excel range of cells, such as `B3:C5`, or a single cell.
+pub(super) fn parse_excel_range(s: &str) -> Option<Rect> {
+    match s.split_once(':') {
+        Some((start, end)) => Some(Rect::new_span(
+            parse_excel_cell(start)?,
+            parse_excel_cell(end)?,
+        )),
+        None => Some(Rect::single_pos(parse_excel_cell(s)?)),
+    }
+}
+
+/// Converts an Excel ARGB color to a CSS color.
+fn excel_color(argb: &str) -> Option<String> {
+    // checked first so that slicing off the alpha is on a char boundary
+    if !argb.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let rgb = match argb.len() {
+        8 => &argb[2..],
+        6 => argb,
+        _ => return None,
+    };
+    Some(format!("#{}", rgb.to_ascii_lowercase()))
+}
+
+fn border_line(style: &str) -> Option<CellBorderLine> {
+    Some(match style {
+        "thin" | "hair" => CellBorderLine::Line1,
+        "medium" => CellBorderLine::Line2,
+        "thick" => CellBorderLine::Line3,
+        "dotted" => CellBorderLine::Dotted,
+        "double" => CellBorderLine::Double,
+        "dashed" | "mediumDashed" | "dashDot" | "mediumDashDot" | "dashDotDot"
+        | "mediumDashDotDot" | "slantDashDot" => CellBorderLine::Dashed,
+        _ => return None,
+    })
+}
+
+/// Returns the code of a number format that Excel does not store in the
+/// file. Formats that depend on the locale, and dates, are left out.
+fn builtin_number_format(id: u32) -> Option<&'static str> {
+    Some(match id {
+        1 => "0",
+        2 => "0.00",
+        3 | 37 | 38 => "#,##0",
+        4 | 39 | 40 => "#,##0.00",
+        5 | 6 => "\"$\"#,##0",
+        7 | 8 => "\"$\"#,##0.00",
+        9 => "0%",
+        10 => "0.00%",
+        11 => "0.00E+00",
+        48 => "##0.0E+0",
+        _ => return None,
+    })
+}
+
+/// Converts an Excel number format code to a cell format, or returns `None`
+/// if the format cannot be represented, such as a date format.
+fn parse_number_format(code: &str) -> Option<Format> {
+    // only the format for positive numbers is used
+    let section = code.split(';').next()?;
+    let mut symbol = None;
+    let mut plain = String::new();
+    let mut chars = section.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let text: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                if text.chars().any(is_currency_symbol) {
+                    symbol = Some(text.trim().to_string());
+                }
+            }
+            '[' => {
+                let tag: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                if let Some(currency) = tag.strip_prefix('$') {
+                    let currency = currency.split('-').next().unwrap_or_default();
+                    if !currency.is_empty() {
+                        symbol = Some(currency.to_string());
+                    }
+                } else if tag
+                    .chars()
+                    .all(|c| matches!(c, 'h' | 'm' | 's' | 'H' | 'M' | 'S'))
+                {
+                    // elapsed time
+                    return None;
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next().filter(|&c| is_currency_symbol(c)) {
+                    symbol = Some(c.to_string());
+                }
+            }
+            '_' | '*' => {
+                chars.next();
+            }
+            c if is_currency_symbol(c) => symbol = Some(c.to_string()),
+            c => plain.push(c),
+        }
+    }
+
+    let plain = plain.to_ascii_lowercase();
+    if plain.trim() == "general"
+        || plain.contains('@')
+        || plain
+            .chars()
+            .any(|c| matches!(c, 'y' | 'm' | 'd' | 'h' | 's'))
+        || !plain.contains(['0', '#', '?'])
+    {
+        return None;
+    }
+
+    let kind = if plain.contains('%') {
+        NumericFormatKind::Percentage
+    } else if plain.contains("e+") || plain.contains("e-") {
+        NumericFormatKind::Exponential
+    } else if symbol.is_some() {
+        NumericFormatKind::Currency
+    } else {
+        NumericFormatKind::Number
+    };
+    let decimals = plain.split_once('.').map_or(0, |(_, fraction)| {
+        fraction
+            .chars()
+            .take_while(|c| matches!(c, '0' | '#' | '?'))
+            .count()
+    });
+    let commas = plain.contains(',');
+    Some(Format {
+        numeric_format: (kind != NumericFormatKind::Number).then(|| NumericFormat {
+            kind,
+            symbol: symbol.filter(|_| kind == NumericFormatKind::Currency),
+        }),
+        numeric_decimals: Some(decimals as i16),
+        numeric_commas: match kind {
+            NumericFormatKind::Currency => Some(commas),
+            _ => commas.then_some(true),
+        },
+        ..Default::default()
+    })
+}
+
+fn is_currency_symbol(c: char) -> bool {
+    matches!(c, '$' | '€' | '£' | '¥' | '₹' | '₩' | '₽' | '₺' | '₪' | '฿')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number_format() {
+        let format = |code: &str| {
+            parse_number_format(code).map(|format| {
+                (
+                    format.numeric_format.map(|f| (f.kind, f.symbol)),
+                    format.numeric_decimals,
+                    format.numeric_commas,
+                )
+            })
+        };
+        assert_eq!(format("0.00"), Some((None, Some(2), None)));
+        assert_eq!(format("#,##0"), Some((None, Some(0), Some(true))));
+        assert_eq!(
+            format(r##""$"#,##0.00_);[Red]\("$"#,##0.00\)"##),
+            Some((
+                Some((NumericFormatKind::Currency, Some("$".into()))),
+                Some(2),
+                Some(true)
+            ))
+        );
+        assert_eq!(
+            format("[$€-407]0.0"),
+            Some((
+                Some((NumericFormatKind::Currency, Some("€".into()))),
+                Some(1),
+                Some(false)
+            ))
+        );
+        assert_eq!(
+            format("0.0%"),
+            Some((Some((NumericFormatKind::Percentage, None)), Some(1), None))
+        );
+        assert_eq!(
+            format("0.00E+00"),
+            Some((Some((NumericFormatKind::Exponential, None)), Some(2), None))
+        );
+        assert_eq!(format("General"), None);
+        assert_eq!(format("yyyy-mm-dd"), None);
+        assert_eq!(format("[h]:mm:ss"), None);
+        assert_eq!(format("@"), None);
+    }
+
+    #[test]
+    fn test_parse_excel_range() {
+        assert_eq!(parse_excel_cell("B3"), Some(Pos { x: 1, y: 3 }));
+        assert_eq!(parse_excel_cell("$B$3"), Some(Pos { x: 1, y: 3 }));
+        assert_eq!(parse_excel_cell("nB3"), None);
+        assert_eq!(parse_excel_range("A1:C2"), Some(Rect::new(0, 1, 2, 2)));
+        assert_eq!(parse_excel_range("C2"), Some(Rect::new(2, 2, 2, 2)));
+        assert_eq!(excel_color("FFFF0000"), Some("#ff0000".into()));
+        assert_eq!(excel_color("theme"), None);
+        assert_eq!(excel_color("Fé00000"), None);
+        assert_eq!(excel_color("FFGG0000"), None);
+    }
+}
@@ -0,0 +1,375 @@
+//! Imports JSON files, either an array of records or newline-delimited
+//! records, as a table with a column for each field.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+use bigdecimal::BigDecimal;
+use indexmap::IndexMap;
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Number;
+
+use super::IMPORT_LINES_PER_OPERATION;
+use crate::{
+    cell_values::CellValues,
+    controller::{operations::operation::Operation, GridController},
+    grid::SheetId,
+    CellValue, Pos, SheetPos,
+};
+
+/// Header for records that are not objects.
+const VALUE_HEADER: &str = "value";
+
+/// A JSON value. Unlike `serde_json::Value`, objects keep their fields in the
+/// order they are written, which is the order of the columns.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(IndexMap<String, Value>),
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Number(value.into()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(value).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut array = vec![];
+        while let Some(value) = seq.next_element()? {
+            array.push(value);
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = IndexMap::new();
+        while let Some((key, value)) = map.next_entry()? {
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+impl GridController {
+    /// Imports a JSON file into the grid. The file is either an array of
+    /// records or a sequence of records, such as newline-delimited JSON. The
+    /// first row has the names of the fields of all records, in the order
+    /// they are first seen, with nested fields named by their dotted path.
+    pub fn import_json_operations(
+        &mut self,
+        sheet_id: SheetId,
+        file: &[u8],
+        file_name: &str,
+        insert_at: Pos,
+    ) -> Result<Vec<Operation>> {
+        let error = |message: String| anyhow!("Error parsing JSON file {}: {}", file_name, message);
+        let text = std::str::from_utf8(file).map_err(|e| error(e.to_string()))?;
+        let text = text.trim_start_matches('\u{FEFF}');
+
+        let mut records = vec![];
+        for value in serde_json::Deserializer::from_str(text).into_iter::<Value>() {
+            let value = value.map_err(|e| error(format!("line {}: {}", e.line(), e)))?;
+            records.push(value);
+        }
+        // a single array is a list of records
+        if let [Value::Array(_)] = records.as_slice() {
+            if let Some(Value::Array(array)) = records.pop() {
+                records = array;
+            }
+        }
+
+        let mut headers = JsonHeaders::default();
+        let rows: Vec<Vec<(usize, CellValue)>> = records
+            .into_iter()
+            .map(|record| {
+                let mut row = vec![];
+                match record {
+                    Value::Object(_) => flatten_json(record, String::new(), &mut headers, &mut row),
+                    _ => flatten_json(record, VALUE_HEADER.into(), &mut headers, &mut row),
+                }
+                row
+            })
+            .collect();
+
+        let width = headers.names.len() as u32;
+        let height = rows.len() as u32;
+        if width == 0 {
+            bail!("empty files cannot be processed");
+        }
+
+        let mut ops = vec![Operation::SetCellValues {
+            sheet_pos: SheetPos {
+                x: insert_at.x,
+                y: insert_at.y,
+                sheet_id,
+            },
+            values: CellValues::from_flat_array(
+                width,
+                1,
+                headers.names.into_iter().map(CellValue::Text).collect(),
+            ),
+        }];
+
+        // the records are added in operations of IMPORT_LINES_PER_OPERATION rows
+        let mut current_y = 0;
+        for chunk in rows.chunks(IMPORT_LINES_PER_OPERATION as usize) {
+            let mut values = CellValues::new(width, chunk.len() as u32);
+            for (y, row) in chunk.iter().enumerate() {
+                for (x, value) in row {
+                    values.set(*x as u32, y as u32, value.clone());
+                }
+            }
+            ops.push(Operation::SetCellValues {
+                sheet_pos: SheetPos {
+                    x: insert_at.x,
+                    y: insert_at.y + 1 + current_y as i64,
+                    sheet_id,
+                },
+                values,
+            });
+            current_y += chunk.len() as u32;
+
+            // update the progress bar every time there's a new operation
+            if cfg!(target_family = "wasm") {
+                crate::wasm_bindings::js::jsImportProgress(
+                    file_name,
+                    current_y,
+                    height,
+                    insert_at.x,
+                    insert_at.y,
+                    width,
+                    height + 1,
+                );
+            }
+        }
+
+        Ok(ops)
+    }
+}
+
+/// Names of the columns of a JSON import, in the order they were found.
+#[derive(Default)]
+struct JsonHeaders {
+    names: Vec<String>,
+    columns: HashMap<String, usize>,
+}
+
+impl JsonHeaders {
+    /// Returns the column of a field, adding it if it is new.
+    fn column(&mut self, name: String) -> usize {
+        if let Some(&column) = self.columns.get(&name) {
+            return column;
+        }
+        let column = self.names.len();
+        self.names.push(name.clone());
+        self.columns.insert(name, column);
+        column
+    }
+}
+
+/// Adds the cell values of a JSON value at `path` to a row. Objects are
+/// flattened, with the path of each field joined to `path` by a dot.
+fn flatten_json(
+    value: Value,
+    path: String,
+    headers: &mut JsonHeaders,
+    row: &mut Vec<(usize, CellValue)>,
+) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let path = if path.is_empty() {
+                    key
+                } else {
+                    format!("{path}.{key}")
+                };
+                flatten_json(value, path, headers, row);
+            }
+        }
+        value => {
+            let column = headers.column(path);
+            row.push((column, json_cell_value(value)));
+        }
+    }
+}
+
+/// Converts a JSON value that is not an object to a cell value. Arrays are
+/// kept as JSON text.
+fn json_cell_value(value: Value) -> CellValue {
+    match value {
+        Value::Null => CellValue::Blank,
+        Value::Bool(value) => CellValue::Logical(value),
+        Value::Number(number) => match BigDecimal::from_str(&number.to_string()) {
+            Ok(number) => CellValue::Number(number),
+            Err(_) => CellValue::Text(number.to_string()),
+        },
+        Value::String(text) if text.is_empty() => CellValue::Blank,
+        Value::String(text) => CellValue::Text(text),
+        value => CellValue::Text(serde_json::to_string(&value).unwrap_or_default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_flatten_json() {
+        let mut headers = JsonHeaders::default();
+        let mut row = vec![];
+        let record = parse(
+            r#"{
+                "name": "Ada",
+                "address": { "city": "London", "geo": { "lat": 51.5 } },
+                "tags": ["a", 1, { "z": 1, "y": 2 }],
+                "active": true,
+                "manager": null
+            }"#,
+        );
+        flatten_json(record, String::new(), &mut headers, &mut row);
+        assert_eq!(
+            headers.names,
+            vec![
+                "name",
+                "address.city",
+                "address.geo.lat",
+                "tags",
+                "active",
+                "manager"
+            ]
+        );
+        assert_eq!(
+            row,
+            vec![
+                (0, CellValue::Text("Ada".into())),
+                (1, CellValue::Text("London".into())),
+                (2, CellValue::Number(BigDecimal::from_str("51.5").unwrap())),
+                (3, CellValue::Text(r#"["a",1,{"z":1,"y":2}]"#.into())),
+                (4, CellValue::Logical(true)),
+                (5, CellValue::Blank),
+            ]
+        );
+
+        // fields seen before keep their column
+        let mut row = vec![];
+        flatten_json(
+            parse(r#"{ "age": 36, "name": "Grace" }"#),
+            String::new(),
+            &mut headers,
+            &mut row,
+        );
+        assert_eq!(headers.names.len(), 7);
+        assert_eq!(
+            row,
+            vec![
+                (6, CellValue::Number(36.into())),
+                (0, CellValue::Text("Grace".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_json_operations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let pos = Pos { x: 1, y: 2 };
+
+        let mut values = CellValues::new(3, 2);
+        values.set(0, 0, CellValue::Number(1.into()));
+        values.set(1, 0, CellValue::Text("x".into()));
+        values.set(2, 1, CellValue::Logical(false));
+
+        let array = r#"[{ "a": 1, "b": { "c": "x" } }, { "d": false }]"#;
+        let ndjson = "{\"a\": 1, \"b\": {\"c\": \"x\"}}\n\n{\"d\": false}\n";
+        for file in [array, ndjson] {
+            let ops = gc
+                .import_json_operations(sheet_id, file.as_bytes(), "test.json", pos)
+                .unwrap();
+            assert_eq!(
+                ops,
+                vec![
+                    Operation::SetCellValues {
+                        sheet_pos: SheetPos {
+                            x: 1,
+                            y: 2,
+                            sheet_id
+                        },
+                        values: CellValues::from(vec![vec!["a"], vec!["b.c"], vec!["d"]]),
+                    },
+                    Operation::SetCellValues {
+                        sheet_pos: SheetPos {
+                            x: 1,
+                            y: 3,
+                            sheet_id
+                        },
+                        values: values.clone(),
+                    },
+                ]
+            );
+        }
+
+        // records that are not objects are in one column
+        let ops = gc
+            .import_json_operations(sheet_id, b"[1, \"two\"]", "test.json", pos)
+            .unwrap();
+        assert_eq!(ops.len(), 2);
+
+        let error = gc
+            .import_json_operations(sheet_id, b"{\"a\": 1}\n{\"a\": ", "bad.json", pos)
+            .unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+        assert!(gc
+            .import_json_operations(sheet_id, b"[]", "empty.json", pos)
+            .is_err());
+    }
+}
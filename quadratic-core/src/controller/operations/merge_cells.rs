@@ -0,0 +1,100 @@
+use super::operation::Operation;
+use crate::{controller::GridController, selection::Selection, Rect, SheetRect};
+
+impl GridController {
+    /// Returns the operations to merge the cells in `sheet_rect`.
+    ///
+    /// The region grows to cover any merged regions it overlaps, which are
+    /// unmerged first. Only the value of the top-left cell is kept.
+    pub fn merge_cells_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_rect.sheet_id) else {
+            return vec![];
+        };
+        let rect = sheet.expand_to_merged_cells(sheet_rect.into());
+        if rect.len() < 2 {
+            return vec![];
+        }
+        let mut ops = self.unmerge_cells_operations(rect.to_sheet_rect(sheet.id));
+
+        // every cell except the top-left one
+        let rects = [
+            Rect::new(rect.min.x + 1, rect.min.y, rect.max.x, rect.min.y),
+            Rect::new(rect.min.x, rect.min.y + 1, rect.max.x, rect.max.y),
+        ]
+        .into_iter()
+        .filter(|rect| rect.min.x <= rect.max.x && rect.min.y <= rect.max.y)
+        .collect();
+        ops.extend(self.delete_cells_operations(&Selection {
+            sheet_id: sheet.id,
+            x: rect.min.x,
+            y: rect.min.y,
+            rects: Some(rects),
+            ..Default::default()
+        }));
+
+        ops.push(Operation::MergeCells {
+            sheet_rect: rect.to_sheet_rect(sheet.id),
+        });
+        ops
+    }
+
+    /// Returns the operations to unmerge every merged region that intersects
+    /// `sheet_rect`.
+    pub fn unmerge_cells_operations(&self, sheet_rect: SheetRect) -> Vec<Operation> {
+        let Some(sheet) = self.try_sheet(sheet_rect.sheet_id) else {
+            return vec![];
+        };
+        sheet
+            .merged_rects_intersecting(sheet_rect.into())
+            .map(|merged| Operation::UnmergeCells {
+                sheet_rect: merged.to_sheet_rect(sheet.id),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cell_values::CellValues, CellValue, SheetPos};
+
+    #[test]
+    fn test_merge_cells_operations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id).merge_cells(Rect::new(2, 1, 3, 1));
+        gc.sheet_mut(sheet_id)
+            .set_cell_value(crate::Pos { x: 1, y: 1 }, "a");
+
+        let ops = gc.merge_cells_operations(SheetRect::from_numbers(0, 0, 3, 2, sheet_id));
+        let merged = Rect::new(0, 0, 3, 1).to_sheet_rect(sheet_id);
+        let mut values = CellValues::new(1, 1);
+        values.set(0, 0, CellValue::Blank);
+        assert_eq!(
+            ops,
+            vec![
+                Operation::UnmergeCells {
+                    sheet_rect: Rect::new(2, 1, 3, 1).to_sheet_rect(sheet_id),
+                },
+                Operation::SetCellValues {
+                    sheet_pos: SheetPos {
+                        x: 1,
+                        y: 1,
+                        sheet_id
+                    },
+                    values,
+                },
+                Operation::MergeCells { sheet_rect: merged },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_single_cell() {
+        let gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        assert!(gc
+            .merge_cells_operations(SheetRect::from_numbers(1, 1, 1, 1, sheet_id))
+            .is_empty());
+    }
+}
@@ -7,8 +7,15 @@ pub mod borders;
 pub mod cell_value;
 pub mod clipboard;
 pub mod code_cell;
+pub mod comments;
+pub mod conditional_formats;
+pub mod defined_names;
 pub mod formats;
 pub mod formatting;
 pub mod import;
+pub mod merge_cells;
 pub mod operation;
+pub mod outline;
 pub mod sheets;
+pub mod sort;
+pub mod validations;
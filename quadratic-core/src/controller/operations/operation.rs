@@ -1,13 +1,23 @@
 use core::fmt;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::{
     cell_values::CellValues,
     grid::{
-        file::sheet_schema::SheetSchema, formats::Formats, formatting::CellFmtArray, CodeRun,
-        Sheet, SheetBorders, SheetId,
+        defined_names::NameValue,
+        file::sheet_schema::SheetSchema,
+        formats::Formats,
+        formatting::CellFmtArray,
+        sheet::{
+            comments::{Comment, CommentThread},
+            conditional_formats::ConditionalFormat,
+            validations::Validation,
+        },
+        CodeRun, Sheet, SheetBorders, SheetId,
     },
     selection::Selection,
+    sheet_offsets::offsets::OutlineGroup,
     SheetPos, SheetRect,
 };
 
@@ -73,6 +83,11 @@ pub enum Operation {
         sheet_id: SheetId,
         color: Option<String>,
     },
+    SetSheetFrozenPanes {
+        sheet_id: SheetId,
+        frozen_columns: u32,
+        frozen_rows: u32,
+    },
     ReorderSheet {
         target: SheetId,
         order: String,
@@ -101,6 +116,115 @@ pub enum Operation {
         client_resized: bool,
     },
 
+    // Hidden columns/rows and outline groups. The Set*Groups operations
+    // replace all of a sheet's column/row groups.
+    SetColumnsHidden {
+        sheet_id: SheetId,
+        columns: Vec<i64>,
+        hidden: bool,
+    },
+    SetRowsHidden {
+        sheet_id: SheetId,
+        rows: Vec<i64>,
+        hidden: bool,
+    },
+    SetColumnGroups {
+        sheet_id: SheetId,
+        groups: Vec<OutlineGroup>,
+    },
+    SetRowGroups {
+        sheet_id: SheetId,
+        groups: Vec<OutlineGroup>,
+    },
+
+    // Column and row operations. These shift the cells after the inserted or
+    // deleted column/row and rewrite cell references in formulas.
+    InsertColumn {
+        sheet_id: SheetId,
+        column: i64,
+    },
+    DeleteColumn {
+        sheet_id: SheetId,
+        column: i64,
+    },
+    InsertRow {
+        sheet_id: SheetId,
+        row: i64,
+    },
+    DeleteRow {
+        sheet_id: SheetId,
+        row: i64,
+    },
+
+    // Merged cells. MergeCells does nothing if the region overlaps an existing
+    // merged region, and UnmergeCells only removes a merged region with
+    // exactly the same bounds.
+    MergeCells {
+        sheet_rect: SheetRect,
+    },
+    UnmergeCells {
+        sheet_rect: SheetRect,
+    },
+
+    // Data validations. SetValidation replaces the validation with the same
+    // id, or otherwise inserts it at `index` (or at the end). The index is
+    // only used to restore a removed validation's priority.
+    SetValidation {
+        sheet_id: SheetId,
+        validation: Validation,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        index: Option<usize>,
+    },
+    RemoveValidation {
+        sheet_id: SheetId,
+        validation_id: Uuid,
+    },
+
+    // Conditional formats. These follow the same rules as the validation
+    // operations.
+    SetConditionalFormat {
+        sheet_id: SheetId,
+        conditional_format: ConditionalFormat,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        index: Option<usize>,
+    },
+    RemoveConditionalFormat {
+        sheet_id: SheetId,
+        conditional_format_id: Uuid,
+    },
+
+    // Cell comments. AddComment starts a thread or replies to the cell's
+    // existing thread. Deleting the first comment of a thread deletes the
+    // thread. SetCommentThread replaces all of a cell's comments and is used
+    // to undo deletes and to move comments with their cells.
+    AddComment {
+        sheet_pos: SheetPos,
+        comment: Comment,
+    },
+    EditComment {
+        sheet_pos: SheetPos,
+        comment_id: Uuid,
+        text: String,
+    },
+    ResolveComments {
+        sheet_pos: SheetPos,
+        resolved: bool,
+    },
+    DeleteComment {
+        sheet_pos: SheetPos,
+        comment_id: Uuid,
+    },
+    SetCommentThread {
+        sheet_pos: SheetPos,
+        thread: Option<CommentThread>,
+    },
+
+    // Defines a name for the whole file, or removes it if value is None.
+    SetDefinedName {
+        name: String,
+        value: Option<NameValue>,
+    },
+
     // Deprecated in favor of SetCursorSelection. This operation remains to
     // support offline operations for now.
     SetCursor {
@@ -160,6 +284,15 @@ impl fmt::Display for Operation {
                 "SetSheetColor {{ sheet_id: {}, color: {:?} }}",
                 sheet_id, color
             ),
+            Operation::SetSheetFrozenPanes {
+                sheet_id,
+                frozen_columns,
+                frozen_rows,
+            } => write!(
+                fmt,
+                "SetSheetFrozenPanes {{ sheet_id: {}, frozen_columns: {}, frozen_rows: {} }}",
+                sheet_id, frozen_columns, frozen_rows
+            ),
             Operation::ReorderSheet { target, order } => write!(
                 fmt,
                 "ReorderSheet {{ target: {}, order: {} }}",
@@ -185,6 +318,130 @@ impl fmt::Display for Operation {
                 "ResizeRow {{ sheet_id: {}, row: {}, new_size: {}, client_resized: {} }}",
                 sheet_id, row, new_size, client_resized
             ),
+            Operation::InsertColumn { sheet_id, column } => write!(
+                fmt,
+                "InsertColumn {{ sheet_id: {}, column: {} }}",
+                sheet_id, column
+            ),
+            Operation::DeleteColumn { sheet_id, column } => write!(
+                fmt,
+                "DeleteColumn {{ sheet_id: {}, column: {} }}",
+                sheet_id, column
+            ),
+            Operation::InsertRow { sheet_id, row } => {
+                write!(fmt, "InsertRow {{ sheet_id: {}, row: {} }}", sheet_id, row)
+            }
+            Operation::DeleteRow { sheet_id, row } => {
+                write!(fmt, "DeleteRow {{ sheet_id: {}, row: {} }}", sheet_id, row)
+            }
+            Operation::MergeCells { sheet_rect } => {
+                write!(fmt, "MergeCells {{ sheet_rect: {} }}", sheet_rect)
+            }
+            Operation::UnmergeCells { sheet_rect } => {
+                write!(fmt, "UnmergeCells {{ sheet_rect: {} }}", sheet_rect)
+            }
+            Operation::SetValidation {
+                sheet_id,
+                validation,
+                index,
+            } => write!(
+                fmt,
+                "SetValidation {{ sheet_id: {}, validation: {:?}, index: {:?} }}",
+                sheet_id, validation, index
+            ),
+            Operation::RemoveValidation {
+                sheet_id,
+                validation_id,
+            } => write!(
+                fmt,
+                "RemoveValidation {{ sheet_id: {}, validation_id: {} }}",
+                sheet_id, validation_id
+            ),
+            Operation::SetConditionalFormat {
+                sheet_id,
+                conditional_format,
+                index,
+            } => write!(
+                fmt,
+                "SetConditionalFormat {{ sheet_id: {}, conditional_format: {:?}, index: {:?} }}",
+                sheet_id, conditional_format, index
+            ),
+            Operation::RemoveConditionalFormat {
+                sheet_id,
+                conditional_format_id,
+            } => write!(
+                fmt,
+                "RemoveConditionalFormat {{ sheet_id: {}, conditional_format_id: {} }}",
+                sheet_id, conditional_format_id
+            ),
+            Operation::AddComment { sheet_pos, comment } => write!(
+                fmt,
+                "AddComment {{ sheet_pos: {}, comment: {:?} }}",
+                sheet_pos, comment
+            ),
+            Operation::EditComment {
+                sheet_pos,
+                comment_id,
+                text,
+            } => write!(
+                fmt,
+                "EditComment {{ sheet_pos: {}, comment_id: {}, text: {} }}",
+                sheet_pos, comment_id, text
+            ),
+            Operation::ResolveComments {
+                sheet_pos,
+                resolved,
+            } => write!(
+                fmt,
+                "ResolveComments {{ sheet_pos: {}, resolved: {} }}",
+                sheet_pos, resolved
+            ),
+            Operation::DeleteComment {
+                sheet_pos,
+                comment_id,
+            } => write!(
+                fmt,
+                "DeleteComment {{ sheet_pos: {}, comment_id: {} }}",
+                sheet_pos, comment_id
+            ),
+            Operation::SetCommentThread { sheet_pos, thread } => write!(
+                fmt,
+                "SetCommentThread {{ sheet_pos: {}, thread: {:?} }}",
+                sheet_pos, thread
+            ),
+            Operation::SetDefinedName { name, value } => write!(
+                fmt,
+                "SetDefinedName {{ name: {}, value: {:?} }}",
+                name, value
+            ),
+            Operation::SetColumnsHidden {
+                sheet_id,
+                columns,
+                hidden,
+            } => write!(
+                fmt,
+                "SetColumnsHidden {{ sheet_id: {}, columns: {:?}, hidden: {} }}",
+                sheet_id, columns, hidden
+            ),
+            Operation::SetRowsHidden {
+                sheet_id,
+                rows,
+                hidden,
+            } => write!(
+                fmt,
+                "SetRowsHidden {{ sheet_id: {}, rows: {:?}, hidden: {} }}",
+                sheet_id, rows, hidden
+            ),
+            Operation::SetColumnGroups { sheet_id, groups } => write!(
+                fmt,
+                "SetColumnGroups {{ sheet_id: {}, groups: {:?} }}",
+                sheet_id, groups
+            ),
+            Operation::SetRowGroups { sheet_id, groups } => write!(
+                fmt,
+                "SetRowGroups {{ sheet_id: {}, groups: {:?} }}",
+                sheet_id, groups
+            ),
             Operation::SetBorders { .. } => write!(fmt, "SetBorders {{ todo }}"),
             Operation::SetCursor { sheet_rect } => {
                 write!(fmt, "SetCursor {{ sheet_rect: {} }}", sheet_rect)
@@ -0,0 +1,226 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    formulas::EXCEL_MAX_ROWS,
+    grid::SheetId,
+    sheet_offsets::offsets::{OutlineGroup, MAX_OUTLINE_LEVEL},
+    Axis,
+};
+
+impl GridController {
+    /// Returns the operations to hide or show columns (`Axis::X`) or rows
+    /// (`Axis::Y`).
+    pub fn set_hidden_operations(
+        &self,
+        sheet_id: SheetId,
+        axis: Axis,
+        indices: Vec<i64>,
+        hidden: bool,
+    ) -> Vec<Operation> {
+        if indices.is_empty() {
+            return vec![];
+        }
+        vec![match axis {
+            Axis::X => Operation::SetColumnsHidden {
+                sheet_id,
+                columns: indices,
+                hidden,
+            },
+            Axis::Y => Operation::SetRowsHidden {
+                sheet_id,
+                rows: indices,
+                hidden,
+            },
+        }]
+    }
+
+    /// Returns the operations to group the columns/rows in `start..=end`.
+    ///
+    /// The new group must nest with the existing groups, may not be nested
+    /// deeper than [`MAX_OUTLINE_LEVEL`], and may not extend further from the
+    /// origin than the number of rows in an Excel sheet.
+    pub fn group_operations(
+        &self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Operation>> {
+        let bounds = -EXCEL_MAX_ROWS..=EXCEL_MAX_ROWS;
+        if !bounds.contains(&start) || !bounds.contains(&end) {
+            bail!("Groups may not extend past column/row {EXCEL_MAX_ROWS}");
+        }
+        let mut groups = self.outline_groups(sheet_id, axis)?;
+        let group = OutlineGroup::new(start, end);
+        if groups
+            .iter()
+            .any(|other| other.start == group.start && other.end == group.end)
+        {
+            bail!("Group already exists");
+        }
+        if !groups.iter().all(|other| other.nests_with(&group)) {
+            bail!("Groups may not partially overlap");
+        }
+        groups.push(group);
+
+        // the deepest level is reached by the innermost groups, which are
+        // found by checking each group's endpoints
+        let too_deep = groups.iter().any(|candidate| {
+            [candidate.start, candidate.end].iter().any(|&index| {
+                groups.iter().filter(|group| group.contains(index)).count() > MAX_OUTLINE_LEVEL
+            })
+        });
+        if too_deep {
+            bail!("Groups may not be nested more than {MAX_OUTLINE_LEVEL} levels deep");
+        }
+        Ok(vec![Self::set_groups_operation(sheet_id, axis, groups)])
+    }
+
+    /// Returns the operations to remove the group that spans exactly
+    /// `start..=end`.
+    pub fn ungroup_operations(
+        &self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Operation>> {
+        let mut groups = self.outline_groups(sheet_id, axis)?;
+        let group = OutlineGroup::new(start, end);
+        let len = groups.len();
+        groups.retain(|other| other.start != group.start || other.end != group.end);
+        if groups.len() == len {
+            bail!("Group not found");
+        }
+        Ok(vec![Self::set_groups_operation(sheet_id, axis, groups)])
+    }
+
+    /// Returns the operations to collapse or expand the group that spans
+    /// exactly `start..=end`.
+    pub fn set_group_collapsed_operations(
+        &self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        end: i64,
+        collapsed: bool,
+    ) -> Result<Vec<Operation>> {
+        let mut groups = self.outline_groups(sheet_id, axis)?;
+        let group = OutlineGroup::new(start, end);
+        let Some(existing) = groups
+            .iter_mut()
+            .find(|other| other.start == group.start && other.end == group.end)
+        else {
+            bail!("Group not found");
+        };
+        if existing.collapsed == collapsed {
+            return Ok(vec![]);
+        }
+        existing.collapsed = collapsed;
+        Ok(vec![Self::set_groups_operation(sheet_id, axis, groups)])
+    }
+
+    fn outline_groups(&self, sheet_id: SheetId, axis: Axis) -> Result<Vec<OutlineGroup>> {
+        let sheet = self
+            .try_sheet(sheet_id)
+            .ok_or_else(|| anyhow!("Sheet not found"))?;
+        Ok(match axis {
+            Axis::X => sheet.offsets.column_groups().to_vec(),
+            Axis::Y => sheet.offsets.row_groups().to_vec(),
+        })
+    }
+
+    fn set_groups_operation(sheet_id: SheetId, axis: Axis, groups: Vec<OutlineGroup>) -> Operation {
+        match axis {
+            Axis::X => Operation::SetColumnGroups { sheet_id, groups },
+            Axis::Y => Operation::SetRowGroups { sheet_id, groups },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_operations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        let ops = gc.group_operations(sheet_id, Axis::X, 5, 2).unwrap();
+        assert_eq!(
+            ops,
+            vec![Operation::SetColumnGroups {
+                sheet_id,
+                groups: vec![OutlineGroup::new(2, 5)]
+            }]
+        );
+
+        gc.sheet_mut(sheet_id)
+            .offsets
+            .set_column_groups(vec![OutlineGroup::new(2, 5)]);
+        assert!(gc.group_operations(sheet_id, Axis::X, 2, 5).is_err());
+        assert!(gc.group_operations(sheet_id, Axis::X, 4, 8).is_err());
+        assert!(gc.group_operations(sheet_id, Axis::X, 3, 4).is_ok());
+        assert!(gc.group_operations(sheet_id, Axis::X, 0, 10).is_ok());
+
+        // rows are separate from columns
+        assert!(gc.group_operations(sheet_id, Axis::Y, 4, 8).is_ok());
+
+        // groups may not be unbounded
+        assert!(gc
+            .group_operations(sheet_id, Axis::Y, 20, i64::MAX)
+            .is_err());
+        let past_min = -EXCEL_MAX_ROWS - 1;
+        assert!(gc.group_operations(sheet_id, Axis::Y, past_min, 0).is_err());
+        let max = EXCEL_MAX_ROWS;
+        assert!(gc.group_operations(sheet_id, Axis::Y, 20, max).is_ok());
+    }
+
+    #[test]
+    fn test_group_operations_max_level() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let groups = (0..MAX_OUTLINE_LEVEL as i64)
+            .map(|level| OutlineGroup::new(level, 20 - level))
+            .collect();
+        gc.sheet_mut(sheet_id).offsets.set_row_groups(groups);
+
+        assert!(gc.group_operations(sheet_id, Axis::Y, 10, 10).is_err());
+        assert!(gc.group_operations(sheet_id, Axis::Y, 21, 25).is_ok());
+    }
+
+    #[test]
+    fn test_ungroup_and_collapse_operations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        assert!(gc.ungroup_operations(sheet_id, Axis::Y, 0, 1).is_err());
+
+        gc.sheet_mut(sheet_id)
+            .offsets
+            .set_row_groups(vec![OutlineGroup::new(0, 1)]);
+        let mut collapsed = OutlineGroup::new(0, 1);
+        collapsed.collapsed = true;
+        assert_eq!(
+            gc.set_group_collapsed_operations(sheet_id, Axis::Y, 0, 1, true)
+                .unwrap(),
+            vec![Operation::SetRowGroups {
+                sheet_id,
+                groups: vec![collapsed]
+            }]
+        );
+        assert!(gc
+            .set_group_collapsed_operations(sheet_id, Axis::Y, 0, 1, false)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            gc.ungroup_operations(sheet_id, Axis::Y, 1, 0).unwrap(),
+            vec![Operation::SetRowGroups {
+                sheet_id,
+                groups: vec![]
+            }]
+        );
+    }
+}
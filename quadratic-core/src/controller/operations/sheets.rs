@@ -22,6 +22,19 @@ impl GridController {
         vec![Operation::SetSheetColor { sheet_id, color }]
     }
 
+    pub fn set_sheet_frozen_panes_operations(
+        &mut self,
+        sheet_id: SheetId,
+        frozen_columns: u32,
+        frozen_rows: u32,
+    ) -> Vec<Operation> {
+        vec![Operation::SetSheetFrozenPanes {
+            sheet_id,
+            frozen_columns,
+            frozen_rows,
+        }]
+    }
+
     /// Returns all sheet names
     pub fn sheet_names(&self) -> Vec<&str> {
         self.grid.sheets().iter().map(|s| s.name.as_str()).collect()
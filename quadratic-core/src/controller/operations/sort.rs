@@ -0,0 +1,202 @@
+use anyhow::{anyhow, bail, Result};
+
+use super::operation::Operation;
+use crate::{
+    cell_values::CellValues,
+    controller::GridController,
+    formulas::{replace_a1_notation, replace_internal_cell_references},
+    grid::{formats::Formats, sheet::sort::SortKey, CodeCellLanguage},
+    selection::Selection,
+    CellValue, Pos, Rect, SheetPos, SheetRect,
+};
+
+impl GridController {
+    /// Returns the operations to sort the rows of `sheet_rect` by `keys`.
+    /// Values, formats and borders move with their rows. Code cells that move
+    /// have their relative references adjusted and are rerun.
+    ///
+    /// Ranges that overlap the output of a code cell that spills into more
+    /// than one cell may not be sorted, since the output cannot be moved.
+    pub fn sort_range_operations(
+        &self,
+        sheet_rect: SheetRect,
+        keys: &[SortKey],
+    ) -> Result<Vec<Operation>> {
+        let sheet = self
+            .try_sheet(sheet_rect.sheet_id)
+            .ok_or_else(|| anyhow!("Sheet not found"))?;
+        if keys.is_empty() {
+            bail!("At least one sort key is required");
+        }
+        if let Some(key) = keys
+            .iter()
+            .find(|key| !sheet_rect.x_range().contains(&key.column))
+        {
+            bail!("Sort column {} is outside the range", key.column);
+        }
+
+        let rect: Rect = sheet_rect.into();
+        if sheet
+            .iter_code_output_in_rect(rect)
+            .any(|(output_rect, _)| output_rect.min != output_rect.max)
+        {
+            bail!("Cannot sort a range that contains part of a code cell's output");
+        }
+
+        let order = sheet.sort_order(rect, keys);
+        if order.iter().enumerate().all(|(new, old)| new == *old) {
+            return Ok(vec![]);
+        }
+
+        let mut values = CellValues::new(rect.width(), rect.height());
+        let mut moved_code = vec![];
+        for (new_row, old_row) in order.iter().enumerate() {
+            let old_y = rect.min.y + *old_row as i64;
+            let new_y = rect.min.y + new_row as i64;
+            for x in rect.x_range() {
+                let Some(mut value) = sheet.cell_value(Pos { x, y: old_y }) else {
+                    continue;
+                };
+                if let CellValue::Code(code_cell) = &mut value {
+                    if old_y != new_y {
+                        if matches!(code_cell.language, CodeCellLanguage::Formula) {
+                            code_cell.code = replace_internal_cell_references(
+                                &replace_a1_notation(&code_cell.code, Pos { x, y: old_y }),
+                                Pos { x, y: new_y },
+                            );
+                        }
+                        moved_code.push(SheetPos {
+                            x,
+                            y: new_y,
+                            sheet_id: sheet.id,
+                        });
+                    }
+                }
+                values.set((x - rect.min.x) as u32, new_row as u32, value);
+            }
+        }
+
+        // formats are ordered by column, then row
+        let mut formats = Formats::default();
+        for x in rect.x_range() {
+            for old_row in order.iter() {
+                let format = sheet.format_cell(x, rect.min.y + *old_row as i64, false);
+                formats.push(format.to_replace());
+            }
+        }
+
+        let mut ops = vec![
+            Operation::SetCellValues {
+                sheet_pos: rect.min.to_sheet_pos(sheet.id),
+                values,
+            },
+            Operation::SetCellFormatsSelection {
+                selection: Selection::sheet_rect(sheet_rect),
+                formats,
+            },
+        ];
+        if let Some(borders) = sheet.sorted_borders(rect, &order) {
+            ops.push(Operation::SetBorders {
+                sheet_rect,
+                borders,
+            });
+        }
+        ops.extend(
+            moved_code
+                .into_iter()
+                .map(|sheet_pos| Operation::ComputeCode { sheet_pos }),
+        );
+        Ok(ops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::sheet::sort::SortDirection;
+
+    #[test]
+    fn test_sort_range_operations() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id)
+            .test_set_values(0, 0, 1, 3, vec!["3", "1", "2"]);
+        let key = SortKey {
+            column: 0,
+            direction: SortDirection::Ascending,
+            case_sensitive: false,
+        };
+        let sheet_rect = SheetRect::from_numbers(0, 0, 1, 3, sheet_id);
+
+        let ops = gc
+            .sort_range_operations(sheet_rect, &[key.clone()])
+            .unwrap();
+        assert_eq!(ops.len(), 2);
+        let Operation::SetCellValues { sheet_pos, values } = &ops[0] else {
+            panic!("Expected Operation::SetCellValues");
+        };
+        assert_eq!(*sheet_pos, Pos { x: 0, y: 0 }.to_sheet_pos(sheet_id));
+        assert_eq!(values.get(0, 0), Some(&CellValue::Number(1.into())));
+        assert_eq!(values.get(0, 2), Some(&CellValue::Number(3.into())));
+
+        // already sorted
+        let ops = gc
+            .sort_range_operations(SheetRect::from_numbers(0, 1, 1, 2, sheet_id), &[key])
+            .unwrap();
+        assert!(ops.is_empty());
+
+        // key outside the range
+        let outside = SortKey {
+            column: 1,
+            direction: SortDirection::Ascending,
+            case_sensitive: false,
+        };
+        assert!(gc.sort_range_operations(sheet_rect, &[outside]).is_err());
+        assert!(gc.sort_range_operations(sheet_rect, &[]).is_err());
+    }
+
+    #[test]
+    fn test_sort_range_operations_code_output() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id)
+            .test_set_values(0, 0, 1, 3, vec!["3", "1", "2"]);
+        let key = SortKey {
+            column: 0,
+            direction: SortDirection::Ascending,
+            case_sensitive: false,
+        };
+
+        // a code cell with a single value moves with its row
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 0),
+            CodeCellLanguage::Formula,
+            "5 + 5".to_string(),
+            None,
+        );
+        let sheet_rect = SheetRect::from_numbers(0, 0, 2, 3, sheet_id);
+        let ops = gc
+            .sort_range_operations(sheet_rect, &[key.clone()])
+            .unwrap();
+        assert!(ops.contains(&Operation::ComputeCode {
+            sheet_pos: SheetPos::new(sheet_id, 1, 2),
+        }));
+
+        // a spill into the range can't be moved with the rows
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 2, 0),
+            CodeCellLanguage::Formula,
+            "{1; 2; 3}".to_string(),
+            None,
+        );
+        assert!(gc
+            .sort_range_operations(
+                SheetRect::from_numbers(0, 0, 3, 3, sheet_id),
+                &[key.clone()]
+            )
+            .is_err());
+        assert!(gc
+            .sort_range_operations(SheetRect::from_numbers(0, 1, 3, 2, sheet_id), &[key])
+            .is_err());
+    }
+}
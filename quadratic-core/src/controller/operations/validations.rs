@@ -0,0 +1,73 @@
+use uuid::Uuid;
+
+use super::operation::Operation;
+use crate::{
+    controller::GridController,
+    formulas::parse_formula,
+    grid::{
+        sheet::validations::{Validation, ValidationRule},
+        SheetId,
+    },
+};
+
+impl GridController {
+    /// Returns the operations to add a validation, or to replace the
+    /// validation with the same id. Custom formulas that do not parse are
+    /// ignored.
+    pub fn add_validation_operations(&self, validation: Validation) -> Vec<Operation> {
+        if let ValidationRule::Custom { formula } = &validation.rule {
+            if parse_formula(formula, validation.anchor()).is_err() {
+                return vec![];
+            }
+        }
+        vec![Operation::SetValidation {
+            sheet_id: validation.selection.sheet_id,
+            validation,
+            index: None,
+        }]
+    }
+
+    pub fn remove_validation_operations(
+        &self,
+        sheet_id: SheetId,
+        validation_id: Uuid,
+    ) -> Vec<Operation> {
+        vec![Operation::RemoveValidation {
+            sheet_id,
+            validation_id,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::selection::Selection;
+
+    #[test]
+    fn test_add_validation_operations() {
+        let gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let validation = Validation::new(
+            Selection::pos(0, 0, sheet_id),
+            ValidationRule::Custom {
+                formula: "A0 >".into(),
+            },
+            None,
+        );
+        assert!(gc.add_validation_operations(validation.clone()).is_empty());
+
+        let mut validation = validation;
+        validation.rule = ValidationRule::Custom {
+            formula: "A0 > 0".into(),
+        };
+        assert_eq!(
+            gc.add_validation_operations(validation.clone()),
+            vec![Operation::SetValidation {
+                sheet_id,
+                validation,
+                index: None,
+            }]
+        );
+    }
+}
@@ -67,6 +67,39 @@ impl GridController {
         }
     }
 
+    /// Sends a sheet's validation dropdowns and invalid cells to the client
+    pub fn send_validations(&self, sheet_id: SheetId) {
+        if !cfg!(target_family = "wasm") && !cfg!(test) {
+            return;
+        }
+        if let Ok(validations) = serde_json::to_string(&self.render_validations(sheet_id)) {
+            crate::wasm_bindings::js::jsSheetValidations(sheet_id.to_string(), validations);
+        }
+    }
+
+    /// Sends a sheet's comment indicators to the client
+    pub fn send_comments(&self, sheet_id: SheetId) {
+        if !cfg!(target_family = "wasm") && !cfg!(test) {
+            return;
+        }
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return;
+        };
+        if let Ok(comments) = serde_json::to_string(&sheet.get_render_comments()) {
+            crate::wasm_bindings::js::jsSheetComments(sheet_id.to_string(), comments);
+        }
+    }
+
+    /// Sends the file's defined names to the client
+    pub fn send_defined_names(&self) {
+        if !cfg!(target_family = "wasm") && !cfg!(test) {
+            return;
+        }
+        if let Ok(names) = serde_json::to_string(self.grid.defined_names()) {
+            crate::wasm_bindings::js::jsDefinedNames(names);
+        }
+    }
+
     /// Sends all fills to the client
     pub fn sheet_fills(&self, sheet_id: SheetId) -> Vec<JsRenderFill> {
         if let Some(sheet) = self.try_sheet(sheet_id) {
@@ -212,6 +245,9 @@ mod test {
             language: None,
             value: "test 1".to_string(),
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
             align: None,
             wrap: None,
             bold: None,
@@ -232,6 +268,9 @@ mod test {
             language: None,
             value: "test 2".to_string(),
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
             align: None,
             wrap: None,
             bold: None,
@@ -386,6 +425,9 @@ mod test {
             language: None,
             value: "test 1".to_string(),
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
             align: None,
             wrap: None,
             bold: None,
@@ -405,6 +447,9 @@ mod test {
             language: None,
             value: "test 2".to_string(),
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
             align: None,
             wrap: None,
             bold: None,
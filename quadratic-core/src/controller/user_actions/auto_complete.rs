@@ -609,4 +609,36 @@ mod tests {
         let result = grid.autocomplete(SheetId::new(), selected, range, None);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_autocomplete_merged_cells() {
+        let selected = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 2 });
+        let range = Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 6, y: 6 });
+        let mut grid = GridController::test();
+        let sheet_id = grid.sheet_ids()[0];
+        grid.merge_cells(SheetRect::from_numbers(0, 0, 2, 2, sheet_id), None);
+        grid.merge_cells(SheetRect::from_numbers(4, 4, 3, 1, sheet_id), None);
+
+        grid.autocomplete(sheet_id, selected, range, None).unwrap();
+        let mut merged_cells = grid.sheet(sheet_id).merged_cells.clone();
+        merged_cells.sort_by_key(|rect| (rect.min.y, rect.min.x));
+        assert_eq!(
+            merged_cells,
+            vec![
+                Rect::new(0, 0, 1, 1),
+                Rect::new(2, 0, 3, 1),
+                Rect::new(4, 0, 5, 1),
+                Rect::new(0, 3, 1, 4),
+                Rect::new(2, 3, 3, 4),
+                Rect::new(4, 3, 5, 4),
+            ]
+        );
+
+        grid.undo(None);
+        let sheet = grid.sheet(sheet_id);
+        assert_eq!(
+            sheet.merged_cells,
+            vec![Rect::new(0, 0, 1, 1), Rect::new(4, 4, 6, 4)]
+        );
+    }
 }
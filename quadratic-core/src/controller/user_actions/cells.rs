@@ -1,42 +1,59 @@
 use crate::controller::active_transactions::transaction_name::TransactionName;
 use crate::controller::GridController;
+use crate::grid::js_types::JsValidationFailure;
 
 use crate::selection::Selection;
 use crate::SheetPos;
 
 impl GridController {
-    /// Starts a transaction to set the value of a cell by converting a user's String input
-    pub fn set_cell_value(&mut self, sheet_pos: SheetPos, value: String, cursor: Option<String>) {
+    /// Starts a transaction to set the value of a cell by converting a user's String input.
+    /// Returns the failure instead if the input is rejected by a validation.
+    pub fn set_cell_value(
+        &mut self,
+        sheet_pos: SheetPos,
+        value: String,
+        cursor: Option<String>,
+    ) -> Option<JsValidationFailure> {
+        if let Some(failure) = self.validate_input(sheet_pos, &value) {
+            return Some(failure);
+        }
         let ops = self.set_cell_value_operations(sheet_pos, value);
         self.start_user_transaction(ops, cursor, TransactionName::SetCells);
+        None
     }
 
     /// Starts a transaction to set cell values using a 2d array of user's &str input where [[1, 2, 3], [4, 5, 6]] creates a grid of width 3 and height 2.
+    /// Values rejected by a validation are skipped and their failures are returned.
     pub fn set_cell_values(
         &mut self,
         sheet_pos: SheetPos,
         values: Vec<Vec<&str>>,
         cursor: Option<String>,
-    ) {
+    ) -> Vec<JsValidationFailure> {
         let mut ops = vec![];
+        let mut failures = vec![];
         let mut x = sheet_pos.x;
         let mut y = sheet_pos.y;
         for row in values {
             for value in row {
-                ops.extend(self.set_cell_value_operations(
-                    SheetPos {
-                        x,
-                        y,
-                        sheet_id: sheet_pos.sheet_id,
-                    },
-                    value.to_string(),
-                ));
+                let cell_pos = SheetPos {
+                    x,
+                    y,
+                    sheet_id: sheet_pos.sheet_id,
+                };
+                match self.validate_input(cell_pos, value) {
+                    Some(failure) => failures.push(failure),
+                    None => ops.extend(self.set_cell_value_operations(cell_pos, value.to_string())),
+                }
                 x += 1;
             }
             x = sheet_pos.x;
             y += 1;
         }
-        self.start_user_transaction(ops, cursor, TransactionName::SetCells);
+        if !ops.is_empty() {
+            self.start_user_transaction(ops, cursor, TransactionName::SetCells);
+        }
+        failures
     }
 
     /// Starts a transaction to deletes the cell values and code in a given rect and updates dependent cells.
@@ -812,6 +812,57 @@ mod test {
         );
     }
 
+    #[test]
+    fn move_and_cut_cells_with_comments() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_comment((1, 1, sheet_id).into(), "ann".into(), "note".into(), None);
+
+        gc.move_cells(
+            SheetRect::new_pos_span(Pos { x: 0, y: 0 }, Pos { x: 1, y: 1 }, sheet_id),
+            (5, 5, sheet_id).into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.comment_thread(Pos { x: 1, y: 1 }).is_none());
+        assert!(sheet.comment_thread(Pos { x: 6, y: 6 }).is_some());
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.comment_thread(Pos { x: 1, y: 1 }).is_some());
+        assert!(sheet.comment_thread(Pos { x: 6, y: 6 }).is_none());
+
+        let selection = Selection::rect(Rect::new(1, 1, 1, 1), sheet_id);
+        let (plain_text, html) = gc.cut_to_clipboard(&selection, None).unwrap();
+        assert!(gc.sheet(sheet_id).comments.is_empty());
+        gc.paste_from_clipboard(
+            Selection::rect(Rect::new(3, 0, 3, 0), sheet_id),
+            Some(plain_text),
+            Some(html),
+            PasteSpecial::None,
+            None,
+        );
+        let thread = gc
+            .sheet(sheet_id)
+            .comment_thread(Pos { x: 3, y: 0 })
+            .unwrap();
+        assert_eq!(thread.comments[0].text, "note");
+
+        // comments outside the copied cells of a column are not cut
+        gc.set_cell_value((1, 0, sheet_id).into(), "value".into(), None);
+        gc.add_comment((1, 0, sheet_id).into(), "ann".into(), "copied".into(), None);
+        gc.add_comment((1, 5, sheet_id).into(), "ann".into(), "kept".into(), None);
+        let selection = Selection {
+            sheet_id,
+            columns: Some(vec![1]),
+            ..Default::default()
+        };
+        gc.cut_to_clipboard(&selection, None).unwrap();
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.comment_thread(Pos { x: 1, y: 0 }).is_none());
+        assert!(sheet.comment_thread(Pos { x: 1, y: 5 }).is_some());
+    }
+
     #[test]
     fn copy_cell_formats() {
         let mut gc = GridController::test();
@@ -873,4 +924,55 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn copy_and_move_merged_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        set_cell_value(&mut gc, sheet_id, "merged", 1, 1);
+        gc.merge_cells(SheetRect::from_numbers(1, 1, 2, 2, sheet_id), None);
+
+        // a merged region partially in the selection is not copied
+        gc.merge_cells(SheetRect::from_numbers(3, 0, 1, 2, sheet_id), None);
+
+        let selection = Selection::rect(Rect::new(0, 1, 3, 2), sheet_id);
+        let (_, html) = gc.sheet(sheet_id).copy_to_clipboard(&selection).unwrap();
+        gc.paste_from_clipboard(
+            Selection::pos(10, 10, sheet_id),
+            None,
+            Some(html),
+            PasteSpecial::None,
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.merged_rect_at(Pos { x: 11, y: 10 }),
+            Some(Rect::new(11, 10, 12, 11))
+        );
+        assert_eq!(sheet.merged_rect_at(Pos { x: 13, y: 10 }), None);
+
+        gc.move_cells(
+            SheetRect::from_numbers(0, 0, 3, 3, sheet_id),
+            (20, 20, sheet_id).into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.merged_rect_at(Pos { x: 1, y: 1 }), None);
+        assert_eq!(
+            sheet.merged_rect_at(Pos { x: 21, y: 21 }),
+            Some(Rect::new(21, 21, 22, 22))
+        );
+        assert_eq!(
+            sheet.display_value(Pos { x: 21, y: 21 }),
+            Some(CellValue::Text("merged".into()))
+        );
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.merged_rect_at(Pos { x: 1, y: 1 }),
+            Some(Rect::new(1, 1, 2, 2))
+        );
+        assert_eq!(sheet.merged_rect_at(Pos { x: 21, y: 21 }), None);
+    }
 }
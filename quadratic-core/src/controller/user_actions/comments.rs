@@ -0,0 +1,58 @@
+use uuid::Uuid;
+
+use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::GridController;
+use crate::grid::sheet::comments::Comment;
+use crate::SheetPos;
+
+impl GridController {
+    /// Adds a comment to a cell, starting a thread or replying to the existing
+    /// one. Returns the id of the new comment.
+    pub fn add_comment(
+        &mut self,
+        sheet_pos: SheetPos,
+        author: String,
+        text: String,
+        cursor: Option<String>,
+    ) -> Uuid {
+        let comment = Comment::new(author, text);
+        let comment_id = comment.id;
+        let ops = self.add_comment_operations(sheet_pos, comment);
+        self.start_user_transaction(ops, cursor, TransactionName::Comments);
+        comment_id
+    }
+
+    pub fn edit_comment(
+        &mut self,
+        sheet_pos: SheetPos,
+        comment_id: Uuid,
+        text: String,
+        cursor: Option<String>,
+    ) {
+        let ops = self.edit_comment_operations(sheet_pos, comment_id, text);
+        self.start_user_transaction(ops, cursor, TransactionName::Comments);
+    }
+
+    /// Marks the comments on a cell as resolved (or reopens them).
+    pub fn resolve_comments(
+        &mut self,
+        sheet_pos: SheetPos,
+        resolved: bool,
+        cursor: Option<String>,
+    ) {
+        let ops = self.resolve_comments_operations(sheet_pos, resolved);
+        self.start_user_transaction(ops, cursor, TransactionName::Comments);
+    }
+
+    /// Deletes a comment. Deleting the first comment on a cell deletes its
+    /// replies too.
+    pub fn delete_comment(
+        &mut self,
+        sheet_pos: SheetPos,
+        comment_id: Uuid,
+        cursor: Option<String>,
+    ) {
+        let ops = self.delete_comment_operations(sheet_pos, comment_id);
+        self.start_user_transaction(ops, cursor, TransactionName::Comments);
+    }
+}
@@ -0,0 +1,119 @@
+use uuid::Uuid;
+
+use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::GridController;
+use crate::grid::{sheet::conditional_formats::ConditionalFormat, SheetId};
+
+impl GridController {
+    /// Adds a conditional format, or replaces the format with the same id.
+    pub fn add_conditional_format(
+        &mut self,
+        conditional_format: ConditionalFormat,
+        cursor: Option<String>,
+    ) {
+        let ops = self.add_conditional_format_operations(conditional_format);
+        self.start_user_transaction(ops, cursor, TransactionName::ConditionalFormats);
+    }
+
+    pub fn remove_conditional_format(
+        &mut self,
+        sheet_id: SheetId,
+        conditional_format_id: Uuid,
+        cursor: Option<String>,
+    ) {
+        let ops = self.remove_conditional_format_operations(sheet_id, conditional_format_id);
+        self.start_user_transaction(ops, cursor, TransactionName::ConditionalFormats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::sheet::conditional_formats::{
+            Comparison, ConditionalFormatRule, ConditionalFormatStyle,
+        },
+        selection::Selection,
+        Pos, Rect, SheetPos,
+    };
+
+    #[test]
+    #[serial]
+    fn test_conditional_format_follows_cell_changes() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 0,
+            sheet_id,
+        };
+        gc.set_cell_value(sheet_pos, "1".into(), None);
+
+        // B0 is bold when A0 is greater than 10
+        gc.add_conditional_format(
+            ConditionalFormat::new(
+                Selection::pos(1, 0, sheet_id),
+                ConditionalFormatRule::Formula {
+                    formula: "A0 > 10".into(),
+                },
+                ConditionalFormatStyle {
+                    bold: Some(true),
+                    ..Default::default()
+                },
+            ),
+            None,
+        );
+        let is_bold = |gc: &GridController| {
+            gc.sheet(sheet_id)
+                .get_render_cells(Rect::single_pos(Pos { x: 1, y: 0 }))[0]
+                .bold
+                == Some(true)
+        };
+        assert!(!is_bold(&gc));
+
+        gc.set_cell_value(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            "20".into(),
+            None,
+        );
+        assert!(is_bold(&gc));
+
+        gc.undo(None);
+        assert!(!is_bold(&gc));
+
+        // formulas that do not parse are rejected
+        let invalid = ConditionalFormat::new(
+            Selection::pos(1, 0, sheet_id),
+            ConditionalFormatRule::Formula {
+                formula: "A0 >".into(),
+            },
+            ConditionalFormatStyle::default(),
+        );
+        assert!(gc.add_conditional_format_operations(invalid).is_empty());
+
+        // value rules re-render as values change
+        gc.add_conditional_format(
+            ConditionalFormat::new(
+                Selection::pos(1, 0, sheet_id),
+                ConditionalFormatRule::Compare {
+                    comparison: Comparison::GreaterThan,
+                    value: 5.0,
+                },
+                ConditionalFormatStyle {
+                    bold: Some(true),
+                    ..Default::default()
+                },
+            ),
+            None,
+        );
+        assert!(!is_bold(&gc));
+        gc.set_cell_value(sheet_pos, "6".into(), None);
+        assert!(is_bold(&gc));
+    }
+}
@@ -0,0 +1,20 @@
+use anyhow::Result;
+
+use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::GridController;
+use crate::grid::defined_names::NameValue;
+
+impl GridController {
+    /// Defines `name` for the whole file, or removes it if `value` is `None`
+    /// (see [`GridController::set_defined_name_operations`]).
+    pub fn set_defined_name(
+        &mut self,
+        name: String,
+        value: Option<NameValue>,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.set_defined_name_operations(name, value)?;
+        self.start_user_transaction(ops, cursor, TransactionName::DefinedNames);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::operations::import::CsvImportOptions;
 use crate::controller::GridController;
 use crate::{grid::SheetId, Pos};
 use anyhow::Result;
@@ -11,19 +12,38 @@ impl GridController {
         file: &[u8],
         file_name: &str,
         insert_at: Pos,
+        options: &CsvImportOptions,
         cursor: Option<String>,
     ) -> Result<()> {
-        let ops = self.import_csv_operations(sheet_id, file, file_name, insert_at)?;
+        let ops = self.import_csv_operations(sheet_id, file, file_name, insert_at, options)?;
         self.start_user_transaction(ops, cursor, TransactionName::Import);
         Ok(())
     }
 
-    /// Imports an Excel file into the grid.
-    ///
-    /// Returns a [`TransactionSummary`].
+    /// Imports an Excel file into the grid. Features of the file that could
+    /// not be imported are reported to the client.
     pub fn import_excel(&mut self, file: Vec<u8>, file_name: &str) -> Result<()> {
-        let ops = self.import_excel_operations(file, file_name)?;
+        let (ops, unsupported) = self.import_excel_operations(file, file_name)?;
         self.server_apply_transaction(ops);
+        if (cfg!(target_family = "wasm") || cfg!(test)) && !unsupported.is_empty() {
+            if let Ok(report) = serde_json::to_string(&unsupported) {
+                crate::wasm_bindings::js::jsExcelImportReport(file_name, report);
+            }
+        }
+        Ok(())
+    }
+
+    /// Imports a JSON or newline-delimited JSON file into the grid.
+    pub fn import_json(
+        &mut self,
+        sheet_id: SheetId,
+        file: &[u8],
+        file_name: &str,
+        insert_at: Pos,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.import_json_operations(sheet_id, file, file_name, insert_at)?;
+        self.start_user_transaction(ops, cursor, TransactionName::Import);
         Ok(())
     }
 
@@ -46,12 +66,23 @@ impl GridController {
 mod tests {
 
     use std::fs::File;
-    use std::io::Read;
+    use std::io::{Read, Write};
+
+    use chrono::NaiveDate;
+    use serial_test::serial;
 
     use crate::{
-        test_util::{assert_cell_value_row, print_table},
-        wasm_bindings::js::clear_js_calls,
-        Rect,
+        color::Rgba,
+        grid::{
+            get_cell_borders_in_rect, js_types::JsUnsupportedExcelFeatures, BorderSelection,
+            BorderStyle, CellBorderLine, CodeCellLanguage, NumericFormatKind,
+        },
+        test_util::{
+            assert_cell_format_bold, assert_cell_format_bold_row, assert_cell_value_row,
+            print_table,
+        },
+        wasm_bindings::js::{clear_js_calls, expect_js_call},
+        CellValue, CodeCellValue, Rect, SheetPos, SheetRect,
     };
 
     use super::*;
@@ -76,8 +107,14 @@ mod tests {
         let sheet_id = grid_controller.grid.sheets()[0].id;
         let pos = Pos { x: 0, y: 0 };
 
-        let _ =
-            grid_controller.import_csv(sheet_id, scv_file.as_slice(), "smallpop.csv", pos, None);
+        let _ = grid_controller.import_csv(
+            sheet_id,
+            scv_file.as_slice(),
+            "smallpop.csv",
+            pos,
+            &CsvImportOptions::default(),
+            None,
+        );
 
         print_table(
             &grid_controller,
@@ -110,7 +147,14 @@ mod tests {
         let sheet_id = grid_controller.grid.sheets()[0].id;
         let pos = Pos { x: 0, y: 0 };
 
-        let result = grid_controller.import_csv(sheet_id, "".as_bytes(), "smallpop.csv", pos, None);
+        let result = grid_controller.import_csv(
+            sheet_id,
+            "".as_bytes(),
+            "smallpop.csv",
+            pos,
+            &CsvImportOptions::default(),
+            None,
+        );
         assert!(result.is_err());
     }
 
@@ -129,6 +173,7 @@ mod tests {
             csv.as_bytes(),
             "large.csv",
             Pos { x: 0, y: 0 },
+            &CsvImportOptions::default(),
             None,
         );
         assert!(result.is_ok());
@@ -145,6 +190,7 @@ mod tests {
                 csv.as_bytes(),
                 "bad line",
                 Pos { x: 0, y: 0 },
+                &CsvImportOptions::default(),
             )
             .unwrap();
         let op = &ops[0];
@@ -210,6 +256,17 @@ mod tests {
                 "Hello Red",
             ],
         );
+
+        // dates are imported as instants, and formulas as code cells
+        let sheet = grid_controller.sheet(sheet_id);
+        assert!(matches!(
+            sheet.cell_value(Pos { x: 5, y: 1 }),
+            Some(CellValue::Instant(_))
+        ));
+        assert!(matches!(
+            sheet.cell_value(Pos { x: 7, y: 1 }),
+            Some(CellValue::Code(_))
+        ));
     }
 
     #[test]
@@ -325,8 +382,15 @@ mod tests {
         let sheet_id = gc.grid.sheets()[0].id;
         let pos = Pos { x: 0, y: 0 };
 
-        gc.import_csv(sheet_id, scv_file.as_slice(), "test.csv", pos, None)
-            .expect("import_csv");
+        gc.import_csv(
+            sheet_id,
+            scv_file.as_slice(),
+            "test.csv",
+            pos,
+            &CsvImportOptions::default(),
+            None,
+        )
+        .expect("import_csv");
 
         print_table(&gc, sheet_id, Rect::new_span(pos, Pos { x: 3, y: 4 }));
 
@@ -342,8 +406,15 @@ mod tests {
         let sheet_id = gc.grid.sheets()[0].id;
         let pos = Pos { x: 0, y: 0 };
 
-        gc.import_csv(sheet_id, scv_file.as_slice(), "test.csv", pos, None)
-            .expect("import_csv");
+        gc.import_csv(
+            sheet_id,
+            scv_file.as_slice(),
+            "test.csv",
+            pos,
+            &CsvImportOptions::default(),
+            None,
+        )
+        .expect("import_csv");
 
         print_table(&gc, sheet_id, Rect::new_span(pos, Pos { x: 3, y: 4 }));
 
@@ -360,8 +431,15 @@ mod tests {
         let sheet_id = gc.grid.sheets()[0].id;
         let pos = Pos { x: 0, y: 0 };
 
-        gc.import_csv(sheet_id, scv_file.as_slice(), "test.csv", pos, None)
-            .expect("import_csv");
+        gc.import_csv(
+            sheet_id,
+            scv_file.as_slice(),
+            "test.csv",
+            pos,
+            &CsvImportOptions::default(),
+            None,
+        )
+        .expect("import_csv");
 
         print_table(&gc, sheet_id, Rect::new_span(pos, Pos { x: 2, y: 3 }));
 
@@ -370,6 +448,297 @@ mod tests {
         assert_cell_value_row(&gc, sheet_id, 0, 2, 2, vec!["0", " 2", " Valid"]);
     }
 
+    #[test]
+    fn imports_json() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.grid.sheets()[0].id;
+        let pos = Pos { x: 0, y: 0 };
+
+        let mut json = String::new();
+        for i in 0..10_001 {
+            json.push_str(&format!(
+                "{{\"id\": {i}, \"user\": {{\"name\": \"user{i}\", \"admin\": {}}}}}\n",
+                i == 0
+            ));
+        }
+        gc.import_json(sheet_id, json.as_bytes(), "users.json", pos, None)
+            .unwrap();
+
+        assert_cell_value_row(
+            &gc,
+            sheet_id,
+            0,
+            2,
+            0,
+            vec!["id", "user.name", "user.admin"],
+        );
+        assert_cell_value_row(&gc, sheet_id, 0, 2, 1, vec!["0", "user0", "TRUE"]);
+        assert_cell_value_row(
+            &gc,
+            sheet_id,
+            0,
+            2,
+            10_001,
+            vec!["10000", "user10000", "FALSE"],
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 2, y: 1 }),
+            Some(CellValue::Logical(true))
+        );
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 0 }), None);
+        gc.redo(None);
+        assert_cell_value_row(
+            &gc,
+            sheet_id,
+            0,
+            2,
+            0,
+            vec!["id", "user.name", "user.admin"],
+        );
+    }
+
+    #[test]
+    fn imports_csv_with_options() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.grid.sheets()[0].id;
+
+        // a Windows-1252 file with a title line, semicolons and decimal commas
+        let mut csv = vec![];
+        for line in [
+            "Sales report",
+            "name;code;amount",
+            "Caf\u{e9};007;1.234,5",
+            "Th\u{e9};010;some, text",
+        ] {
+            csv.extend(line.chars().map(|c| c as u8));
+            csv.push(b'\n');
+        }
+
+        let options = CsvImportOptions {
+            skip_rows: Some(1),
+            header_row: Some(true),
+            decimal_comma: Some(true),
+            ..Default::default()
+        };
+        gc.import_csv(
+            sheet_id,
+            &csv,
+            "sales.csv",
+            Pos { x: 0, y: 0 },
+            &options,
+            None,
+        )
+        .unwrap();
+        assert_cell_value_row(&gc, sheet_id, 0, 2, 0, vec!["name", "code", "amount"]);
+        assert_cell_value_row(&gc, sheet_id, 0, 2, 1, vec!["Café", "7", "1234.5"]);
+        assert_cell_value_row(&gc, sheet_id, 0, 2, 2, vec!["Thé", "10", "some, text"]);
+        assert_cell_format_bold_row(&gc, sheet_id, 0, 3, 0, vec![true, true, true, false]);
+        assert_cell_format_bold_row(&gc, sheet_id, 0, 2, 1, vec![false, false, false]);
+
+        let options = CsvImportOptions {
+            skip_rows: Some(1),
+            all_text: Some(true),
+            ..Default::default()
+        };
+        gc.import_csv(
+            sheet_id,
+            &csv,
+            "sales.csv",
+            Pos { x: 5, y: 0 },
+            &options,
+            None,
+        )
+        .unwrap();
+        assert_cell_value_row(&gc, sheet_id, 5, 7, 1, vec!["Café", "007", "1.234,5"]);
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 6, y: 2 }),
+            Some(CellValue::Text("010".into()))
+        );
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 6, y: 2 }), None);
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 0 }), None);
+        assert_cell_format_bold(&gc, sheet_id, 0, 0, false);
+    }
+
+    /// Returns a copy of an Excel file with one of its parts changed.
+    fn replace_excel_part(xlsx: &[u8], path: &str, replace: impl Fn(String) -> String) -> Vec<u8> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(xlsx)).unwrap();
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index).unwrap();
+            let name = file.name().to_string();
+            let mut contents = String::new();
+            file.read_to_string(&mut contents).unwrap();
+            if name == path {
+                contents = replace(contents);
+            }
+            writer
+                .start_file(name, zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(contents.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    #[serial]
+    fn imports_formulas_and_formats_from_excel() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.add_sheet(None);
+        let sheet_id_2 = gc.sheet_ids()[1];
+        let sheet_pos = |x, y| SheetPos { x, y, sheet_id };
+        gc.set_cell_value(sheet_pos(0, 0), "1".into(), None);
+        gc.set_cell_value(sheet_pos(0, 1), "2".into(), None);
+        for (x, y, sheet_id, code) in [
+            (1, 0, sheet_id, "A0 + $A$1"),
+            (2, 0, sheet_id, "'Sheet 2'!A0 + 1"),
+            (3, 0, sheet_id, "{1, 2; 3, 4}"),
+            (0, 0, sheet_id_2, "'Sheet 1'!B0 * 2"),
+        ] {
+            gc.set_code_cell(
+                SheetPos { x, y, sheet_id },
+                CodeCellLanguage::Formula,
+                code.into(),
+                None,
+            );
+        }
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        gc.sheet_mut(sheet_id)
+            .set_cell_value(Pos { x: 0, y: 2 }, CellValue::Instant(date.into()));
+        let cell = SheetRect::single_pos(Pos { x: 0, y: 0 }, sheet_id);
+        gc.set_cell_bold(cell, Some(true), None);
+        gc.set_currency(&cell, Some("€".into()), None);
+        gc.set_borders(
+            cell,
+            vec![BorderSelection::Bottom],
+            Some(BorderStyle {
+                color: Rgba::color_from_str("#ff0000").unwrap(),
+                line: CellBorderLine::Line2,
+            }),
+            None,
+        );
+        gc.set_cell_fill_color(
+            SheetRect::single_pos(Pos { x: 0, y: 1 }, sheet_id),
+            Some("#00ff00".into()),
+            None,
+        );
+        gc.commit_single_resize(sheet_id, Some(0), None, 200.0, None);
+        gc.merge_cells(SheetRect::from_numbers(5, 5, 2, 1, sheet_id), None);
+        let xlsx = gc.export_xlsx().unwrap();
+
+        let mut imported = GridController::test_blank();
+        imported.import_excel(xlsx.clone(), "export.xlsx").unwrap();
+        let sheet = &imported.grid.sheets()[0];
+        let code = |pos: Pos| match sheet.cell_value(pos) {
+            Some(CellValue::Code(CodeCellValue { code, .. })) => code,
+            _ => panic!("expected a code cell at {pos:?}"),
+        };
+        let display = |pos: Pos| sheet.display_value(pos).unwrap().to_string();
+
+        // rows move up by one, and formulas that read from formulas on a
+        // later sheet still run after them
+        assert_eq!(code(Pos { x: 1, y: 0 }), "A0 + $A$1");
+        assert_eq!(display(Pos { x: 1, y: 0 }), "3");
+        assert_eq!(code(Pos { x: 2, y: 0 }), "\"Sheet 2\"!A0 + 1");
+        assert_eq!(display(Pos { x: 2, y: 0 }), "7");
+        assert_eq!(code(Pos { x: 3, y: 0 }), "{1, 2; 3, 4}");
+        assert_eq!(display(Pos { x: 4, y: 1 }), "4");
+        assert!(!sheet.code_run(Pos { x: 3, y: 0 }).unwrap().spill_error);
+        assert!(matches!(
+            sheet.cell_value(Pos { x: 0, y: 2 }),
+            Some(CellValue::Instant(instant)) if instant == date.into()
+        ));
+
+        let format = sheet.format_cell(0, 0, false);
+        assert_eq!(format.bold, Some(true));
+        let numeric_format = format.numeric_format.unwrap();
+        assert_eq!(numeric_format.kind, NumericFormatKind::Currency);
+        assert_eq!(numeric_format.symbol.as_deref(), Some("€"));
+        assert_eq!(
+            sheet.format_cell(0, 1, false).fill_color.as_deref(),
+            Some("#00ff00")
+        );
+        let borders = get_cell_borders_in_rect(sheet, Rect::new(0, 0, 0, 0), None);
+        let bottom = borders[0].2.as_ref().unwrap().borders[3].unwrap();
+        assert_eq!(bottom.line, CellBorderLine::Line2);
+        assert_eq!(bottom.color, Rgba::color_from_str("#ff0000").unwrap());
+        assert!((sheet.offsets.column_width(0) - 200.0).abs() < 0.1);
+        assert_eq!(
+            sheet.merged_rect_at(Pos { x: 6, y: 5 }),
+            Some(Rect::new(5, 5, 6, 5))
+        );
+        let sheet_2 = &imported.grid.sheets()[1];
+        assert_eq!(
+            sheet_2.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(6.into()))
+        );
+
+        // formulas that cannot be converted keep their values, and features
+        // that are not imported are reported
+        let xlsx = replace_excel_part(&xlsx, "xl/worksheets/sheet1.xml", |xml| {
+            xml.replace("<f>A1 + $A$2</f>", "<f>NOSUCHFUNCTION(A1)</f>")
+                .replace(
+                    "</worksheet>",
+                    r#"<hyperlinks><hyperlink ref="A1" r:id="rId1"/></hyperlinks></worksheet>"#,
+                )
+        });
+        let mut imported = GridController::test_blank();
+        imported.import_excel(xlsx, "export.xlsx").unwrap();
+        let sheet = &imported.grid.sheets()[0];
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        let report = vec![JsUnsupportedExcelFeatures {
+            sheet_name: "Sheet 1".into(),
+            features: vec![
+                "hyperlinks".into(),
+                "formulas in B1 (imported as values)".into(),
+            ],
+        }];
+        expect_js_call(
+            "jsExcelImportReport",
+            format!("export.xlsx,{}", serde_json::to_string(&report).unwrap()),
+            true,
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn imports_whole_column_formula_from_excel() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet_pos = |x, y| SheetPos { x, y, sheet_id };
+        gc.set_cell_value(sheet_pos(0, 0), "1".into(), None);
+        gc.set_cell_value(sheet_pos(0, 1), "2".into(), None);
+        // the whole column is read before the formula in it runs
+        for (x, code) in [(1, "SUM(C:C)"), (2, "A0 + A1")] {
+            gc.set_code_cell(
+                sheet_pos(x, 0),
+                CodeCellLanguage::Formula,
+                code.into(),
+                None,
+            );
+        }
+        let xlsx = gc.export_xlsx().unwrap();
+
+        let mut imported = GridController::test_blank();
+        imported.import_excel(xlsx, "export.xlsx").unwrap();
+        let sheet = &imported.grid.sheets()[0];
+        assert_eq!(
+            sheet.display_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+    }
+
     // #[test]
     // fn imports_a_large_parquet() {
     //     let mut grid_controller = GridController::test();
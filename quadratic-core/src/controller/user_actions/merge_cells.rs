@@ -0,0 +1,57 @@
+use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::GridController;
+use crate::SheetRect;
+
+impl GridController {
+    /// Merges the cells in `sheet_rect`, keeping only the top-left value.
+    pub fn merge_cells(&mut self, sheet_rect: SheetRect, cursor: Option<String>) {
+        let ops = self.merge_cells_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor, TransactionName::MergeCells);
+    }
+
+    /// Unmerges every merged region that intersects `sheet_rect`.
+    pub fn unmerge_cells(&mut self, sheet_rect: SheetRect, cursor: Option<String>) {
+        let ops = self.unmerge_cells_operations(sheet_rect);
+        self.start_user_transaction(ops, cursor, TransactionName::MergeCells);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{CellValue, Pos, Rect};
+
+    #[test]
+    #[serial]
+    fn test_merge_and_unmerge_cells() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(Pos { x: 0, y: 0 }.to_sheet_pos(sheet_id), "a".into(), None);
+        gc.set_cell_value(Pos { x: 1, y: 1 }.to_sheet_pos(sheet_id), "b".into(), None);
+
+        gc.merge_cells(SheetRect::from_numbers(0, 0, 2, 2, sheet_id), None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.merged_cells, vec![Rect::new(0, 0, 1, 1)]);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::from("a"))
+        );
+        assert_eq!(sheet.cell_value(Pos { x: 1, y: 1 }), None);
+
+        gc.unmerge_cells(SheetRect::single_pos(Pos { x: 1, y: 0 }, sheet_id), None);
+        assert!(gc.sheet(sheet_id).merged_cells.is_empty());
+
+        gc.undo(None);
+        assert_eq!(gc.sheet(sheet_id).merged_cells, vec![Rect::new(0, 0, 1, 1)]);
+
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.merged_cells.is_empty());
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::from("b"))
+        );
+    }
+}
@@ -5,8 +5,15 @@ pub mod borders;
 pub mod cells;
 pub mod clipboard;
 pub mod code;
+pub mod comments;
+pub mod conditional_formats;
+pub mod defined_names;
 pub mod formats;
 pub mod formatting;
 pub mod import;
+pub mod merge_cells;
+pub mod rows_columns;
 pub mod sheets;
+pub mod sort;
 pub mod undo;
+pub mod validations;
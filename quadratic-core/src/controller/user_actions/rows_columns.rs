@@ -0,0 +1,105 @@
+use crate::{
+    controller::{
+        active_transactions::transaction_name::TransactionName, operations::operation::Operation,
+        GridController,
+    },
+    grid::SheetId,
+    Axis,
+};
+use anyhow::Result;
+
+impl GridController {
+    /// Inserts an empty column before `column`, shifting the columns after it
+    /// to the right.
+    pub fn insert_column(&mut self, sheet_id: SheetId, column: i64, cursor: Option<String>) {
+        let ops = vec![Operation::InsertColumn { sheet_id, column }];
+        self.start_user_transaction(ops, cursor, TransactionName::ManipulateColumnRow);
+    }
+
+    /// Deletes a column, shifting the columns after it to the left.
+    pub fn delete_column(&mut self, sheet_id: SheetId, column: i64, cursor: Option<String>) {
+        let ops = vec![Operation::DeleteColumn { sheet_id, column }];
+        self.start_user_transaction(ops, cursor, TransactionName::ManipulateColumnRow);
+    }
+
+    /// Inserts an empty row before `row`, shifting the rows after it down.
+    pub fn insert_row(&mut self, sheet_id: SheetId, row: i64, cursor: Option<String>) {
+        let ops = vec![Operation::InsertRow { sheet_id, row }];
+        self.start_user_transaction(ops, cursor, TransactionName::ManipulateColumnRow);
+    }
+
+    /// Deletes a row, shifting the rows after it up.
+    pub fn delete_row(&mut self, sheet_id: SheetId, row: i64, cursor: Option<String>) {
+        let ops = vec![Operation::DeleteRow { sheet_id, row }];
+        self.start_user_transaction(ops, cursor, TransactionName::ManipulateColumnRow);
+    }
+
+    /// Hides or shows columns.
+    pub fn set_columns_hidden(
+        &mut self,
+        sheet_id: SheetId,
+        columns: Vec<i64>,
+        hidden: bool,
+        cursor: Option<String>,
+    ) {
+        let ops = self.set_hidden_operations(sheet_id, Axis::X, columns, hidden);
+        self.start_user_transaction(ops, cursor, TransactionName::HideColumnRow);
+    }
+
+    /// Hides or shows rows.
+    pub fn set_rows_hidden(
+        &mut self,
+        sheet_id: SheetId,
+        rows: Vec<i64>,
+        hidden: bool,
+        cursor: Option<String>,
+    ) {
+        let ops = self.set_hidden_operations(sheet_id, Axis::Y, rows, hidden);
+        self.start_user_transaction(ops, cursor, TransactionName::HideColumnRow);
+    }
+
+    /// Groups the columns or rows in `start..=end`. See
+    /// [`GridController::group_operations`].
+    pub fn group(
+        &mut self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        end: i64,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.group_operations(sheet_id, axis, start, end)?;
+        self.start_user_transaction(ops, cursor, TransactionName::GroupColumnRow);
+        Ok(())
+    }
+
+    /// Removes the group of columns or rows that spans exactly `start..=end`.
+    pub fn ungroup(
+        &mut self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        end: i64,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.ungroup_operations(sheet_id, axis, start, end)?;
+        self.start_user_transaction(ops, cursor, TransactionName::GroupColumnRow);
+        Ok(())
+    }
+
+    /// Collapses (hides) or expands the group of columns or rows that spans
+    /// exactly `start..=end`.
+    pub fn set_group_collapsed(
+        &mut self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        end: i64,
+        collapsed: bool,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.set_group_collapsed_operations(sheet_id, axis, start, end, collapsed)?;
+        self.start_user_transaction(ops, cursor, TransactionName::GroupColumnRow);
+        Ok(())
+    }
+}
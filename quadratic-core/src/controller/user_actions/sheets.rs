@@ -19,6 +19,19 @@ impl GridController {
         self.start_user_transaction(ops, cursor, TransactionName::SetSheetMetadata);
     }
 
+    /// Freezes the first `frozen_columns` columns and `frozen_rows` rows so
+    /// they stay in view when the sheet scrolls.
+    pub fn set_sheet_frozen_panes(
+        &mut self,
+        sheet_id: SheetId,
+        frozen_columns: u32,
+        frozen_rows: u32,
+        cursor: Option<String>,
+    ) {
+        let ops = self.set_sheet_frozen_panes_operations(sheet_id, frozen_columns, frozen_rows);
+        self.start_user_transaction(ops, cursor, TransactionName::SetSheetMetadata);
+    }
+
     pub fn add_sheet(&mut self, cursor: Option<String>) {
         let ops = self.add_sheet_operations(None);
         self.start_user_transaction(ops, cursor, TransactionName::SheetAdd);
@@ -0,0 +1,145 @@
+use anyhow::Result;
+
+use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::GridController;
+use crate::grid::sheet::sort::SortKey;
+use crate::SheetRect;
+
+impl GridController {
+    /// Sorts the rows of `sheet_rect` by `keys` (see
+    /// [`GridController::sort_range_operations`]).
+    pub fn sort_range(
+        &mut self,
+        sheet_rect: SheetRect,
+        keys: Vec<SortKey>,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.sort_range_operations(sheet_rect, &keys)?;
+        self.start_user_transaction(ops, cursor, TransactionName::SortRange);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::{
+            formats::format_update::FormatUpdate, sheet::sort::SortDirection, CodeCellLanguage,
+        },
+        CellValue, Pos, SheetPos,
+    };
+
+    fn key(column: i64, direction: SortDirection) -> SortKey {
+        SortKey {
+            column,
+            direction,
+            case_sensitive: false,
+        }
+    }
+
+    fn column(gc: &GridController, sheet_pos: SheetPos, len: i64) -> Vec<Option<CellValue>> {
+        let sheet = gc.sheet(sheet_pos.sheet_id);
+        (0..len)
+            .map(|y| {
+                sheet.display_value(Pos {
+                    x: sheet_pos.x,
+                    y: sheet_pos.y + y,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    #[serial]
+    fn test_sort_range() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet = gc.sheet_mut(sheet_id);
+        sheet.test_set_values(0, 0, 2, 4, vec!["b", "2", "", "4", "a", "1", "c", "3"]);
+        sheet.test_set_format(
+            0,
+            0,
+            FormatUpdate {
+                bold: Some(Some(true)),
+                ..Default::default()
+            },
+        );
+
+        gc.sort_range(
+            SheetRect::from_numbers(0, 0, 2, 4, sheet_id),
+            vec![key(0, SortDirection::Descending)],
+            None,
+        )
+        .unwrap();
+
+        // blanks stay last and formats move with their row
+        let text = |s: &str| Some(CellValue::from(s));
+        let number = |n: i32| Some(CellValue::Number(n.into()));
+        let origin = Pos { x: 0, y: 0 }.to_sheet_pos(sheet_id);
+        assert_eq!(
+            column(&gc, origin, 4),
+            vec![text("c"), text("b"), text("a"), None]
+        );
+        assert_eq!(
+            column(&gc, Pos { x: 1, y: 0 }.to_sheet_pos(sheet_id), 4),
+            vec![number(3), number(2), number(1), number(4)]
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.format_cell(0, 1, false).bold, Some(true));
+        assert_eq!(sheet.format_cell(0, 0, false).bold, None);
+
+        // the sort is a single undoable transaction
+        gc.undo(None);
+        assert_eq!(
+            column(&gc, origin, 4),
+            vec![text("b"), None, text("a"), text("c")]
+        );
+        assert_eq!(gc.sheet(sheet_id).format_cell(0, 0, false).bold, Some(true));
+        assert_eq!(gc.sheet(sheet_id).format_cell(0, 1, false).bold, None);
+
+        gc.redo(None);
+        assert_eq!(
+            column(&gc, origin, 4),
+            vec![text("c"), text("b"), text("a"), None]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_sort_range_with_formulas() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.sheet_mut(sheet_id)
+            .test_set_values(0, 0, 1, 3, vec!["3", "1", "2"]);
+
+        // each formula doubles the value to its left
+        for y in 0..3 {
+            gc.set_code_cell(
+                Pos { x: 1, y }.to_sheet_pos(sheet_id),
+                CodeCellLanguage::Formula,
+                format!("A{y} * 2"),
+                None,
+            );
+        }
+
+        gc.sort_range(
+            SheetRect::from_numbers(0, 0, 2, 3, sheet_id),
+            vec![key(0, SortDirection::Ascending)],
+            None,
+        )
+        .unwrap();
+
+        let number = |n: i32| Some(CellValue::Number(n.into()));
+        assert_eq!(
+            column(&gc, Pos { x: 1, y: 0 }.to_sheet_pos(sheet_id), 3),
+            vec![number(2), number(4), number(6)]
+        );
+        let Some(CellValue::Code(code)) = gc.sheet(sheet_id).cell_value(Pos { x: 1, y: 0 }) else {
+            panic!("Expected a code cell");
+        };
+        assert_eq!(code.code, "A0 * 2");
+    }
+}
@@ -0,0 +1,104 @@
+use uuid::Uuid;
+
+use crate::controller::active_transactions::transaction_name::TransactionName;
+use crate::controller::GridController;
+use crate::grid::{sheet::validations::Validation, SheetId};
+
+impl GridController {
+    /// Adds a validation, or replaces the validation with the same id.
+    pub fn add_validation(&mut self, validation: Validation, cursor: Option<String>) {
+        let ops = self.add_validation_operations(validation);
+        self.start_user_transaction(ops, cursor, TransactionName::Validations);
+    }
+
+    pub fn remove_validation(
+        &mut self,
+        sheet_id: SheetId,
+        validation_id: Uuid,
+        cursor: Option<String>,
+    ) {
+        let ops = self.remove_validation_operations(sheet_id, validation_id);
+        self.start_user_transaction(ops, cursor, TransactionName::Validations);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        grid::{js_types::JsValidationFailure, sheet::validations::ValidationRule},
+        selection::Selection,
+        wasm_bindings::js::{clear_js_calls, expect_js_call},
+        CellValue, Pos, Rect, SheetPos,
+    };
+
+    #[test]
+    #[serial]
+    fn test_set_cell_values_with_validation() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let validation = Validation::new(
+            Selection::rect(Rect::new(0, 0, 0, 9), sheet_id),
+            ValidationRule::Number {
+                min: Some(0.0),
+                max: None,
+            },
+            Some("Must be positive".into()),
+        );
+        gc.add_validation(validation.clone(), None);
+
+        let sheet_pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        assert_eq!(
+            gc.set_cell_value(sheet_pos, "-1".into(), None),
+            Some(JsValidationFailure {
+                x: 0,
+                y: 0,
+                message: "Must be positive".into(),
+            })
+        );
+        assert_eq!(gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 0 }), None);
+
+        clear_js_calls();
+        assert_eq!(gc.set_cell_value(sheet_pos, "3".into(), None), None);
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        expect_js_call(
+            "jsSheetValidations",
+            format!(
+                "{},{}",
+                sheet_id,
+                serde_json::to_string(&gc.render_validations(sheet_id)).unwrap()
+            ),
+            true,
+        );
+
+        // invalid values are skipped, other values are still set
+        let failures = gc.set_cell_values(sheet_pos, vec![vec!["-2", "-3"], vec!["4"]], None);
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].x, failures[0].y), (0, 0));
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number((-3).into()))
+        );
+        assert_eq!(
+            sheet.cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(4.into()))
+        );
+
+        gc.remove_validation(sheet_id, validation.id, None);
+        assert_eq!(gc.set_cell_value(sheet_pos, "-1".into(), None), None);
+    }
+}
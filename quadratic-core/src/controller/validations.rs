@@ -0,0 +1,378 @@
+use bigdecimal::ToPrimitive;
+
+use super::GridController;
+use crate::{
+    formulas::{parse_formula, Ctx},
+    grid::{
+        js_types::{JsRenderValidations, JsValidationFailure},
+        sheet::validations::{Validation, ValidationRule},
+        SheetId,
+    },
+    CellValue, Instant, IsBlank, Pos, SheetPos,
+};
+
+/// Returns whether `value` is within the (inclusive) bounds, where a missing
+/// bound is unbounded.
+fn within<T: PartialOrd>(value: T, min: Option<T>, max: Option<T>) -> bool {
+    min.into_iter().all(|min| value >= min) && max.into_iter().all(|max| value <= max)
+}
+
+impl GridController {
+    /// Returns the allowed values for the list validation at `sheet_pos`. This
+    /// is used to populate the client's dropdown.
+    pub fn validation_list(&self, sheet_pos: SheetPos) -> Option<Vec<String>> {
+        let validation = self
+            .try_sheet(sheet_pos.sheet_id)?
+            .validation_at(sheet_pos.into())?;
+        self.list_values(&validation.rule)
+    }
+
+    fn list_values(&self, rule: &ValidationRule) -> Option<Vec<String>> {
+        match rule {
+            ValidationRule::List { values } => Some(values.clone()),
+            ValidationRule::ListFromRange { range } => {
+                let sheet = self.try_sheet(range.sheet_id)?;
+                let mut values = vec![];
+                for y in range.y_range() {
+                    for x in range.x_range() {
+                        if let Some(value) = sheet.display_value(Pos { x, y }) {
+                            let value = value.to_edit();
+                            if !value.is_empty() && !values.contains(&value) {
+                                values.push(value);
+                            }
+                        }
+                    }
+                }
+                Some(values)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether `value` satisfies `validation` at `sheet_pos`. Custom
+    /// formulas read the grid, so they expect `value` to already be there.
+    fn passes_validation(
+        &self,
+        sheet_pos: SheetPos,
+        validation: &Validation,
+        value: &CellValue,
+    ) -> bool {
+        if value.is_blank() {
+            return true;
+        }
+        match &validation.rule {
+            ValidationRule::List { .. } | ValidationRule::ListFromRange { .. } => {
+                let value = value.to_edit().to_lowercase();
+                self.list_values(&validation.rule)
+                    .is_some_and(|list| list.iter().any(|item| item.to_lowercase() == value))
+            }
+            ValidationRule::Number { min, max } => {
+                let CellValue::Number(n) = value else {
+                    return false;
+                };
+                let Some(n) = n.to_f64() else {
+                    return false;
+                };
+                within(n, *min, *max)
+            }
+            ValidationRule::Date { start, end } => {
+                let Some(date) = Instant::try_from(value)
+                    .ok()
+                    .and_then(|instant| instant.to_naive_date())
+                else {
+                    return false;
+                };
+                within(
+                    date,
+                    start.and_then(|start| start.to_naive_date()),
+                    end.and_then(|end| end.to_naive_date()),
+                )
+            }
+            ValidationRule::TextLength { min, max } => {
+                let len = value.to_edit().chars().count() as u32;
+                within(len, *min, *max)
+            }
+            ValidationRule::Custom { formula } => {
+                let Ok(parsed) = parse_formula(formula, validation.anchor()) else {
+                    return false;
+                };
+                let mut ctx = Ctx::new_for_cell_rule(self.grid(), sheet_pos);
+                parsed
+                    .eval(&mut ctx, false)
+                    .ok()
+                    .and_then(|result| result.into_cell_value().ok())
+                    .and_then(|result| bool::try_from(&result).ok())
+                    .unwrap_or(false)
+            }
+        }
+    }
+
+    /// Checks a user's input against the validation at `sheet_pos`. Returns
+    /// the failure if the input should be rejected.
+    pub fn validate_input(
+        &mut self,
+        sheet_pos: SheetPos,
+        input: &str,
+    ) -> Option<JsValidationFailure> {
+        let pos: Pos = sheet_pos.into();
+        let validation = self
+            .try_sheet(sheet_pos.sheet_id)?
+            .validation_at(pos)?
+            .clone();
+        let (_, value) = self.string_to_cell_value(sheet_pos, input.trim());
+        let value = &value;
+
+        let passes = if matches!(validation.rule, ValidationRule::Custom { .. }) {
+            // temporarily place the value so the formula can read it
+            let sheet = self.try_sheet_mut(sheet_pos.sheet_id)?;
+            let old_value = sheet.set_cell_value(pos, value.clone());
+            let passes = self.passes_validation(sheet_pos, &validation, value);
+            let sheet = self.try_sheet_mut(sheet_pos.sheet_id)?;
+            sheet.set_cell_value(pos, old_value.unwrap_or(CellValue::Blank));
+            passes
+        } else {
+            self.passes_validation(sheet_pos, &validation, value)
+        };
+
+        (!passes).then(|| JsValidationFailure {
+            x: pos.x,
+            y: pos.y,
+            message: validation.failure_message(),
+        })
+    }
+
+    /// Returns the dropdowns and invalid cells that the client draws for a
+    /// sheet's validations.
+    pub fn render_validations(&self, sheet_id: SheetId) -> JsRenderValidations {
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return JsRenderValidations::default();
+        };
+        let mut invalid = vec![];
+        for validation in &sheet.validations {
+            let Some(values) = sheet.selection(&validation.selection, None, false) else {
+                continue;
+            };
+            invalid.extend(values.into_iter().filter_map(|(pos, value)| {
+                let applies = sheet
+                    .validation_at(pos)
+                    .is_some_and(|applied| applied.id == validation.id);
+                (applies && !self.passes_validation(pos.to_sheet_pos(sheet_id), validation, value))
+                    .then_some(pos)
+            }));
+        }
+        invalid.sort_by_key(|pos| (pos.y, pos.x));
+
+        JsRenderValidations {
+            dropdowns: sheet
+                .validations
+                .iter()
+                .filter(|validation| validation.has_dropdown())
+                .map(|validation| validation.selection.clone())
+                .collect(),
+            invalid,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{selection::Selection, Rect, SheetRect};
+
+    fn add_validation(gc: &mut GridController, rect: Rect, rule: ValidationRule) -> SheetId {
+        let sheet_id = gc.sheet_ids()[0];
+        let validation = Validation::new(Selection::rect(rect, sheet_id), rule, None);
+        gc.grid
+            .try_sheet_mut(sheet_id)
+            .unwrap()
+            .set_validation(validation, None);
+        sheet_id
+    }
+
+    fn passes(gc: &mut GridController, sheet_pos: SheetPos, input: &str) -> bool {
+        gc.validate_input(sheet_pos, input).is_none()
+    }
+
+    #[test]
+    fn test_validate_list() {
+        let mut gc = GridController::test();
+        let sheet_id = add_validation(
+            &mut gc,
+            Rect::new(0, 0, 0, 5),
+            ValidationRule::List {
+                values: vec!["Yes".into(), "No".into()],
+            },
+        );
+        let pos = SheetPos {
+            x: 0,
+            y: 1,
+            sheet_id,
+        };
+        assert!(passes(&mut gc, pos, "yes"));
+        assert!(passes(&mut gc, pos, ""));
+        assert!(!passes(&mut gc, pos, "maybe"));
+
+        // outside the selection
+        let outside = SheetPos {
+            x: 1,
+            y: 1,
+            sheet_id,
+        };
+        assert!(passes(&mut gc, outside, "maybe"));
+        assert_eq!(
+            gc.validation_list(pos),
+            Some(vec!["Yes".to_string(), "No".to_string()])
+        );
+        assert_eq!(gc.validation_list(outside), None);
+    }
+
+    #[test]
+    fn test_validate_list_from_range() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet = gc.grid.try_sheet_mut(sheet_id).unwrap();
+        sheet.set_cell_value(Pos { x: 5, y: 0 }, "red");
+        sheet.set_cell_value(Pos { x: 5, y: 1 }, "green");
+        sheet.set_cell_value(Pos { x: 5, y: 2 }, "red");
+        add_validation(
+            &mut gc,
+            Rect::new(0, 0, 0, 0),
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(5, 0, 1, 4, sheet_id),
+            },
+        );
+        let pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        assert_eq!(
+            gc.validation_list(pos),
+            Some(vec!["red".to_string(), "green".to_string()])
+        );
+        assert!(passes(&mut gc, pos, "Green"));
+        assert!(!passes(&mut gc, pos, "blue"));
+    }
+
+    #[test]
+    fn test_validate_number_date_and_text_length() {
+        let mut gc = GridController::test();
+        let sheet_id = add_validation(
+            &mut gc,
+            Rect::new(0, 0, 0, 0),
+            ValidationRule::Number {
+                min: Some(1.0),
+                max: Some(10.0),
+            },
+        );
+        let pos = SheetPos {
+            x: 0,
+            y: 0,
+            sheet_id,
+        };
+        assert!(passes(&mut gc, pos, "10"));
+        assert!(!passes(&mut gc, pos, "11"));
+        assert!(!passes(&mut gc, pos, "five"));
+
+        add_validation(
+            &mut gc,
+            Rect::new(1, 0, 1, 0),
+            ValidationRule::Date {
+                start: Some(Instant::from_naive_date(
+                    chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                )),
+                end: Some(Instant::from_naive_date(
+                    chrono::NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                )),
+            },
+        );
+        let pos = SheetPos {
+            x: 1,
+            y: 0,
+            sheet_id,
+        };
+        assert!(passes(&mut gc, pos, "2024-01-31"));
+        assert!(!passes(&mut gc, pos, "2024-02-01"));
+        assert!(!passes(&mut gc, pos, "soon"));
+
+        add_validation(
+            &mut gc,
+            Rect::new(2, 0, 2, 0),
+            ValidationRule::TextLength {
+                min: Some(2),
+                max: Some(3),
+            },
+        );
+        let pos = SheetPos {
+            x: 2,
+            y: 0,
+            sheet_id,
+        };
+        assert!(passes(&mut gc, pos, "abc"));
+        assert!(!passes(&mut gc, pos, "a"));
+        assert!(!passes(&mut gc, pos, "abcd"));
+    }
+
+    #[test]
+    fn test_validate_custom() {
+        let mut gc = GridController::test();
+
+        // each cell in the column must be greater than the cell to its left
+        let sheet_id = add_validation(
+            &mut gc,
+            Rect::new(1, 0, 1, 5),
+            ValidationRule::Custom {
+                formula: "B0 > A0".into(),
+            },
+        );
+        let sheet = gc.grid.try_sheet_mut(sheet_id).unwrap();
+        sheet.set_cell_value(Pos { x: 0, y: 3 }, CellValue::Number(5.into()));
+        sheet.set_cell_value(Pos { x: 1, y: 3 }, CellValue::Number(1.into()));
+
+        let pos = SheetPos {
+            x: 1,
+            y: 3,
+            sheet_id,
+        };
+        assert!(passes(&mut gc, pos, "6"));
+        assert!(!passes(&mut gc, pos, "4"));
+
+        // the original value is restored after checking
+        assert_eq!(
+            gc.sheet(sheet_id).cell_value(Pos { x: 1, y: 3 }),
+            Some(CellValue::Number(1.into()))
+        );
+    }
+
+    #[test]
+    fn test_render_validations() {
+        let mut gc = GridController::test();
+        let sheet_id = add_validation(
+            &mut gc,
+            Rect::new(0, 0, 0, 5),
+            ValidationRule::List {
+                values: vec!["a".into()],
+            },
+        );
+        add_validation(
+            &mut gc,
+            Rect::new(0, 3, 0, 3),
+            ValidationRule::TextLength {
+                min: None,
+                max: Some(1),
+            },
+        );
+        let sheet = gc.grid.try_sheet_mut(sheet_id).unwrap();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, "a");
+        sheet.set_cell_value(Pos { x: 0, y: 1 }, "b");
+        sheet.set_cell_value(Pos { x: 0, y: 3 }, "c");
+        sheet.set_cell_value(Pos { x: 0, y: 4 }, "dd");
+
+        let render = gc.render_validations(sheet_id);
+        assert_eq!(
+            render.dropdowns,
+            vec![Selection::rect(Rect::new(0, 0, 0, 5), sheet_id)]
+        );
+        assert_eq!(render.invalid, vec![Pos { x: 0, y: 1 }, Pos { x: 0, y: 4 }]);
+    }
+}
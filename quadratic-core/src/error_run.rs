@@ -92,6 +92,7 @@ pub enum RunErrorMsg {
 
     // Runtime errors
     CircularReference,
+    LambdaTooDeep,
     Overflow,
     DivideByZero,
     NegativeExponent,
@@ -213,6 +214,9 @@ impl fmt::Display for RunErrorMsg {
             Self::CircularReference => {
                 write!(f, "Circular reference")
             }
+            Self::LambdaTooDeep => {
+                write!(f, "LAMBDA recursion too deep")
+            }
             Self::Overflow => {
                 write!(f, "Numeric overflow")
             }
@@ -241,6 +245,60 @@ impl fmt::Display for RunErrorMsg {
     }
 }
 impl RunErrorMsg {
+    /// Returns the number used to identify this kind of error in the
+    /// `ERROR.TYPE` formula function. These numbers match the error codes used
+    /// by other spreadsheet software and must not change.
+    ///
+    /// | Code | Error     | Meaning                                  |
+    /// |------|-----------|------------------------------------------|
+    /// | 2    | `#DIV/0!` | divide by zero                           |
+    /// | 3    | `#VALUE!` | wrong type or shape of value             |
+    /// | 4    | `#REF!`   | invalid or circular cell reference       |
+    /// | 5    | `#NAME?`  | unknown function name                    |
+    /// | 6    | `#NUM!`   | invalid numeric argument or result       |
+    /// | 7    | `#N/A`    | no match found                           |
+    /// | 9    | `#SPILL!` | array output is blocked by another value |
+    /// | 14   | `#CALC!`  | empty array or other calculation error   |
+    pub fn error_type_code(&self) -> u8 {
+        match self {
+            Self::DivideByZero => 2,
+
+            Self::Expected { .. }
+            | Self::Unexpected(_)
+            | Self::Unterminated(_)
+            | Self::TooManyArguments { .. }
+            | Self::MissingRequiredArgument { .. }
+            | Self::BadNumber
+            | Self::ExactArraySizeMismatch { .. }
+            | Self::ExactArrayAxisMismatch { .. }
+            | Self::ArrayAxisMismatch { .. }
+            | Self::NonRectangularArray
+            | Self::NonLinearArray
+            | Self::PythonError(_) => 3,
+
+            Self::BadCellReference | Self::CircularReference | Self::IndexOutOfBounds => 4,
+
+            Self::BadFunctionName => 5,
+
+            Self::Overflow
+            | Self::LambdaTooDeep
+            | Self::NegativeExponent
+            | Self::NotANumber
+            | Self::Infinity
+            | Self::InvalidArgument
+            | Self::ArrayTooBig => 6,
+
+            Self::NoMatch => 7,
+
+            Self::Spill => 9,
+
+            Self::EmptyArray
+            | Self::Unimplemented
+            | Self::UnknownError
+            | Self::InternalError(_) => 14,
+        }
+    }
+
     /// Attaches a span to this error message, returning a Error.
     pub fn with_span(self, span: impl Into<Span>) -> RunError {
         RunError {
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::rc::Rc;
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -6,7 +7,7 @@ use smallvec::smallvec;
 
 use super::*;
 use crate::{
-    Array, ArraySize, CellValue, CodeResult, CoerceInto, Pos, RunErrorMsg, Spanned, Value,
+    Array, ArraySize, CellValue, CodeResult, CoerceInto, RunErrorMsg, SheetRect, Spanned, Value,
 };
 
 /// Abstract syntax tree of a formula expression.
@@ -32,6 +33,11 @@ pub enum AstNodeContents {
     Paren(Box<AstNode>),
     Array(Vec<Vec<AstNode>>),
     CellRef(CellRef),
+    /// Reference to whole columns or rows.
+    RangeRef(RangeRef),
+    Identifier(String),
+    /// Reference to a cell that has been deleted.
+    RefError,
     String(String),
     Number(f64),
     Bool(bool),
@@ -58,6 +64,9 @@ impl fmt::Display for AstNodeContents {
                 a.iter().map(|row| row.iter().join(", ")).join("; "),
             ),
             AstNodeContents::CellRef(cellref) => write!(f, "{cellref}"),
+            AstNodeContents::RangeRef(range_ref) => write!(f, "{range_ref}"),
+            AstNodeContents::Identifier(name) => write!(f, "{name}"),
+            AstNodeContents::RefError => write!(f, "#REF!"),
             AstNodeContents::String(s) => write!(f, "{s:?}"),
             AstNodeContents::Number(n) => write!(f, "{n:?}"),
             AstNodeContents::Bool(false) => write!(f, "FALSE"),
@@ -71,12 +80,20 @@ impl AstNodeContents {
             AstNodeContents::Empty => "empty expression",
             AstNodeContents::FunctionCall { func, .. } => match func.inner.as_str() {
                 "=" | "==" | "<>" | "!=" | "<" | ">" | "<=" | ">=" => "comparison",
-                s if s.chars().all(|c| c.is_alphanumeric() || c == '_') => "function call",
+                s if s
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.') =>
+                {
+                    "function call"
+                }
                 _ => "expression",
             },
             AstNodeContents::Paren(contents) => contents.inner.type_string(),
             AstNodeContents::Array(_) => "array literal",
             AstNodeContents::CellRef(_) => "cell reference",
+            AstNodeContents::RangeRef(_) => "cell range reference",
+            AstNodeContents::Identifier(_) => "name",
+            AstNodeContents::RefError => "deleted cell reference",
             AstNodeContents::String(_) => "string literal",
             AstNodeContents::Number(_) => "numeric literal",
             AstNodeContents::Bool(_) => "boolean literal",
@@ -95,6 +112,79 @@ impl Spanned<AstNodeContents> {
             .with_span(self.span)),
         }
     }
+
+    /// Returns the region of the grid referenced by this expression, or `None`
+    /// if it is not a cell reference or cell range.
+    pub fn to_sheet_rect(&self, ctx: &Ctx<'_>) -> Option<SheetRect> {
+        match &self.inner {
+            AstNodeContents::CellRef(cell_ref) => {
+                ctx.resolve_range_ref(cell_ref, cell_ref, self.span).ok()
+            }
+            AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
+                let [ref1, ref2] = args.as_slice() else {
+                    return None;
+                };
+                let ref1 = ref1.to_cell_ref().ok()?;
+                let ref2 = ref2.to_cell_ref().ok()?;
+                ctx.resolve_range_ref(&ref1, &ref2, self.span).ok()
+            }
+            AstNodeContents::RangeRef(range_ref) => ctx
+                .resolve_line_range(range_ref, self.span)
+                .ok()
+                .map(|sheet_rect| ctx.bound_line_range(sheet_rect)),
+            AstNodeContents::Identifier(name) if ctx.lookup_variable(name).is_none() => {
+                ctx.defined_name_range(name)
+            }
+            AstNodeContents::Paren(contents) => contents.to_sheet_rect(ctx),
+            _ => None,
+        }
+    }
+
+    /// Returns the name in this expression, or an error if it is not a name.
+    fn to_identifier(&self) -> CodeResult<String> {
+        match &self.inner {
+            AstNodeContents::Identifier(name) => Ok(name.clone()),
+            _ => Err(RunErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some(self.inner.type_string().into()),
+            }
+            .with_span(self.span)),
+        }
+    }
+
+    /// Returns the function defined by this expression, or `None` if it is not
+    /// a `LAMBDA` or a name bound to one. The function captures the names that
+    /// are currently in scope.
+    fn to_lambda(&self, ctx: &Ctx<'_>, only_parse: bool) -> Option<CodeResult<Rc<Lambda>>> {
+        match &self.inner {
+            AstNodeContents::FunctionCall { func, args }
+                if func.inner.eq_ignore_ascii_case("LAMBDA") =>
+            {
+                let Some((body, params)) = args.split_last() else {
+                    return Some(Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "LAMBDA".into(),
+                        arg_name: "calculation".into(),
+                    }
+                    .with_span(self.span)));
+                };
+                let params = params.iter().map(|param| param.to_identifier());
+                Some(params.collect::<CodeResult<_>>().map(|params| {
+                    Rc::new(Lambda {
+                        params,
+                        body: body.clone(),
+                        captured: ctx.variables.clone(),
+                        only_parse,
+                    })
+                }))
+            }
+            AstNodeContents::Identifier(name) => match ctx.lookup_variable(name)? {
+                Binding::Lambda(lambda) => Some(Ok(Rc::clone(lambda))),
+                Binding::Value(_) => None,
+            },
+            AstNodeContents::Paren(contents) => contents.to_lambda(ctx, only_parse),
+            _ => None,
+        }
+    }
 }
 
 impl Formula {
@@ -105,7 +195,11 @@ impl Formula {
 }
 
 impl AstNode {
-    fn eval<'ctx: 'a, 'a>(&'a self, ctx: &'a mut Ctx<'ctx>, only_parse: bool) -> CodeResult {
+    pub(super) fn eval<'ctx: 'a, 'a>(
+        &'a self,
+        ctx: &'a mut Ctx<'ctx>,
+        only_parse: bool,
+    ) -> CodeResult {
         let value = match &self.inner {
             AstNodeContents::Empty => CellValue::Blank.into(),
 
@@ -116,61 +210,94 @@ impl AstNode {
                 }
                 let ref1 = args[0].to_cell_ref()?;
                 let ref2 = args[1].to_cell_ref()?;
-                let sheet_name = ref1.sheet.clone();
-                let corner1 = ref1.resolve_from(ctx.sheet_pos.into());
-                let corner2 = ref2.resolve_from(ctx.sheet_pos.into());
-
-                let x1 = std::cmp::min(corner1.x, corner2.x);
-                let y1 = std::cmp::min(corner1.y, corner2.y);
-
-                let x2 = std::cmp::max(corner1.x, corner2.x);
-                let y2 = std::cmp::max(corner1.y, corner2.y);
-
-                let width = x2
-                    .saturating_sub(x1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                let height = y2
-                    .saturating_sub(y1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
-                    return Err(RunErrorMsg::ArrayTooBig.with_span(self.span));
-                }
-
-                // todo: this should call a new ctx.get_cells to push a full SheetRect to cells_accessed instead of an array of SheetPos
-                let mut flat_array = smallvec![];
-                // Reuse the same `CellRef` object so that we don't have to
-                // clone `sheet_name.`
-                let mut cell_ref = CellRef::absolute(sheet_name, Pos::ORIGIN); // We'll overwrite the position.
-                for y in y1..=y2 {
-                    cell_ref.y = CellRefCoord::Absolute(y);
-                    for x in x1..=x2 {
-                        cell_ref.x = CellRefCoord::Absolute(x);
-                        flat_array.push(ctx.get_cell(&cell_ref, self.span)?.inner);
-                    }
-                }
-
-                let size = ArraySize::new_or_err(width, height)?;
-                Array::new_row_major(size, flat_array)?.into()
+                let sheet_rect = ctx.resolve_range_ref(&ref1, &ref2, self.span)?;
+                ctx.get_cell_array(sheet_rect, self.span)?.inner.into()
             }
 
             // Other operator/function
             AstNodeContents::FunctionCall { func, args } => {
+                let func_name = &func.inner;
+
+                // `LET` alternates between names and values, and each name is
+                // in scope for the rest of the arguments.
+                let is_let = func_name.eq_ignore_ascii_case("LET");
+                let outer_scope_len = ctx.variables.len();
+
+                // `IFERROR` and `IFNA` only evaluate their fallback if `value`
+                // contains an error that they catch, so that an unused
+                // fallback is not computed or recorded as accessed.
+                let is_iferror = func_name.eq_ignore_ascii_case("IFERROR");
+                let is_ifna = func_name.eq_ignore_ascii_case("IFNA");
+                let has_lazy_fallback = (is_iferror || is_ifna)
+                    && args.len() == 2
+                    && !only_parse
+                    && ctx.lookup_variable(func_name).is_none();
+                let catches_error = |value: &Spanned<Value>| {
+                    value.inner.cell_values_slice().iter().any(|v| match v {
+                        CellValue::Error(e) => is_iferror || e.msg == RunErrorMsg::NoMatch,
+                        _ => false,
+                    })
+                };
+
                 let mut arg_values = vec![];
-                for arg in args {
-                    arg_values.push(arg.eval(&mut *ctx, only_parse)?);
+                let mut arg_sheet_rects = vec![];
+                let mut arg_lambdas = vec![];
+                for (i, arg) in args.iter().enumerate() {
+                    let is_let_binding = is_let && i + 1 < args.len();
+                    let (value, lambda) = if is_let_binding && i % 2 == 0 {
+                        let value = match arg.to_identifier() {
+                            Ok(_) => CellValue::Blank,
+                            Err(e) => CellValue::Error(Box::new(e)),
+                        };
+                        (
+                            Spanned {
+                                span: arg.span,
+                                inner: value.into(),
+                            },
+                            None,
+                        )
+                    } else if has_lazy_fallback && i == 1 && !catches_error(&arg_values[0]) {
+                        (
+                            Spanned {
+                                span: arg.span,
+                                inner: CellValue::Blank.into(),
+                            },
+                            None,
+                        )
+                    } else {
+                        arg.eval_arg(ctx, only_parse)?
+                    };
+                    if is_let_binding && i % 2 == 1 {
+                        if let Ok(name) = args[i - 1].to_identifier() {
+                            let binding = match &lambda {
+                                Some(lambda) => Binding::Lambda(Rc::clone(lambda)),
+                                None => Binding::Value(value.inner.clone()),
+                            };
+                            ctx.variables.push((name, binding));
+                        }
+                    }
+                    arg_sheet_rects.push(arg.to_sheet_rect(ctx));
+                    arg_values.push(value);
+                    arg_lambdas.push(lambda);
                 }
 
-                let func_name = &func.inner;
-                match functions::lookup_function(func_name) {
-                    Some(f) => {
-                        let args = FormulaFnArgs::new(arg_values, self.span, f.name);
-                        (f.eval)(&mut *ctx, only_parse, args)?
+                ctx.variables.truncate(outer_scope_len);
+
+                if let Some(Binding::Lambda(lambda)) = ctx.lookup_variable(func_name) {
+                    let lambda = Rc::clone(lambda);
+                    let args = FormulaFnArgs::new(arg_values, self.span, "LAMBDA")
+                        .with_lambdas(arg_lambdas);
+                    lambda.call(ctx, args)?.inner
+                } else {
+                    match functions::lookup_function(func_name) {
+                        Some(f) => {
+                            let args = FormulaFnArgs::new(arg_values, self.span, f.name)
+                                .with_sheet_rects(arg_sheet_rects)
+                                .with_lambdas(arg_lambdas);
+                            (f.eval)(&mut *ctx, only_parse, args)?
+                        }
+                        None => return Err(RunErrorMsg::BadFunctionName.with_span(func.span)),
                     }
-                    None => return Err(RunErrorMsg::BadFunctionName.with_span(func.span)),
                 }
             }
 
@@ -203,6 +330,26 @@ impl AstNode {
                 Array::from(ctx.get_cell(cell_ref, self.span)?.inner).into()
             }
 
+            AstNodeContents::RangeRef(range_ref) => {
+                ctx.get_line_range_array(range_ref, self.span)?.inner.into()
+            }
+
+            AstNodeContents::Identifier(name) => match ctx.lookup_variable(name) {
+                Some(Binding::Value(value)) => value.clone(),
+                Some(Binding::Lambda(_)) => {
+                    return Err(RunErrorMsg::Expected {
+                        expected: "value".into(),
+                        got: Some("LAMBDA".into()),
+                    }
+                    .with_span(self.span))
+                }
+                None => ctx.eval_defined_name(name, self.span, only_parse)?,
+            },
+
+            AstNodeContents::RefError => {
+                return Err(RunErrorMsg::BadCellReference.with_span(self.span))
+            }
+
             AstNodeContents::String(s) => Value::from(s.to_string()),
             AstNodeContents::Number(n) => Value::from(*n),
             AstNodeContents::Bool(b) => Value::from(*b),
@@ -213,4 +360,50 @@ impl AstNode {
             inner: value,
         })
     }
+
+    /// Evaluates a function argument. If the argument is a `LAMBDA` or a name
+    /// bound to one, then it is not evaluated and the function is returned
+    /// instead.
+    ///
+    /// Errors are passed to the function as values so that functions such as
+    /// `IFERROR` can handle them. Most functions propagate them when coercing
+    /// arguments. When only checking the formula, errors that make the
+    /// formula invalid, such as unknown functions or the wrong number of
+    /// arguments, are returned instead.
+    fn eval_arg(
+        &self,
+        ctx: &mut Ctx<'_>,
+        only_parse: bool,
+    ) -> CodeResult<(Spanned<Value>, Option<Rc<Lambda>>)> {
+        let result = match self.to_lambda(ctx, only_parse) {
+            Some(Ok(lambda)) => {
+                let value = Spanned {
+                    span: self.span,
+                    inner: CellValue::Blank.into(),
+                };
+                return Ok((value, Some(lambda)));
+            }
+            Some(Err(e)) => Err(e),
+            None => self.eval(ctx, only_parse),
+        };
+        let value = match result {
+            Ok(value) => value,
+            Err(e)
+                if only_parse
+                    && matches!(
+                        e.msg,
+                        RunErrorMsg::BadFunctionName
+                            | RunErrorMsg::TooManyArguments { .. }
+                            | RunErrorMsg::MissingRequiredArgument { .. }
+                    ) =>
+            {
+                return Err(e);
+            }
+            Err(e) => Spanned {
+                span: self.span,
+                inner: CellValue::Error(Box::new(e)).into(),
+            },
+        };
+        Ok((value, None))
+    }
 }
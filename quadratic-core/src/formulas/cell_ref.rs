@@ -6,19 +6,24 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use crate::formulas::{escape_string, parse_sheet_name};
-use crate::Pos;
+use crate::{Axis, Pos, Rect};
+
+/// Number of columns in an Excel worksheet.
+pub const EXCEL_MAX_COLUMNS: i64 = 16_384;
+/// Number of rows in an Excel worksheet.
+pub const EXCEL_MAX_ROWS: i64 = 1_048_576;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 #[serde(tag = "type")]
 pub enum RangeRef {
-    // this is not yet used...
+    /// Whole rows, such as `2:10`.
     RowRange {
         start: CellRefCoord,
         end: CellRefCoord,
         sheet: Option<String>,
     },
-    // this is not yet used...
+    /// Whole columns, such as `A:C`.
     ColRange {
         start: CellRefCoord,
         end: CellRefCoord,
@@ -35,8 +40,12 @@ pub enum RangeRef {
 impl fmt::Display for RangeRef {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            RangeRef::RowRange { start, end, .. } => write!(f, "R{start}:R{end}"),
-            RangeRef::ColRange { start, end, .. } => write!(f, "C{start}:C{end}"),
+            RangeRef::RowRange { start, end, sheet } => {
+                write!(f, "{}R{start}:R{end}", sheet_prefix(sheet))
+            }
+            RangeRef::ColRange { start, end, sheet } => {
+                write!(f, "{}C{start}:C{end}", sheet_prefix(sheet))
+            }
             RangeRef::CellRange { start, end } => write!(f, "{start}:{end}"),
             RangeRef::Cell { pos } => write!(f, "{pos}"),
         }
@@ -52,18 +61,118 @@ impl RangeRef {
     /// A1-style notation.
     pub fn a1_string(self, base: Pos) -> String {
         match self {
-            RangeRef::RowRange { start, end, .. } => {
-                format!("{}:{}", start.row_string(base.y), end.row_string(base.y))
-            }
-            RangeRef::ColRange { start, end, .. } => {
-                format!("{}:{}", start.col_string(base.x), end.col_string(base.x))
-            }
+            RangeRef::RowRange { start, end, sheet } => format!(
+                "{}{}:{}",
+                sheet_prefix(&sheet),
+                start.row_string(base.y),
+                end.row_string(base.y),
+            ),
+            RangeRef::ColRange { start, end, sheet } => format!(
+                "{}{}:{}",
+                sheet_prefix(&sheet),
+                start.col_string(base.x),
+                end.col_string(base.x),
+            ),
             RangeRef::CellRange { start, end } => {
                 format!("{}:{}", start.a1_string(base), end.a1_string(base))
             }
             RangeRef::Cell { pos } => pos.a1_string(base),
         }
     }
+
+    /// Returns the name of the sheet this reference is to, or `None` if it is
+    /// to the sheet containing the formula.
+    pub fn sheet_name(&self) -> Option<&str> {
+        match self {
+            RangeRef::RowRange { sheet, .. } | RangeRef::ColRange { sheet, .. } => sheet.as_deref(),
+            RangeRef::CellRange { start, .. } => start.sheet.as_deref(),
+            RangeRef::Cell { pos } => pos.sheet.as_deref(),
+        }
+    }
+
+    /// Returns this reference in Excel's A1-style notation, with the cells it
+    /// refers to moved by `offset`, or `None` if Excel cannot refer to them.
+    /// `sheet` is the name to use for the sheet, if any.
+    pub fn excel_string(&self, base: Pos, offset: Pos, sheet: Option<&str>) -> Option<String> {
+        let col = |coord: CellRefCoord| coord.excel_col_string(base.x, offset.x);
+        let row = |coord: CellRefCoord| coord.excel_row_string(base.y, offset.y);
+        let cell = |cell_ref: &CellRef| Some(format!("{}{}", col(cell_ref.x)?, row(cell_ref.y)?));
+        let range = match self {
+            RangeRef::RowRange { start, end, .. } => format!("{}:{}", row(*start)?, row(*end)?),
+            RangeRef::ColRange { start, end, .. } => format!("{}:{}", col(*start)?, col(*end)?),
+            RangeRef::CellRange { start, end } => format!("{}:{}", cell(start)?, cell(end)?),
+            RangeRef::Cell { pos } => cell(pos)?,
+        };
+        Some(match sheet {
+            Some(sheet) => format!("'{}'!{range}", sheet.replace('\'', "''")),
+            None => range,
+        })
+    }
+
+    /// Returns this reference with its absolute coordinates moved by
+    /// `offset`. Relative coordinates are unchanged, since they move with the
+    /// formula.
+    pub fn moved_by(self, offset: Pos) -> RangeRef {
+        let cell = |cell_ref: CellRef| CellRef {
+            sheet: cell_ref.sheet,
+            x: cell_ref.x.moved_by(offset.x),
+            y: cell_ref.y.moved_by(offset.y),
+        };
+        match self {
+            RangeRef::RowRange { start, end, sheet } => RangeRef::RowRange {
+                start: start.moved_by(offset.y),
+                end: end.moved_by(offset.y),
+                sheet,
+            },
+            RangeRef::ColRange { start, end, sheet } => RangeRef::ColRange {
+                start: start.moved_by(offset.x),
+                end: end.moved_by(offset.x),
+                sheet,
+            },
+            RangeRef::CellRange { start, end } => RangeRef::CellRange {
+                start: cell(start),
+                end: cell(end),
+            },
+            RangeRef::Cell { pos } => RangeRef::Cell { pos: cell(pos) },
+        }
+    }
+
+    /// Parses a whole-column or whole-row range reference without a sheet
+    /// name, relative to a given location. Both A1-style notation (`A:C`,
+    /// `$2:10`) and internal notation (`C[0]:C[2]`, `R{2}:R{10}`) are
+    /// accepted.
+    pub fn parse_line_range(s: &str, base: Pos) -> Option<RangeRef> {
+        let (start, end) = s.trim().split_once(':')?;
+        let col = |s: &str| {
+            CellRefCoord::parse_a1_col(s, base.x).or_else(|| s.strip_prefix('C')?.parse().ok())
+        };
+        let row = |s: &str| {
+            CellRefCoord::parse_a1_row(s, base.y).or_else(|| s.strip_prefix('R')?.parse().ok())
+        };
+        if let (Some(start), Some(end)) = (col(start), col(end)) {
+            Some(RangeRef::ColRange {
+                start,
+                end,
+                sheet: None,
+            })
+        } else if let (Some(start), Some(end)) = (row(start), row(end)) {
+            Some(RangeRef::RowRange {
+                start,
+                end,
+                sheet: None,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the prefix for a reference to a sheet, such as `"Sheet 2"!`.
+fn sheet_prefix(sheet: &Option<String>) -> String {
+    match sheet {
+        Some(sheet_name) => format!("{}!", escape_string(sheet_name)),
+        None => String::new(),
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -131,10 +240,7 @@ impl CellRef {
     /// Returns the human-friendly string representing this cell reference in
     /// A1-style notation.
     pub fn a1_string(&self, base: Pos) -> String {
-        let sheet_str = match &self.sheet {
-            Some(sheet_name) => format!("{}!", escape_string(sheet_name)),
-            None => String::new(),
-        };
+        let sheet_str = sheet_prefix(&self.sheet);
         let col = self.x.col_string(base.x);
         let row = self.y.row_string(base.y);
         format!("{sheet_str}{col}{row}")
@@ -234,6 +340,45 @@ impl CellRefCoord {
             CellRefCoord::Absolute(coord) => coord,
         }
     }
+    /// Moves the coordinate by `offset` if it is absolute.
+    fn moved_by(self, offset: i64) -> Self {
+        match self {
+            CellRefCoord::Relative(delta) => CellRefCoord::Relative(delta),
+            CellRefCoord::Absolute(coord) => CellRefCoord::Absolute(coord + offset),
+        }
+    }
+    /// Parses an A1-style column name, such as `B` or `$nC`, relative to the
+    /// column `base`.
+    fn parse_a1_col(s: &str, base: i64) -> Option<Self> {
+        match s.strip_prefix('$') {
+            Some(name) => Some(Self::Absolute(crate::util::column_from_name(name)?)),
+            None => Some(Self::Relative(crate::util::column_from_name(s)? - base)),
+        }
+    }
+    /// Parses an A1-style row name, such as `2` or `$n4`, relative to the row
+    /// `base`.
+    fn parse_a1_row(s: &str, base: i64) -> Option<Self> {
+        let (is_absolute, name) = match s.strip_prefix('$') {
+            Some(name) => (true, name),
+            None => (false, s),
+        };
+        let (is_negative, digits) = match name.strip_prefix('n') {
+            Some(digits) => (true, digits),
+            None => (false, name),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let mut row = digits.parse::<i64>().ok()?;
+        if is_negative {
+            row = -row;
+        }
+        Some(match is_absolute {
+            true => Self::Absolute(row),
+            false => Self::Relative(row - base),
+        })
+    }
+
     /// Returns the `$` prefix if this is an absolute reference, or the empty
     /// string if it is a relative reference.
     fn prefix(self) -> &'static str {
@@ -255,6 +400,22 @@ impl CellRefCoord {
         format!("{}{row}", self.prefix())
     }
 
+    /// Returns this column coordinate in Excel's A1-style notation, moved by
+    /// `offset`, or `None` if it is outside the columns that Excel supports.
+    fn excel_col_string(self, base: i64, offset: i64) -> Option<String> {
+        let col = self.resolve_from(base) + offset;
+        (0..EXCEL_MAX_COLUMNS)
+            .contains(&col)
+            .then(|| format!("{}{}", self.prefix(), crate::util::column_name(col)))
+    }
+    /// Returns this row coordinate in Excel's A1-style notation, moved by
+    /// `offset`, or `None` if it is outside the rows that Excel supports.
+    fn excel_row_string(self, base: i64, offset: i64) -> Option<String> {
+        let row = self.resolve_from(base) + offset;
+        (1..=EXCEL_MAX_ROWS)
+            .contains(&row)
+            .then(|| format!("{}{row}", self.prefix()))
+    }
     /// Returns whether the coordinate is relative (i.e., no '$' prefix).
     #[cfg(test)]
     fn is_relative(self) -> bool {
@@ -265,6 +426,113 @@ impl CellRefCoord {
     }
 }
 
+/// Insertion or deletion of columns or rows, which shifts every cell after
+/// it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RefAdjust {
+    /// Whether columns ([`Axis::X`]) or rows ([`Axis::Y`]) are affected.
+    pub axis: Axis,
+    /// Index of the first column or row that is inserted or deleted.
+    pub start: i64,
+    /// Number of columns or rows inserted, or negative for the number
+    /// deleted.
+    pub delta: i64,
+}
+impl RefAdjust {
+    /// Constructs an adjustment for inserting a single column or row.
+    pub fn insert(axis: Axis, index: i64) -> Self {
+        Self {
+            axis,
+            start: index,
+            delta: 1,
+        }
+    }
+    /// Constructs an adjustment for deleting a single column or row.
+    pub fn delete(axis: Axis, index: i64) -> Self {
+        Self {
+            axis,
+            start: index,
+            delta: -1,
+        }
+    }
+
+    /// Returns whether this is a deletion.
+    pub fn is_delete(self) -> bool {
+        self.delta < 0
+    }
+    /// Returns the range of columns or rows that are deleted.
+    pub fn deleted_range(self) -> std::ops::Range<i64> {
+        self.start..self.start - std::cmp::min(self.delta, 0)
+    }
+
+    /// Returns the new index of a column or row, or `None` if it was
+    /// deleted.
+    pub fn adjust(self, index: i64) -> Option<i64> {
+        if index < self.start {
+            Some(index)
+        } else if self.deleted_range().contains(&index) {
+            None
+        } else {
+            // saturate so that unbounded ranges stay unbounded
+            Some(index.saturating_add(self.delta))
+        }
+    }
+    /// Returns the new bounds of the inclusive range `start..=end`, or `None`
+    /// if the whole range was deleted. Inserting inside the range grows it
+    /// and deleting part of the range shrinks it.
+    pub fn adjust_range(self, start: i64, end: i64) -> Option<(i64, i64)> {
+        let new_start = self.adjust(start).unwrap_or(self.start);
+        let new_end = self.adjust(end).unwrap_or(self.start - 1);
+        (new_start <= new_end).then_some((new_start, new_end))
+    }
+
+    /// Returns the new position of a cell, or `None` if it was deleted.
+    pub fn adjust_pos(self, pos: Pos) -> Option<Pos> {
+        Some(match self.axis {
+            Axis::X => Pos {
+                x: self.adjust(pos.x)?,
+                y: pos.y,
+            },
+            Axis::Y => Pos {
+                x: pos.x,
+                y: self.adjust(pos.y)?,
+            },
+        })
+    }
+    /// Returns the first cell after `pos` along the axis that is not
+    /// deleted, or `pos` itself if it is not deleted. The position is from
+    /// before the adjustment.
+    pub fn skip_deleted(self, pos: Pos) -> Pos {
+        let skip = |index: i64| {
+            if self.deleted_range().contains(&index) {
+                self.deleted_range().end
+            } else {
+                index
+            }
+        };
+        match self.axis {
+            Axis::X => Pos {
+                x: skip(pos.x),
+                y: pos.y,
+            },
+            Axis::Y => Pos {
+                x: pos.x,
+                y: skip(pos.y),
+            },
+        }
+    }
+    /// Returns the new bounds of a rectangle, or `None` if it was entirely
+    /// deleted.
+    pub fn adjust_rect(self, rect: Rect) -> Option<Rect> {
+        let mut rect = rect;
+        match self.axis {
+            Axis::X => (rect.min.x, rect.max.x) = self.adjust_range(rect.min.x, rect.max.x)?,
+            Axis::Y => (rect.min.y, rect.max.y) = self.adjust_range(rect.min.y, rect.max.y)?,
+        }
+        Some(rect)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,4 +580,66 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_line_range_parsing() {
+        let base = pos![B3];
+        let range = RangeRef::parse_line_range("A:$C", base).unwrap();
+        assert_eq!(
+            range,
+            RangeRef::ColRange {
+                start: CellRefCoord::Relative(-1),
+                end: CellRefCoord::Absolute(2),
+                sheet: None,
+            }
+        );
+        assert_eq!(range.clone().a1_string(base), "A:$C");
+        assert_eq!(
+            RangeRef::parse_line_range(&range.to_string(), base),
+            Some(range)
+        );
+
+        let range = RangeRef::parse_line_range("$n2:10", base).unwrap();
+        assert_eq!(
+            range,
+            RangeRef::RowRange {
+                start: CellRefCoord::Absolute(-2),
+                end: CellRefCoord::Relative(7),
+                sheet: None,
+            }
+        );
+        assert_eq!(range.clone().a1_string(base), "$n2:10");
+        assert_eq!(
+            RangeRef::parse_line_range(&range.to_string(), base),
+            Some(range)
+        );
+
+        assert_eq!(RangeRef::parse_line_range("A:2", base), None);
+        assert_eq!(RangeRef::parse_line_range("A1:B2", base), None);
+    }
+
+    #[test]
+    fn test_ref_adjust() {
+        let insert = RefAdjust::insert(Axis::X, 3);
+        assert_eq!(insert.adjust(2), Some(2));
+        assert_eq!(insert.adjust(3), Some(4));
+        assert_eq!(insert.adjust_range(1, 5), Some((1, 6)));
+        assert_eq!(insert.adjust_range(3, 5), Some((4, 6)));
+
+        let delete = RefAdjust::delete(Axis::Y, 3);
+        assert_eq!(delete.adjust(2), Some(2));
+        assert_eq!(delete.adjust(3), None);
+        assert_eq!(delete.adjust(4), Some(3));
+        assert_eq!(delete.skip_deleted(pos![B3]), pos![B4]);
+        assert_eq!(delete.skip_deleted(pos![B2]), pos![B2]);
+        assert_eq!(delete.adjust_range(1, 5), Some((1, 4)));
+        assert_eq!(delete.adjust_range(3, 5), Some((3, 4)));
+        assert_eq!(delete.adjust_range(1, 3), Some((1, 2)));
+        assert_eq!(delete.adjust_range(3, 3), None);
+        assert_eq!(
+            delete.adjust_rect(Rect::new(0, 2, 4, 6)),
+            Some(Rect::new(0, 2, 4, 5)),
+        );
+        assert_eq!(delete.adjust_pos(Pos { x: 5, y: 3 }), None);
+    }
 }
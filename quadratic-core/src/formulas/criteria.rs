@@ -150,6 +150,57 @@ impl Criterion {
             // Ignore blank values
             .filter_map_ok(|v| v.coerce_nonblank::<T>()))
     }
+
+    /// Iterates over values in `output_values_range`, excluding those where
+    /// any criterion does not match the corresponding value in its eval range.
+    /// If `output_values_range` is `None`, then the first eval range is used
+    /// instead.
+    ///
+    /// Returns an error if the ranges are not all the same size.
+    pub fn iter_matching_all<'a>(
+        eval_ranges: &'a [Spanned<Array>],
+        criteria: &'a [Criterion],
+        output_values_range: Option<&'a Spanned<Array>>,
+    ) -> CodeResult<impl 'a + Iterator<Item = Spanned<&'a CellValue>>> {
+        let output_values_range = output_values_range
+            .or(eval_ranges.first())
+            .ok_or_else(|| RunErrorMsg::InternalError("no eval ranges".into()).without_span())?;
+        for eval_range in eval_ranges {
+            eval_range.check_array_size_exact(output_values_range.inner.size())?;
+        }
+
+        Ok(output_values_range
+            .inner
+            .cell_values_slice()
+            .iter()
+            .enumerate()
+            .filter(move |(i, _output_value)| {
+                std::iter::zip(eval_ranges, criteria).all(|(eval_range, criterion)| {
+                    criterion.matches(&eval_range.inner.cell_values_slice()[*i])
+                })
+            })
+            .map(|(_i, output_value)| output_value)
+            .with_all_same_span(output_values_range.span))
+    }
+    /// Iterates over values and coerces each one, excluding those that do not
+    /// match all criteria or where coercion fails. See
+    /// [`Criterion::iter_matching_all()`].
+    pub fn iter_matching_all_coerced<'a, T>(
+        eval_ranges: &'a [Spanned<Array>],
+        criteria: &'a [Criterion],
+        output_values_range: Option<&'a Spanned<Array>>,
+    ) -> CodeResult<impl 'a + Iterator<Item = CodeResult<T>>>
+    where
+        &'a CellValue: TryInto<T>,
+    {
+        Ok(
+            Self::iter_matching_all(eval_ranges, criteria, output_values_range)?
+                // Propogate errors
+                .map(|v| v.into_non_error_value())
+                // Ignore blank values
+                .filter_map_ok(|v| v.coerce_nonblank::<T>()),
+        )
+    }
 }
 
 fn strip_compare_fn_prefix(s: &str) -> Option<(CompareFn, &str)> {
@@ -4,7 +4,8 @@ use smallvec::SmallVec;
 
 use super::*;
 use crate::{
-    grid::Grid, Array, CellValue, CodeResult, RunErrorMsg, SheetPos, SheetRect, Span, Spanned,
+    grid::{defined_names::NameValue, Grid, GridBounds, Sheet},
+    Array, ArraySize, CellValue, CodeResult, Pos, RunErrorMsg, SheetPos, SheetRect, Span, Spanned,
     Value,
 };
 
@@ -16,6 +17,18 @@ pub struct Ctx<'ctx> {
     pub sheet_pos: SheetPos,
     /// Cells that have been accessed in evaluating the formula.
     pub cells_accessed: HashSet<SheetRect>,
+    /// Names that are in scope, from outermost to innermost.
+    pub variables: Vec<(String, Binding)>,
+    /// Whether the formula may read the cell it is evaluated at. This is only
+    /// allowed for rules about a cell, such as validations and conditional
+    /// formats, which check the cell's own value.
+    pub allow_self_reference: bool,
+    /// Number of `LAMBDA` calls that are being evaluated, used to stop
+    /// recursion that would overflow the stack.
+    pub lambda_depth: usize,
+    /// Defined names whose formulas are being evaluated, used to detect
+    /// names that refer to themselves.
+    names_in_use: Vec<String>,
 }
 impl<'ctx> Ctx<'ctx> {
     /// Constructs a context for evaluating a formula at `pos` in `grid`.
@@ -24,25 +37,174 @@ impl<'ctx> Ctx<'ctx> {
             grid,
             sheet_pos,
             cells_accessed: HashSet::new(),
+            variables: vec![],
+            allow_self_reference: false,
+            lambda_depth: 0,
+            names_in_use: vec![],
         }
     }
 
+    /// Constructs a context for evaluating a rule about the cell at
+    /// `sheet_pos`, which may read that cell.
+    pub fn new_for_cell_rule(grid: &'ctx Grid, sheet_pos: SheetPos) -> Self {
+        Ctx {
+            allow_self_reference: true,
+            ..Ctx::new(grid, sheet_pos)
+        }
+    }
+
+    /// Returns the innermost binding for `name`, which is case-insensitive.
+    pub fn lookup_variable(&self, name: &str) -> Option<&Binding> {
+        self.variables
+            .iter()
+            .rev()
+            .find(|(variable_name, _)| variable_name.eq_ignore_ascii_case(name))
+            .map(|(_, binding)| binding)
+    }
+
+    /// Evaluates the defined name `name`, or returns an error if there is no
+    /// such name. Errors in the formula of a name are reported at `span`.
+    ///
+    /// When only checking the formula, names are not known, so a blank value
+    /// is returned for names that are not defined.
+    pub fn eval_defined_name(
+        &mut self,
+        name: &str,
+        span: Span,
+        only_parse: bool,
+    ) -> CodeResult<Value> {
+        let Some(defined_name) = self.grid.defined_name(name) else {
+            if only_parse {
+                return Ok(CellValue::Blank.into());
+            }
+            return Err(RunErrorMsg::BadFunctionName.with_span(span));
+        };
+        match &defined_name.value {
+            NameValue::Range(sheet_rect) => {
+                Ok(self.get_cell_array(*sheet_rect, span)?.inner.into())
+            }
+            NameValue::Constant(value) => Ok(value.clone().into()),
+            NameValue::Formula(formula) => {
+                if self
+                    .names_in_use
+                    .iter()
+                    .any(|name_in_use| name_in_use.eq_ignore_ascii_case(name))
+                {
+                    return Err(RunErrorMsg::CircularReference.with_span(span));
+                }
+                let parsed = parse_formula(formula, self.sheet_pos.into())
+                    .map_err(|e| e.msg.with_span(span))?;
+
+                // names from `LET` and `LAMBDA` are not visible in the formula
+                let variables = std::mem::take(&mut self.variables);
+                self.names_in_use.push(name.to_string());
+                let result = parsed.ast.eval(self, only_parse);
+                self.names_in_use.pop();
+                self.variables = variables;
+                Ok(result.map_err(|e| e.msg.with_span(span))?.inner)
+            }
+        }
+    }
+
+    /// Returns the range referred to by the defined name `name`, or `None` if
+    /// it does not refer to a range.
+    pub fn defined_name_range(&self, name: &str) -> Option<SheetRect> {
+        match self.grid.defined_name(name)?.value {
+            NameValue::Range(sheet_rect) => Some(sheet_rect),
+            _ => None,
+        }
+    }
+
+    /// Returns the sheet with the name `sheet_name`, or the sheet containing
+    /// the formula if `sheet_name` is `None`.
+    fn get_sheet(&self, sheet_name: &Option<String>, span: Span) -> CodeResult<&'ctx Sheet> {
+        match sheet_name {
+            Some(sheet_name) => self.grid.try_sheet_from_name(sheet_name.clone()),
+            None => self.grid.try_sheet(self.sheet_pos.sheet_id),
+        }
+        .ok_or(RunErrorMsg::BadCellReference.with_span(span))
+    }
+
+    /// Resolves the cell range from `ref1` to `ref2`, evaluated at the
+    /// position of the formula. The sheet is taken from `ref1`.
+    pub fn resolve_range_ref(
+        &self,
+        ref1: &CellRef,
+        ref2: &CellRef,
+        span: Span,
+    ) -> CodeResult<SheetRect> {
+        let sheet = self.get_sheet(&ref1.sheet, span)?;
+        let corner1 = ref1.resolve_from(self.sheet_pos.into());
+        let corner2 = ref2.resolve_from(self.sheet_pos.into());
+        Ok(SheetRect::new_pos_span(corner1, corner2, sheet.id))
+    }
+
+    /// Resolves a reference to whole columns or rows. The region returned is
+    /// unbounded along the other axis.
+    pub fn resolve_line_range(&self, range_ref: &RangeRef, span: Span) -> CodeResult<SheetRect> {
+        let base: Pos = self.sheet_pos.into();
+        let (sheet, min, max) = match range_ref {
+            RangeRef::ColRange { start, end, sheet } => {
+                let (x1, x2) = (start.resolve_from(base.x), end.resolve_from(base.x));
+                let min = Pos {
+                    x: x1.min(x2),
+                    y: i64::MIN,
+                };
+                let max = Pos {
+                    x: x1.max(x2),
+                    y: i64::MAX,
+                };
+                (sheet, min, max)
+            }
+            RangeRef::RowRange { start, end, sheet } => {
+                let (y1, y2) = (start.resolve_from(base.y), end.resolve_from(base.y));
+                let min = Pos {
+                    x: i64::MIN,
+                    y: y1.min(y2),
+                };
+                let max = Pos {
+                    x: i64::MAX,
+                    y: y1.max(y2),
+                };
+                (sheet, min, max)
+            }
+            _ => internal_error!("expected a column or row range"),
+        };
+        let sheet = self.get_sheet(sheet, span)?;
+        Ok(SheetRect {
+            min,
+            max,
+            sheet_id: sheet.id,
+        })
+    }
+
+    /// Limits the unbounded axis of a region returned by
+    /// [`Ctx::resolve_line_range()`] to the data bounds of its sheet.
+    pub fn bound_line_range(&self, mut sheet_rect: SheetRect) -> SheetRect {
+        let bounds = match self.grid.try_sheet(sheet_rect.sheet_id) {
+            Some(sheet) => sheet.bounds(true),
+            None => GridBounds::Empty,
+        };
+        let (min, max) = match bounds {
+            GridBounds::NonEmpty(rect) => (rect.min, rect.max),
+            GridBounds::Empty => (Pos { x: 0, y: 0 }, Pos { x: 0, y: 0 }),
+        };
+        if sheet_rect.min.x == i64::MIN || sheet_rect.max.x == i64::MAX {
+            (sheet_rect.min.x, sheet_rect.max.x) = (min.x, max.x);
+        }
+        if sheet_rect.min.y == i64::MIN || sheet_rect.max.y == i64::MAX {
+            (sheet_rect.min.y, sheet_rect.max.y) = (min.y, max.y);
+        }
+        sheet_rect
+    }
+
     /// Fetches the contents of the cell at `ref_pos` evaluated at `base_pos`,
     /// or returns an error in the case of a circular reference.
     pub fn get_cell(&mut self, ref_pos: &CellRef, span: Span) -> CodeResult<Spanned<CellValue>> {
-        let sheet = match &ref_pos.sheet {
-            Some(sheet_name) => self
-                .grid
-                .try_sheet_from_name(sheet_name.clone())
-                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
-            None => self
-                .grid
-                .try_sheet(self.sheet_pos.sheet_id)
-                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
-        };
+        let sheet = self.get_sheet(&ref_pos.sheet, span)?;
         let ref_pos = ref_pos.resolve_from(self.sheet_pos.into());
         let ref_pos_with_sheet = ref_pos.to_sheet_pos(sheet.id);
-        if ref_pos_with_sheet == self.sheet_pos {
+        if ref_pos_with_sheet == self.sheet_pos && !self.allow_self_reference {
             return Err(RunErrorMsg::CircularReference.with_span(span));
         }
 
@@ -52,6 +214,77 @@ impl<'ctx> Ctx<'ctx> {
         Ok(Spanned { inner: value, span })
     }
 
+    /// Fetches the contents of the cells in `sheet_rect`, or returns an error
+    /// in the case of a circular reference or if the range is too big.
+    ///
+    /// The whole of `sheet_rect` is recorded in `cells_accessed`.
+    pub fn get_cell_array(
+        &mut self,
+        sheet_rect: SheetRect,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        self.get_cells_accessing(sheet_rect, sheet_rect, span)
+    }
+
+    /// Fetches the contents of whole columns or rows, limited to the data
+    /// bounds of the sheet.
+    ///
+    /// The unbounded region is recorded in `cells_accessed`, so that the
+    /// formula is rerun when cells are added outside the current bounds.
+    pub fn get_line_range_array(
+        &mut self,
+        range_ref: &RangeRef,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let accessed = self.resolve_line_range(range_ref, span)?;
+        self.get_cells_accessing(self.bound_line_range(accessed), accessed, span)
+    }
+
+    /// Fetches the contents of the cells in `sheet_rect` and records
+    /// `accessed`, which must contain `sheet_rect`, in `cells_accessed`.
+    fn get_cells_accessing(
+        &mut self,
+        sheet_rect: SheetRect,
+        accessed: SheetRect,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let sheet = self
+            .grid
+            .try_sheet(sheet_rect.sheet_id)
+            .ok_or(RunErrorMsg::BadCellReference.with_span(span))?;
+
+        let width = (sheet_rect.max.x.saturating_sub(sheet_rect.min.x))
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        let height = (sheet_rect.max.y.saturating_sub(sheet_rect.min.y))
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
+            return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+        }
+        if accessed.contains(self.sheet_pos) && !self.allow_self_reference {
+            return Err(RunErrorMsg::CircularReference.with_span(span));
+        }
+
+        self.cells_accessed.insert(accessed);
+
+        let values = sheet_rect
+            .iter()
+            .map(|sheet_pos| {
+                sheet
+                    .display_value(sheet_pos.into())
+                    .unwrap_or(CellValue::Blank)
+            })
+            .collect();
+        let size = ArraySize::new_or_err(width, height)?;
+        Ok(Spanned {
+            inner: Array::new_row_major(size, values)?,
+            span,
+        })
+    }
+
     /// Evaluates a function once for each corresponding set of values from
     /// `arrays`.
     ///
@@ -0,0 +1,557 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use super::*;
+use crate::ArraySize;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Array functions",
+    docs: "These functions return arrays, which spill into the cells below \
+           and to the right of the formula. If any of those cells is not \
+           empty, then the formula shows a spill error instead.\n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the rows of `array` for which the corresponding value in
+            /// `include` is truthy.
+            ///
+            /// `include` must be a single column with the same height as
+            /// `array`, or a single row with the same width as `array`. If it
+            /// is a single row, then columns are filtered instead of rows.
+            ///
+            /// If no rows are included, then `if_empty` is returned, or an
+            /// error if `if_empty` is omitted.
+            #[examples(
+                "FILTER(A1:C10, B1:B10 > 50)",
+                "FILTER(A1:C10, A1:A10 = \"apple\", \"no apples\")"
+            )]
+            fn FILTER(
+                span: Span,
+                array: (Spanned<Array>),
+                include: (Spanned<Array>),
+                if_empty: (Option<Spanned<Value>>),
+            ) {
+                let axis = line_axis(&array.inner, &include)?;
+                let keep = include
+                    .inner
+                    .cell_values_slice()
+                    .iter()
+                    .map(|value| {
+                        let value = Spanned {
+                            span: include.span,
+                            inner: value,
+                        };
+                        Ok(value.try_coerce::<bool>()?.inner)
+                    })
+                    .collect::<CodeResult<Vec<bool>>>()?;
+
+                if !keep.contains(&true) {
+                    return match if_empty {
+                        Some(if_empty) => Ok(if_empty.inner),
+                        None => Err(RunErrorMsg::EmptyArray.with_span(span)),
+                    };
+                }
+
+                Value::from(rearrange_lines(&array.inner, axis, |lines| {
+                    Ok(lines
+                        .into_iter()
+                        .zip(&keep)
+                        .filter(|(_line, &keep)| keep)
+                        .map(|(line, _keep)| line)
+                        .collect())
+                })?)
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of `array` by the values in one of its columns.
+            ///
+            /// `sort_index` is the column to sort by, starting from `1`. If it
+            /// is omitted, then the first column is used.
+            ///
+            /// If `sort_order` is `1` or omitted, then rows are sorted in
+            /// ascending order. If `sort_order` is `-1`, then rows are sorted
+            /// in descending order.
+            ///
+            /// If `by_column` is `TRUE`, then columns are sorted by the values
+            /// in one of the rows instead.
+            ///
+            /// Numbers sort before text, which sorts before logical values.
+            /// Blank values sort as zero.
+            #[examples("SORT(A1:C10)", "SORT(A1:C10, 3, -1)", "SORT(A1:J3, 2, 1, TRUE)")]
+            fn SORT(
+                array: (Spanned<Array>),
+                sort_index: (Option<Spanned<i64>>),
+                sort_order: (Option<Spanned<i64>>),
+                by_column: (Option<bool>),
+            ) {
+                let axis = if by_column == Some(true) {
+                    Axis::X
+                } else {
+                    Axis::Y
+                };
+                let line_len = match axis {
+                    Axis::X => array.inner.height(),
+                    Axis::Y => array.inner.width(),
+                };
+                let index = match sort_index {
+                    None => 0,
+                    Some(sort_index) => {
+                        u32::try_from(sort_index.inner)
+                            .ok()
+                            .filter(|i| (1..=line_len).contains(i))
+                            .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(sort_index.span))?
+                            as usize
+                            - 1
+                    }
+                };
+                let sort_order = SortOrder::try_from(sort_order)?;
+
+                rearrange_lines(&array.inner, axis, |mut lines| {
+                    try_sort_by(&mut lines, |a, b| {
+                        Ok(sort_order.apply(a[index].cmp(&b[index])?))
+                    })?;
+                    Ok(lines)
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of `array` by the values in other arrays.
+            ///
+            /// Each `by_array` must be a single column with the same height as
+            /// `array`, or a single row with the same width as `array`. If they
+            /// are single rows, then columns are sorted instead of rows.
+            ///
+            /// Each `by_array` may be followed by a `sort_order`. If
+            /// `sort_order` is `1` or omitted, then values are sorted in
+            /// ascending order. If `sort_order` is `-1`, then values are sorted
+            /// in descending order. Later arrays are only used to break ties
+            /// between values in earlier arrays.
+            #[examples(
+                "SORTBY(A1:C10, B1:B10)",
+                "SORTBY(A1:C10, B1:B10, -1, C1:C10, 1)",
+                "SORTBY(A1:J1, A2:J2)"
+            )]
+            fn SORTBY(
+                span: Span,
+                array: (Spanned<Array>),
+                by_arrays_and_sort_orders: (Iter<Spanned<Value>>),
+            ) {
+                let mut sort_keys = vec![];
+                let mut axis = None;
+                while let Some(by_array) = by_arrays_and_sort_orders.next() {
+                    let by_array = by_array?.map(Array::from);
+                    let by_array_axis = line_axis(&array.inner, &by_array)?;
+                    if *axis.get_or_insert(by_array_axis) != by_array_axis {
+                        return Err(RunErrorMsg::ExactArraySizeMismatch {
+                            expected: ArraySize::new_or_err(1, array.inner.height())?,
+                            got: by_array.inner.size(),
+                        }
+                        .with_span(by_array.span));
+                    }
+                    let sort_order = match by_arrays_and_sort_orders.next() {
+                        Some(sort_order) => Some(sort_order?.try_coerce::<i64>()?),
+                        None => None,
+                    };
+                    sort_keys.push((by_array.inner, SortOrder::try_from(sort_order)?));
+                }
+                let Some(axis) = axis else {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "SORTBY".into(),
+                        arg_name: "by_array".into(),
+                    }
+                    .with_span(span));
+                };
+
+                rearrange_lines(&array.inner, axis, |lines| {
+                    let mut order = (0..lines.len()).collect::<Vec<usize>>();
+                    try_sort_by(&mut order, |&a, &b| {
+                        for (by_array, sort_order) in &sort_keys {
+                            let by_values = by_array.cell_values_slice();
+                            match sort_order.apply(by_values[a].cmp(&by_values[b])?) {
+                                Ordering::Equal => continue,
+                                other => return Ok(other),
+                            }
+                        }
+                        Ok(Ordering::Equal)
+                    })?;
+                    Ok(order.into_iter().map(|i| lines[i]).collect())
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the unique rows of `array`, in the order in which they
+            /// first appear.
+            ///
+            /// If `by_column` is `TRUE`, then unique columns are returned
+            /// instead.
+            ///
+            /// If `exactly_once` is `TRUE`, then only rows that appear exactly
+            /// once in `array` are returned.
+            ///
+            /// Text is compared case-insensitively.
+            #[examples("UNIQUE(A1:A10)", "UNIQUE(A1:C10, FALSE, TRUE)", "UNIQUE(A1:J1, TRUE)")]
+            fn UNIQUE(
+                array: (Spanned<Array>),
+                by_column: (Option<bool>),
+                exactly_once: (Option<bool>),
+            ) {
+                let axis = if by_column == Some(true) {
+                    Axis::X
+                } else {
+                    Axis::Y
+                };
+                let exactly_once = exactly_once == Some(true);
+
+                rearrange_lines(&array.inner, axis, |lines| {
+                    let keys = lines
+                        .iter()
+                        .map(|line| line.iter().map(unique_key).collect())
+                        .collect::<CodeResult<Vec<Vec<String>>>>()?;
+
+                    let mut counts = HashMap::<&Vec<String>, usize>::new();
+                    for key in &keys {
+                        *counts.entry(key).or_default() += 1;
+                    }
+                    let mut seen = HashSet::new();
+
+                    Ok(lines
+                        .into_iter()
+                        .zip(&keys)
+                        .filter(|(_line, key)| match exactly_once {
+                            true => counts[key] == 1,
+                            false => seen.insert(*key),
+                        })
+                        .map(|(line, _key)| line)
+                        .collect())
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array of sequential numbers with `rows` rows and
+            /// `columns` columns, filled row by row.
+            ///
+            /// The sequence begins at `start` and increases by `step`. If
+            /// `columns`, `start`, or `step` are omitted, they default to
+            /// `1`.
+            #[examples("SEQUENCE(10)", "SEQUENCE(3, 4)", "SEQUENCE(5, 1, 10, -2)")]
+            fn SEQUENCE(
+                span: Span,
+                rows: (Spanned<i64>),
+                columns: (Option<Spanned<i64>>),
+                start: (Option<f64>),
+                step: (Option<f64>),
+            ) {
+                let h = sequence_len(rows)?;
+                let w = columns.map_or(Ok(1), sequence_len)?;
+                if w as u64 * h as u64 > crate::limits::CELL_RANGE_LIMIT as u64 {
+                    return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+                }
+
+                let start = start.unwrap_or(1.0);
+                let step = step.unwrap_or(1.0);
+                let size = ArraySize::new_or_err(w, h)?;
+                let values = (0..size.len())
+                    .map(|i| CellValue::from(start + step * i as f64))
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Swaps the rows and columns of an array.
+            #[examples("TRANSPOSE(A1:C10)", "TRANSPOSE({1, 2, 3})")]
+            fn TRANSPOSE(array: Array) {
+                array.transpose()
+            }
+        ),
+    ]
+}
+
+/// Returns the axis along which `key` lines up with `array`: `Axis::Y` if
+/// `key` is a single column with the same height as `array`, or `Axis::X` if
+/// `key` is a single row with the same width as `array`.
+fn line_axis(array: &Array, key: &Spanned<Array>) -> CodeResult<Axis> {
+    if key.inner.width() == 1 && key.inner.height() == array.height() {
+        Ok(Axis::Y)
+    } else if key.inner.height() == 1 && key.inner.width() == array.width() {
+        Ok(Axis::X)
+    } else {
+        Err(RunErrorMsg::ExactArraySizeMismatch {
+            expected: ArraySize::new_or_err(1, array.height())?,
+            got: key.inner.size(),
+        }
+        .with_span(key.span))
+    }
+}
+
+/// Constructs a new array from the rows of `array` (or its columns, if `axis`
+/// is `Axis::X`) after they have been filtered or reordered by `f`. Returns an
+/// error if `f` returns no lines.
+fn rearrange_lines(
+    array: &Array,
+    axis: Axis,
+    f: impl for<'a> FnOnce(Vec<&'a [CellValue]>) -> CodeResult<Vec<&'a [CellValue]>>,
+) -> CodeResult<Array> {
+    let transposed;
+    let array = match axis {
+        Axis::X => {
+            transposed = array.transpose();
+            &transposed
+        }
+        Axis::Y => array,
+    };
+
+    let lines = f(array.rows().collect())?;
+    let size = ArraySize::new_or_err(array.width(), lines.len() as u32)?;
+    let result = Array::new_row_major(size, lines.into_iter().flatten().cloned().collect())?;
+
+    Ok(match axis {
+        Axis::X => result.transpose(),
+        Axis::Y => result,
+    })
+}
+
+/// Sorts `values` using a comparison function that may return an error, and
+/// returns the first error encountered (if any). The sort is stable.
+///
+/// This is a merge sort that stops at the first error. `sort_by()` may panic
+/// if the comparison is not a total order, which it would not be if errors
+/// were treated as equal.
+fn try_sort_by<T: Copy>(
+    values: &mut [T],
+    mut compare: impl FnMut(&T, &T) -> CodeResult<Ordering>,
+) -> CodeResult<()> {
+    fn merge_sort<T: Copy>(
+        values: &mut [T],
+        compare: &mut impl FnMut(&T, &T) -> CodeResult<Ordering>,
+    ) -> CodeResult<()> {
+        if values.len() <= 1 {
+            return Ok(());
+        }
+        let mid = values.len() / 2;
+        merge_sort(&mut values[..mid], compare)?;
+        merge_sort(&mut values[mid..], compare)?;
+
+        let mut merged = Vec::with_capacity(values.len());
+        let (mut i, mut j) = (0, mid);
+        while i < mid && j < values.len() {
+            // take from the right only if it is strictly less, to keep the
+            // sort stable
+            if compare(&values[j], &values[i])? == Ordering::Less {
+                merged.push(values[j]);
+                j += 1;
+            } else {
+                merged.push(values[i]);
+                i += 1;
+            }
+        }
+        merged.extend_from_slice(&values[i..mid]);
+        merged.extend_from_slice(&values[j..]);
+        values.copy_from_slice(&merged);
+        Ok(())
+    }
+
+    merge_sort(values, &mut compare)
+}
+
+/// Returns a key that is equal for values that `UNIQUE` considers the same.
+fn unique_key(value: &CellValue) -> CodeResult<String> {
+    match value {
+        CellValue::Error(e) => Err((**e).clone()),
+        CellValue::Text(s) => Ok(format!("text:{}", s.to_ascii_uppercase())),
+        CellValue::Number(n) => Ok(format!("number:{}", n.normalized())),
+        other => Ok(format!("{}:{other}", other.type_name())),
+    }
+}
+
+/// Returns the length of one side of a `SEQUENCE`.
+fn sequence_len(len: Spanned<i64>) -> CodeResult<u32> {
+    match len.inner {
+        0 => Err(RunErrorMsg::EmptyArray.with_span(len.span)),
+        n if n < 0 => Err(RunErrorMsg::InvalidArgument.with_span(len.span)),
+        n => u32::try_from(n)
+            .ok()
+            .filter(|&n| n <= crate::limits::CELL_RANGE_LIMIT)
+            .ok_or(RunErrorMsg::ArrayTooBig.with_span(len.span)),
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+impl SortOrder {
+    fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    }
+}
+impl TryFrom<Option<Spanned<i64>>> for SortOrder {
+    type Error = RunError;
+
+    fn try_from(value: Option<Spanned<i64>>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(SortOrder::default()),
+            Some(v) => match v.inner {
+                1 => Ok(SortOrder::Ascending),
+                -1 => Ok(SortOrder::Descending),
+                _ => Err(RunErrorMsg::InvalidArgument.with_span(v.span)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    fn grid_with_table() -> Grid {
+        Grid::from_array(
+            pos![A1],
+            &array![
+                "pear", 3, "b";
+                "apple", 10, "a";
+                "fig", 3, "c";
+                "Apple", 7, "a";
+            ],
+        )
+    }
+
+    #[test]
+    fn test_filter() {
+        let g = grid_with_table();
+
+        assert_eq!(
+            "{apple, 10, a; Apple, 7, a}",
+            eval_to_string(&g, "FILTER(A1:C4, B1:B4 > 5)"),
+        );
+        assert_eq!(
+            "{pear; fig}",
+            eval_to_string(&g, "FILTER(A1:A4, {TRUE; FALSE; 1; 0})"),
+        );
+        assert_eq!(
+            "{pear, b; apple, a; fig, c; Apple, a}",
+            eval_to_string(&g, "FILTER(A1:C4, {TRUE, FALSE, TRUE})"),
+        );
+        assert_eq!(
+            "none",
+            eval_to_string(&g, "FILTER(A1:C4, B1:B4 > 50, 'none')")
+        );
+
+        expect_err(&RunErrorMsg::EmptyArray, &g, "FILTER(A1:C4, B1:B4 > 50)");
+        assert!(matches!(
+            eval_to_err(&g, "FILTER(A1:C4, B1:B3 > 5)").msg,
+            RunErrorMsg::ExactArraySizeMismatch { .. },
+        ));
+    }
+
+    #[test]
+    fn test_sort() {
+        let g = grid_with_table();
+
+        assert_eq!(
+            "{apple, 10, a; Apple, 7, a; fig, 3, c; pear, 3, b}",
+            eval_to_string(&g, "SORT(A1:C4)"),
+        );
+        assert_eq!(
+            "{apple, 10, a; Apple, 7, a; pear, 3, b; fig, 3, c}",
+            eval_to_string(&g, "SORT(A1:C4, 2, -1)"),
+        );
+        assert_eq!(
+            "{1, 2, 3, a, TRUE}",
+            eval_to_string(&g, "SORT({3, TRUE, 'a', 1, 2}, 1, 1, TRUE)")
+        );
+        assert_eq!("{3; 2; 1}", eval_to_string(&g, "SORT({2; 3; 1}, , -1)"));
+
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "SORT(A1:C4, 4)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "SORT(A1:C4, 1, 2)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "SORT({1; 2} / {1; 0})");
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            "SORT(SEQUENCE(40) / (SEQUENCE(40) - 20))",
+        );
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            "SORTBY(SEQUENCE(40), 1 / (SEQUENCE(40) - 20))",
+        );
+    }
+
+    #[test]
+    fn test_sortby() {
+        let g = grid_with_table();
+
+        assert_eq!(
+            "{pear; fig; Apple; apple}",
+            eval_to_string(&g, "SORTBY(A1:A4, B1:B4)"),
+        );
+        assert_eq!(
+            "{apple; Apple; pear; fig}",
+            eval_to_string(&g, "SORTBY(A1:A4, C1:C4, 1, B1:B4, -1)"),
+        );
+        assert_eq!(
+            "{c, b, a}",
+            eval_to_string(&g, "SORTBY({'a', 'b', 'c'}, {3, 2, 1})"),
+        );
+
+        assert!(matches!(
+            eval_to_err(&g, "SORTBY(A1:A4)").msg,
+            RunErrorMsg::MissingRequiredArgument { .. },
+        ));
+        assert!(matches!(
+            eval_to_err(&g, "SORTBY(A1:C4, B1:B3)").msg,
+            RunErrorMsg::ExactArraySizeMismatch { .. },
+        ));
+    }
+
+    #[test]
+    fn test_unique() {
+        let g = grid_with_table();
+
+        assert_eq!("{a; c}", eval_to_string(&g, "UNIQUE({'a'; 'c'; 'A'; 'c'})"));
+        assert_eq!("{b; c}", eval_to_string(&g, "UNIQUE(C1:C4, FALSE, TRUE)"));
+        assert_eq!(
+            "{pear, 3; apple, 10; fig, 3; Apple, 7}",
+            eval_to_string(&g, "UNIQUE(A1:B4)"),
+        );
+        assert_eq!("{1, 2}", eval_to_string(&g, "UNIQUE({1, 2, 1, 2.0}, TRUE)"));
+
+        expect_err(&RunErrorMsg::EmptyArray, &g, "UNIQUE({1, 1}, TRUE, TRUE)");
+    }
+
+    #[test]
+    fn test_sequence() {
+        let g = Grid::new();
+
+        assert_eq!("{1; 2; 3}", eval_to_string(&g, "SEQUENCE(3)"));
+        assert_eq!("{1, 2, 3; 4, 5, 6}", eval_to_string(&g, "SEQUENCE(2, 3)"));
+        assert_eq!("{10; 8; 6}", eval_to_string(&g, "SEQUENCE(3, 1, 10, -2)"));
+        assert_eq!("{0, 0.5, 1}", eval_to_string(&g, "SEQUENCE(1, 3, 0, 0.5)"));
+
+        expect_err(&RunErrorMsg::EmptyArray, &g, "SEQUENCE(0)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "SEQUENCE(-1)");
+        expect_err(&RunErrorMsg::ArrayTooBig, &g, "SEQUENCE(10000, 10000)");
+        expect_err(&RunErrorMsg::ArrayTooBig, &g, "SEQUENCE(2000000)");
+    }
+
+    #[test]
+    fn test_transpose() {
+        let g = grid_with_table();
+
+        assert_eq!("{1; 2; 3}", eval_to_string(&g, "TRANSPOSE({1, 2, 3})"));
+        assert_eq!(
+            "{pear, apple; 3, 10}",
+            eval_to_string(&g, "TRANSPOSE(A1:B2)"),
+        );
+        assert_eq!("{5}", eval_to_string(&g, "TRANSPOSE(5)"));
+    }
+}
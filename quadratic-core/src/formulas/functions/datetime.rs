@@ -0,0 +1,500 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc, Weekday};
+
+use crate::{Duration, Instant};
+
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Date & time functions",
+    docs: "Dates and times are in UTC. Adding or subtracting a number from a \
+           date adds or subtracts that many days.\n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the current date and time.
+            #[examples("NOW()")]
+            fn NOW() {
+                CellValue::Instant(Instant::from(Utc::now().naive_utc()))
+            }
+        ),
+        formula_fn!(
+            /// Returns the current date, at midnight.
+            #[examples("TODAY()")]
+            fn TODAY() {
+                CellValue::Instant(Instant::from(Utc::now().date_naive()))
+            }
+        ),
+        formula_fn!(
+            /// Returns a date constructed from a year, month, and day.
+            ///
+            /// If `month` is greater than `12` or less than `1`, then it adds
+            /// or subtracts years. If `day` is greater than the number of days
+            /// in the month or less than `1`, then it adds or subtracts days.
+            #[examples("DATE(2024, 3, 14)", "DATE(A1, B1 + 1, 1)")]
+            #[zip_map]
+            fn DATE(span: Span, [year]: i64, [month]: i64, [day]: i64) {
+                let date = year
+                    .checked_mul(12)
+                    .and_then(|months| months.checked_add(month.checked_sub(1)?))
+                    .and_then(|months| {
+                        let year = i32::try_from(months.div_euclid(12)).ok()?;
+                        let month = months.rem_euclid(12) as u32 + 1;
+                        NaiveDate::from_ymd_opt(year, month, 1)
+                    })
+                    .and_then(|first_of_month| {
+                        let days = TimeDelta::try_days(day.checked_sub(1)?)?;
+                        first_of_month.checked_add_signed(days)
+                    })
+                    .ok_or(RunErrorMsg::InvalidArgument.with_span(*span))?;
+                Instant::from(date)
+            }
+        ),
+        formula_fn!(
+            /// Returns a duration constructed from a number of hours, minutes,
+            /// and seconds.
+            #[examples("TIME(13, 30, 0)", "DATE(2024, 3, 14) + TIME(9, 0, 0)")]
+            #[zip_map]
+            fn TIME([hour]: f64, [minute]: f64, [second]: f64) {
+                Duration::from_seconds(hour * 3600.0 + minute * 60.0 + second)
+            }
+        ),
+        formula_fn!(
+            /// Returns the year of a date.
+            #[examples("YEAR(A1)", "YEAR(DATE(2024, 3, 14))")]
+            #[zip_map]
+            fn YEAR([date]: (Spanned<Instant>)) {
+                date_time_arg(date)?.year() as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the month of a date, from `1` to `12`.
+            #[examples("MONTH(A1)", "MONTH(DATE(2024, 3, 14))")]
+            #[zip_map]
+            fn MONTH([date]: (Spanned<Instant>)) {
+                date_time_arg(date)?.month() as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the month of a date, from `1` to `31`.
+            #[examples("DAY(A1)", "DAY(DATE(2024, 3, 14))")]
+            #[zip_map]
+            fn DAY([date]: (Spanned<Instant>)) {
+                date_time_arg(date)?.day() as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the hour of a date and time or a duration, from `0` to
+            /// `23`.
+            #[examples("HOUR(NOW())", "HOUR(TIME(13, 30, 0))")]
+            #[zip_map]
+            fn HOUR([time]: (Spanned<CellValue>)) {
+                (seconds_of_day_arg(*time)? / 3600.0).floor()
+            }
+        ),
+        formula_fn!(
+            /// Returns the minute of a date and time or a duration, from `0` to
+            /// `59`.
+            #[examples("MINUTE(NOW())", "MINUTE(TIME(13, 30, 0))")]
+            #[zip_map]
+            fn MINUTE([time]: (Spanned<CellValue>)) {
+                (seconds_of_day_arg(*time)? / 60.0).floor() % 60.0
+            }
+        ),
+        formula_fn!(
+            /// Returns the second of a date and time or a duration, from `0` to
+            /// `59`.
+            #[examples("SECOND(NOW())", "SECOND(TIME(13, 30, 15))")]
+            #[zip_map]
+            fn SECOND([time]: (Spanned<CellValue>)) {
+                seconds_of_day_arg(*time)?.floor() % 60.0
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the week of a date as a number.
+            ///
+            /// `return_type` determines the numbering:
+            ///
+            /// - `1` (default): Sunday is `1` and Saturday is `7`
+            /// - `2`: Monday is `1` and Sunday is `7`
+            /// - `3`: Monday is `0` and Sunday is `6`
+            #[examples("WEEKDAY(A1)", "WEEKDAY(DATE(2024, 3, 14), 2)")]
+            #[zip_map]
+            fn WEEKDAY([date]: (Spanned<Instant>), [return_type]: (Option<Spanned<i64>>)) {
+                let weekday = date_time_arg(date)?.weekday();
+                match return_type
+                    .map(|t| (t.inner, t.span))
+                    .unwrap_or((1, date.span))
+                {
+                    (1, _) => weekday.number_from_sunday() as f64,
+                    (2, _) => weekday.number_from_monday() as f64,
+                    (3, _) => weekday.num_days_from_monday() as f64,
+                    (_, span) => return Err(RunErrorMsg::InvalidArgument.with_span(span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `months` months before or after
+            /// `start_date`.
+            ///
+            /// If the day does not exist in the resulting month, then the last
+            /// day of the month is used instead.
+            #[examples("EDATE(A1, 1)", "EDATE(DATE(2024, 1, 31), 1)")]
+            #[zip_map]
+            fn EDATE(span: Span, [start_date]: (Spanned<Instant>), [months]: i64) {
+                let date = date_time_arg(start_date)?.date();
+                Instant::from(
+                    add_months(date, months)
+                        .ok_or(RunErrorMsg::InvalidArgument.with_span(*span))?,
+                )
+            }
+        ),
+        formula_fn!(
+            /// Returns the last day of the month that is `months` months
+            /// before or after `start_date`.
+            #[examples("EOMONTH(A1, 0)", "EOMONTH(DATE(2024, 1, 15), 1)")]
+            #[zip_map]
+            fn EOMONTH(span: Span, [start_date]: (Spanned<Instant>), [months]: i64) {
+                let date = date_time_arg(start_date)?.date();
+                let end_of_month = date
+                    .with_day(1)
+                    .and_then(|first_of_month| add_months(first_of_month, months.checked_add(1)?))
+                    .and_then(|first_of_next_month| first_of_next_month.pred_opt())
+                    .ok_or(RunErrorMsg::InvalidArgument.with_span(*span))?;
+                Instant::from(end_of_month)
+            }
+        ),
+        formula_fn!(
+            /// Returns the difference between two dates in the given `unit`.
+            ///
+            /// `unit` must be one of the following:
+            ///
+            /// - `"Y"`: whole years
+            /// - `"M"`: whole months
+            /// - `"D"`: days
+            /// - `"MD"`: days, ignoring months and years
+            /// - `"YM"`: whole months, ignoring years
+            /// - `"YD"`: days, ignoring years
+            ///
+            /// Returns an error if `start_date` is after `end_date`.
+            #[examples("DATEDIF(A1, B1, \"Y\")", "DATEDIF(DATE(2020, 1, 15), TODAY(), \"M\")")]
+            #[zip_map]
+            fn DATEDIF(
+                span: Span,
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                [unit]: (Spanned<String>),
+            ) {
+                let start = date_time_arg(start_date)?.date();
+                let end = date_time_arg(end_date)?.date();
+                if start > end {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(*span));
+                }
+                let whole_months = whole_months_between(start, end);
+                match unit.inner.to_ascii_uppercase().as_str() {
+                    "Y" => (whole_months / 12) as f64,
+                    "M" => whole_months as f64,
+                    "D" => (end - start).num_days() as f64,
+                    "MD" => {
+                        let anchor = add_months(start, whole_months as i64).unwrap_or(start);
+                        (end - anchor).num_days() as f64
+                    }
+                    "YM" => (whole_months % 12) as f64,
+                    "YD" => {
+                        let anchor =
+                            add_months(start, (whole_months / 12 * 12) as i64).unwrap_or(start);
+                        (end - anchor).num_days() as f64
+                    }
+                    _ => return Err(RunErrorMsg::InvalidArgument.with_span(unit.span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of working days (Monday through Friday)
+            /// between two dates, including both `start_date` and `end_date`.
+            ///
+            /// Dates in `holidays` are not counted. If `start_date` is after
+            /// `end_date`, then the result is negative.
+            #[examples(
+                "NETWORKDAYS(A1, B1)",
+                "NETWORKDAYS(DATE(2024, 1, 1), DATE(2024, 1, 31), C1:C5)"
+            )]
+            fn NETWORKDAYS(
+                start_date: (Spanned<Instant>),
+                end_date: (Spanned<Instant>),
+                holidays: (Option<Spanned<Array>>),
+            ) {
+                let start = date_time_arg(start_date)?.date();
+                let end = date_time_arg(end_date)?.date();
+                let mut holiday_dates = HashSet::new();
+                if let Some(holidays) = holidays {
+                    for value in holidays.inner.cell_values_slice() {
+                        if value.is_blank() {
+                            continue;
+                        }
+                        let holiday = Spanned {
+                            span: holidays.span,
+                            inner: value,
+                        }
+                        .try_coerce::<Instant>()?;
+                        holiday_dates.insert(date_time_arg(holiday)?.date());
+                    }
+                }
+
+                let (first, last, sign) = if start <= end {
+                    (start, end, 1.0)
+                } else {
+                    (end, start, -1.0)
+                };
+                // every whole week has five working days, and the days left
+                // over start on the same day of the week as `first`
+                let days = (last - first).num_days() + 1;
+                let first_weekday = first.weekday().num_days_from_monday() as i64;
+                let extra_days = (0..days % 7)
+                    .filter(|i| (first_weekday + i) % 7 < 5)
+                    .count() as i64;
+                let holidays = holiday_dates
+                    .iter()
+                    .filter(|date| (first..=last).contains(*date))
+                    .filter(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+                    .count() as i64;
+                sign * (days / 7 * 5 + extra_days - holidays) as f64
+            }
+        ),
+    ]
+}
+
+/// Converts an instant argument to a date and time, returning an error if it
+/// is out of range.
+fn date_time_arg(instant: Spanned<Instant>) -> CodeResult<NaiveDateTime> {
+    instant
+        .inner
+        .to_naive_date_time()
+        .ok_or(RunErrorMsg::InvalidArgument.with_span(instant.span))
+}
+
+/// Returns the number of seconds since midnight of an instant or duration.
+fn seconds_of_day_arg(value: Spanned<&CellValue>) -> CodeResult<f64> {
+    match value.inner {
+        CellValue::Duration(duration) => Ok(duration.seconds.rem_euclid(SECONDS_PER_DAY)),
+        _ => {
+            let instant = value.try_coerce::<Instant>()?;
+            let time = date_time_arg(instant)?.time();
+            Ok(time.num_seconds_from_midnight() as f64)
+        }
+    }
+}
+
+/// Adds a (possibly negative) number of months to a date, clamping the day to
+/// the end of the month.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let abs_months = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        date.checked_add_months(abs_months)
+    } else {
+        date.checked_sub_months(abs_months)
+    }
+}
+
+/// Returns the number of whole months from `start` to `end`, assuming `start
+/// <= end`.
+fn whole_months_between(start: NaiveDate, end: NaiveDate) -> i32 {
+    let mut months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    if end.day() < start.day() {
+        months -= 1;
+    }
+    months.max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_formula_date() {
+        let g = Grid::new();
+        assert_eq!(
+            "2024-03-14 00:00:00",
+            eval_to_string(&g, "DATE(2024, 3, 14)"),
+        );
+        // Months and days overflow into years and months.
+        assert_eq!(
+            "2025-01-31 00:00:00",
+            eval_to_string(&g, "DATE(2024, 13, 31)"),
+        );
+        assert_eq!(
+            "2024-03-01 00:00:00",
+            eval_to_string(&g, "DATE(2024, 2, 30)"),
+        );
+        assert_eq!(
+            "2023-12-31 00:00:00",
+            eval_to_string(&g, "DATE(2024, 1, 0)"),
+        );
+        assert_eq!(
+            "2023-11-01 00:00:00",
+            eval_to_string(&g, "DATE(2024, -1, 1)"),
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "DATE(999999999, 1, 1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "DATE(0, -2^63, 1)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_date_parts() {
+        let g = Grid::new();
+        assert_eq!("2024", eval_to_string(&g, "YEAR(DATE(2024, 3, 14))"));
+        assert_eq!("3", eval_to_string(&g, "MONTH(DATE(2024, 3, 14))"));
+        assert_eq!("14", eval_to_string(&g, "DAY(DATE(2024, 3, 14))"));
+        assert_eq!("2024", eval_to_string(&g, "YEAR(\"2024-03-14\")"));
+
+        let d = "DATE(2024, 3, 14) + TIME(13, 45, 30)";
+        assert_eq!("13", eval_to_string(&g, &format!("HOUR({d})")));
+        assert_eq!("45", eval_to_string(&g, &format!("MINUTE({d})")));
+        assert_eq!("30", eval_to_string(&g, &format!("SECOND({d})")));
+        assert_eq!("13", eval_to_string(&g, "HOUR(TIME(37, 0, 0))"));
+        assert_eq!("13", eval_to_string(&g, "HOUR(\"2024-03-14 13:45:30\")"),);
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "time instant".into(),
+                got: Some("text".into()),
+            },
+            eval_to_err(&g, "YEAR(\"hello\")").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_weekday() {
+        let g = Grid::new();
+        // 2024-03-14 is a Thursday.
+        assert_eq!("5", eval_to_string(&g, "WEEKDAY(DATE(2024, 3, 14))"));
+        assert_eq!("5", eval_to_string(&g, "WEEKDAY(DATE(2024, 3, 14), 1)"));
+        assert_eq!("4", eval_to_string(&g, "WEEKDAY(DATE(2024, 3, 14), 2)"));
+        assert_eq!("3", eval_to_string(&g, "WEEKDAY(DATE(2024, 3, 14), 3)"));
+        assert_eq!("1", eval_to_string(&g, "WEEKDAY(DATE(2024, 3, 17))"));
+        assert_eq!("7", eval_to_string(&g, "WEEKDAY(DATE(2024, 3, 17), 2)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "WEEKDAY(DATE(2024, 3, 14), 4)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_edate_eomonth() {
+        let g = Grid::new();
+        assert_eq!(
+            "2024-02-29 00:00:00",
+            eval_to_string(&g, "EDATE(DATE(2024, 1, 31), 1)"),
+        );
+        assert_eq!(
+            "2023-11-30 00:00:00",
+            eval_to_string(&g, "EDATE(DATE(2024, 1, 30), -2)"),
+        );
+        assert_eq!(
+            "2024-01-31 00:00:00",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), 0)"),
+        );
+        assert_eq!(
+            "2024-02-29 00:00:00",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), 1)"),
+        );
+        assert_eq!(
+            "2023-12-31 00:00:00",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), -1)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_datedif() {
+        let g = Grid::new();
+        let dates = "DATE(2020, 5, 20), DATE(2024, 3, 14)";
+        assert_eq!("3", eval_to_string(&g, &format!("DATEDIF({dates}, \"Y\")")));
+        assert_eq!(
+            "45",
+            eval_to_string(&g, &format!("DATEDIF({dates}, \"M\")"))
+        );
+        assert_eq!(
+            "1394",
+            eval_to_string(&g, &format!("DATEDIF({dates}, \"D\")"))
+        );
+        assert_eq!(
+            "23",
+            eval_to_string(&g, &format!("DATEDIF({dates}, \"MD\")"))
+        );
+        assert_eq!(
+            "9",
+            eval_to_string(&g, &format!("DATEDIF({dates}, \"ym\")"))
+        );
+        assert_eq!(
+            "299",
+            eval_to_string(&g, &format!("DATEDIF({dates}, \"YD\")"))
+        );
+
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "DATEDIF(DATE(2024, 3, 14), DATE(2020, 5, 20), \"D\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, &format!("DATEDIF({dates}, \"W\")")).msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_networkdays() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        sheet.set_cell_value(pos![A1], "2024-03-08");
+        sheet.set_cell_value(pos![A2], "2024-03-11");
+        // repeated holidays and holidays on weekends are not subtracted again
+        sheet.set_cell_value(pos![A3], "2024-03-08");
+        sheet.set_cell_value(pos![A4], "2024-03-09");
+
+        // 2024-03-04 is a Monday.
+        let dates = "DATE(2024, 3, 4), DATE(2024, 3, 15)";
+        assert_eq!("10", eval_to_string(&g, &format!("NETWORKDAYS({dates})")));
+        assert_eq!(
+            "8",
+            eval_to_string(&g, &format!("NETWORKDAYS({dates}, A1:A5)")),
+        );
+        assert_eq!(
+            "10",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 3, 6), DATE(2024, 3, 19))"),
+        );
+        assert_eq!(
+            "262",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 1, 1), DATE(2024, 12, 31))"),
+        );
+        assert_eq!(
+            "-10",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 3, 15), DATE(2024, 3, 4))"),
+        );
+        assert_eq!(
+            "0",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 3, 9), DATE(2024, 3, 10))"),
+        );
+    }
+
+    #[test]
+    fn test_formula_now_today() {
+        let g = Grid::new();
+        assert_eq!(
+            "TRUE",
+            eval_to_string(
+                &g,
+                "AND(TODAY() <= NOW(), NOW() - TODAY() < TIME(24, 0, 0))"
+            ),
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use super::*;
+use crate::ArraySize;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Lambda functions",
+    docs: "`LET` gives names to values so that they can be reused later in \
+           the formula. `LAMBDA` defines a function that can be given a name \
+           using `LET` or passed to a function such as `MAP` or `REDUCE`.\
+           \n\n\
+           Names may contain letters, digits, and underscores, but must not \
+           begin with a digit or look like a cell reference. Names are not \
+           case-sensitive.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        // The names in `LET` are bound while its arguments are evaluated (see
+        // `ast.rs`), so all that is left here is to return the calculation.
+        FormulaFunction {
+            name: "LET",
+            arg_completion: Some("${1:name1}, ${2:value1}, ${3:calculation}"),
+            usage: "name1, value1, [name2, value2, ...], calculation",
+            examples: &[
+                "LET(x, 5, x * x)",
+                "LET(total, SUM(A1:A10), count, COUNT(A1:A10), total / count)",
+            ],
+            doc: "Assigns each `value` to the corresponding `name`, and then \
+                  returns `calculation`, which may refer to those names.\n\n\
+                  Each `value` may also refer to the names before it.",
+            eval: |_ctx, _only_parse, mut args| {
+                if args.lambdas.back().is_some_and(|lambda| lambda.is_some()) {
+                    return Err(lambda_is_not_a_value(args.span));
+                }
+                let values = args.take_rest().collect_vec();
+                if values.len() < 3 || values.len() % 2 == 0 {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "LET".into(),
+                        arg_name: "calculation".into(),
+                    }
+                    .with_span(args.span));
+                }
+                let mut values = values.into_iter();
+                let calculation = values.next_back();
+                for name in values.step_by(2) {
+                    // Propagate errors from names that are not valid.
+                    name.into_non_error_value()?;
+                }
+                Ok(calculation.map(|v| v.inner).unwrap_or_default())
+            },
+        },
+        // A `LAMBDA` that is passed to a function or given a name is never
+        // evaluated (see `ast.rs`). Anywhere else, it is an error.
+        FormulaFunction {
+            name: "LAMBDA",
+            arg_completion: Some("${1:parameter1}, ${2:calculation}"),
+            usage: "[parameter1, parameter2, ...], calculation",
+            examples: &[
+                "LAMBDA(x, x * 2)",
+                "LET(hypot, LAMBDA(a, b, SQRT(a^2 + b^2)), hypot(3, 4))",
+            ],
+            doc: "Returns a function that takes the given parameters and returns \
+                  `calculation`, which may refer to the parameters.\n\n\
+                  The function must be given a name using `LET` or passed to a \
+                  function such as `MAP`. A function with a name can be called \
+                  like any other function.",
+            eval: |_ctx, _only_parse, args| Err(lambda_is_not_a_value(args.span)),
+        },
+        FormulaFunction {
+            name: "MAP",
+            arg_completion: Some("${1:array1}, ${2:lambda}"),
+            usage: "array1, [array2, ...], lambda",
+            examples: &[
+                "MAP(A1:A10, LAMBDA(x, x * 2))",
+                "MAP(A1:C1, A2:C2, LAMBDA(a, b, a + b))",
+            ],
+            doc: "Calls `lambda` for each value in the arrays and returns an \
+                  array of the results.\n\n\
+                  `lambda` must take one parameter for each array. The arrays \
+                  are expanded to the same size in the same way as the \
+                  arguments to operators such as `+`.",
+            eval: |ctx, _only_parse, mut args| {
+                let lambda = args.take_last_required_lambda("lambda")?;
+                let arrays = args.take_rest().collect_vec();
+                if arrays.is_empty() {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "MAP".into(),
+                        arg_name: "array1".into(),
+                    }
+                    .with_span(args.span));
+                }
+                ctx.zip_map(&arrays, |ctx, values| {
+                    let values = values.iter().map(|v| Spanned {
+                        span: v.span,
+                        inner: Value::from(v.inner.clone()),
+                    });
+                    Ok(call_lambda(ctx, &lambda, values.collect_vec())?
+                        .into_cell_value()?
+                        .inner)
+                })
+            },
+        },
+        formula_fn!(
+            /// Calls `lambda` for each value in `array`, passing the result of
+            /// the previous call (or `initial_value` for the first call) and
+            /// the value. Returns the result of the last call.
+            #[examples(
+                "REDUCE(0, A1:A10, LAMBDA(total, x, total + x^2))",
+                "REDUCE(1, {1, 2, 3, 4}, LAMBDA(product, x, product * x))"
+            )]
+            fn REDUCE(
+                ctx: Ctx,
+                initial_value: (Spanned<Value>),
+                array: (Spanned<Array>),
+                lambda: (Spanned<Lambda>),
+            ) {
+                let mut accumulator = initial_value;
+                for value in array.inner.cell_values_slice() {
+                    let value = Spanned {
+                        span: array.span,
+                        inner: Value::from(value.clone()),
+                    };
+                    accumulator = call_lambda(ctx, &lambda, [accumulator, value])?;
+                }
+                accumulator.inner
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` for each value in `array`, passing the result of
+            /// the previous call (or `initial_value` for the first call) and
+            /// the value. Returns an array of the results of every call, with
+            /// the same size as `array`.
+            #[examples("SCAN(0, A1:A10, LAMBDA(total, x, total + x))")]
+            fn SCAN(
+                ctx: Ctx,
+                initial_value: (Spanned<Value>),
+                array: (Spanned<Array>),
+                lambda: (Spanned<Lambda>),
+            ) {
+                let mut accumulator = initial_value;
+                let mut results = smallvec::SmallVec::new();
+                for value in array.inner.cell_values_slice() {
+                    let value = Spanned {
+                        span: array.span,
+                        inner: Value::from(value.clone()),
+                    };
+                    accumulator = call_lambda(ctx, &lambda, [accumulator, value])?;
+                    results.push(accumulator.clone().into_cell_value()?.inner);
+                }
+                Array::new_row_major(array.inner.size(), results)?
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` for each row in `array` and returns a column of
+            /// the results.
+            #[examples("BYROW(A1:C10, LAMBDA(row, MAX(row)))")]
+            fn BYROW(ctx: Ctx, array: (Spanned<Array>), lambda: (Spanned<Lambda>)) {
+                map_lines(ctx, array, &lambda, Axis::Y)?
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` for each column in `array` and returns a row of
+            /// the results.
+            #[examples("BYCOL(A1:C10, LAMBDA(col, SUM(col)))")]
+            fn BYCOL(ctx: Ctx, array: (Spanned<Array>), lambda: (Spanned<Lambda>)) {
+                map_lines(ctx, array, &lambda, Axis::X)?
+            }
+        ),
+    ]
+}
+
+/// Returns the error for a `LAMBDA` that is used where a value is expected.
+fn lambda_is_not_a_value(span: Span) -> RunError {
+    RunErrorMsg::Expected {
+        expected: "value".into(),
+        got: Some("LAMBDA".into()),
+    }
+    .with_span(span)
+}
+
+/// Calls `lambda` with `args`.
+fn call_lambda(
+    ctx: &mut Ctx<'_>,
+    lambda: &Spanned<Rc<Lambda>>,
+    args: impl Into<VecDeque<Spanned<Value>>>,
+) -> CodeResult {
+    lambda
+        .inner
+        .call(ctx, FormulaFnArgs::new(args, lambda.span, "LAMBDA"))
+}
+
+/// Calls `lambda` with each column (if `axis` is `Axis::X`) or row (if `axis`
+/// is `Axis::Y`) of `array`, and returns a row or column of the results.
+fn map_lines(
+    ctx: &mut Ctx<'_>,
+    array: Spanned<Array>,
+    lambda: &Spanned<Rc<Lambda>>,
+    axis: Axis,
+) -> CodeResult<Array> {
+    // Work with rows, transposing columns into rows and back.
+    let lines = match axis {
+        Axis::X => array.inner.transpose(),
+        Axis::Y => array.inner,
+    };
+    let line_size = ArraySize::new_or_err(lines.width(), 1)?;
+
+    let results = lines
+        .rows()
+        .map(|row| {
+            let mut line = Array::new_row_major(line_size, row.iter().cloned().collect())?;
+            if axis == Axis::X {
+                line = line.transpose();
+            }
+            let line = Spanned {
+                span: array.span,
+                inner: Value::from(line),
+            };
+            Ok(call_lambda(ctx, lambda, [line])?.into_cell_value()?.inner)
+        })
+        .collect::<CodeResult<_>>()?;
+
+    let results = Array::new_row_major(ArraySize::new_or_err(1, lines.height())?, results)?;
+    Ok(match axis {
+        Axis::X => results.transpose(),
+        Axis::Y => results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_let() {
+        let g = Grid::new();
+
+        assert_eq!("25", eval_to_string(&g, "LET(x, 5, x * x)"));
+        assert_eq!("12", eval_to_string(&g, "LET(x, 5, y, x + 1, x + y + 1)"));
+        assert_eq!("3", eval_to_string(&g, "LET(X, 1, x_2, 2, x + X_2)"));
+
+        // Inner names shadow outer names.
+        assert_eq!("30", eval_to_string(&g, "LET(x, 5, LET(x, 10, x * 3))"));
+        assert_eq!(
+            "{15, 6}",
+            eval_to_string(&g, "LET(x, 5, {LET(x, 10, x + x) - x, x + 1})"),
+        );
+
+        // Names are only in scope inside `LET`.
+        assert_eq!(
+            RunErrorMsg::BadFunctionName,
+            eval_to_err(&g, "LET(x, 5, x) + x").msg,
+        );
+
+        // Errors can be bound to names.
+        assert_eq!(
+            "oops",
+            eval_to_string(&g, "LET(x, 1/0, IFERROR(x, \"oops\"))")
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "LET(x, 1/0, x)").msg
+        );
+
+        // Names that are not valid.
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some("cell reference".into()),
+            },
+            eval_to_err(&g, "LET(A1, 5, 10)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some("numeric literal".into()),
+            },
+            eval_to_err(&g, "LET(5, 5, 10)").msg,
+        );
+
+        // Missing calculation.
+        for formula in ["LET(x, 5)", "LET(x, 5, y, 6)", "LET(x)"] {
+            assert_eq!(
+                RunErrorMsg::MissingRequiredArgument {
+                    func_name: "LET".into(),
+                    arg_name: "calculation".into(),
+                },
+                eval_to_err(&g, formula).msg,
+            );
+        }
+    }
+
+    #[test]
+    fn test_let_cell_references() {
+        let g = Grid::from_array(pos![A1], &array![1, 2; 3, 4]);
+
+        assert_eq!("10", eval_to_string(&g, "LET(cells, A1:B2, SUM(cells))"));
+        assert_eq!(
+            "7",
+            eval_to_string(&g, "LET(a1_plus, A1 + 1, a1_plus * 3 + A1)")
+        );
+    }
+
+    #[test]
+    fn test_lambda() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "6",
+            eval_to_string(&g, "LET(double, LAMBDA(x, x * 2), double(3))")
+        );
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "LET(hypot, LAMBDA(a, b, SQRT(a^2 + b^2)), hypot(3, 4))"),
+        );
+        assert_eq!(
+            "42",
+            eval_to_string(&g, "LET(answer, LAMBDA(42), answer())")
+        );
+
+        // Functions capture names from where they are defined.
+        assert_eq!(
+            "15",
+            eval_to_string(&g, "LET(n, 10, add_n, LAMBDA(x, x + n), n, 100, add_n(5))"),
+        );
+
+        // Functions can be passed to functions.
+        assert_eq!(
+            "9",
+            eval_to_string(
+                &g,
+                "LET(twice, LAMBDA(f, x, f(f(x))), add_3, LAMBDA(x, x + 3), twice(add_3, 3))",
+            ),
+        );
+
+        // Functions are not values.
+        let not_a_value = RunErrorMsg::Expected {
+            expected: "value".into(),
+            got: Some("LAMBDA".into()),
+        };
+        assert_eq!(not_a_value, eval_to_err(&g, "LAMBDA(x, x + 1)").msg);
+        assert_eq!(not_a_value, eval_to_err(&g, "LET(f, LAMBDA(x, x), f)").msg);
+
+        // Wrong number of arguments.
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "LAMBDA".into(),
+                arg_name: "b".into(),
+            },
+            eval_to_err(&g, "LET(f, LAMBDA(a, b, a + b), f(1))").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: 1,
+            },
+            eval_to_err(&g, "LET(f, LAMBDA(a, a), f(1, 2))").msg,
+        );
+
+        // Parameters that are not valid.
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some("string literal".into()),
+            },
+            eval_to_err(&g, "MAP({1, 2}, LAMBDA(\"x\", 1))").msg,
+        );
+
+        // Functions may call themselves, but not forever.
+        assert_eq!(
+            "120",
+            eval_to_string(
+                &g,
+                "LET(fact, LAMBDA(f, n, IF(n <= 1, 1, n * f(f, n - 1))), fact(fact, 5))",
+            ),
+        );
+        assert_eq!(
+            RunErrorMsg::LambdaTooDeep,
+            eval_to_err(&g, "LET(f, LAMBDA(g, x, g(g, x)), f(f, 1))").msg,
+        );
+    }
+
+    #[test]
+    fn test_map() {
+        let g = Grid::from_array(pos![A1], &array![1, 2; 3, 4]);
+
+        assert_eq!(
+            "{2, 4; 6, 8}",
+            eval_to_string(&g, "MAP(A1:B2, LAMBDA(x, x * 2))")
+        );
+        assert_eq!(
+            "{11, 22; 13, 24}",
+            eval_to_string(&g, "MAP(A1:B2, {10, 20}, LAMBDA(a, b, a + b))"),
+        );
+        assert_eq!("5", eval_to_string(&g, "MAP(4, LAMBDA(x, x + 1))"));
+        assert_eq!(
+            "{2, 3; 4, 5}",
+            eval_to_string(&g, "LET(inc, LAMBDA(x, x + 1), MAP(A1:B2, inc))"),
+        );
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "LAMBDA".into(),
+                got: Some("number".into()),
+            },
+            eval_to_err(&g, "MAP(A1:B2, 5)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "MAP".into(),
+                arg_name: "array1".into(),
+            },
+            eval_to_err(&g, "MAP(LAMBDA(x, x))").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "MAP".into(),
+                arg_name: "lambda".into(),
+            },
+            eval_to_err(&g, "MAP()").msg,
+        );
+    }
+
+    #[test]
+    fn test_reduce_and_scan() {
+        let g = Grid::from_array(pos![A1], &array![1; 2; 3; 4]);
+
+        assert_eq!(
+            "30",
+            eval_to_string(&g, "REDUCE(0, A1:A4, LAMBDA(total, x, total + x^2))"),
+        );
+        assert_eq!(
+            "24",
+            eval_to_string(&g, "REDUCE(1, A1:A4, LAMBDA(p, x, p * x))")
+        );
+        assert_eq!("7", eval_to_string(&g, "REDUCE(5, 2, LAMBDA(a, x, a + x))"));
+        assert_eq!(
+            "{1; 3; 6; 10}",
+            eval_to_string(&g, "SCAN(0, A1:A4, LAMBDA(total, x, total + x))"),
+        );
+        assert_eq!(
+            "{a, ab, abc}",
+            eval_to_string(&g, "SCAN(\"\", {\"a\", \"b\", \"c\"}, LAMBDA(s, x, s & x))"),
+        );
+
+        assert_eq!(
+            RunErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: 1,
+            },
+            eval_to_err(&g, "REDUCE(0, A1:A4, LAMBDA(total, total))").msg,
+        );
+    }
+
+    #[test]
+    fn test_byrow_and_bycol() {
+        let g = Grid::from_array(pos![A1], &array![1, 2, 3; 4, 5, 6]);
+
+        assert_eq!(
+            "{6; 15}",
+            eval_to_string(&g, "BYROW(A1:C2, LAMBDA(row, SUM(row)))")
+        );
+        assert_eq!(
+            "{5, 7, 9}",
+            eval_to_string(&g, "BYCOL(A1:C2, LAMBDA(col, SUM(col)))"),
+        );
+        assert_eq!(
+            "{2, 2, 2}",
+            eval_to_string(&g, "BYCOL(A1:C2, LAMBDA(col, ROWS(col)))"),
+        );
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "single value".into(),
+                got: Some("array".into()),
+            },
+            eval_to_err(&g, "BYROW(A1:C2, LAMBDA(row, row))").msg,
+        );
+    }
+}
@@ -81,12 +81,225 @@ fn get_functions() -> Vec<FormulaFunction> {
                 if condition { t } else { f }.clone()
             }
         ),
+        formula_fn!(
+            /// Returns `fallback` if there was an error computing `value`;
+            /// otherwise returns `value`.
+            #[examples("IFERROR(A1/B1, 0)", "IFERROR(VLOOKUP(A1, B1:C10, 2), \"missing\")")]
+            #[zip_map]
+            fn IFERROR([value]: CellValue, [fallback]: CellValue) {
+                match value {
+                    CellValue::Error(_) => fallback,
+                    _ => value,
+                }
+                .clone()
+            }
+        ),
+        formula_fn!(
+            /// Returns `fallback` if `value` is a `#N/A` error, such as when
+            /// no match is found by a lookup function; otherwise returns
+            /// `value`.
+            ///
+            /// Other errors are not caught.
+            #[examples("IFNA(XLOOKUP(A1, B1:B10, C1:C10), \"not found\")")]
+            #[zip_map]
+            fn IFNA([value]: CellValue, [fallback]: CellValue) {
+                match value {
+                    CellValue::Error(e) if e.msg == RunErrorMsg::NoMatch => fallback,
+                    _ => value,
+                }
+                .clone()
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is an error, and `FALSE` otherwise.
+            #[examples("ISERROR(A1/B1)")]
+            #[zip_map]
+            fn ISERROR([value]: CellValue) {
+                value.type_name() == "error"
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is blank, and `FALSE` otherwise.
+            ///
+            /// Text containing no characters is not considered blank.
+            #[examples("ISBLANK(A1)")]
+            #[zip_map]
+            fn ISBLANK([value]: CellValue) {
+                value.type_name() == "blank"
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a number, and `FALSE` otherwise.
+            ///
+            /// Text that looks like a number is not considered a number.
+            #[examples("ISNUMBER(A1)")]
+            #[zip_map]
+            fn ISNUMBER([value]: CellValue) {
+                value.type_name() == "number"
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is text, and `FALSE` otherwise.
+            #[examples("ISTEXT(A1)")]
+            #[zip_map]
+            fn ISTEXT([value]: CellValue) {
+                value.type_name() == "text"
+            }
+        ),
+        formula_fn!(
+            /// Returns a `#N/A` error, which indicates that a value is not
+            /// available.
+            #[include_args_in_completion(false)]
+            #[examples("NA()")]
+            fn NA(span: Span) {
+                CodeResult::<Value>::Err(RunErrorMsg::NoMatch.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns a number identifying the kind of error in `value`, or
+            /// a `#N/A` error if `value` is not an error.
+            ///
+            /// | Code | Error     |
+            /// |------|-----------|
+            /// | 2    | `#DIV/0!` |
+            /// | 3    | `#VALUE!` |
+            /// | 4    | `#REF!`   |
+            /// | 5    | `#NAME?`  |
+            /// | 6    | `#NUM!`   |
+            /// | 7    | `#N/A`    |
+            /// | 9    | `#SPILL!` |
+            /// | 14   | `#CALC!`  |
+            #[name("ERROR.TYPE")]
+            #[examples("ERROR.TYPE(A1)", "IF(ERROR.TYPE(A1)=2, \"divide by zero\", A1)")]
+            #[zip_map]
+            fn ERROR_TYPE(span: Span, [value]: CellValue) {
+                match value {
+                    CellValue::Error(e) => f64::from(e.msg.error_type_code()),
+                    _ => return Err(RunErrorMsg::NoMatch.with_span(*span)),
+                }
+            }
+        ),
     ]
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{formulas::tests::*, Pos};
+    use crate::{formulas::tests::*, Pos, SheetRect};
+
+    #[test]
+    fn test_formula_iferror() {
+        let g = Grid::new();
+
+        assert_eq!("ok", eval_to_string(&g, "IFERROR(\"ok\", 42)"));
+        assert_eq!("42", eval_to_string(&g, "IFERROR(1/0, 42)"));
+        assert_eq!("42", eval_to_string(&g, "IFERROR(NA(), 42)"));
+        assert_eq!("42", eval_to_string(&g, "IFERROR(ASIN(2), 42)"));
+        assert_eq!("42", eval_to_string(&g, "IFERROR(NOSUCHFUNCTION(), 42)"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "IFERROR({1, 2}, 0)"));
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "IFERROR(1/0, 1/0)").msg,
+        );
+
+        // Errors are only propagated when they are used.
+        assert_eq!("1", eval_to_string(&g, "IF(TRUE, 1, 1/0)"));
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "IF(FALSE, 1, 1/0)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "SUM(1, 1/0)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_ifna() {
+        let g = Grid::new();
+
+        assert_eq!("5", eval_to_string(&g, "IFNA(5, 42)"));
+        assert_eq!("42", eval_to_string(&g, "IFNA(NA(), 42)"));
+        assert_eq!(
+            "missing",
+            eval_to_string(&g, "IFNA(XLOOKUP(4, {1, 2, 3}, {1, 2, 3}), \"missing\")"),
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "IFNA(1/0, 42)").msg
+        );
+    }
+
+    #[test]
+    fn test_formula_iferror_unused_fallback() {
+        let g = Grid::new();
+        let sheet_id = g.sheets()[0].id;
+        let pos = pos![D1].to_sheet_pos(sheet_id);
+        let fallback = SheetRect::single_sheet_pos(pos![B1].to_sheet_pos(sheet_id));
+
+        for (formula, uses_fallback) in [
+            ("IFERROR(1, B1)", false),
+            ("IFERROR(1/0, B1)", true),
+            ("IFNA(1, B1)", false),
+            ("IFNA(1/0, B1)", false),
+            ("IFNA(NA(), B1)", true),
+            ("IFERROR({1, 1/0}, B1)", true),
+        ] {
+            let mut ctx = Ctx::new(&g, pos);
+            parse_formula(formula, pos.into())
+                .unwrap()
+                .eval(&mut ctx, false)
+                .ok();
+            assert_eq!(
+                uses_fallback,
+                ctx.cells_accessed.contains(&fallback),
+                "{formula}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_formula_type_predicates() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], 12);
+        let _ = sheet.set_cell_value(pos![A2], "hello");
+        let _ = sheet.set_cell_value(pos![A3], true);
+
+        assert_eq!("FALSE", eval_to_string(&g, "ISERROR(A1)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(A1/0)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(NA())"));
+
+        assert_eq!(
+            "{FALSE; FALSE; FALSE; TRUE}",
+            eval_to_string(&g, "ISBLANK(A1:A4)"),
+        );
+        assert_eq!("FALSE", eval_to_string(&g, "ISBLANK(\"\")"));
+
+        assert_eq!(
+            "{TRUE; FALSE; FALSE; FALSE}",
+            eval_to_string(&g, "ISNUMBER(A1:A4)"),
+        );
+        assert_eq!("FALSE", eval_to_string(&g, "ISNUMBER(\"12\")"));
+
+        assert_eq!(
+            "{FALSE; TRUE; FALSE; FALSE}",
+            eval_to_string(&g, "ISTEXT(A1:A4)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_error_type() {
+        let g = Grid::new();
+
+        assert_eq!("2", eval_to_string(&g, "ERROR.TYPE(1/0)"));
+        assert_eq!("3", eval_to_string(&g, "ERROR.TYPE(\"a\" + 1)"));
+        assert_eq!("5", eval_to_string(&g, "ERROR.TYPE(NOSUCHFUNCTION())"));
+        assert_eq!("6", eval_to_string(&g, "ERROR.TYPE(ASIN(2))"));
+        assert_eq!("7", eval_to_string(&g, "ERROR.TYPE(NA())"));
+        assert_eq!("7", eval_to_string(&g, "error.type(na())"));
+        assert_eq!(RunErrorMsg::NoMatch, eval_to_err(&g, "ERROR.TYPE(1)").msg);
+        assert_eq!(RunErrorMsg::NoMatch, eval_to_err(&g, "NA()").msg);
+    }
 
     #[test]
     fn test_formula_if() {
@@ -1,7 +1,7 @@
 use regex::Regex;
 use smallvec::smallvec;
 
-use crate::ArraySize;
+use crate::{ArraySize, Pos};
 
 use super::*;
 
@@ -191,16 +191,7 @@ fn get_functions() -> Vec<FormulaFunction> {
                 let match_mode = LookupMatchMode::try_from(match_mode)?;
                 let search_mode = LookupSearchMode::try_from(search_mode)?;
 
-                // Check for invalid combination
-                if match_mode == LookupMatchMode::Wildcard {
-                    match search_mode {
-                        LookupSearchMode::LinearForward | LookupSearchMode::LinearReverse => (), //ok
-                        LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending => {
-                            // not ok -- can't do binary search with wildcard
-                            return Err(RunErrorMsg::InvalidArgument.with_span(search_mode_span));
-                        }
-                    }
-                }
+                check_lookup_modes(match_mode, search_mode, search_mode_span)?;
 
                 // Give more concise names so it's easier to keep track of them
                 // while reading this code.
@@ -274,9 +265,309 @@ fn get_functions() -> Vec<FormulaFunction> {
                 Array::new_row_major(result_size, final_output_array)?
             }
         ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its position
+            /// in the range, starting from `1`. Returns an error if no match is
+            /// found.
+            ///
+            /// `search_range` must be either a single row or a single column.
+            ///
+            /// # Match types
+            ///
+            /// - 1 = find the largest value less than or equal to
+            ///   `search_key` (default)
+            /// - 0 = exact match
+            /// - -1 = find the smallest value greater than or equal to
+            ///   `search_key`
+            ///
+            /// If `match_type` is `1`, then `search_range` must be sorted in
+            /// ascending order. If `match_type` is `-1`, then `search_range`
+            /// must be sorted in descending order. Otherwise the result of this
+            /// function will be meaningless.
+            #[examples(
+                "MATCH(\"zebra\", A1:A10, 0)",
+                "MATCH(50, B1:Z1)",
+                "INDEX(C1:C10, MATCH(\"zebra\", A1:A10, 0))"
+            )]
+            #[zip_map]
+            fn MATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                [match_type]: (Option<Spanned<i64>>),
+            ) {
+                search_range.array_linear_axis()?;
+                let (match_mode, search_mode) = match match_type {
+                    None => (
+                        LookupMatchMode::NextSmaller,
+                        LookupSearchMode::BinaryAscending,
+                    ),
+                    Some(match_type) => match match_type.inner {
+                        1 => (
+                            LookupMatchMode::NextSmaller,
+                            LookupSearchMode::BinaryAscending,
+                        ),
+                        0 => (LookupMatchMode::Exact, LookupSearchMode::LinearForward),
+                        -1 => (
+                            LookupMatchMode::NextLarger,
+                            LookupSearchMode::BinaryDescending,
+                        ),
+                        _ => return Err(RunErrorMsg::InvalidArgument.with_span(match_type.span)),
+                    },
+                };
+
+                let haystack = search_range.inner.cell_values_slice();
+                let index = lookup(search_key, haystack, match_mode, search_mode)?
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(*span))?;
+                index as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its position
+            /// in the range, starting from `1`. Returns an error if no match is
+            /// found.
+            ///
+            /// `search_range` must be either a single row or a single column.
+            ///
+            /// `match_mode` and `search_mode` work the same as in `XLOOKUP`.
+            #[examples(
+                "XMATCH(\"zebra\", A1:Z1)",
+                "XMATCH(\"z*\", A1:A10, 2)",
+                "XMATCH(50, C4:C834, -1, 2)"
+            )]
+            #[zip_map]
+            fn XMATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                match_mode: (Option<Spanned<i64>>),
+                search_mode: (Option<Spanned<i64>>),
+            ) {
+                search_range.array_linear_axis()?;
+                let search_mode_span = search_mode.map_or(*span, |arg| arg.span);
+                let match_mode = LookupMatchMode::try_from(*match_mode)?;
+                let search_mode = LookupSearchMode::try_from(*search_mode)?;
+                check_lookup_modes(match_mode, search_mode, search_mode_span)?;
+
+                let haystack = search_range.inner.cell_values_slice();
+                let index = lookup(search_key, haystack, match_mode, search_mode)?
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(*span))?;
+                index as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Returns the value at a given row and column of a range, starting
+            /// from `1`.
+            ///
+            /// If `row` is omitted or `0`, then the whole column is returned.
+            /// If `column` is omitted or `0`, then the whole row is returned.
+            /// If `range` is a single row or a single column, then only one
+            /// index is needed.
+            #[examples(
+                "INDEX(A1:C10, 3, 2)",
+                "INDEX(A1:A10, 5)",
+                "INDEX(A1:C10, 0, 2)",
+                "INDEX(C1:C10, MATCH(\"zebra\", A1:A10, 0))"
+            )]
+            fn INDEX(
+                range: (Spanned<Array>),
+                row: (Option<Spanned<i64>>),
+                column: (Option<Spanned<i64>>),
+            ) {
+                let array = range.inner;
+                let (row, column) = if array.height() == 1 && column.is_none() {
+                    (None, row)
+                } else {
+                    (row, column)
+                };
+                // A single row or column only needs one index.
+                let y =
+                    one_based_index(row, array.height())?.or((array.height() == 1).then_some(0));
+                let x =
+                    one_based_index(column, array.width())?.or((array.width() == 1).then_some(0));
+
+                match (x, y) {
+                    (Some(x), Some(y)) => Value::from(array.get(x, y)?.clone()),
+                    (None, Some(y)) => {
+                        let values = (0..array.width())
+                            .map(|x| array.get(x, y).cloned())
+                            .try_collect()?;
+                        let size = ArraySize::new_or_err(array.width(), 1)?;
+                        Array::new_row_major(size, values)?.into()
+                    }
+                    (Some(x), None) => {
+                        let values = (0..array.height())
+                            .map(|y| array.get(x, y).cloned())
+                            .try_collect()?;
+                        let size = ArraySize::new_or_err(1, array.height())?;
+                        Array::new_row_major(size, values)?.into()
+                    }
+                    (None, None) => array.into(),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the value with index `index` in `values`, starting from
+            /// `1`.
+            ///
+            /// Only the chosen value is checked for errors.
+            #[examples("CHOOSE(2, \"red\", \"green\", \"blue\")", "CHOOSE(A1, B1:B5, C1:C5)")]
+            fn CHOOSE(index: (Spanned<i64>), values: (Iter<Spanned<Value>>)) {
+                usize::try_from(index.inner)
+                    .ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| values.nth(i))
+                    .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(index.span))??
+                    .inner
+            }
+        ),
+        formula_fn!(
+            /// Returns the values in a range that is `rows` rows and `columns`
+            /// columns away from `reference`.
+            ///
+            /// `height` and `width` specify the size of the returned range. If
+            /// they are omitted, then the returned range is the same size as
+            /// `reference`.
+            #[examples(
+                "OFFSET(A1, 2, 3)",
+                "OFFSET(A1:B2, 1, 0, 5, 2)",
+                "SUM(OFFSET(B3, 0, 0, A1))"
+            )]
+            fn OFFSET(
+                ctx: Ctx,
+                span: Span,
+                reference: (Spanned<SheetRect>),
+                rows: (Spanned<i64>),
+                columns: (Spanned<i64>),
+                height: (Option<Spanned<i64>>),
+                width: (Option<Spanned<i64>>),
+            ) {
+                let reference = reference.inner;
+                let height = positive_size_arg(height, reference.height())?;
+                let width = positive_size_arg(width, reference.width())?;
+
+                let offset_range = (|| {
+                    let x = reference.min.x.checked_add(columns.inner)?;
+                    let y = reference.min.y.checked_add(rows.inner)?;
+                    let min = Pos { x, y };
+                    let max = Pos {
+                        x: x.checked_add(width - 1)?,
+                        y: y.checked_add(height - 1)?,
+                    };
+                    Some(SheetRect::new_pos_span(min, max, reference.sheet_id))
+                })()
+                .ok_or(RunErrorMsg::Overflow.with_span(span))?;
+
+                ctx.get_cell_array(offset_range, span)?.inner
+            }
+        ),
+        formula_fn!(
+            /// Returns the row number of a cell reference, or the row number
+            /// of the cell containing the formula if `reference` is omitted.
+            ///
+            /// If `reference` is a range with more than one row, then the row
+            /// numbers of all rows in the range are returned as a column.
+            #[examples("ROW()", "ROW(B7)", "ROW(A3:A5)")]
+            fn ROW(ctx: Ctx, reference: (Option<Spanned<SheetRect>>)) {
+                match reference {
+                    None => Value::from(ctx.sheet_pos.y),
+                    Some(reference) if reference.inner.height() == 1 => {
+                        Value::from(reference.inner.min.y)
+                    }
+                    Some(reference) => {
+                        let values = reference.inner.y_range().map(CellValue::from).collect();
+                        let size = ArraySize::new_or_err(1, reference.inner.height() as u32)?;
+                        Array::new_row_major(size, values)?.into()
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the column number of a cell reference, or the column
+            /// number of the cell containing the formula if `reference` is
+            /// omitted. Column `A` is column `1`.
+            ///
+            /// If `reference` is a range with more than one column, then the
+            /// column numbers of all columns in the range are returned as a
+            /// row.
+            #[examples("COLUMN()", "COLUMN(B7)", "COLUMN(A3:C3)")]
+            fn COLUMN(ctx: Ctx, reference: (Option<Spanned<SheetRect>>)) {
+                match reference {
+                    None => Value::from(ctx.sheet_pos.x + 1),
+                    Some(reference) if reference.inner.width() == 1 => {
+                        Value::from(reference.inner.min.x + 1)
+                    }
+                    Some(reference) => {
+                        let values = reference
+                            .inner
+                            .x_range()
+                            .map(|x| CellValue::from(x + 1))
+                            .collect();
+                        let size = ArraySize::new_or_err(reference.inner.width() as u32, 1)?;
+                        Array::new_row_major(size, values)?.into()
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of rows in a range or array.
+            #[examples("ROWS(A1:C10)", "ROWS({1, 2; 3, 4; 5, 6})")]
+            fn ROWS(range: Array) {
+                range.height()
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of columns in a range or array.
+            #[examples("COLUMNS(A1:C10)", "COLUMNS({1, 2; 3, 4; 5, 6})")]
+            fn COLUMNS(range: Array) {
+                range.width()
+            }
+        ),
     ]
 }
 
+/// Converts an optional index starting from `1` into an index starting from
+/// `0`. Returns `None` if the index is omitted or `0`, and an error if the
+/// index is out of bounds.
+fn one_based_index(index: Option<Spanned<i64>>, len: u32) -> CodeResult<Option<u32>> {
+    match index {
+        None | Some(Spanned { inner: 0, .. }) => Ok(None),
+        Some(index) => u32::try_from(index.inner)
+            .ok()
+            .filter(|&i| i <= len)
+            .map(|i| Some(i - 1))
+            .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(index.span)),
+    }
+}
+
+/// Returns the size given by an optional argument, or `default` if it is
+/// omitted. Returns an error if the size is not positive.
+fn positive_size_arg(size: Option<Spanned<i64>>, default: usize) -> CodeResult<i64> {
+    match size {
+        None => Ok(default as i64),
+        Some(size) if size.inner >= 1 => Ok(size.inner),
+        Some(size) => Err(RunErrorMsg::InvalidArgument.with_span(size.span)),
+    }
+}
+
+/// Returns an error if `match_mode` and `search_mode` cannot be used together.
+fn check_lookup_modes(
+    match_mode: LookupMatchMode,
+    search_mode: LookupSearchMode,
+    search_mode_span: Span,
+) -> CodeResult<()> {
+    if match_mode == LookupMatchMode::Wildcard {
+        match search_mode {
+            LookupSearchMode::LinearForward | LookupSearchMode::LinearReverse => (), //ok
+            LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending => {
+                // not ok -- can't do binary search with wildcard
+                return Err(RunErrorMsg::InvalidArgument.with_span(search_mode_span));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Performs a `LOOKUP` and returns the index of the best match.
 fn lookup<V: ToString + AsRef<CellValue>>(
     needle: &CellValue,
@@ -463,7 +754,7 @@ mod tests {
     use lazy_static::lazy_static;
     use smallvec::smallvec;
 
-    use crate::{formulas::tests::*, Pos};
+    use crate::{formulas::tests::*, Pos, SheetRect};
 
     lazy_static! {
         static ref NUMBERS_LOOKUP_ARRAY: Array = array![
@@ -1026,4 +1317,150 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_match() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("3", eval_to_string(&g, "MATCH(50, A1:A4, 0)"));
+        assert_eq!("2", eval_to_string(&g, "MATCH(\"TWO\", B1:B4, 0)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(60, A1:A4)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(60, A1:A4, 1)"));
+        assert_eq!("2", eval_to_string(&g, "MATCH(2, {100, 2, 1}, -1)"));
+        assert_eq!("1", eval_to_string(&g, "MATCH(60, {100, 50, 2, 1}, -1)"));
+        assert_eq!("2", eval_to_string(&g, "MATCH(20, {1, 20, 300})"));
+        assert_eq!("{1; 4}", eval_to_string(&g, "MATCH({1; 100}, A1:A4, 0)"));
+
+        expect_err(&RunErrorMsg::NoMatch, &g, "MATCH(60, A1:A4, 0)");
+        expect_err(&RunErrorMsg::NoMatch, &g, "MATCH(0, A1:A4)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "MATCH(1, A1:A4, 2)");
+        expect_err(&RunErrorMsg::NonLinearArray, &g, "MATCH(1, A1:B4, 0)");
+    }
+
+    #[test]
+    fn test_xmatch() {
+        let g = Grid::from_array(pos![A1], &STRINGS_LOOKUP_ARRAY);
+
+        assert_eq!("3", eval_to_string(&g, "XMATCH(\"BREAD\", A1:A4)"));
+        assert_eq!("2", eval_to_string(&g, "XMATCH(\"b*\", A1:A4, 2)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(\"b*\", A1:A4, 2, -1)"));
+        assert_eq!("2", eval_to_string(&g, "XMATCH(2.5, B1:B4, -1)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(2.5, B1:B4, 1)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(4, B1:B4, 0, 2)"));
+
+        expect_err(&RunErrorMsg::NoMatch, &g, "XMATCH(\"zebra\", A1:A4)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "XMATCH(\"b*\", A1:A4, 2, 2)",
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "XMATCH(1, A1:A4, 3)");
+    }
+
+    #[test]
+    fn test_index() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("fifty", eval_to_string(&g, "INDEX(A1:C4, 3, 2)"));
+        assert_eq!("100", eval_to_string(&g, "INDEX(A1:A4, 4)"));
+        assert_eq!("tu", eval_to_string(&g, "INDEX(A2:C2, 3)"));
+        assert_eq!("{2, two, tu}", eval_to_string(&g, "INDEX(A1:C4, 2)"));
+        assert_eq!("{2, two, tu}", eval_to_string(&g, "INDEX(A1:C4, 2, 0)"));
+        assert_eq!(
+            "{one; two; fifty; hundred}",
+            eval_to_string(&g, "INDEX(A1:C4, , 2)"),
+        );
+        assert_eq!(
+            "{1, 2; 3, 4}",
+            eval_to_string(&g, "INDEX({1, 2; 3, 4}, 0, 0)")
+        );
+        assert_eq!(
+            "hundred",
+            eval_to_string(&g, "INDEX(B1:B4, MATCH(100, A1:A4, 0))"),
+        );
+
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, 5, 1)");
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, 1, 4)");
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, -1, 1)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "INDEX(1/0, 1)");
+    }
+
+    #[test]
+    fn test_choose() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "green",
+            eval_to_string(&g, "CHOOSE(2, 'red', 'green', 'blue')")
+        );
+        assert_eq!("{1, 2}", eval_to_string(&g, "CHOOSE(1, {1, 2}, 3)"));
+        assert_eq!("3", eval_to_string(&g, "CHOOSE(2, 1/0, 3)"));
+
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "CHOOSE(0, 'a', 'b')");
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "CHOOSE(3, 'a', 'b')");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "CHOOSE(1, 1/0, 3)");
+    }
+
+    #[test]
+    fn test_offset() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("{fifty}", eval_to_string(&g, "OFFSET(A1, 2, 1)"));
+        assert_eq!(
+            "{two, tu; fifty, mute}",
+            eval_to_string(&g, "OFFSET(A1:B2, 1, 1)")
+        );
+        assert_eq!("{2; 50; 100}", eval_to_string(&g, "OFFSET(A1, 1, 0, 3)"));
+        assert_eq!(
+            "{fifty, mute}",
+            eval_to_string(&g, "OFFSET(C4, -1, -1, 1, 2)")
+        );
+        assert_eq!("152", eval_to_string(&g, "SUM(OFFSET((A1), 1, 0, 3))"));
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "OFFSET(A1, 1, 1, 0)");
+        expect_err(&RunErrorMsg::CircularReference, &g, "OFFSET(A1, -1, 0)");
+        assert!(matches!(
+            eval_to_err(&g, "OFFSET(5, 1, 1)").msg,
+            RunErrorMsg::Expected { .. },
+        ));
+
+        // The whole range is recorded as accessed, even if it is empty.
+        let sheet_id = g.sheets()[0].id;
+        let form = parse_formula("OFFSET(A1, 10, 10, 2, 3)", pos![A0]).unwrap();
+        let mut ctx = Ctx::new(&g, pos![A0].to_sheet_pos(sheet_id));
+        form.eval(&mut ctx, false).unwrap();
+        assert!(ctx.cells_accessed.contains(&SheetRect::new_pos_span(
+            pos![K11],
+            pos![M12],
+            sheet_id,
+        )));
+    }
+
+    #[test]
+    fn test_row_column_rows_columns() {
+        let g = Grid::new();
+        let sheet_id = g.sheets()[0].id;
+
+        assert_eq!("7", eval_to_string(&g, "ROW(B7)"));
+        assert_eq!("{3; 4; 5}", eval_to_string(&g, "ROW(A3:B5)"));
+        assert_eq!("2", eval_to_string(&g, "COLUMN(B7)"));
+        assert_eq!("{1, 2, 3}", eval_to_string(&g, "COLUMN(A3:C5)"));
+        assert_eq!(
+            "4",
+            eval_to_string_at(&g, pos![C4].to_sheet_pos(sheet_id), "ROW()")
+        );
+        assert_eq!(
+            "3",
+            eval_to_string_at(&g, pos![C4].to_sheet_pos(sheet_id), "COLUMN()")
+        );
+        assert!(matches!(
+            eval_to_err(&g, "ROW(\"B7\")").msg,
+            RunErrorMsg::Expected { .. },
+        ));
+
+        assert_eq!("3", eval_to_string(&g, "ROWS(A3:C5)"));
+        assert_eq!("3", eval_to_string(&g, "ROWS({1, 2; 3, 4; 5, 6})"));
+        assert_eq!("2", eval_to_string(&g, "COLUMNS({1, 2; 3, 4; 5, 6})"));
+        assert_eq!("1", eval_to_string(&g, "COLUMNS(7)"));
+    }
 }
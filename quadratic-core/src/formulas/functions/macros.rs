@@ -74,6 +74,8 @@ macro_rules! see_docs_for_more_about_wildcards {
 /// Attributes must be specified in the order listed below.
 ///
 /// - `#[doc = "..."]` (or doc comments using `///`) - user-facing documentation
+/// - `#[name("...")]` - name of the function, if it is not a valid Rust
+///   identifier (such as `ERROR.TYPE`)
 /// - `#[operator]` - removes the function from documentation
 /// - `#[examples("EXAMPLE()", "EXAMPLE(A, B)")]` - example usages
 /// - `#[zip_map]` - if certain arguments are arrays, **zip** them together
@@ -105,6 +107,12 @@ macro_rules! see_docs_for_more_about_wildcards {
 /// Special types:
 /// - `Ctx` - context (type is `Ctx<'_>`)
 /// - `Span` - span of the function call (type is `Span`)
+/// - `Spanned<SheetRect>` - region of the grid referenced by an argument, which
+///   must be a cell reference or cell range
+/// - `Option<Spanned<SheetRect>>` - optional region of the grid referenced by
+///   an argument
+/// - `Spanned<Lambda>` - function passed as an argument, which must be a
+///   `LAMBDA` or a name bound to one (type is `Spanned<Rc<Lambda>>`)
 ///
 /// Additionally, if the parameter name is surrounded by square brackets (such
 /// as `[arg]: f64`) then if the argument is an array then the function will be
@@ -135,6 +143,7 @@ macro_rules! formula_fn {
     (
         #[doc = $doc:expr]
         $(#[doc = $additional_doc:expr])*
+        $(#[name($name:literal)])?
         $(#[include_args_in_completion($include_args_in_completion:expr)])?
         #[examples($($example_str:expr),+ $(,)?)]
         $(#[$($attr:tt)*])*
@@ -146,7 +155,7 @@ macro_rules! formula_fn {
         let include_args_in_completion = [$($include_args_in_completion, )? true][0];
 
         $crate::formulas::functions::FormulaFunction {
-            name: stringify!($fn_name),
+            name: [$($name, )? stringify!($fn_name)][0],
             arg_completion: include_args_in_completion.then(|| {
                 $crate::formulas::params::arg_completion_string(&params_list)
             }),
@@ -313,10 +322,16 @@ macro_rules! formula_fn_arg {
     };
 
     // Repeating argument
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Value >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< Value > >)
+    };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Value > >) => {
         // Do not flatten `Value`s.
         let mut $arg_name = $args.take_rest().map(CodeResult::Ok);
     };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< Array > >)
+    };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array > >) => {
         // Do not flatten arrays.
         let mut $arg_name = $args.take_rest().map(Array::from).map(CodeResult::Ok);
@@ -336,6 +351,22 @@ macro_rules! formula_fn_arg {
         let mut $arg_name = $arg_name.without_spans();
     };
 
+    // Cell reference argument
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< Spanned< SheetRect >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Option< Spanned< SheetRect > >)
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< Spanned< SheetRect > >) => {
+        let $arg_name = $args.take_next_optional_sheet_rect()?;
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Spanned< SheetRect >) => {
+        let $arg_name = $args.take_next_required_sheet_rect(stringify!($arg_name))?;
+    };
+
+    // Function argument
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Spanned< Lambda >) => {
+        let $arg_name = $args.take_next_required_lambda(stringify!($arg_name))?;
+    };
+
     // Optional argument
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< $($arg_type:tt)*) => {
         let $arg_name = match $args.take_next_optional() {
@@ -455,9 +486,14 @@ macro_rules! formula_fn_convert_arg {
     (@convert $value:expr, Value -> Spanned< CellValue > $(>)?) => {
         $value.into_cell_value()?
     };
-    (@convert $value:expr, Value -> Spanned< Array > $(>)?) => {
-        $value.map(Array::from)
-    };
+    (@convert $value:expr, Value -> Spanned< Array > $(>)?) => {{
+        let value: Spanned<Value> = $value;
+        let span = value.span;
+        Spanned {
+            span,
+            inner: Array::from(value.into_non_error_value()?),
+        }
+    }};
     (@convert $value:expr, Value -> Spanned< $arg_type:ty > $(>)?) => {
         $value.try_coerce::<$arg_type>()?
     };
@@ -504,6 +540,8 @@ macro_rules! params_list {
     // Entry points (at the bottom so that the other rules take priority)
     () => { vec![] };
     ($($arg_name:tt: $arg_type:tt),+ $(,)?) => {{
+        // This is unused if all parameters are special types such as `Span`.
+        #[allow(unused_mut)]
         let mut result = vec![];
 
         $(
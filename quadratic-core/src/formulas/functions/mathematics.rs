@@ -1,3 +1,6 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use rand::Rng;
+
 use super::*;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
@@ -8,6 +11,13 @@ pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     get_functions,
 };
 
+/// Rounding to more digits than this never changes a number that came from
+/// an `f64`.
+const MAX_ROUND_DIGITS: i64 = 1100;
+
+/// Largest number whose factorial fits in an `f64`.
+const MAX_FACTORIAL: u64 = 170;
+
 fn get_functions() -> Vec<FormulaFunction> {
     vec![
         formula_fn!(
@@ -41,6 +51,38 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers.sum::<CodeResult<f64>>()
             }
         ),
+        formula_fn!(
+            /// Adds values in `sum_range` wherever the corresponding values in
+            /// every `eval_range` meet their respective `criteria`.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "SUMIFS(C1:C10, A1:A10, \">0\")",
+                "SUMIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn SUMIFS(
+                ctx: Ctx,
+                span: Span,
+                sum_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                util::zip_map_eval_ranges_and_criteria(
+                    ctx,
+                    "SUMIFS",
+                    span,
+                    eval_ranges_and_criteria,
+                    |eval_ranges, criteria| {
+                        let numbers = Criterion::iter_matching_all_coerced::<f64>(
+                            eval_ranges,
+                            criteria,
+                            Some(&sum_range),
+                        )?;
+                        Ok(CellValue::from(numbers.sum::<CodeResult<f64>>()?))
+                    },
+                )?
+            }
+        ),
         formula_fn!(
             /// Multiplies all values.
             /// Returns `1` if given no values.
@@ -65,6 +107,287 @@ fn get_functions() -> Vec<FormulaFunction> {
                 number.sqrt()
             }
         ),
+        formula_fn!(
+            /// Returns `base` raised to the power of `exponent`.
+            ///
+            /// This is the same as the `^` operator, except that it returns an
+            /// error instead of a non-finite result.
+            #[examples("POWER(2, 10)", "POWER(A1, 0.5)")]
+            #[zip_map]
+            fn POWER(span: Span, [base]: f64, [exponent]: f64) {
+                let result = base.powf(exponent);
+                if result.is_nan() {
+                    return Err(RunErrorMsg::NotANumber.with_span(*span));
+                }
+                if result.is_infinite() {
+                    if base == 0.0 {
+                        return Err(RunErrorMsg::DivideByZero.with_span(*span));
+                    }
+                    return Err(RunErrorMsg::Overflow.with_span(*span));
+                }
+                result
+            }
+        ),
+        formula_fn!(
+            /// Returns the result of raising [Euler's
+            /// number](https://en.wikipedia.org/wiki/E_(mathematical_constant))
+            /// *e* to the power of `exponent`.
+            #[examples("EXP(1)", "EXP(A1)")]
+            #[zip_map]
+            fn EXP(span: Span, [exponent]: f64) {
+                finite_or_overflow(*span, exponent.exp())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the [natural
+            /// logarithm](https://en.wikipedia.org/wiki/Natural_logarithm) of
+            /// a number.
+            ///
+            /// Returns an error if `number` is not positive.
+            #[examples("LN(50)", "LN(EXP(2))")]
+            #[zip_map]
+            fn LN([number]: (Spanned<f64>)) {
+                positive_arg(number)?.ln()
+            }
+        ),
+        formula_fn!(
+            /// Returns the [logarithm](https://en.wikipedia.org/wiki/Logarithm)
+            /// of a number to the base `base`. If `base` is omitted, it is
+            /// assumed to be `10`.
+            ///
+            /// Returns an error if `number` or `base` is not positive.
+            #[examples("LOG(100)", "LOG(144, 12)", "LOG(50, EXP(1))")]
+            #[zip_map]
+            fn LOG(span: Span, [number]: (Spanned<f64>), [base]: (Option<Spanned<f64>>)) {
+                let number = positive_arg(number)?;
+                match base {
+                    Some(base) => {
+                        let base = positive_arg(base)?;
+                        util::checked_div(span, number.ln(), base.ln())?
+                    }
+                    None => number.log10(),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the [base-10
+            /// logarithm](https://en.wikipedia.org/wiki/Common_logarithm) of a
+            /// number.
+            ///
+            /// Returns an error if `number` is not positive.
+            #[examples("LOG10(100)")]
+            #[zip_map]
+            fn LOG10([number]: (Spanned<f64>)) {
+                positive_arg(number)?.log10()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sign of a number: `1` if it is positive, `-1` if it
+            /// is negative, and `0` if it is zero.
+            #[examples("SIGN(-4)", "SIGN(A1)")]
+            #[zip_map]
+            fn SIGN([number]: f64) {
+                if number > 0.0 {
+                    1.0
+                } else if number < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the remainder after dividing `number` by `divisor`. The
+            /// result always has the same sign as `divisor`.
+            ///
+            /// Returns an error if `divisor` is zero.
+            #[examples("MOD(10, 3)", "MOD(-10, 3)")]
+            #[zip_map]
+            fn MOD(span: Span, [number]: f64, [divisor]: f64) {
+                if divisor == 0.0 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                number - divisor * (number / divisor).floor()
+            }
+        ),
+        // Rounding
+        formula_fn!(
+            /// Rounds a number to `digits` digits after the decimal point,
+            /// rounding halfway values away from zero. If `digits` is omitted,
+            /// it is assumed to be `0`. If `digits` is negative, the number is
+            /// rounded to the left of the decimal point.
+            ///
+            /// This rounds the same way as the number format in a cell, so
+            /// `ROUND(A1, 2)` matches how `A1` looks with two decimal places.
+            #[examples("ROUND(6.553, 2)", "ROUND(1234, -2)")]
+            #[zip_map]
+            fn ROUND([number]: (Spanned<CellValue>), [digits]: (Option<i64>)) {
+                round_number(*number, digits.unwrap_or(0), RoundingMode::HalfUp)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number away from zero to `digits` digits after the
+            /// decimal point. If `digits` is omitted, it is assumed to be `0`.
+            /// If `digits` is negative, the number is rounded to the left of
+            /// the decimal point.
+            #[examples("ROUNDUP(6.551, 2)", "ROUNDUP(-1234, -2)")]
+            #[zip_map]
+            fn ROUNDUP([number]: (Spanned<CellValue>), [digits]: (Option<i64>)) {
+                round_number(*number, digits.unwrap_or(0), RoundingMode::Up)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number toward zero to `digits` digits after the decimal
+            /// point. If `digits` is omitted, it is assumed to be `0`. If
+            /// `digits` is negative, the number is rounded to the left of the
+            /// decimal point.
+            #[examples("ROUNDDOWN(6.559, 2)", "ROUNDDOWN(-1234, -2)")]
+            #[zip_map]
+            fn ROUNDDOWN([number]: (Spanned<CellValue>), [digits]: (Option<i64>)) {
+                round_number(*number, digits.unwrap_or(0), RoundingMode::Down)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number to the nearest multiple of `multiple`, rounding
+            /// halfway values away from zero.
+            ///
+            /// Returns an error if `number` and `multiple` have different
+            /// signs.
+            #[examples("MROUND(10, 3)", "MROUND(-7.5, -5)")]
+            #[zip_map]
+            fn MROUND([number]: (Spanned<CellValue>), [multiple]: (Spanned<CellValue>)) {
+                let (n, m) = (bigdecimal_arg(*number)?, bigdecimal_arg(*multiple)?);
+                if !n.is_zero() && !m.is_zero() && n.sign() != m.sign() {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(multiple.span));
+                }
+                round_to_multiple(*number, Some(*multiple), RoundingMode::HalfUp)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number up to the nearest multiple of `significance`. If
+            /// `significance` is omitted, it is assumed to be `1`.
+            ///
+            /// If `number` and `significance` are both negative, then the
+            /// number is rounded away from zero instead. Returns an error if
+            /// `number` is positive and `significance` is negative.
+            #[examples("CEILING(6.3)", "CEILING(22, 5)", "CEILING(-2.5, -2)")]
+            #[zip_map]
+            fn CEILING(
+                [number]: (Spanned<CellValue>),
+                [significance]: (Option<Spanned<CellValue>>),
+            ) {
+                round_to_multiple(*number, significance.copied(), RoundingMode::Ceiling)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest multiple of `significance`.
+            /// If `significance` is omitted, it is assumed to be `1`.
+            ///
+            /// If `number` and `significance` are both negative, then the
+            /// number is rounded toward zero instead. Returns an error if
+            /// `number` is positive and `significance` is negative.
+            #[examples("FLOOR(6.7)", "FLOOR(22, 5)", "FLOOR(-2.5, -2)")]
+            #[zip_map]
+            fn FLOOR([number]: (Spanned<CellValue>), [significance]: (Option<Spanned<CellValue>>)) {
+                round_to_multiple(*number, significance.copied(), RoundingMode::Floor)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest integer. This is the same
+            /// as `FLOOR(number)`.
+            #[examples("INT(6.7)", "INT(-6.7)")]
+            #[zip_map]
+            fn INT([number]: (Spanned<CellValue>)) {
+                round_number(*number, 0, RoundingMode::Floor)?
+            }
+        ),
+        formula_fn!(
+            /// Removes the fractional part of a number, keeping `digits`
+            /// digits after the decimal point. If `digits` is omitted, it is
+            /// assumed to be `0`. This is the same as `ROUNDDOWN`.
+            #[examples("TRUNC(6.7)", "TRUNC(-6.789, 1)")]
+            #[zip_map]
+            fn TRUNC([number]: (Spanned<CellValue>), [digits]: (Option<i64>)) {
+                round_number(*number, digits.unwrap_or(0), RoundingMode::Down)?
+            }
+        ),
+        // Number theory
+        formula_fn!(
+            /// Returns the [greatest common
+            /// divisor](https://en.wikipedia.org/wiki/Greatest_common_divisor)
+            /// of all values. Values are truncated to integers.
+            ///
+            /// Returns an error if any value is negative.
+            #[examples("GCD(12, 18)", "GCD(A1:A10)")]
+            fn GCD(numbers: (Iter<Spanned<f64>>)) {
+                let mut result = 0;
+                for n in numbers {
+                    result = gcd(result, nonnegative_integer_arg(n?)?);
+                }
+                result as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the [least common
+            /// multiple](https://en.wikipedia.org/wiki/Least_common_multiple)
+            /// of all values. Values are truncated to integers.
+            ///
+            /// Returns an error if any value is negative.
+            #[examples("LCM(4, 6)", "LCM(A1:A10)")]
+            fn LCM(span: Span, numbers: (Iter<Spanned<f64>>)) {
+                let mut result: u64 = 1;
+                for n in numbers {
+                    let n = nonnegative_integer_arg(n?)?;
+                    if n == 0 {
+                        return Ok(Value::from(0.0));
+                    }
+                    result = (result / gcd(result, n))
+                        .checked_mul(n)
+                        .ok_or(RunErrorMsg::Overflow.with_span(span))?;
+                }
+                result as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the [factorial](https://en.wikipedia.org/wiki/Factorial)
+            /// of a number. The number is truncated to an integer.
+            ///
+            /// Returns an error if `number` is negative.
+            #[examples("FACT(5)", "FACT(A1)")]
+            #[zip_map]
+            fn FACT(span: Span, [number]: (Spanned<f64>)) {
+                let n = nonnegative_integer_arg(number)?;
+                if n > MAX_FACTORIAL {
+                    return Err(RunErrorMsg::Overflow.with_span(*span));
+                }
+                (1..=n).map(|i| i as f64).product::<f64>()
+            }
+        ),
+        // Random numbers
+        formula_fn!(
+            /// Returns a random number between `0` (inclusive) and `1`
+            /// (exclusive).
+            #[examples("RAND()", "RAND() * 10")]
+            fn RAND() {
+                rand::thread_rng().gen::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns a random integer between `low` and `high`, inclusive.
+            ///
+            /// Returns an error if there is no integer between `low` and
+            /// `high`.
+            #[examples("RANDBETWEEN(1, 6)", "RANDBETWEEN(-10, 10)")]
+            #[zip_map]
+            fn RANDBETWEEN(span: Span, [low]: f64, [high]: f64) {
+                let low = low.ceil();
+                let high = high.floor();
+                if !low.is_finite() || !high.is_finite() || low > high {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                rand::thread_rng().gen_range(low..=high).floor()
+            }
+        ),
         // Constants
         formula_fn!(
             /// Returns π, the circle constant.
@@ -83,6 +406,99 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Converts a number argument to a `BigDecimal`. Numbers from cells are used
+/// exactly, so that rounding matches the displayed value.
+fn bigdecimal_arg(value: Spanned<&CellValue>) -> CodeResult<BigDecimal> {
+    match value.inner {
+        CellValue::Number(n) => Ok(n.clone()),
+        _ => {
+            let n = value.try_coerce::<f64>()?;
+            BigDecimal::try_from(n.inner).map_err(|_| RunErrorMsg::NotANumber.with_span(n.span))
+        }
+    }
+}
+
+/// Rounds a number to `digits` digits after the decimal point.
+fn round_number(
+    number: Spanned<&CellValue>,
+    digits: i64,
+    mode: RoundingMode,
+) -> CodeResult<BigDecimal> {
+    let digits = digits.clamp(-MAX_ROUND_DIGITS, MAX_ROUND_DIGITS);
+    Ok(normalize(
+        bigdecimal_arg(number)?.with_scale_round(digits, mode),
+    ))
+}
+
+/// Rounds a number to a multiple of `multiple`, which defaults to `1`. Returns
+/// an error if `number` is positive and `multiple` is negative.
+fn round_to_multiple(
+    number: Spanned<&CellValue>,
+    multiple: Option<Spanned<&CellValue>>,
+    mode: RoundingMode,
+) -> CodeResult<BigDecimal> {
+    let span = multiple.map_or(number.span, |m| m.span);
+    let number = bigdecimal_arg(number)?;
+    let multiple = match multiple {
+        Some(m) => bigdecimal_arg(m)?,
+        None => BigDecimal::from(1),
+    };
+    if multiple.is_zero() || number.is_zero() {
+        return Ok(BigDecimal::zero());
+    }
+    if number > BigDecimal::zero() && multiple < BigDecimal::zero() {
+        return Err(RunErrorMsg::InvalidArgument.with_span(span));
+    }
+    let quotient = (&number / &multiple).with_scale_round(0, mode);
+    Ok(normalize(quotient * multiple))
+}
+
+/// Removes trailing zeros from a number without using a negative scale, so
+/// that it is displayed without an exponent.
+fn normalize(n: BigDecimal) -> BigDecimal {
+    let n = n.normalized();
+    if n.as_bigint_and_exponent().1 < 0 {
+        n.with_scale(0)
+    } else {
+        n
+    }
+}
+
+/// Returns an error if a number is not positive.
+fn positive_arg(number: Spanned<f64>) -> CodeResult<f64> {
+    if number.inner > 0.0 {
+        Ok(number.inner)
+    } else {
+        Err(RunErrorMsg::InvalidArgument.with_span(number.span))
+    }
+}
+
+/// Truncates a number to an integer, returning an error if it is negative.
+fn nonnegative_integer_arg(number: Spanned<f64>) -> CodeResult<u64> {
+    if number.inner >= 0.0 {
+        Ok(number.inner.trunc() as u64)
+    } else {
+        Err(RunErrorMsg::InvalidArgument.with_span(number.span))
+    }
+}
+
+/// Returns an error if a number is infinite.
+fn finite_or_overflow(span: Span, number: f64) -> CodeResult<f64> {
+    if number.is_finite() {
+        Ok(number)
+    } else {
+        Err(RunErrorMsg::Overflow.with_span(span))
+    }
+}
+
+/// Returns the greatest common divisor of two integers.
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{formulas::tests::*, Pos};
@@ -150,6 +566,231 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sumifs() {
+        let g = Grid::new();
+        assert_eq!("15", eval_to_string(&g, "SUMIFS(0..10, 0..10, \"<=5\")"));
+        assert_eq!(
+            "12",
+            eval_to_string(&g, "SUMIFS(0..10, 0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            "60",
+            eval_to_string(&g, "SUMIFS(2^0..10, 0..10, \"<=5\", 0..10, \">=2\")"),
+        );
+        // Test with an array of conditions.
+        assert_eq!(
+            "{12, 0}",
+            eval_to_string(&g, "SUMIFS(0..10, 0..10, \"<=5\", 0..10, {\">2\", \">5\"})"),
+        );
+
+        // Error on range size mismatch.
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(1, 10).unwrap(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 0..10, \"<=5\", 1..10, \">2\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(1, 1).unwrap(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 3, \"<=5\")").msg,
+        );
+
+        // Error on missing criteria.
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "SUMIFS".into(),
+                arg_name: "criteria".into(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 0..10, \"<=5\", 0..10)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "SUMIFS".into(),
+                arg_name: "eval_range".into(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10)").msg,
+        );
+    }
+
+    #[test]
+    fn test_round() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], CellValue::Number("2.675".parse().unwrap()));
+        let _ = sheet.set_cell_value(pos![A2], CellValue::Number("-2.675".parse().unwrap()));
+
+        // Rounding uses the exact value in the cell, the same as the number
+        // format.
+        let a1 = g.sheets()[0].cell_value(pos![A1]).unwrap();
+        assert_eq!("2.68", a1.to_display(None, Some(2), None));
+        assert_eq!("2.68", eval_to_string(&g, "ROUND(A1, 2)"));
+        assert_eq!("-2.68", eval_to_string(&g, "ROUND(A2, 2)"));
+        assert_eq!("3", eval_to_string(&g, "ROUND(A1)"));
+        assert_eq!("3", eval_to_string(&g, "ROUND(2.5)"));
+        assert_eq!("-3", eval_to_string(&g, "ROUND(-2.5)"));
+        assert_eq!("1200", eval_to_string(&g, "ROUND(1234, -2)"));
+        assert_eq!("1.5", eval_to_string(&g, "ROUND(1.5, 3)"));
+        assert_eq!("{1.2, 1.23}", eval_to_string(&g, "ROUND(1.234, {1, 2})"));
+
+        assert_eq!("2.68", eval_to_string(&g, "ROUNDUP(A1, 2)"));
+        assert_eq!("2.7", eval_to_string(&g, "ROUNDUP(2.61, 1)"));
+        assert_eq!("-1300", eval_to_string(&g, "ROUNDUP(-1234, -2)"));
+        assert_eq!("2.67", eval_to_string(&g, "ROUNDDOWN(A1, 2)"));
+        assert_eq!("-1200", eval_to_string(&g, "ROUNDDOWN(-1234, -2)"));
+        assert_eq!("6", eval_to_string(&g, "TRUNC(6.7)"));
+        assert_eq!("-6.7", eval_to_string(&g, "TRUNC(-6.789, 1)"));
+        assert_eq!("6", eval_to_string(&g, "INT(6.7)"));
+        assert_eq!("-7", eval_to_string(&g, "INT(-6.7)"));
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("text".into()),
+            },
+            eval_to_err(&g, "ROUND(\"abc\")").msg,
+        );
+    }
+
+    #[test]
+    fn test_round_to_multiple() {
+        let g = Grid::new();
+        assert_eq!("9", eval_to_string(&g, "MROUND(10, 3)"));
+        assert_eq!("-10", eval_to_string(&g, "MROUND(-7.5, -5)"));
+        assert_eq!("0", eval_to_string(&g, "MROUND(5, 0)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "MROUND(-10, 3)").msg,
+        );
+
+        assert_eq!("7", eval_to_string(&g, "CEILING(6.3)"));
+        assert_eq!("25", eval_to_string(&g, "CEILING(22, 5)"));
+        assert_eq!("0.5", eval_to_string(&g, "CEILING(0.3, 0.25)"));
+        assert_eq!("-2", eval_to_string(&g, "CEILING(-2.5, 2)"));
+        assert_eq!("-4", eval_to_string(&g, "CEILING(-2.5, -2)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "CEILING(2.5, -2)").msg,
+        );
+
+        assert_eq!("6", eval_to_string(&g, "FLOOR(6.7)"));
+        assert_eq!("20", eval_to_string(&g, "FLOOR(22, 5)"));
+        assert_eq!("-4", eval_to_string(&g, "FLOOR(-2.5, 2)"));
+        assert_eq!("-2", eval_to_string(&g, "FLOOR(-2.5, -2)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "FLOOR(2.5, -2)").msg,
+        );
+    }
+
+    #[test]
+    fn test_mod_and_power() {
+        let g = Grid::new();
+        assert_eq!("1", eval_to_string(&g, "MOD(10, 3)"));
+        assert_eq!("2", eval_to_string(&g, "MOD(-10, 3)"));
+        assert_eq!("-2", eval_to_string(&g, "MOD(10, -3)"));
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "MOD(10, 0)").msg);
+
+        assert_eq!("1024", eval_to_string(&g, "POWER(2, 10)"));
+        assert_eq!("3", eval_to_string(&g, "POWER(9, 0.5)"));
+        assert_eq!(
+            RunErrorMsg::NotANumber,
+            eval_to_err(&g, "POWER(-8, 0.5)").msg
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "POWER(0, -1)").msg
+        );
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "POWER(10, 400)").msg);
+
+        assert_eq!("1", eval_to_string(&g, "EXP(0)"));
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "EXP(1000)").msg);
+
+        assert_eq!("-1", eval_to_string(&g, "SIGN(-4)"));
+        assert_eq!("0", eval_to_string(&g, "SIGN(0)"));
+        assert_eq!("1", eval_to_string(&g, "SIGN(0.1)"));
+    }
+
+    #[test]
+    fn test_logarithms() {
+        let g = Grid::new();
+        assert_eq!("0", eval_to_string(&g, "LN(1)"));
+        assert_eq!("2", eval_to_string(&g, "LN(EXP(2))"));
+        assert_eq!("2", eval_to_string(&g, "LOG(100)"));
+        assert_eq!("3", eval_to_string(&g, "LOG(8, 2)"));
+        assert_eq!("3", eval_to_string(&g, "LOG10(1000)"));
+
+        assert_eq!(RunErrorMsg::InvalidArgument, eval_to_err(&g, "LN(0)").msg);
+        assert_eq!(RunErrorMsg::InvalidArgument, eval_to_err(&g, "LN(-1)").msg);
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "LOG10(0)").msg
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "LOG(8, -2)").msg
+        );
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "LOG(8, 1)").msg);
+    }
+
+    #[test]
+    fn test_number_theory() {
+        let g = Grid::new();
+        assert_eq!("6", eval_to_string(&g, "GCD(12, 18)"));
+        assert_eq!("6", eval_to_string(&g, "GCD(12.9, {18, 24})"));
+        assert_eq!("5", eval_to_string(&g, "GCD(0, 5)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "GCD(-4, 6)").msg
+        );
+
+        assert_eq!("12", eval_to_string(&g, "LCM(4, 6)"));
+        assert_eq!("60", eval_to_string(&g, "LCM(1..5)"));
+        assert_eq!("0", eval_to_string(&g, "LCM(0, 5)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "LCM(-4, 6)").msg
+        );
+
+        assert_eq!("120", eval_to_string(&g, "FACT(5)"));
+        assert_eq!("120", eval_to_string(&g, "FACT(5.9)"));
+        assert_eq!("1", eval_to_string(&g, "FACT(0)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "FACT(-1)").msg
+        );
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "FACT(200)").msg);
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "FACT(1e308)").msg);
+        assert!(eval_to_string(&g, "FACT(170)").starts_with("7257415615307"));
+    }
+
+    #[test]
+    fn test_random() {
+        let g = Grid::new();
+        for _ in 0..20 {
+            let n = eval_to_string(&g, "RAND()").parse::<f64>().unwrap();
+            assert!((0.0..1.0).contains(&n));
+
+            let n = eval_to_string(&g, "RANDBETWEEN(1.5, 4)")
+                .parse::<f64>()
+                .unwrap();
+            assert!([2.0, 3.0, 4.0].contains(&n));
+        }
+        assert_eq!("3", eval_to_string(&g, "RANDBETWEEN(3, 3)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "RANDBETWEEN(5, 1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "RANDBETWEEN(1.2, 1.8)").msg,
+        );
+    }
+
     #[test]
     fn test_product() {
         let g = Grid::new();
@@ -1,11 +1,15 @@
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
 
 use itertools::Itertools;
 use lazy_static::lazy_static;
 
 #[macro_use]
 mod macros;
+mod array;
+mod datetime;
+mod lambda;
 mod logic;
 mod lookup;
 mod mathematics;
@@ -15,12 +19,16 @@ mod string;
 mod trigonometry;
 mod util;
 
-use super::{CellRef, Criterion, Ctx, Param, ParamKind};
+use super::{Binding, CellRef, Criterion, Ctx, Lambda, Param, ParamKind};
 use crate::{
-    Array, Axis, CellValue, CodeResult, CoerceInto, IsBlank, RunError, RunErrorMsg, Span, Spanned,
-    SpannedIterExt, Value,
+    Array, Axis, CellValue, CodeResult, CoerceInto, IsBlank, RunError, RunErrorMsg, SheetRect,
+    Span, Spanned, SpannedIterExt, Value,
 };
 
+/// Number of seconds in a day, which is the unit of numbers added to or
+/// subtracted from dates.
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
 pub fn lookup_function(name: &str) -> Option<&'static FormulaFunction> {
     ALL_FUNCTIONS.get(name.to_ascii_uppercase().as_str())
 }
@@ -32,7 +40,10 @@ pub const CATEGORIES: &[FormulaFunctionCategory] = &[
     statistics::CATEGORY,
     logic::CATEGORY,
     string::CATEGORY,
+    datetime::CATEGORY,
     lookup::CATEGORY,
+    array::CATEGORY,
+    lambda::CATEGORY,
 ];
 
 lazy_static! {
@@ -58,6 +69,12 @@ lazy_static! {
 pub struct FormulaFnArgs {
     pub span: Span,
     values: VecDeque<Spanned<Value>>,
+    /// Regions of the grid referenced by each argument, for arguments that are
+    /// cell references or cell ranges.
+    sheet_rects: VecDeque<Option<SheetRect>>,
+    /// Functions passed as each argument, for arguments that are a `LAMBDA`
+    /// or a name bound to one.
+    lambdas: VecDeque<Option<Rc<Lambda>>>,
     func_name: &'static str,
     args_popped: usize,
 }
@@ -71,15 +88,29 @@ impl FormulaFnArgs {
         Self {
             span,
             values: values.into(),
+            sheet_rects: VecDeque::new(),
+            lambdas: VecDeque::new(),
             func_name,
             args_popped: 0,
         }
     }
+    /// Sets the regions of the grid referenced by each argument.
+    pub fn with_sheet_rects(mut self, sheet_rects: impl Into<VecDeque<Option<SheetRect>>>) -> Self {
+        self.sheet_rects = sheet_rects.into();
+        self
+    }
+    /// Sets the functions passed as each argument.
+    pub fn with_lambdas(mut self, lambdas: impl Into<VecDeque<Option<Rc<Lambda>>>>) -> Self {
+        self.lambdas = lambdas.into();
+        self
+    }
     /// Takes the next argument.
     fn take_next(&mut self) -> Option<Spanned<Value>> {
         if !self.values.is_empty() {
             self.args_popped += 1;
         }
+        self.sheet_rects.pop_front();
+        self.lambdas.pop_front();
         self.values.pop_front()
     }
     /// Takes the next argument, or returns `None` if there is none or the
@@ -101,8 +132,107 @@ impl FormulaFnArgs {
             .with_span(self.span)
         })
     }
+    /// Takes the next argument, which must be a cell reference or cell range,
+    /// and returns the region of the grid that it references. Returns an error
+    /// if there is no argument.
+    pub fn take_next_required_sheet_rect(
+        &mut self,
+        arg_name: impl Into<Cow<'static, str>>,
+    ) -> CodeResult<Spanned<SheetRect>> {
+        let sheet_rect = self.sheet_rects.front().copied().flatten();
+        let value = self.take_next_required(arg_name)?;
+        Self::expect_sheet_rect(value, sheet_rect)
+    }
+    /// Takes the next argument, which must be a cell reference or cell range,
+    /// and returns the region of the grid that it references. Returns `None` if
+    /// there is no argument or the argument is blank.
+    pub fn take_next_optional_sheet_rect(&mut self) -> CodeResult<Option<Spanned<SheetRect>>> {
+        let sheet_rect = self.sheet_rects.front().copied().flatten();
+        self.take_next_optional()
+            .map(|value| Self::expect_sheet_rect(value, sheet_rect))
+            .transpose()
+    }
+    fn expect_sheet_rect(
+        value: Spanned<Value>,
+        sheet_rect: Option<SheetRect>,
+    ) -> CodeResult<Spanned<SheetRect>> {
+        let span = value.span;
+        match sheet_rect {
+            Some(inner) => Ok(Spanned { span, inner }),
+            None => Err(RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some(match &value.inner {
+                    Value::Single(v) => v.type_name().into(),
+                    Value::Array(a) => a.type_name().into(),
+                }),
+            }
+            .with_span(span)),
+        }
+    }
+    /// Takes the next argument, which must be a `LAMBDA` or a name bound to
+    /// one. Returns an error if there is no argument.
+    pub fn take_next_required_lambda(
+        &mut self,
+        arg_name: impl Into<Cow<'static, str>>,
+    ) -> CodeResult<Spanned<Rc<Lambda>>> {
+        let lambda = self.lambdas.front().cloned().flatten();
+        let value = self.take_next_required(arg_name)?;
+        Self::expect_lambda(value, lambda)
+    }
+    /// Takes the last argument, which must be a `LAMBDA` or a name bound to
+    /// one. Returns an error if there is no argument.
+    pub fn take_last_required_lambda(
+        &mut self,
+        arg_name: impl Into<Cow<'static, str>>,
+    ) -> CodeResult<Spanned<Rc<Lambda>>> {
+        let lambda = self.lambdas.pop_back().flatten();
+        self.sheet_rects.pop_back();
+        match self.values.pop_back() {
+            Some(value) => Self::expect_lambda(value, lambda),
+            None => Err(RunErrorMsg::MissingRequiredArgument {
+                func_name: self.func_name.into(),
+                arg_name: arg_name.into(),
+            }
+            .with_span(self.span)),
+        }
+    }
+    fn expect_lambda(
+        value: Spanned<Value>,
+        lambda: Option<Rc<Lambda>>,
+    ) -> CodeResult<Spanned<Rc<Lambda>>> {
+        let span = value.span;
+        match lambda {
+            Some(inner) => Ok(Spanned { span, inner }),
+            None => {
+                let got = match value.into_non_error_value()? {
+                    Value::Single(v) => v.type_name(),
+                    Value::Array(a) => a.type_name(),
+                };
+                Err(RunErrorMsg::Expected {
+                    expected: "LAMBDA".into(),
+                    got: Some(got.into()),
+                }
+                .with_span(span))
+            }
+        }
+    }
+    /// Takes the next argument, keeping it as a function if it is a `LAMBDA`
+    /// or a name bound to one. Returns an error if there is no argument.
+    pub fn take_next_required_binding(
+        &mut self,
+        arg_name: impl Into<Cow<'static, str>>,
+    ) -> CodeResult<Binding> {
+        let lambda = self.lambdas.front().cloned().flatten();
+        let value = self.take_next_required(arg_name)?;
+        Ok(match lambda {
+            Some(lambda) => Binding::Lambda(lambda),
+            None => Binding::Value(value.inner),
+        })
+    }
     /// Takes the rest of the arguments and iterates over them.
     pub fn take_rest(&mut self) -> impl Iterator<Item = Spanned<Value>> {
+        self.sheet_rects.clear();
+        self.lambdas.clear();
         std::mem::take(&mut self.values).into_iter()
     }
 
@@ -1,4 +1,4 @@
-use crate::ArraySize;
+use crate::{ArraySize, Duration};
 
 use super::*;
 
@@ -25,17 +25,23 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "+"([a]: f64, [b]: (Option<f64>)) {
-                a + b.unwrap_or(0.0)
+            fn "+"(span: Span, [a]: (Spanned<CellValue>), [b]: (Option<Spanned<CellValue>>)) {
+                match b {
+                    Some(b) => add(*span, *a, *b)?,
+                    None => CellValue::from(a.try_coerce::<f64>()?.inner),
+                }
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "-"([a]: f64, [b]: (Option<f64>)) {
+            fn "-"(span: Span, [a]: (Spanned<CellValue>), [b]: (Option<Spanned<CellValue>>)) {
                 match b {
-                    Some(b) => a - b,
-                    None => -a
+                    Some(b) => subtract(*span, *a, *b)?,
+                    None => match a.inner {
+                        CellValue::Duration(d) => CellValue::Duration(-*d),
+                        _ => CellValue::from(-a.try_coerce::<f64>()?.inner),
+                    },
                 }
             }
         ),
@@ -95,6 +101,50 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Adds two values. Durations and numbers of days may be added to instants,
+/// and durations may be added to each other.
+fn add(span: Span, a: Spanned<&CellValue>, b: Spanned<&CellValue>) -> CodeResult<CellValue> {
+    let result = match (a.inner, b.inner) {
+        (CellValue::Instant(i), CellValue::Duration(d))
+        | (CellValue::Duration(d), CellValue::Instant(i)) => i.checked_add(*d).map(CellValue::from),
+        (CellValue::Instant(i), _) => i.checked_add(days(b)?).map(CellValue::from),
+        (_, CellValue::Instant(i)) => i.checked_add(days(a)?).map(CellValue::from),
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => {
+            d1.checked_add(*d2).map(CellValue::from)
+        }
+        _ => Some(CellValue::from(
+            a.try_coerce::<f64>()?.inner + b.try_coerce::<f64>()?.inner,
+        )),
+    };
+    result.ok_or(RunErrorMsg::Overflow.with_span(span))
+}
+
+/// Subtracts two values. Subtracting two instants returns the duration between
+/// them.
+fn subtract(span: Span, a: Spanned<&CellValue>, b: Spanned<&CellValue>) -> CodeResult<CellValue> {
+    let result = match (a.inner, b.inner) {
+        (CellValue::Instant(i1), CellValue::Instant(i2)) => {
+            Some(CellValue::from(i1.duration_since(*i2)))
+        }
+        (CellValue::Instant(i), CellValue::Duration(d)) => i.checked_sub(*d).map(CellValue::from),
+        (CellValue::Instant(i), _) => i.checked_sub(days(b)?).map(CellValue::from),
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => {
+            d1.checked_sub(*d2).map(CellValue::from)
+        }
+        _ => Some(CellValue::from(
+            a.try_coerce::<f64>()?.inner - b.try_coerce::<f64>()?.inner,
+        )),
+    };
+    result.ok_or(RunErrorMsg::Overflow.with_span(span))
+}
+
+/// Coerces a value to a number of days and returns it as a duration.
+fn days(value: Spanned<&CellValue>) -> CodeResult<Duration> {
+    Ok(Duration::from_seconds(
+        value.try_coerce::<f64>()?.inner * SECONDS_PER_DAY,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::formulas::tests::*;
@@ -113,6 +163,62 @@ mod tests {
         assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "0/ 0").msg);
     }
 
+    #[test]
+    fn test_formula_date_time_operators() {
+        let g = Grid::new();
+
+        let date = "DATE(2024, 1, 31)";
+        assert_eq!(
+            "2024-02-03 00:00:00",
+            eval_to_string(&g, &format!("{date} + 3")),
+        );
+        assert_eq!(
+            "2024-02-03 00:00:00",
+            eval_to_string(&g, &format!("3 + {date}")),
+        );
+        assert_eq!(
+            "2024-01-30 12:00:00",
+            eval_to_string(&g, &format!("{date} - 0.5")),
+        );
+        assert_eq!(
+            "2024-01-31 01:30:00",
+            eval_to_string(&g, &format!("{date} + TIME(1, 30, 0)")),
+        );
+        assert_eq!(
+            "2024-01-30 22:30:00",
+            eval_to_string(&g, &format!("{date} - TIME(1, 30, 0)")),
+        );
+        assert_eq!(
+            "0 years, 0 months, 86400 seconds",
+            eval_to_string(&g, &format!("{date} + 1 - {date}")),
+        );
+        assert_eq!(
+            "0 years, 0 months, 5400 seconds",
+            eval_to_string(&g, "TIME(1, 0, 0) + TIME(0, 30, 0)"),
+        );
+        assert_eq!(
+            "0 years, 0 months, 1800 seconds",
+            eval_to_string(&g, "TIME(1, 0, 0) - TIME(0, 30, 0)"),
+        );
+        assert_eq!(
+            "0 years, 0 months, -3600 seconds",
+            eval_to_string(&g, "-TIME(1, 0, 0)"),
+        );
+        assert_eq!("TRUE", eval_to_string(&g, &format!("{date} + 1 > {date}")),);
+
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("text".into()),
+            },
+            eval_to_err(&g, &format!("{date} + \"apple\"")).msg,
+        );
+        assert!(matches!(
+            eval_to_err(&g, &format!("{date} * 2")).msg,
+            RunErrorMsg::Expected { .. },
+        ));
+    }
+
     #[test]
     fn test_formula_math_operators_on_empty_string() {
         // Empty string should coerce to zero
@@ -42,6 +42,39 @@ fn get_functions() -> Vec<FormulaFunction> {
                 util::average(span, numbers)
             }
         ),
+        formula_fn!(
+            /// Computes the arithmetic mean of values in `average_range`
+            /// wherever the corresponding values in every `eval_range` meet
+            /// their respective `criteria`.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\")",
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn AVERAGEIFS(
+                ctx: Ctx,
+                span: Span,
+                average_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                util::zip_map_eval_ranges_and_criteria(
+                    ctx,
+                    "AVERAGEIFS",
+                    span,
+                    eval_ranges_and_criteria,
+                    |eval_ranges, criteria| {
+                        let numbers = Criterion::iter_matching_all_coerced::<f64>(
+                            eval_ranges,
+                            criteria,
+                            Some(&average_range),
+                        )?;
+                        Ok(CellValue::from(util::average(span, numbers)?))
+                    },
+                )?
+            }
+        ),
         formula_fn!(
             /// Returns the number of numeric values.
             ///
@@ -49,7 +82,7 @@ fn get_functions() -> Vec<FormulaFunction> {
             /// - Cells containing an error are not counted.
             #[examples("COUNT(A1:C42, E17)", "SUM(A1:A10) / COUNT(A1:A10)")]
             fn COUNT(numbers: (Iter<CellValue>)) {
-                // Ignore error values.
+                // Error values are not numbers, so they are not counted.
                 numbers
                     .filter(|x| matches!(x, Ok(CellValue::Number(_))))
                     .count() as f64
@@ -79,12 +112,39 @@ fn get_functions() -> Vec<FormulaFunction> {
             )]
             #[zip_map]
             fn COUNTIF(range: (Spanned<Array>), [criteria]: (Spanned<CellValue>)) {
+                // An error in `criteria` is returned. Error values in `range`
+                // are counted if they match, such as with `"<>0"`.
                 let criteria = Criterion::try_from(*criteria)?;
-                // Ignore error values.
                 let count = criteria.iter_matching(range, None)?.count();
                 count as f64
             }
         ),
+        formula_fn!(
+            /// Counts how many positions meet every criteria, where each
+            /// `criteria` is evaluated on the corresponding value in its
+            /// `eval_range`.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "COUNTIFS(A1:A10, \">0\")",
+                "COUNTIFS(A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn COUNTIFS(ctx: Ctx, span: Span, eval_ranges_and_criteria: (Iter<Spanned<Value>>)) {
+                util::zip_map_eval_ranges_and_criteria(
+                    ctx,
+                    "COUNTIFS",
+                    span,
+                    eval_ranges_and_criteria,
+                    |eval_ranges, criteria| {
+                        // Error values in the ranges are counted if they match.
+                        let count =
+                            Criterion::iter_matching_all(eval_ranges, criteria, None)?.count();
+                        Ok(CellValue::from(count as f64))
+                    },
+                )?
+            }
+        ),
         formula_fn!(
             /// Counts how many values in the range are empty.
             ///
@@ -94,7 +154,7 @@ fn get_functions() -> Vec<FormulaFunction> {
             /// - Cells with an error are not counted.
             #[examples("COUNTBLANK(A1:A10)")]
             fn COUNTBLANK(range: (Iter<CellValue>)) {
-                // Ignore error values.
+                // Error values are not blank, so they are not counted.
                 range
                     .filter_map(|v| v.ok())
                     .filter(|v| v.is_blank_or_empty_string())
@@ -117,9 +177,300 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers.try_fold(-f64::INFINITY, |a, b| Ok(f64::max(a, b?)))
             }
         ),
+        formula_fn!(
+            /// Returns the smallest value in `min_range` wherever the
+            /// corresponding values in every `eval_range` meet their
+            /// respective `criteria`.
+            /// Returns `0` if no values meet the criteria.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "MINIFS(C1:C10, A1:A10, \">0\")",
+                "MINIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn MINIFS(
+                ctx: Ctx,
+                span: Span,
+                min_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                util::zip_map_eval_ranges_and_criteria(
+                    ctx,
+                    "MINIFS",
+                    span,
+                    eval_ranges_and_criteria,
+                    |eval_ranges, criteria| {
+                        let numbers = Criterion::iter_matching_all_coerced::<f64>(
+                            eval_ranges,
+                            criteria,
+                            Some(&min_range),
+                        )?;
+                        let min = numbers
+                            .collect::<CodeResult<Vec<f64>>>()?
+                            .into_iter()
+                            .reduce(f64::min);
+                        Ok(CellValue::from(min.unwrap_or(0.0)))
+                    },
+                )?
+            }
+        ),
+        formula_fn!(
+            /// Returns the largest value in `max_range` wherever the
+            /// corresponding values in every `eval_range` meet their
+            /// respective `criteria`.
+            /// Returns `0` if no values meet the criteria.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "MAXIFS(C1:C10, A1:A10, \">0\")",
+                "MAXIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn MAXIFS(
+                ctx: Ctx,
+                span: Span,
+                max_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                util::zip_map_eval_ranges_and_criteria(
+                    ctx,
+                    "MAXIFS",
+                    span,
+                    eval_ranges_and_criteria,
+                    |eval_ranges, criteria| {
+                        let numbers = Criterion::iter_matching_all_coerced::<f64>(
+                            eval_ranges,
+                            criteria,
+                            Some(&max_range),
+                        )?;
+                        let max = numbers
+                            .collect::<CodeResult<Vec<f64>>>()?
+                            .into_iter()
+                            .reduce(f64::max);
+                        Ok(CellValue::from(max.unwrap_or(0.0)))
+                    },
+                )?
+            }
+        ),
+        formula_fn!(
+            /// Returns the median of all values. If there is an even number of
+            /// values, returns the arithmetic mean of the two middle values.
+            ///
+            /// Blank cells and non-numeric text in ranges are ignored.
+            #[examples("MEDIAN(A1:A6)", "MEDIAN(1, 2, 3, 10)")]
+            fn MEDIAN(span: Span, numbers: (Iter<f64>)) {
+                percentile(span, numbers.collect::<CodeResult<_>>()?, 0.5)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the most common value. If there is a tie, returns the
+            /// one that appears first.
+            ///
+            /// Returns an error if no value appears more than once.
+            #[examples("MODE(A1:A6)", "MODE(1, 2, 2, 3)")]
+            fn MODE(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                let counts = numbers.iter().counts_by(|n| n.to_bits());
+                let max_count = counts.values().copied().max().unwrap_or(0);
+                if max_count < 2 {
+                    return Err(RunErrorMsg::NoMatch.with_span(span));
+                }
+                numbers
+                    .into_iter()
+                    .find(|n| counts[&n.to_bits()] == max_count)
+                    .ok_or(RunErrorMsg::NoMatch.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sample standard deviation of all values, which is
+            /// the square root of `VAR`.
+            #[examples("STDEV(A1:A6)", "STDEV(1, 2, 3, 4)")]
+            fn STDEV(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers, true)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the population standard deviation of all values, which
+            /// is the square root of `VARP`.
+            #[examples("STDEVP(A1:A6)", "STDEVP(1, 2, 3, 4)")]
+            fn STDEVP(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers, false)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sample variance of all values, which divides the sum
+            /// of squared deviations from the mean by one less than the number
+            /// of values.
+            ///
+            /// Returns an error if given fewer than two values.
+            #[examples("VAR(A1:A6)", "VAR(1, 2, 3, 4)")]
+            fn VAR(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers, true)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the population variance of all values, which divides the
+            /// sum of squared deviations from the mean by the number of values.
+            #[examples("VARP(A1:A6)", "VARP(1, 2, 3, 4)")]
+            fn VARP(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers, false)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th percentile of `numbers`, where `k` is between
+            /// `0` and `1` inclusive. If `k` falls between two values, the
+            /// result is interpolated linearly between them.
+            #[examples("PERCENTILE(A1:A6, 0.9)", "PERCENTILE({1, 2, 3, 4}, 0.25)")]
+            fn PERCENTILE(span: Span, numbers: (Spanned<Value>), k: (Spanned<f64>)) {
+                let numbers = numbers.into_iter::<f64>().without_spans();
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                if !(0.0..=1.0).contains(&k.inner) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(k.span));
+                }
+                percentile(span, numbers, k.inner)?
+            }
+        ),
+        formula_fn!(
+            /// Returns a quartile of `numbers`. `quart` must be an integer from
+            /// `0` to `4`:
+            ///
+            /// - `0` returns the minimum value
+            /// - `1` returns the 25th percentile
+            /// - `2` returns the median
+            /// - `3` returns the 75th percentile
+            /// - `4` returns the maximum value
+            ///
+            /// Quartiles are interpolated the same way as in `PERCENTILE`.
+            #[examples("QUARTILE(A1:A6, 1)", "QUARTILE({1, 2, 3, 4}, 3)")]
+            fn QUARTILE(span: Span, numbers: (Spanned<Value>), quart: (Spanned<i64>)) {
+                let numbers = numbers.into_iter::<f64>().without_spans();
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                if !(0..=4).contains(&quart.inner) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(quart.span));
+                }
+                percentile(span, numbers, quart.inner as f64 / 4.0)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rank of `number` among `numbers`, where `1` is the
+            /// largest value. Equal values have the same rank.
+            ///
+            /// If `ascending` is `TRUE`, then `1` is the smallest value
+            /// instead.
+            ///
+            /// Returns an error if `number` does not appear in `numbers`.
+            #[examples("RANK(A1, A1:A6)", "RANK(3, {1, 5, 3, 2}, TRUE)")]
+            fn RANK(number: (Spanned<f64>), numbers: (Spanned<Value>), ascending: (Option<bool>)) {
+                let numbers = numbers.into_iter::<f64>().without_spans();
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                if !numbers.contains(&number.inner) {
+                    return Err(RunErrorMsg::NoMatch.with_span(number.span));
+                }
+                let ranked_higher = if ascending.unwrap_or(false) {
+                    numbers.iter().filter(|&&n| n < number.inner).count()
+                } else {
+                    numbers.iter().filter(|&&n| n > number.inner).count()
+                };
+                (ranked_higher + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the [Pearson correlation
+            /// coefficient](https://en.wikipedia.org/wiki/Pearson_correlation_coefficient)
+            /// between two arrays of the same size.
+            ///
+            /// Pairs where either value is not a number are ignored.
+            #[examples("CORREL(A1:A10, B1:B10)")]
+            fn CORREL(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = numeric_pairs(&array1, &array2)?;
+                let covariance = covariance(span, &pairs)?;
+                let (xs, ys): (Vec<f64>, Vec<f64>) = pairs.into_iter().unzip();
+                let std_devs = variance(span, xs.into_iter().map(Ok), false)?.sqrt()
+                    * variance(span, ys.into_iter().map(Ok), false)?.sqrt();
+                util::checked_div(span, covariance, std_devs)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the population covariance between two arrays of the
+            /// same size.
+            ///
+            /// Pairs where either value is not a number are ignored.
+            #[examples("COVAR(A1:A10, B1:B10)")]
+            fn COVAR(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                covariance(span, &numeric_pairs(&array1, &array2)?)?
+            }
+        ),
     ]
 }
 
+/// Returns the variance of `numbers`. If `sample` is `true`, then the sum of
+/// squared deviations is divided by `n - 1` instead of `n`.
+fn variance(
+    span: Span,
+    numbers: impl IntoIterator<Item = CodeResult<f64>>,
+    sample: bool,
+) -> CodeResult<f64> {
+    let numbers = numbers.into_iter().collect::<CodeResult<Vec<f64>>>()?;
+    let mean = util::average(span, numbers.iter().copied().map(Ok))?;
+    let sum_of_squares: f64 = numbers.iter().map(|n| (n - mean).powi(2)).sum();
+    let count = numbers.len() as f64;
+    util::checked_div(
+        span,
+        sum_of_squares,
+        if sample { count - 1.0 } else { count },
+    )
+}
+
+/// Returns the population covariance of a list of pairs.
+fn covariance(span: Span, pairs: &[(f64, f64)]) -> CodeResult<f64> {
+    let mean_x = util::average(span, pairs.iter().map(|&(x, _)| Ok(x)))?;
+    let mean_y = util::average(span, pairs.iter().map(|&(_, y)| Ok(y)))?;
+    util::average(
+        span,
+        pairs.iter().map(|&(x, y)| Ok((x - mean_x) * (y - mean_y))),
+    )
+}
+
+/// Returns the `k`th percentile of `numbers`, interpolating linearly between
+/// values. `k` must be between `0` and `1` inclusive.
+fn percentile(span: Span, mut numbers: Vec<f64>, k: f64) -> CodeResult<f64> {
+    if numbers.is_empty() {
+        return Err(RunErrorMsg::EmptyArray.with_span(span));
+    }
+    numbers.sort_by(f64::total_cmp);
+    let index = k * (numbers.len() - 1) as f64;
+    let lower = numbers[index.floor() as usize];
+    let upper = numbers[index.ceil() as usize];
+    Ok(lower + (upper - lower) * index.fract())
+}
+
+/// Returns pairs of corresponding values from two arrays of the same size,
+/// skipping pairs where either value is not a number.
+fn numeric_pairs(array1: &Spanned<Array>, array2: &Spanned<Array>) -> CodeResult<Vec<(f64, f64)>> {
+    array2.check_array_size_exact(array1.inner.size())?;
+    let mut pairs = vec![];
+    for (x, y) in std::iter::zip(
+        array1.inner.cell_values_slice(),
+        array2.inner.cell_values_slice(),
+    ) {
+        let x = Spanned {
+            span: array1.span,
+            inner: x,
+        }
+        .coerce_or_none::<f64>();
+        let y = Spanned {
+            span: array2.span,
+            inner: y,
+        }
+        .coerce_or_none::<f64>();
+        if let (Some(x), Some(y)) = (x.transpose()?, y.transpose()?) {
+            pairs.push((x.inner, y.inner));
+        }
+    }
+    Ok(pairs)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{formulas::tests::*, Pos};
@@ -295,6 +646,86 @@ mod tests {
         assert_eq!("6", eval_to_string(&g, "COUNTIF(Bn5:B10, \"<=5\")"));
     }
 
+    #[test]
+    fn test_countifs() {
+        let g = Grid::new();
+        assert_eq!("6", eval_to_string(&g, "COUNTIFS(0..10, \"<=5\")"));
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "COUNTIFS(0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            "{3, 0}",
+            eval_to_string(&g, "COUNTIFS(0..10, \"<=5\", 0..10, {\">2\", \">5\"})"),
+        );
+        assert_eq!(
+            "3",
+            eval_to_string(
+                &g,
+                "COUNTIFS({\"a\", \"b\", \"a\"; \"a\", \"a\", \"b\"}, \"a\", {1, 2, 3; 4, 5, 6}, \">2\")",
+            ),
+        );
+
+        // Test that blank cells are ignored
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        for y in 0..=10 {
+            let _ = sheet.set_cell_value(Pos { x: 1, y }, y);
+        }
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "COUNTIFS(Bn5:B10, \"<=5\", Bn5:B10, \">=3\")"),
+        );
+
+        // Error on range size mismatch.
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "COUNTIFS(0..10, \"<=5\", {A1, A2}, \">2\")").msg,
+        );
+    }
+
+    #[test]
+    fn test_averageifs() {
+        let g = Grid::new();
+        assert_eq!(
+            "4",
+            eval_to_string(&g, "AVERAGEIFS(0..10, 0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "AVERAGEIFS(0..10, 0..10, \">20\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 10).unwrap(),
+                got: ArraySize::new(1, 11).unwrap(),
+            },
+            eval_to_err(&g, "AVERAGEIFS(1..10, 0..10, \"<=5\")").msg,
+        );
+    }
+
+    #[test]
+    fn test_minifs_maxifs() {
+        let g = Grid::new();
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "MINIFS(0..10, 0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "MAXIFS(0..10, 0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            "-8",
+            eval_to_string(&g, "MINIFS({-2, -8, 3}, {\"a\", \"b\", \"b\"}, \"b\")"),
+        );
+        assert_eq!("0", eval_to_string(&g, "MINIFS(0..10, 0..10, \">20\")"));
+        assert_eq!("0", eval_to_string(&g, "MAXIFS(0..10, 0..10, \">20\")"));
+    }
+
     #[test]
     fn test_countblank() {
         let g = Grid::new();
@@ -319,4 +750,107 @@ mod tests {
         let g = Grid::new();
         assert_eq!("3", eval_to_string(&g, "MAX(1, 3, 2)"));
     }
+
+    #[test]
+    fn test_median() {
+        let g = Grid::new();
+        assert_eq!("2", eval_to_string(&g, "MEDIAN(1, 3, 2)"));
+        assert_eq!("2.5", eval_to_string(&g, "MEDIAN(1, 3, 2, 10)"));
+        assert_eq!("3", eval_to_string(&g, "MEDIAN({5, \"a\", 1}, A1:A3)"));
+        assert_eq!(
+            RunErrorMsg::EmptyArray,
+            eval_to_err(&g, "MEDIAN(A1:A3)").msg
+        );
+    }
+
+    #[test]
+    fn test_mode() {
+        let g = Grid::new();
+        assert_eq!("2", eval_to_string(&g, "MODE(1, 2, 2, 3)"));
+        assert_eq!("3", eval_to_string(&g, "MODE(3, 1, 1, 3)"));
+        assert_eq!(RunErrorMsg::NoMatch, eval_to_err(&g, "MODE(1, 2, 3)").msg);
+    }
+
+    #[test]
+    fn test_variance_and_stdev() {
+        let g = Grid::new();
+        let data = "2, 4, 4, 4, 5, 5, 7, 9";
+        assert_eq!("4", eval_to_string(&g, &format!("VARP({data})")));
+        assert_eq!("2", eval_to_string(&g, &format!("STDEVP({data})")));
+        assert_eq!("2.5", eval_to_string(&g, "VAR(1..5)"));
+        assert_eq!("2", eval_to_string(&g, "STDEV({1, 3, 5})"));
+        assert_eq!("0", eval_to_string(&g, "VARP(5)"));
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "VAR(5)").msg);
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "STDEV(A1:A3)").msg
+        );
+    }
+
+    #[test]
+    fn test_percentile_and_quartile() {
+        let g = Grid::new();
+        assert_eq!("1.75", eval_to_string(&g, "PERCENTILE({1, 2, 3, 4}, 0.25)"));
+        assert_eq!("4", eval_to_string(&g, "PERCENTILE({4, 1, 3, 2}, 1)"));
+        assert_eq!("4.5", eval_to_string(&g, "PERCENTILE(1..5, 0.875)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "PERCENTILE(1..4, 1.5)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::EmptyArray,
+            eval_to_err(&g, "PERCENTILE(A1:A3, 0.5)").msg,
+        );
+
+        assert_eq!("1", eval_to_string(&g, "QUARTILE({1, 2, 3, 4}, 0)"));
+        assert_eq!("1.75", eval_to_string(&g, "QUARTILE({1, 2, 3, 4}, 1)"));
+        assert_eq!("2.5", eval_to_string(&g, "QUARTILE({1, 2, 3, 4}, 2)"));
+        assert_eq!("3.25", eval_to_string(&g, "QUARTILE({1, 2, 3, 4}, 3)"));
+        assert_eq!("4", eval_to_string(&g, "QUARTILE({1, 2, 3, 4}, 4)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "QUARTILE({1, 2, 3, 4}, 5)").msg,
+        );
+    }
+
+    #[test]
+    fn test_rank() {
+        let g = Grid::new();
+        assert_eq!("1", eval_to_string(&g, "RANK(5, {1, 5, 3, 3})"));
+        assert_eq!("2", eval_to_string(&g, "RANK(3, {1, 5, 3, 3})"));
+        assert_eq!("4", eval_to_string(&g, "RANK(1, {1, 5, 3, 3})"));
+        assert_eq!("1", eval_to_string(&g, "RANK(1, {1, 5, 3, 3}, TRUE)"));
+        assert_eq!("2", eval_to_string(&g, "RANK(3, {1, 5, 3, 3}, TRUE)"));
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "RANK(2, {1, 5, 3})").msg
+        );
+    }
+
+    #[test]
+    fn test_correl_and_covar() {
+        let g = Grid::new();
+        assert_eq!("1", eval_to_string(&g, "CORREL({1, 2, 3}, {2, 4, 6})"));
+        assert_eq!("-1", eval_to_string(&g, "CORREL({1, 2, 3}, {6, 4, 2})"));
+        assert_eq!(
+            "2.5",
+            eval_to_string(&g, "COVAR({1, 2, 3, 4}, {2, 4, 6, 8})"),
+        );
+        // Pairs with non-numeric values are ignored.
+        assert_eq!(
+            "1",
+            eval_to_string(&g, "CORREL({1, 2, \"a\", 3}, {2, 4, 100, 6})"),
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "CORREL({1, 1, 1}, {2, 4, 6})").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(3, 1).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "COVAR({1, 2, 3}, {2, 4})").msg,
+        );
+    }
 }
@@ -1,22 +1,293 @@
+use smallvec::SmallVec;
+
+use crate::ArraySize;
+
 use super::*;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
     include_in_completions: true,
     name: "String functions",
-    docs: "",
+    docs: "Positions and lengths in these functions count characters, not \
+           bytes, and positions start at `1`.\n\n",
     get_functions,
 };
 
 fn get_functions() -> Vec<FormulaFunction> {
-    vec![formula_fn!(
-        /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
-        /// values as strings.
-        #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
-        fn CONCAT(strings: (Iter<String>)) {
-            strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+    vec![
+        formula_fn!(
+            /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
+            /// values as strings.
+            #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
+            fn CONCAT(strings: (Iter<String>)) {
+                strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+            }
+        ),
+        formula_fn!(
+            /// Concatenates all values as strings, with `delimiter` inserted
+            /// between each one.
+            ///
+            /// If `ignore_empty` is `TRUE`, then blank cells and empty strings
+            /// are skipped.
+            #[examples(
+                "TEXTJOIN(\", \", TRUE, A1:A10)",
+                "TEXTJOIN(\"-\", FALSE, \"a\", B2, \"c\")"
+            )]
+            fn TEXTJOIN(delimiter: String, ignore_empty: bool, strings: (Iter<CellValue>)) {
+                let mut strings_to_join = vec![];
+                for s in strings {
+                    let s = String::try_from(s?)?;
+                    if !(ignore_empty && s.is_empty()) {
+                        strings_to_join.push(s);
+                    }
+                }
+                strings_to_join.join(&delimiter)
+            }
+        ),
+        formula_fn!(
+            /// Splits a string at each occurrence of `delimiter` and returns
+            /// the pieces as a row.
+            ///
+            /// If `split_by_each` is `TRUE` or omitted, then each character in
+            /// `delimiter` is treated as a separate delimiter. If
+            /// `remove_empty` is `TRUE` or omitted, then empty pieces are
+            /// removed from the result.
+            #[examples("SPLIT(\"a,b,c\", \",\")", "SPLIT(A1, \" ,\", TRUE, FALSE)")]
+            fn SPLIT(
+                s: String,
+                delimiter: (Spanned<String>),
+                split_by_each: (Option<bool>),
+                remove_empty: (Option<bool>),
+            ) {
+                if delimiter.inner.is_empty() {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(delimiter.span));
+                }
+                let delimiter = delimiter.inner;
+                let pieces: Vec<&str> = if split_by_each.unwrap_or(true) {
+                    s.split(|c| delimiter.contains(c)).collect()
+                } else {
+                    s.split(delimiter.as_str()).collect()
+                };
+                let values: SmallVec<[CellValue; 1]> = pieces
+                    .into_iter()
+                    .filter(|piece| !(remove_empty.unwrap_or(true) && piece.is_empty()))
+                    .map(CellValue::from)
+                    .collect();
+                if values.is_empty() {
+                    Array::from(CellValue::Blank)
+                } else {
+                    let size = ArraySize::new_or_err(values.len() as u32, 1)?;
+                    Array::new_row_major(size, values)?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of characters in a string.
+            #[examples("LEN(A1)", "LEN(\"abc\")")]
+            #[zip_map]
+            fn LEN([s]: String) {
+                s.chars().count() as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `char_count` characters of a string.
+            ///
+            /// If `char_count` is omitted, it is assumed to be `1`. If
+            /// `char_count` is greater than the length of the string, then the
+            /// whole string is returned.
+            #[examples("LEFT(\"Hello, world!\", 5)", "LEFT(A1)")]
+            #[zip_map]
+            fn LEFT([s]: String, [char_count]: (Option<Spanned<i64>>)) {
+                let char_count = char_count_arg(char_count)?;
+                s.chars().take(char_count).collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the last `char_count` characters of a string.
+            ///
+            /// If `char_count` is omitted, it is assumed to be `1`. If
+            /// `char_count` is greater than the length of the string, then the
+            /// whole string is returned.
+            #[examples("RIGHT(\"Hello, world!\", 6)", "RIGHT(A1)")]
+            #[zip_map]
+            fn RIGHT([s]: String, [char_count]: (Option<Spanned<i64>>)) {
+                let char_count = char_count_arg(char_count)?;
+                let len = s.chars().count();
+                s.chars()
+                    .skip(len.saturating_sub(char_count))
+                    .collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns `char_count` characters of a string, starting at
+            /// `start_char`.
+            ///
+            /// If `start_char` is past the end of the string, then returns an
+            /// empty string. Returns an error if `start_char` is less than `1`
+            /// or `char_count` is negative.
+            #[examples("MID(\"Hello, world!\", 8, 5)")]
+            #[zip_map]
+            fn MID([s]: String, [start_char]: (Spanned<i64>), [char_count]: (Spanned<i64>)) {
+                let start_index = start_char_arg(start_char)?;
+                let char_count = char_count_arg(Some(char_count))?;
+                s.chars()
+                    .skip(start_index)
+                    .take(char_count)
+                    .collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_text`
+            /// in `s`, starting at `start_char` (or `1` if omitted).
+            ///
+            /// This function is case-sensitive and does not support wildcards.
+            /// For a case-insensitive search with wildcards, use `SEARCH`.
+            /// Returns an error if no match is found.
+            #[examples("FIND(\"o\", \"Hello, world!\")", "FIND(\"o\", A1, 6)")]
+            #[zip_map]
+            fn FIND(
+                span: Span,
+                [search_text]: String,
+                [s]: String,
+                [start_char]: (Option<Spanned<i64>>),
+            ) {
+                let (start_index, start_byte) = start_char_byte_index(&s, start_char, *span)?;
+                let byte_index = s[start_byte..]
+                    .find(&search_text)
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                (start_index + s[start_byte..start_byte + byte_index].chars().count() + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_text`
+            /// in `s`, starting at `start_char` (or `1` if omitted).
+            ///
+            /// This function is case-insensitive and supports wildcards. For a
+            /// case-sensitive search, use `FIND`. Returns an error if no match
+            /// is found.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            #[examples("SEARCH(\"WORLD\", \"Hello, world!\")", "SEARCH(\"w?r\", A1)")]
+            #[zip_map]
+            fn SEARCH(
+                span: Span,
+                [search_text]: String,
+                [s]: String,
+                [start_char]: (Option<Spanned<i64>>),
+            ) {
+                let (start_index, start_byte) = start_char_byte_index(&s, start_char, *span)?;
+                let regex = crate::formulas::wildcard_pattern_to_unanchored_regex(&search_text)?;
+                let byte_index = regex
+                    .find(&s[start_byte..])
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?
+                    .start();
+                (start_index + s[start_byte..start_byte + byte_index].chars().count() + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Replaces occurrences of `old_text` in `s` with `new_text`.
+            ///
+            /// If `occurrence` is given, then only that occurrence (starting at
+            /// `1`) is replaced. Otherwise all occurrences are replaced. This
+            /// function is case-sensitive.
+            #[examples(
+                "SUBSTITUTE(\"banana\", \"a\", \"o\")",
+                "SUBSTITUTE(\"banana\", \"a\", \"o\", 2)"
+            )]
+            #[zip_map]
+            fn SUBSTITUTE(
+                [s]: String,
+                [old_text]: String,
+                [new_text]: String,
+                [occurrence]: (Option<Spanned<i64>>),
+            ) {
+                if old_text.is_empty() {
+                    return Ok(CellValue::from(s));
+                }
+                match occurrence {
+                    None => s.replace(&old_text, &new_text),
+                    Some(occurrence) => {
+                        if occurrence.inner < 1 {
+                            return Err(RunErrorMsg::InvalidArgument.with_span(occurrence.span));
+                        }
+                        match s
+                            .match_indices(&old_text)
+                            .nth(occurrence.inner as usize - 1)
+                        {
+                            Some((i, _)) => {
+                                let end = i + old_text.len();
+                                format!("{}{new_text}{}", &s[..i], &s[end..])
+                            }
+                            None => s,
+                        }
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Removes spaces from the beginning and end of a string, and
+            /// replaces each run of spaces inside the string with a single
+            /// space.
+            #[examples("TRIM(\"  Hello,    world!  \")")]
+            #[zip_map]
+            fn TRIM([s]: String) {
+                s.split(' ').filter(|word| !word.is_empty()).join(" ")
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to uppercase.
+            #[examples("UPPER(\"Hello, world!\")")]
+            #[zip_map]
+            fn UPPER([s]: String) {
+                s.to_uppercase()
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to lowercase.
+            #[examples("LOWER(\"Hello, world!\")")]
+            #[zip_map]
+            fn LOWER([s]: String) {
+                s.to_lowercase()
+            }
+        ),
+    ]
+}
+
+/// Validates an optional character count argument, which defaults to `1`.
+fn char_count_arg(char_count: Option<Spanned<i64>>) -> CodeResult<usize> {
+    match char_count {
+        None => Ok(1),
+        Some(Spanned { inner, span }) => {
+            usize::try_from(inner).map_err(|_| RunErrorMsg::InvalidArgument.with_span(span))
         }
-    )]
+    }
+}
+
+/// Validates a 1-based character position argument and returns the
+/// corresponding 0-based character index.
+fn start_char_arg(start_char: Spanned<i64>) -> CodeResult<usize> {
+    match start_char.inner.checked_sub(1).map(usize::try_from) {
+        Some(Ok(i)) => Ok(i),
+        _ => Err(RunErrorMsg::IndexOutOfBounds.with_span(start_char.span)),
+    }
+}
+
+/// Validates an optional 1-based character position argument for searching
+/// within `s` and returns the corresponding 0-based character index and byte
+/// index. The position may be one past the last character of the string.
+fn start_char_byte_index(
+    s: &str,
+    start_char: Option<Spanned<i64>>,
+    span: Span,
+) -> CodeResult<(usize, usize)> {
+    let span = start_char.map_or(span, |arg| arg.span);
+    let char_index = start_char.map(start_char_arg).transpose()?.unwrap_or(0);
+    let byte_index = s
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([s.len()])
+        .nth(char_index)
+        .ok_or_else(|| RunErrorMsg::IndexOutOfBounds.with_span(span))?;
+    Ok((char_index, byte_index))
 }
 
 #[cfg(test)]
@@ -31,4 +302,169 @@ mod tests {
             eval_to_string(&g, "'Hello, ' & 14000605 & ' worlds!'"),
         );
     }
+
+    #[test]
+    fn test_formula_textjoin() {
+        let g = Grid::new();
+        assert_eq!(
+            "a, b, c",
+            eval_to_string(&g, "TEXTJOIN(\", \", TRUE, \"a\", \"b\", \"c\")")
+        );
+        assert_eq!(
+            "a--c",
+            eval_to_string(&g, "TEXTJOIN(\"-\", FALSE, {\"a\", \"\", \"c\"})")
+        );
+        assert_eq!(
+            "a-c",
+            eval_to_string(&g, "TEXTJOIN(\"-\", TRUE, {\"a\", \"\", \"c\"})")
+        );
+        assert_eq!("1;2;3", eval_to_string(&g, "TEXTJOIN(\";\", TRUE, 1..3)"));
+    }
+
+    #[test]
+    fn test_formula_split() {
+        let g = Grid::new();
+        assert_eq!("{a, b, c}", eval_to_string(&g, "SPLIT(\"a,b,c\", \",\")"));
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "SPLIT(\"a, b,,c\", \", \")")
+        );
+        assert_eq!(
+            "{a, , b}",
+            eval_to_string(&g, "SPLIT(\"a,,b\", \",\", TRUE, FALSE)"),
+        );
+        assert_eq!(
+            "{a, b,c}",
+            eval_to_string(&g, "SPLIT(\"a::b,c\", \"::\", FALSE)"),
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "SPLIT(\"abc\", \"\")").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_len() {
+        let g = Grid::new();
+        assert_eq!("0", eval_to_string(&g, "LEN(\"\")"));
+        assert_eq!("13", eval_to_string(&g, "LEN(\"Hello, world!\")"));
+        assert_eq!("4", eval_to_string(&g, "LEN(\"café\")"));
+        assert_eq!(
+            "{1, 2, 3}",
+            eval_to_string(&g, "LEN({\"a\", \"bb\", \"ccc\"})")
+        );
+    }
+
+    #[test]
+    fn test_formula_left_right_mid() {
+        let g = Grid::new();
+        assert_eq!("H", eval_to_string(&g, "LEFT(\"Hello\")"));
+        assert_eq!("Hel", eval_to_string(&g, "LEFT(\"Hello\", 3)"));
+        assert_eq!("Hello", eval_to_string(&g, "LEFT(\"Hello\", 30)"));
+        assert_eq!("", eval_to_string(&g, "LEFT(\"Hello\", 0)"));
+        assert_eq!("o", eval_to_string(&g, "RIGHT(\"Hello\")"));
+        assert_eq!("llo", eval_to_string(&g, "RIGHT(\"Hello\", 3)"));
+        assert_eq!("Hello", eval_to_string(&g, "RIGHT(\"Hello\", 30)"));
+        assert_eq!("ell", eval_to_string(&g, "MID(\"Hello\", 2, 3)"));
+        assert_eq!("lo", eval_to_string(&g, "MID(\"Hello\", 4, 30)"));
+        assert_eq!("", eval_to_string(&g, "MID(\"Hello\", 30, 3)"));
+        assert_eq!(
+            "{H, He, Hel}",
+            eval_to_string(&g, "LEFT(\"Hello\", {1, 2, 3})")
+        );
+
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "LEFT(\"Hello\", -1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "RIGHT(\"Hello\", -1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::IndexOutOfBounds,
+            eval_to_err(&g, "MID(\"Hello\", 0, 1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "MID(\"Hello\", 1, -1)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_find_search() {
+        let g = Grid::new();
+        assert_eq!("5", eval_to_string(&g, "FIND(\"o\", \"Hello, world!\")"));
+        assert_eq!("9", eval_to_string(&g, "FIND(\"o\", \"Hello, world!\", 6)"));
+        assert_eq!("1", eval_to_string(&g, "FIND(\"\", \"Hello\")"));
+        assert_eq!("3", eval_to_string(&g, "FIND(\"fé\", \"cafés\")"));
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "FIND(\"W\", \"Hello, world!\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::IndexOutOfBounds,
+            eval_to_err(&g, "FIND(\"o\", \"Hello\", 0)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::IndexOutOfBounds,
+            eval_to_err(&g, "FIND(\"o\", \"Hello\", 7)").msg,
+        );
+
+        assert_eq!("8", eval_to_string(&g, "SEARCH(\"W\", \"Hello, world!\")"));
+        assert_eq!(
+            "8",
+            eval_to_string(&g, "SEARCH(\"w?r\", \"Hello, world!\")")
+        );
+        assert_eq!(
+            "2",
+            eval_to_string(&g, "SEARCH(\"e*o\", \"Hello, world!\")")
+        );
+        assert_eq!(
+            "9",
+            eval_to_string(&g, "SEARCH(\"o\", \"Hello, world!\", 6)")
+        );
+        assert_eq!("4", eval_to_string(&g, "SEARCH(\"~?\", \"abc?\")"));
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "SEARCH(\"xyz\", \"Hello, world!\")").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_substitute() {
+        let g = Grid::new();
+        assert_eq!(
+            "bonono",
+            eval_to_string(&g, "SUBSTITUTE(\"banana\", \"a\", \"o\")")
+        );
+        assert_eq!(
+            "banona",
+            eval_to_string(&g, "SUBSTITUTE(\"banana\", \"a\", \"o\", 2)"),
+        );
+        assert_eq!(
+            "banana",
+            eval_to_string(&g, "SUBSTITUTE(\"banana\", \"a\", \"o\", 4)"),
+        );
+        assert_eq!(
+            "banana",
+            eval_to_string(&g, "SUBSTITUTE(\"banana\", \"\", \"o\")")
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "SUBSTITUTE(\"banana\", \"a\", \"o\", 0)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_trim_upper_lower() {
+        let g = Grid::new();
+        assert_eq!(
+            "Hello, world!",
+            eval_to_string(&g, "TRIM(\"  Hello,    world!  \")"),
+        );
+        assert_eq!("HELLO", eval_to_string(&g, "UPPER(\"Hello\")"));
+        assert_eq!("hello", eval_to_string(&g, "LOWER(\"Hello\")"));
+        assert_eq!("{A, B}", eval_to_string(&g, "UPPER({\"a\", \"b\"})"));
+    }
 }
@@ -4,7 +4,9 @@ pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
     include_in_completions: true,
     name: "Trigonometric functions",
-    docs: "",
+    docs: "These functions return an error if the input is outside the domain \
+           of the function, such as `ASIN(2)`, or if the result is infinite, \
+           such as `COT(0)`.\n\n",
     get_functions,
 };
 
@@ -26,9 +28,9 @@ fn get_functions() -> Vec<FormulaFunction> {
                         )]
                         #[examples(concat!(stringify!($func_name), "(PI() * 2/3)"))]
                         #[zip_map]
-                        fn $func_name([radians]: f64) {
+                        fn $func_name(span: Span, [radians]: f64) {
                             const F: fn(f64) -> f64 = $f;
-                            F(radians)
+                            check_trig_result(*span, radians, F(radians))?
                         }
                     ),
                     formula_fn!(
@@ -39,9 +41,9 @@ fn get_functions() -> Vec<FormulaFunction> {
                         )]
                         #[examples(concat!(stringify!($inv_func_name), "(A1)"))]
                         #[zip_map]
-                        fn $inv_func_name([number]: f64) {
+                        fn $inv_func_name(span: Span, [number]: f64) {
                             const F: fn(f64) -> f64 = $inv_f;
-                            F(number)
+                            check_trig_result(*span, number, F(number))?
                         }
                     ),
                 )+
@@ -116,6 +118,22 @@ fn get_functions() -> Vec<FormulaFunction> {
     all_trig_functions
 }
 
+/// Returns an error if the result of a trigonometric function is not finite.
+///
+/// A NaN result for a finite input means the input is outside the domain of the
+/// function, and an infinite result means the input is at a pole.
+fn check_trig_result(span: Span, input: f64, result: f64) -> CodeResult<f64> {
+    if result.is_finite() {
+        Ok(result)
+    } else if !input.is_finite() {
+        Err(RunErrorMsg::NotANumber.with_span(span))
+    } else if result.is_nan() {
+        Err(RunErrorMsg::InvalidArgument.with_span(span))
+    } else {
+        Err(RunErrorMsg::DivideByZero.with_span(span))
+    }
+}
+
 /// Inverse cotangent function with the correct range.
 ///
 /// If we just use `.recip().atan()`, then the range is discontinuous and we
@@ -490,4 +508,32 @@ mod tests {
             eval_to_err(&g, "ATAN2(0, 0)").msg,
         );
     }
+
+    #[test]
+    fn test_trig_domain_errors() {
+        let g = Grid::new();
+
+        for formula in [
+            "ASIN(2)",
+            "ACOS(-1.5)",
+            "ACOSH(0.5)",
+            "ASECH(2)",
+            "ATANH(2)",
+        ] {
+            assert_eq!(
+                RunErrorMsg::InvalidArgument,
+                eval_to_err(&g, formula).msg,
+                "{formula}",
+            );
+        }
+        for formula in [
+            "COT(0)", "CSC(0)", "COTH(0)", "CSCH(0)", "ATANH(1)", "ACOTH(1)",
+        ] {
+            assert_eq!(
+                RunErrorMsg::DivideByZero,
+                eval_to_err(&g, formula).msg,
+                "{formula}",
+            );
+        }
+    }
 }
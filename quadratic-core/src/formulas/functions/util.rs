@@ -21,3 +21,45 @@ pub fn average(
     }
     util::checked_div(span, sum, count as f64)
 }
+
+/// Splits repeating arguments into `(eval_range, criteria)` pairs for
+/// functions such as `SUMIFS`, then zip-maps `f` over all the criteria.
+///
+/// `f` is given the eval ranges along with the criteria for each output value.
+pub fn zip_map_eval_ranges_and_criteria(
+    ctx: &mut Ctx<'_>,
+    func_name: &'static str,
+    span: Span,
+    eval_ranges_and_criteria: impl Iterator<Item = CodeResult<Spanned<Value>>>,
+    f: impl Fn(&[Spanned<Array>], &[Criterion]) -> CodeResult<CellValue>,
+) -> CodeResult<Value> {
+    let mut eval_ranges = vec![];
+    let mut criteria_values = vec![];
+    let mut args = eval_ranges_and_criteria;
+    while let Some(eval_range) = args.next() {
+        let criteria = args.next().ok_or_else(|| {
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: func_name.into(),
+                arg_name: "criteria".into(),
+            }
+            .with_span(span)
+        })?;
+        eval_ranges.push(eval_range?.map(Array::from));
+        criteria_values.push(criteria?);
+    }
+    if eval_ranges.is_empty() {
+        return Err(RunErrorMsg::MissingRequiredArgument {
+            func_name: func_name.into(),
+            arg_name: "eval_range".into(),
+        }
+        .with_span(span));
+    }
+
+    ctx.zip_map(&criteria_values, |_ctx, criteria| {
+        let criteria = criteria
+            .iter()
+            .map(|&c| Criterion::try_from(c))
+            .collect::<CodeResult<Vec<_>>>()?;
+        f(&eval_ranges, &criteria)
+    })
+}
@@ -0,0 +1,58 @@
+//! Names defined using `LET` and anonymous functions defined using `LAMBDA`.
+
+use std::rc::Rc;
+
+use super::*;
+use crate::{CodeResult, RunErrorMsg, Value};
+
+/// Maximum number of `LAMBDA` calls that may be nested, such as when a
+/// function calls itself. Each call evaluates its body recursively, so this
+/// keeps recursive functions from overflowing the stack.
+pub const MAX_LAMBDA_DEPTH: usize = 32;
+
+/// Value that a name is bound to, either by `LET` or by calling a `LAMBDA`.
+#[derive(Debug, Clone)]
+pub enum Binding {
+    Value(Value),
+    Lambda(Rc<Lambda>),
+}
+
+/// Anonymous function constructed using `LAMBDA`.
+#[derive(Debug)]
+pub struct Lambda {
+    /// Names of the parameters.
+    pub params: Vec<String>,
+    /// Expression to evaluate when the function is called.
+    pub body: AstNode,
+    /// Names that were in scope where the function was constructed.
+    pub captured: Vec<(String, Binding)>,
+    /// Whether the formula containing the function is only being parsed and
+    /// checked, in which case the body is evaluated the same way.
+    pub only_parse: bool,
+}
+impl Lambda {
+    /// Calls the function with `args`, returning an error if the number of
+    /// arguments does not match the number of parameters or if too many
+    /// calls are nested.
+    pub fn call(&self, ctx: &mut Ctx<'_>, mut args: FormulaFnArgs) -> CodeResult {
+        if ctx.lambda_depth >= MAX_LAMBDA_DEPTH {
+            return Err(RunErrorMsg::LambdaTooDeep.with_span(args.span));
+        }
+        let mut bindings = Vec::with_capacity(self.params.len());
+        for param in &self.params {
+            bindings.push((
+                param.clone(),
+                args.take_next_required_binding(param.clone())?,
+            ));
+        }
+        args.error_if_more_args()?;
+
+        let outer_scope = std::mem::replace(&mut ctx.variables, self.captured.clone());
+        ctx.variables.extend(bindings);
+        ctx.lambda_depth += 1;
+        let result = self.body.eval(ctx, self.only_parse);
+        ctx.lambda_depth -= 1;
+        ctx.variables = outer_scope;
+        result
+    }
+}
@@ -25,19 +25,32 @@ fn new_fullmatch_regex(s: &str) -> Regex {
 }
 
 /// Function call consisting of a letter or underscore followed by any letters,
-/// digits, and/or underscores terminated with a `(`.
-const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*\(";
+/// digits, underscores, and/or periods terminated with a `(`.
+const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d\.]*\(";
 
 /// A1-style cell reference.
 ///
-/// \$?n?[A-Z]+\$?n?\d+
-/// \$?        \$?            optional `$`s
-///    n?         n?          optional `n`s
-///      [A-Z]+               letters
-///                 \d+       digits
-const A1_CELL_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+";
+/// \$?n?[A-Z]+\$?n?\d+\b
+/// \$?        \$?              optional `$`s
+///    n?         n?            optional `n`s
+///      [A-Z]+                 letters
+///                 \d+         digits
+///                    \b       not followed by more letters or digits
+const A1_CELL_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+\b";
 const INTERNAL_CELL_REFERENCE_PATTERN: &str = r"R([\[|\{]-?\d+[\]|\}])C([\[|\{]-?\d+[\]|\}])";
 
+/// Reference to whole columns, such as `A:C`, in A1-style or internal
+/// notation. Column letters must be uppercase.
+const COL_RANGE_REFERENCE_PATTERN: &str =
+    r"(?-i:\$?n?[A-Z]+:\$?n?[A-Z]+\b|C[\[\{]-?\d+[\]\}]:C[\[\{]-?\d+[\]\}])";
+/// Reference to whole rows, such as `2:10`, in A1-style or internal notation.
+const ROW_RANGE_REFERENCE_PATTERN: &str =
+    r"(?-i:\$?n?\d+:\$?n?\d+\b|R[\[\{]-?\d+[\]\}]:R[\[\{]-?\d+[\]\}])";
+
+/// Name defined using `LET` or `LAMBDA`, consisting of a letter or underscore
+/// followed by any letters, digits, and/or underscores.
+const IDENTIFIER_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*";
+
 /// Floating-point or integer number, without leading sign.
 ///
 /// (\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?
@@ -61,6 +74,8 @@ const DOUBLE_QUOTE_STRING_LITERAL_PATTERN: &str = r#""([^"\\]|\\[\s\S])*""#;
 /// as `'Sheet1'!` is parsed as a string followed by a sheet reference operator
 /// `!`.
 const UNQUOTED_SHEET_REFERENCE_PATTERN: &str = r"[A-Za-z_][A-Za-z0-9_\.]*\s*!";
+/// Reference to a cell that has been deleted.
+const REF_ERROR_PATTERN: &str = r"#REF!";
 /// Unterminated string literal.
 const UNTERMINATED_STRING_LITERAL_PATTERN: &str = r#"["']"#;
 
@@ -74,22 +89,29 @@ const TOKEN_PATTERNS: &[&str] = &[
     r"//[^\n]*",
     // Start of a block comment (block comment has special handling).
     r"/\*",
+    // Deleted cell reference.
+    REF_ERROR_PATTERN,
     // Sheet reference.
     UNQUOTED_SHEET_REFERENCE_PATTERN,
     // String literal.
     SINGLE_QUOTE_STRING_LITERAL_PATTERN,
     DOUBLE_QUOTE_STRING_LITERAL_PATTERN,
     UNTERMINATED_STRING_LITERAL_PATTERN,
+    // Reference to whole columns or rows.
+    COL_RANGE_REFERENCE_PATTERN,
+    ROW_RANGE_REFERENCE_PATTERN,
     // Numeric literal.
     NUMERIC_LITERAL_PATTERN,
     // Function call.
     FUNCTION_CALL_PATTERN,
     // Boolean literal (case-insensitive).
-    r#"false|true"#,
+    r#"(false|true)\b"#,
     // Reference to a cell.
     A1_CELL_REFERENCE_PATTERN,
     // Internal cell reference.
     INTERNAL_CELL_REFERENCE_PATTERN,
+    // Name.
+    IDENTIFIER_PATTERN,
     // Whitespace.
     r"\s+",
     // Any other single Unicode character.
@@ -114,6 +136,18 @@ lazy_static! {
     pub static ref A1_CELL_REFERENCE_REGEX: Regex =
         new_fullmatch_regex(A1_CELL_REFERENCE_PATTERN);
 
+    /// Regex that matches a valid whole-column reference.
+    pub static ref COL_RANGE_REFERENCE_REGEX: Regex =
+        new_fullmatch_regex(COL_RANGE_REFERENCE_PATTERN);
+
+    /// Regex that matches a valid whole-row reference.
+    pub static ref ROW_RANGE_REFERENCE_REGEX: Regex =
+        new_fullmatch_regex(ROW_RANGE_REFERENCE_PATTERN);
+
+    /// Regex that matches a valid name.
+    pub static ref IDENTIFIER_REGEX: Regex =
+        new_fullmatch_regex(IDENTIFIER_PATTERN);
+
     /// Regex that matches a valid internal cell reference.
     pub static ref INTERNAL_CELL_REFERENCE_REGEX: Regex =
         new_fullmatch_regex(INTERNAL_CELL_REFERENCE_PATTERN);
@@ -223,6 +257,14 @@ pub enum Token {
     CellRef,
     #[strum(to_string = "internal cell reference")]
     InternalCellRef,
+    #[strum(to_string = "column range reference")]
+    ColRangeRef,
+    #[strum(to_string = "row range reference")]
+    RowRangeRef,
+    #[strum(to_string = "deleted cell reference")]
+    RefError,
+    #[strum(to_string = "name")]
+    Identifier,
     #[strum(to_string = "whitespace")]
     Whitespace,
     #[strum(to_string = "unknown symbol")]
@@ -263,6 +305,7 @@ impl Token {
             ":" => Self::CellRangeOp,
             "!" => Self::SheetRefOp,
             "..." => Self::Ellipsis,
+            s if s.eq_ignore_ascii_case("#REF!") => Self::RefError,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
 
@@ -302,9 +345,18 @@ impl Token {
             s if UNTERMINATED_STRING_LITERAL_REGEX.is_match(s) => Self::UnterminatedStringLiteral,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
+            s if COL_RANGE_REFERENCE_REGEX.is_match(s) => Self::ColRangeRef,
+            s if ROW_RANGE_REFERENCE_REGEX.is_match(s) => Self::RowRangeRef,
             s if NUMERIC_LITERAL_REGEX.is_match(s) => Self::NumericLiteral,
             s if A1_CELL_REFERENCE_REGEX.is_match(s) => Self::CellRef,
             s if INTERNAL_CELL_REFERENCE_REGEX.is_match(s) => Self::InternalCellRef,
+            // Names that look like cell references are not allowed, even in
+            // lowercase.
+            s if IDENTIFIER_REGEX.is_match(s)
+                && !A1_CELL_REFERENCE_REGEX.is_match(&s.to_ascii_uppercase()) =>
+            {
+                Self::Identifier
+            }
             s if s.trim().is_empty() => Self::Whitespace,
 
             // Give up.
@@ -352,6 +404,51 @@ mod tests {
         test_block_comment(false, "/* /*");
         test_block_comment(false, "/*/");
     }
+    #[test]
+    fn test_lex_identifier() {
+        let kinds = |s| tokenize(s).map(|t| t.inner).collect_vec();
+
+        assert_eq!(vec![Token::Identifier], kinds("total"));
+        assert_eq!(vec![Token::Identifier], kinds("_x1"));
+        assert_eq!(vec![Token::Identifier], kinds("A1B"));
+        assert_eq!(vec![Token::Identifier], kinds("a1_plus"));
+        assert_eq!(vec![Token::Identifier], kinds("truth"));
+        assert_eq!(vec![Token::CellRef], kinds("A1"));
+        assert_eq!(vec![Token::CellRef], kinds("$A$1"));
+        assert_eq!(vec![Token::Unknown], kinds("a1"));
+        assert_eq!(vec![Token::True], kinds("TRUE"));
+        assert_eq!(
+            vec![Token::CellRef, Token::CellRangeOp, Token::CellRef],
+            kinds("A1:B2"),
+        );
+    }
+
+    #[test]
+    fn test_lex_line_range() {
+        let kinds = |s| tokenize(s).map(|t| t.inner).collect_vec();
+
+        assert_eq!(vec![Token::ColRangeRef], kinds("A:C"));
+        assert_eq!(vec![Token::ColRangeRef], kinds("$nB:AA"));
+        assert_eq!(vec![Token::ColRangeRef], kinds("C[0]:C{2}"));
+        assert_eq!(vec![Token::RowRangeRef], kinds("2:10"));
+        assert_eq!(vec![Token::RowRangeRef], kinds("$n2:$3"));
+        assert_eq!(vec![Token::RowRangeRef], kinds("R[-1]:R[1]"));
+        assert_eq!(
+            vec![Token::UnquotedSheetReference, Token::ColRangeRef],
+            kinds("Sheet1!A:A"),
+        );
+
+        // lowercase names are not columns
+        assert_eq!(
+            vec![Token::Identifier, Token::CellRangeOp, Token::Identifier],
+            kinds("a:b"),
+        );
+        assert_eq!(
+            vec![Token::CellRef, Token::CellRangeOp, Token::CellRef],
+            kinds("A1:B2"),
+        );
+    }
+
     fn test_block_comment(expected_to_end: bool, s: &str) {
         let tokens = tokenize(s).collect_vec();
         if expected_to_end {
@@ -1,5 +1,7 @@
 //! Language server implementation for Monaco editor
 
+use std::borrow::Cow;
+
 use lazy_static::lazy_static;
 use serde::Serialize;
 
@@ -8,11 +10,15 @@ pub mod types;
 pub use types::*;
 
 use super::functions;
+use crate::grid::{
+    defined_names::{DefinedName, NameValue},
+    Grid,
+};
 
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionList<'a> {
-    suggestions: &'a [CompletionItem],
+    suggestions: Cow<'a, [CompletionItem]>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -41,7 +47,50 @@ lazy_static! {
 
 pub fn provide_completion_items() -> CompletionList<'static> {
     CompletionList {
-        suggestions: &FUNCTION_COMPLETION_ITEMS,
+        suggestions: Cow::Borrowed(&FUNCTION_COMPLETION_ITEMS),
+    }
+}
+
+/// Returns the completions for functions and for the names defined in
+/// `grid`.
+pub fn provide_completion_items_for_grid(grid: &Grid) -> CompletionList<'static> {
+    let names = grid
+        .defined_names()
+        .iter()
+        .map(|defined_name| CompletionItem {
+            detail: Some(defined_name_detail(grid, defined_name)),
+            documentation: None,
+            insert_text: Some(defined_name.name.clone()),
+            insert_text_rules: None,
+            kind: CompletionItemKind::Variable,
+            label: defined_name.name.clone(),
+        });
+    CompletionList {
+        suggestions: Cow::Owned(
+            FUNCTION_COMPLETION_ITEMS
+                .iter()
+                .cloned()
+                .chain(names)
+                .collect(),
+        ),
+    }
+}
+
+/// Describes what a defined name refers to, such as `Sheet 1!A1:B5`.
+fn defined_name_detail(grid: &Grid, defined_name: &DefinedName) -> String {
+    match &defined_name.value {
+        NameValue::Range(sheet_rect) => {
+            let sheet_name = grid
+                .try_sheet(sheet_rect.sheet_id)
+                .map_or("#REF", |sheet| sheet.name.as_str());
+            format!(
+                "{sheet_name}!{}:{}",
+                sheet_rect.min.a1_string(),
+                sheet_rect.max.a1_string()
+            )
+        }
+        NameValue::Constant(value) => value.to_string(),
+        NameValue::Formula(formula) => format!("={formula}"),
     }
 }
 
@@ -53,3 +102,33 @@ pub fn provide_hover(partial_function_name: &str) -> Option<Hover> {
         }],
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CellValue, SheetRect};
+
+    #[test]
+    fn test_completion_items_for_grid() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        grid.set_defined_name(
+            "Sales",
+            Some(NameValue::Range(SheetRect::from_numbers(
+                0, 1, 2, 3, sheet_id,
+            ))),
+        );
+        grid.set_defined_name(
+            "rate",
+            Some(NameValue::Constant(CellValue::Number(7.into()))),
+        );
+
+        let items = provide_completion_items_for_grid(&grid).suggestions;
+        assert_eq!(items.len(), FUNCTION_COMPLETION_ITEMS.len() + 2);
+        let sales = items.iter().find(|item| item.label == "Sales").unwrap();
+        assert_eq!(sales.kind, CompletionItemKind::Variable);
+        assert_eq!(sales.detail.as_deref(), Some("Sheet 1!A1:B3"));
+        let rate = items.iter().find(|item| item.label == "rate").unwrap();
+        assert_eq!(rate.detail.as_deref(), Some("7"));
+    }
+}
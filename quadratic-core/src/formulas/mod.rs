@@ -8,6 +8,7 @@ mod criteria;
 mod ctx;
 #[allow(clippy::vec_init_then_push)]
 pub mod functions;
+mod lambda;
 mod lexer;
 pub mod lsp;
 mod params;
@@ -20,12 +21,14 @@ pub use cell_ref::*;
 pub use criteria::Criterion;
 pub use ctx::Ctx;
 use functions::FormulaFnArgs;
+use lambda::{Binding, Lambda};
 use params::{Param, ParamKind};
 pub use parser::{
-    find_cell_references, parse_and_check_formula, parse_formula, replace_a1_notation,
-    replace_internal_cell_references,
+    adjust_a1_references, adjust_cell_references, find_cell_references, find_names,
+    from_excel_formula, is_valid_name, parse_and_check_formula, parse_formula, replace_a1_notation,
+    replace_internal_cell_references, to_excel_formula,
 };
-use wildcards::wildcard_pattern_to_regex;
+use wildcards::{wildcard_pattern_to_regex, wildcard_pattern_to_unanchored_regex};
 
 /// Escapes a formula string.
 pub fn escape_string(s: &str) -> String {
@@ -12,7 +12,7 @@ use lexer::Token;
 use rules::SyntaxRule;
 
 use super::*;
-use crate::{grid::Grid, CodeResult, Pos, RunError, RunErrorMsg, Span, Spanned};
+use crate::{grid::Grid, Axis, CodeResult, Pos, RunError, RunErrorMsg, Span, Spanned};
 
 pub fn parse_formula(source: &str, pos: Pos) -> CodeResult<ast::Formula> {
     Ok(Formula {
@@ -45,6 +45,23 @@ pub fn find_cell_references(source: &str, pos: Pos) -> Vec<Spanned<RangeRef>> {
     ret
 }
 
+/// Returns the names used in a formula. These may be defined by `LET` or
+/// `LAMBDA` or be defined names for the whole file.
+pub fn find_names(source: &str) -> Vec<String> {
+    lexer::tokenize(source)
+        .filter(|t| t.inner == Token::Identifier)
+        .map(|t| t.span.of_str(source).to_string())
+        .collect()
+}
+
+/// Returns whether `name` can be used as a defined name. Names must start
+/// with a letter or underscore, contain only letters, digits and
+/// underscores, and must not look like a cell reference or boolean.
+pub fn is_valid_name(name: &str) -> bool {
+    let tokens = lexer::tokenize(name).collect_vec();
+    matches!(tokens.as_slice(), [token] if token.inner == Token::Identifier)
+}
+
 /// Parses and checks whether the formula has the correct arguments (which has
 /// to run eval with `only_parse = true`).
 pub fn parse_and_check_formula(formula_string: &str, x: i64, y: i64) -> bool {
@@ -93,6 +110,293 @@ pub fn replace_a1_notation(source: &str, pos: Pos) -> String {
     replace_cell_references(source, pos, &replace_fn)
 }
 
+/// Functions that Excel files must name with the `_xlfn.` prefix because they
+/// were added to Excel after the file format was defined.
+const EXCEL_NEWER_FUNCTIONS: &[&str] = &[
+    "CONCAT", "FILTER", "IFNA", "MAXIFS", "MINIFS", "SEQUENCE", "SORT", "SORTBY", "TEXTJOIN",
+    "UNIQUE", "XLOOKUP", "XMATCH", "XOR",
+];
+/// Functions that Excel does not have.
+const NON_EXCEL_FUNCTIONS: &[&str] = &["SPLIT", "TAU"];
+
+/// Returns the Excel name and offset for a sheet. See [`to_excel_formula()`].
+pub type ExcelSheetFn<'a> = dyn 'a + Fn(Option<&str>) -> Option<(Option<String>, Pos)>;
+
+/// Translates a formula to the syntax used in Excel files (without a leading
+/// `=`), or returns `None` if it uses anything that Excel does not support.
+///
+/// `excel_sheet` is called with the sheet name of each cell reference (`None`
+/// for the sheet containing the formula). It returns the name to use for the
+/// sheet in Excel (`None` to leave it out) and how far to move the cells on
+/// that sheet, or `None` if the sheet is not in the Excel file.
+pub fn to_excel_formula(source: &str, pos: Pos, excel_sheet: &ExcelSheetFn<'_>) -> Option<String> {
+    parse_formula(source, pos).ok()?;
+
+    let mut cell_refs = find_cell_references(source, pos).into_iter().peekable();
+    let mut tokens = lexer::tokenize(source)
+        .skip_while(|t| t.inner.is_skip())
+        .peekable();
+    tokens.next_if(|t| t.inner == Token::Eql);
+
+    let mut ret = String::new();
+    while let Some(token) = tokens.next() {
+        if let Some(cell_ref) = cell_refs.next_if(|r| r.span.start == token.span.start) {
+            let (sheet, offset) = excel_sheet(cell_ref.inner.sheet_name())?;
+            ret.push_str(&cell_ref.inner.excel_string(pos, offset, sheet.as_deref())?);
+            while tokens
+                .next_if(|t| t.span.end <= cell_ref.span.end)
+                .is_some()
+            {}
+            continue;
+        }
+
+        let token_str = token.span.of_str(source);
+        match token.inner {
+            Token::Eql => ret.push('='),
+            Token::Neq => ret.push_str("<>"),
+            Token::StringLiteral => {
+                let s = super::parse_string_literal(token_str)?;
+                ret.push_str(&format!("\"{}\"", s.replace('"', "\"\"")));
+            }
+            Token::FunctionCall => {
+                let name = token_str.strip_suffix('(')?.to_ascii_uppercase();
+                functions::lookup_function(&name)?;
+                if NON_EXCEL_FUNCTIONS.contains(&name.as_str()) {
+                    return None;
+                }
+                if EXCEL_NEWER_FUNCTIONS.contains(&name.as_str()) {
+                    ret.push_str("_xlfn.");
+                }
+                ret.push_str(&name);
+                ret.push('(');
+            }
+
+            // names from `LET` and `LAMBDA` are written differently in Excel
+            // files, and defined names are not exported
+            Token::Identifier => return None,
+
+            Token::RangeOp
+            | Token::Ellipsis
+            | Token::Comment
+            | Token::UnterminatedBlockComment
+            | Token::UnterminatedStringLiteral
+            | Token::Unknown
+            | Token::UnquotedSheetReference
+            | Token::SheetRefOp
+            | Token::CellRef
+            | Token::InternalCellRef
+            | Token::ColRangeRef
+            | Token::RowRangeRef => return None,
+
+            _ => ret.push_str(token_str),
+        }
+    }
+    Some(ret)
+}
+
+/// Translates a formula from the syntax used in Excel files (without a
+/// leading `=`), or returns `None` if it uses anything that is not supported.
+///
+/// `excel_pos` is the cell the formula was written for in Excel, with columns
+/// counted from 0 and rows counted from 1. The cells are moved by `offset`, so
+/// the formula returned is for the cell at `excel_pos + offset`.
+pub fn from_excel_formula(source: &str, excel_pos: Pos, offset: Pos) -> Option<String> {
+    let source = unescape_excel_quotes(source)?;
+    let pos = Pos {
+        x: excel_pos.x + offset.x,
+        y: excel_pos.y + offset.y,
+    };
+
+    let mut cell_refs = find_cell_references(&source, excel_pos)
+        .into_iter()
+        .peekable();
+    let mut tokens = lexer::tokenize(&source).peekable();
+
+    let mut ret = String::new();
+    while let Some(token) = tokens.next() {
+        if let Some(cell_ref) = cell_refs.next_if(|r| r.span.start == token.span.start) {
+            ret.push_str(&cell_ref.inner.moved_by(offset).a1_string(pos));
+            while tokens
+                .next_if(|t| t.span.end <= cell_ref.span.end)
+                .is_some()
+            {}
+            continue;
+        }
+
+        let token_str = token.span.of_str(&source);
+        match token.inner {
+            Token::FunctionCall => {
+                let mut name = token_str.strip_suffix('(')?.to_ascii_uppercase();
+                for prefix in ["_XLFN.", "_XLWS."] {
+                    if let Some(rest) = name.strip_prefix(prefix) {
+                        name = rest.to_string();
+                    }
+                }
+                functions::lookup_function(&name)?;
+                ret.push_str(&name);
+                ret.push('(');
+            }
+
+            // defined names are not imported
+            Token::Identifier
+            | Token::Unknown
+            | Token::UnterminatedStringLiteral
+            | Token::InternalCellRef => return None,
+
+            _ => ret.push_str(token_str),
+        }
+    }
+
+    parse_formula(&ret, pos).ok()?;
+    Some(ret)
+}
+
+/// Rewrites the string literals and quoted sheet names in an Excel formula,
+/// which escape a quote by doubling it, to escape with backslashes instead.
+/// Returns `None` if a quote is not closed.
+fn unescape_excel_quotes(source: &str) -> Option<String> {
+    let mut ret = String::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        ret.push(c);
+        if c != '"' && c != '\'' {
+            continue;
+        }
+        let quote = c;
+        loop {
+            match chars.next()? {
+                c if c == quote && chars.next_if_eq(&quote).is_some() => {
+                    ret.push('\\');
+                    ret.push(quote);
+                }
+                c if c == quote => {
+                    ret.push(quote);
+                    break;
+                }
+                '\\' => ret.push_str("\\\\"),
+                c => ret.push(c),
+            }
+        }
+    }
+    Some(ret)
+}
+
+/// Rewrites the cell references in a formula after columns or rows are
+/// inserted or deleted, so that they keep pointing at the same cells.
+///
+/// `old_pos` and `new_pos` are the position of the formula before and after
+/// the change. `is_affected_sheet` is called with the sheet name of each
+/// reference (`None` for the sheet containing the formula) and returns whether
+/// that sheet is the one being changed. References to deleted cells are
+/// replaced with `#REF!`.
+pub fn adjust_cell_references(
+    source: &str,
+    old_pos: Pos,
+    new_pos: Pos,
+    adjust: RefAdjust,
+    is_affected_sheet: &dyn Fn(Option<&str>) -> bool,
+) -> String {
+    let replace_fn = |range_ref: RangeRef| {
+        let adjusted = match range_ref {
+            RangeRef::Cell { pos } => {
+                let adjust = is_affected_sheet(pos.sheet.as_deref()).then_some(adjust);
+                adjust_cell_ref_range(&pos, &pos, old_pos, new_pos, adjust)
+                    .map(|(pos, _)| RangeRef::Cell { pos })
+            }
+            RangeRef::CellRange { start, end } => {
+                let adjust = is_affected_sheet(start.sheet.as_deref()).then_some(adjust);
+                adjust_cell_ref_range(&start, &end, old_pos, new_pos, adjust)
+                    .map(|(start, end)| RangeRef::CellRange { start, end })
+            }
+            RangeRef::ColRange { start, end, sheet } => {
+                let adjust = is_affected_sheet(sheet.as_deref()).then_some(adjust);
+                adjust_coord_range(Axis::X, start, end, old_pos, new_pos, adjust)
+                    .map(|(start, end)| RangeRef::ColRange { start, end, sheet })
+            }
+            RangeRef::RowRange { start, end, sheet } => {
+                let adjust = is_affected_sheet(sheet.as_deref()).then_some(adjust);
+                adjust_coord_range(Axis::Y, start, end, old_pos, new_pos, adjust)
+                    .map(|(start, end)| RangeRef::RowRange { start, end, sheet })
+            }
+        };
+        match adjusted {
+            Some(range_ref) => range_ref.to_string(),
+            None => "#REF!".to_string(),
+        }
+    };
+    replace_cell_references(source, old_pos, &replace_fn)
+}
+
+/// Adjusts the references of a formula written in A1 notation, like
+/// [`adjust_cell_references()`]. Relative references are written against
+/// `old_pos` and are rewritten against `new_pos`.
+pub fn adjust_a1_references(
+    source: &str,
+    old_pos: Pos,
+    new_pos: Pos,
+    adjust: RefAdjust,
+    is_affected_sheet: &dyn Fn(Option<&str>) -> bool,
+) -> String {
+    let internal = replace_a1_notation(source, old_pos);
+    let adjusted = adjust_cell_references(&internal, old_pos, new_pos, adjust, is_affected_sheet);
+    replace_internal_cell_references(&adjusted, new_pos)
+}
+
+/// Adjusts the corners of a cell range for [`adjust_cell_references()`],
+/// returning `None` if the whole range was deleted. A single cell is passed
+/// as a range with the same start and end.
+fn adjust_cell_ref_range(
+    start: &CellRef,
+    end: &CellRef,
+    old_pos: Pos,
+    new_pos: Pos,
+    adjust: Option<RefAdjust>,
+) -> Option<(CellRef, CellRef)> {
+    let (start_x, end_x) = adjust_coord_range(Axis::X, start.x, end.x, old_pos, new_pos, adjust)?;
+    let (start_y, end_y) = adjust_coord_range(Axis::Y, start.y, end.y, old_pos, new_pos, adjust)?;
+    Some((
+        CellRef {
+            sheet: start.sheet.clone(),
+            x: start_x,
+            y: start_y,
+        },
+        CellRef {
+            sheet: end.sheet.clone(),
+            x: end_x,
+            y: end_y,
+        },
+    ))
+}
+
+/// Adjusts the ends of a range of columns or rows along `axis`, returning
+/// `None` if the whole range was deleted.
+fn adjust_coord_range(
+    axis: Axis,
+    a: CellRefCoord,
+    b: CellRefCoord,
+    old_pos: Pos,
+    new_pos: Pos,
+    adjust: Option<RefAdjust>,
+) -> Option<(CellRefCoord, CellRefCoord)> {
+    let (old_base, new_base) = match axis {
+        Axis::X => (old_pos.x, new_pos.x),
+        Axis::Y => (old_pos.y, new_pos.y),
+    };
+    let (mut a_index, mut b_index) = (a.resolve_from(old_base), b.resolve_from(old_base));
+    if let Some(adjust) = adjust.filter(|adjust| adjust.axis == axis) {
+        if a_index <= b_index {
+            (a_index, b_index) = adjust.adjust_range(a_index, b_index)?;
+        } else {
+            (b_index, a_index) = adjust.adjust_range(b_index, a_index)?;
+        }
+    }
+    let rebase = |coord: CellRefCoord, index: i64| match coord {
+        CellRefCoord::Relative(_) => CellRefCoord::Relative(index - new_base),
+        CellRefCoord::Absolute(_) => CellRefCoord::Absolute(index),
+    };
+    Some((rebase(a, a_index), rebase(b, b_index)))
+}
+
 fn replace_cell_references(
     source: &str,
     pos: Pos,
@@ -308,6 +612,142 @@ mod tests {
 
         let replaced = replace_a1_notation(src, (0, 0).into());
         assert_eq!(replaced, expected);
+
+        let src = "SUM(A:C) + SUM('Sheet 2'!$2:3)";
+        let expected = "SUM(C[-1]:C[1]) + SUM(\"Sheet 2\"!R{2}:R[2])";
+        let replaced = replace_a1_notation(src, (1, 1).into());
+        assert_eq!(replaced, expected);
+        assert_eq!(
+            replace_internal_cell_references(&replaced, (1, 1).into()),
+            "SUM(A:C) + SUM(\"Sheet 2\"!$2:3)",
+        );
+    }
+
+    #[test]
+    fn test_adjust_cell_references() {
+        let adjust_a1 = |src: &str, old_pos: Pos, new_pos: Pos, adjust: RefAdjust| {
+            adjust_a1_references(src, old_pos, new_pos, adjust, &|sheet| sheet.is_none())
+        };
+        let src = "SUM(A1:C3) + $B$2 + C5 + Sheet2!B2";
+
+        // Delete column B, which moves the formula from D5 to C5.
+        assert_eq!(
+            adjust_a1(src, pos![D5], pos![C5], RefAdjust::delete(Axis::X, 1)),
+            "SUM(A1:B3) + #REF! + B5 + \"Sheet2\"!B2",
+        );
+
+        // Insert row 2, which moves the formula from D5 to D6.
+        assert_eq!(
+            adjust_a1(src, pos![D5], pos![D6], RefAdjust::insert(Axis::Y, 2)),
+            "SUM(A1:C4) + $B$3 + C6 + \"Sheet2\"!B2",
+        );
+
+        // Deleting every cell in a range removes the whole range.
+        assert_eq!(
+            adjust_a1(
+                "SUM(B1:B3)",
+                pos![A1],
+                pos![A1],
+                RefAdjust::delete(Axis::X, 1)
+            ),
+            "SUM(#REF!)",
+        );
+
+        // Whole columns and rows are adjusted along their own axis.
+        let src = "SUM(B:D) + SUM(2:$3)";
+        assert_eq!(
+            adjust_a1(src, pos![F1], pos![E1], RefAdjust::delete(Axis::X, 1)),
+            "SUM(B:C) + SUM(2:$3)",
+        );
+        assert_eq!(
+            adjust_a1(src, pos![F1], pos![F1], RefAdjust::insert(Axis::Y, 2)),
+            "SUM(B:D) + SUM(3:$4)",
+        );
+        assert_eq!(
+            adjust_a1(
+                "SUM(B:B)",
+                pos![A1],
+                pos![A1],
+                RefAdjust::delete(Axis::X, 1)
+            ),
+            "SUM(#REF!)",
+        );
+    }
+
+    #[test]
+    fn test_to_excel_formula() {
+        let excel = |src: &str, pos: Pos| {
+            let internal = replace_a1_notation(src, pos);
+            to_excel_formula(&internal, pos, &|sheet| match sheet {
+                None => Some((None, Pos { x: 0, y: 1 })),
+                Some("Other") => Some((Some("Other's".into()), Pos { x: 2, y: 0 })),
+                Some(_) => None,
+            })
+        };
+
+        // rows move down by one
+        assert_eq!(
+            excel("=SUM(A0:$B$2) + C:C + 'Other'!A1", pos![D4]).as_deref(),
+            Some("SUM(A1:$B$3) + C:C + 'Other''s'!C1"),
+        );
+        assert_eq!(
+            excel(
+                "IF(A1 == 1, \"a\\\"b\", 'c') & XLOOKUP(1, A:A, B:B)",
+                pos![D4]
+            )
+            .as_deref(),
+            Some("IF(A2 = 1, \"a\"\"b\", \"c\") & _xlfn.XLOOKUP(1, A:A, B:B)"),
+        );
+        assert_eq!(excel("1..3", pos![D4]), None);
+        assert_eq!(excel("LET(x, 1, x)", pos![D4]), None);
+        assert_eq!(excel("Missing!A1", pos![D4]), None);
+        assert_eq!(excel("SUM(", pos![D4]), None);
+        // A-1 does not exist in Excel
+        assert_eq!(excel("An2", pos![D4]), None);
+    }
+
+    #[test]
+    fn test_from_excel_formula() {
+        // Excel rows start at 1, so the cells move up by one
+        let offset = Pos { x: 0, y: -1 };
+        assert_eq!(
+            from_excel_formula("SUM(A1:$B$2)+C:C+'Other''s'!$C1", pos![D4], offset).as_deref(),
+            Some("SUM(A0:$B$1)+C:C+\"Other's\"!$C0"),
+        );
+        assert_eq!(
+            from_excel_formula(
+                "IF(A1<>1,\"a\"\"b\\\",_xlfn.XLOOKUP(1,A:A,B:B))&$3:4",
+                pos![D4],
+                offset
+            )
+            .as_deref(),
+            Some("IF(A0<>1,\"a\\\"b\\\\\",XLOOKUP(1,A:A,B:B))&$2:3"),
+        );
+        assert_eq!(
+            from_excel_formula("_xlfn._xlws.SORT(A1:A3)", pos![B1], offset).as_deref(),
+            Some("SORT(A0:A2)"),
+        );
+        assert_eq!(from_excel_formula("Sales*2", pos![D4], offset), None);
+        assert_eq!(
+            from_excel_formula("NOSUCHFUNCTION(1)", pos![D4], offset),
+            None
+        );
+        assert_eq!(from_excel_formula("Table1[Col]", pos![D4], offset), None);
+        assert_eq!(from_excel_formula("\"abc", pos![D4], offset), None);
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            find_names("=SUM(Sales) * tax_rate + A1 + LET(x, 1, x)"),
+            vec!["Sales", "tax_rate", "x", "x"],
+        );
+        assert!(is_valid_name("Sales"));
+        assert!(is_valid_name("_total2"));
+        assert!(!is_valid_name("A1"));
+        assert!(!is_valid_name("true"));
+        assert!(!is_valid_name("net sales"));
+        assert!(!is_valid_name(""));
     }
 
     #[test]
@@ -316,7 +756,26 @@ mod tests {
         assert!(!parse_and_check_formula("SUM()", 0, 0));
         assert!(!parse_and_check_formula("SUM(", 0, 0));
         assert!(!parse_and_check_formula("NOT_A_FUNCTION()", 0, 0));
+        assert!(!parse_and_check_formula("SUM(#REF!)", 0, 0));
         assert!(parse_and_check_formula("SUM(10, 20, 30)", 0, 0));
         assert!(parse_and_check_formula("SUM(A1, A2, A3, A4)", 0, 0));
+        assert!(parse_and_check_formula("LET(x, 5, x * 2)", 0, 0));
+        assert!(parse_and_check_formula(
+            "MAP(A1:A3, LAMBDA(x, 1 / x))",
+            0,
+            0
+        ));
+        assert!(parse_and_check_formula("SUM(Sales)", 0, 0));
+        // `y` may be a defined name, which is not known when checking
+        assert!(parse_and_check_formula("LET(x, 5, y)", 0, 0));
+        assert!(!parse_and_check_formula("LET(x, 5, y())", 0, 0));
+        assert!(!parse_and_check_formula(
+            "IF(TRUE, NOT_A_FUNCTION(), 1)",
+            0,
+            0
+        ));
+        assert!(!parse_and_check_formula("IFERROR(SUM(), 0)", 0, 0));
+        assert!(!parse_and_check_formula("IFERROR(ABS(1, 2), 0)", 0, 0));
+        assert!(parse_and_check_formula("IFERROR(1 / 0, 0)", 0, 0));
     }
 }
@@ -118,6 +118,43 @@ impl SyntaxRule for CellReference {
     }
 }
 
+/// Matches a reference to whole columns or rows, such as `A:C` or `2:10`.
+#[derive(Debug, Copy, Clone)]
+pub struct LineRangeReference;
+impl_display!(for LineRangeReference, "column or row range reference, such as 'A:C' or '2:10'");
+impl SyntaxRule for LineRangeReference {
+    type Output = Spanned<RangeRef>;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        let is_line_range = |t| matches!(t, Some(Token::ColRangeRef | Token::RowRangeRef));
+        match p.next() {
+            Some(Token::UnquotedSheetReference) => is_line_range(p.next()),
+            Some(Token::StringLiteral) => {
+                p.next() == Some(Token::SheetRefOp) && is_line_range(p.next())
+            }
+            t => is_line_range(t),
+        }
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        let start_span = p.peek_next_span();
+
+        let sheet_name = p.try_parse(SheetRefPrefix).transpose()?;
+
+        p.next();
+
+        let mut range_ref = RangeRef::parse_line_range(p.token_str(), p.pos)
+            .ok_or_else(|| RunErrorMsg::BadCellReference.with_span(p.span()))?;
+        if let RangeRef::ColRange { sheet, .. } | RangeRef::RowRange { sheet, .. } = &mut range_ref
+        {
+            *sheet = sheet_name;
+        }
+        Ok(Spanned {
+            span: Span::merge(start_span, p.span()),
+            inner: range_ref,
+        })
+    }
+}
+
 /// Matches a single cell reference or a cell range reference on its own, not as
 /// part of an expression.
 #[derive(Debug, Copy, Clone)]
@@ -127,9 +164,13 @@ impl SyntaxRule for CellRangeReference {
     type Output = Spanned<RangeRef>;
 
     fn prefix_matches(&self, p: Parser<'_>) -> bool {
-        CellReference.prefix_matches(p)
+        LineRangeReference.prefix_matches(p) || CellReference.prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        if let Some(line_range) = p.try_parse(LineRangeReference) {
+            return line_range;
+        }
+
         let pos1 = p.parse(CellReference)?;
 
         // Check for a range reference.
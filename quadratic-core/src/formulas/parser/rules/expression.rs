@@ -146,7 +146,11 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 | Token::UnterminatedStringLiteral
                 | Token::NumericLiteral
                 | Token::CellRef
-                | Token::InternalCellRef => true,
+                | Token::InternalCellRef
+                | Token::ColRangeRef
+                | Token::RowRangeRef
+                | Token::RefError
+                | Token::Identifier => true,
 
                 Token::Whitespace => false,
                 Token::Unknown => false,
@@ -163,7 +167,10 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 p,
                 [
                     FunctionCall.map(Some),
+                    LineRangeReferenceExpression.map(Some),
                     CellReferenceExpression.map(Some),
+                    IdentifierExpression.map(Some),
+                    RefErrorExpression.map(Some),
                     StringLiteralExpression.map(Some),
                     NumericLiteral.map(Some),
                     ArrayLiteral.map(Some),
@@ -359,6 +366,60 @@ impl SyntaxRule for CellReferenceExpression {
     }
 }
 
+/// Matches a reference to whole columns or rows.
+#[derive(Debug, Copy, Clone)]
+pub struct LineRangeReferenceExpression;
+impl_display!(for LineRangeReferenceExpression, "column or row range reference, such as 'A:C' or '2:10'");
+impl SyntaxRule for LineRangeReferenceExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, p: Parser<'_>) -> bool {
+        LineRangeReference.prefix_matches(p)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        Ok(p.parse(LineRangeReference)?
+            .map(ast::AstNodeContents::RangeRef))
+    }
+}
+
+/// Matches a name defined using `LET` or `LAMBDA`.
+#[derive(Debug, Copy, Clone)]
+pub struct IdentifierExpression;
+impl_display!(for IdentifierExpression, "name, such as 'x' or 'total'");
+impl SyntaxRule for IdentifierExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::Identifier)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::Identifier)?;
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::Identifier(p.token_str().to_string()),
+        })
+    }
+}
+
+/// Matches a reference to a cell that has been deleted.
+#[derive(Debug, Copy, Clone)]
+pub struct RefErrorExpression;
+impl_display!(for RefErrorExpression, "deleted cell reference '#REF!'");
+impl SyntaxRule for RefErrorExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::RefError)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::RefError)?;
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::RefError,
+        })
+    }
+}
+
 /// Matches a pair of parentheses containing an expression.
 #[derive(Debug, Copy, Clone)]
 pub struct ParenExpression;
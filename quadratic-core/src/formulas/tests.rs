@@ -311,6 +311,24 @@ fn test_find_cell_references() {
                 pos: a1("\"plum\"!$A1"),
             },
         ),
+        // Whole columns
+        (
+            "kiwi!$A:B",
+            RangeRef::ColRange {
+                start: CellRefCoord::Absolute(0),
+                end: CellRefCoord::Relative(1),
+                sheet: Some("kiwi".into()),
+            },
+        ),
+        // Whole rows
+        (
+            "n2:$5",
+            RangeRef::RowRange {
+                start: CellRefCoord::Relative(-2),
+                end: CellRefCoord::Absolute(5),
+                sheet: None,
+            },
+        ),
     ];
     let formula_string = test_cases.iter().map(|(string, _)| string).join(" + ");
     let cell_references_found = find_cell_references(&formula_string, Pos::ORIGIN)
@@ -367,3 +385,89 @@ fn test_currency_string() {
     let g = Grid::new();
     assert_eq!("30", eval_to_string(&g, "\"$10\" + 20"));
 }
+
+#[test]
+fn test_defined_names() {
+    use crate::grid::defined_names::NameValue;
+
+    let mut g = Grid::new();
+    let sheet_id = g.sheet_ids()[0];
+    g.sheets_mut()[0].test_set_values(0, 1, 1, 3, vec!["1", "2", "3"]);
+    g.set_defined_name(
+        "Sales",
+        Some(NameValue::Range(crate::SheetRect::from_numbers(
+            0, 1, 1, 3, sheet_id,
+        ))),
+    );
+    g.set_defined_name(
+        "rate",
+        Some(NameValue::Constant(CellValue::Number(2.into()))),
+    );
+    g.set_defined_name(
+        "total",
+        Some(NameValue::Formula("SUM(sales) * RATE + A1".into())),
+    );
+    g.set_defined_name("forever", Some(NameValue::Formula("forever + 1".into())));
+
+    assert_eq!("{1; 2; 3}", eval_to_string(&g, "Sales"));
+    assert_eq!("13", eval_to_string(&g, "total"));
+    assert_eq!("5", eval_to_string(&g, "SUMIF(Sales, \">1\")"));
+
+    // names from `LET` take precedence, and are not visible in the formula of
+    // a defined name
+    assert_eq!("10", eval_to_string(&g, "LET(rate, 10, rate)"));
+    assert_eq!("13", eval_to_string(&g, "LET(sales, 0, total)"));
+
+    expect_err(&RunErrorMsg::CircularReference, &g, "forever");
+    expect_err(&RunErrorMsg::BadFunctionName, &g, "missing");
+}
+
+#[test]
+fn test_line_range_references() {
+    let mut g = Grid::new();
+    let id1 = g.sheet_ids()[0];
+    let sheet = &mut g.sheets_mut()[0];
+    let _ = sheet.set_cell_value(pos![A1], 1);
+    let _ = sheet.set_cell_value(pos![A2], 2);
+    let _ = sheet.set_cell_value(pos![A5], 5);
+    let _ = sheet.set_cell_value(pos![B3], 10);
+    let _ = sheet.set_cell_value(pos![C3], 20);
+    sheet.recalculate_bounds();
+    let id2 = g.add_sheet(None);
+
+    let pos = pos![E1].to_sheet_pos(id1);
+    let eval_at_pos = |s: &str| -> CodeResult<String> {
+        let mut ctx = Ctx::new(&g, pos);
+        Ok(parse_formula(s, pos.into())?
+            .eval(&mut ctx, false)?
+            .to_string())
+    };
+    assert_eq!("8", eval_at_pos("SUM(A:A)").unwrap());
+    assert_eq!("18", eval_at_pos("SUM(A:B)").unwrap());
+    assert_eq!("30", eval_at_pos("SUM(3:3)").unwrap());
+    assert_eq!("32", eval_at_pos("SUM($2:3)").unwrap());
+    assert_eq!("5", eval_at_pos("ROWS(A:A)").unwrap());
+    assert_eq!(
+        "8",
+        eval_to_string_at(&g, Pos::ORIGIN.to_sheet_pos(id2), "SUM('Sheet 1'!A:A)"),
+    );
+    assert_eq!(
+        RunErrorMsg::CircularReference,
+        eval_at_pos("SUM(E:E)").unwrap_err().msg,
+    );
+    assert_eq!(
+        RunErrorMsg::CircularReference,
+        eval_at_pos("SUM(1:1)").unwrap_err().msg,
+    );
+
+    // the whole column is recorded as accessed, not just the cells in bounds
+    let mut ctx = Ctx::new(&g, pos);
+    parse_formula("SUM(B:C)", pos.into())
+        .unwrap()
+        .eval(&mut ctx, false)
+        .unwrap();
+    assert!(ctx
+        .cells_accessed
+        .iter()
+        .any(|sheet_rect| sheet_rect.contains(pos![B1000000].to_sheet_pos(id1))));
+}
@@ -1,15 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Range;
 
 use serde::{Deserialize, Serialize};
 
+use crate::formulas::RefAdjust;
 use crate::grid::block::SameValue;
 use crate::grid::borders::cell::{CellBorders, CellSide};
 use crate::grid::borders::compute_indices;
 use crate::grid::borders::style::{BorderSelection, BorderStyle};
 use crate::grid::{ColumnData, Sheet};
 use crate::selection::Selection;
-use crate::{Pos, Rect};
+use crate::{Axis, Pos, Rect};
 
 pub fn generate_borders(
     sheet: &Sheet,
@@ -134,6 +135,34 @@ impl SheetBorders {
         previous_borders
     }
 
+    /// Shifts borders after columns or rows are inserted or deleted.
+    pub(crate) fn shift(&mut self, adjust: RefAdjust) {
+        let RefAdjust { axis, start, delta } = adjust;
+        let (keyed_by_index, keyed_by_line) = match axis {
+            Axis::X => (
+                &mut self.render_lookup.vertical,
+                &mut self.render_lookup.horizontal,
+            ),
+            Axis::Y => (
+                &mut self.render_lookup.horizontal,
+                &mut self.render_lookup.vertical,
+            ),
+        };
+        shift_keys(keyed_by_index, start, delta);
+        keyed_by_line
+            .values_mut()
+            .for_each(|line| line.shift(start, delta));
+
+        match axis {
+            Axis::X => shift_keys(&mut self.per_cell.borders, start, delta),
+            Axis::Y => self
+                .per_cell
+                .borders
+                .values_mut()
+                .for_each(|column| column.shift(start, delta)),
+        }
+    }
+
     fn get_rect(&self, rect: &Rect) -> SheetBorders {
         let mut sheet_borders = SheetBorders::default();
         let cloned_id_space = self.per_cell.clone_rect(rect);
@@ -146,6 +175,14 @@ impl SheetBorders {
     }
 }
 
+/// Shifts the keys of a map of columns or rows, like
+/// [`crate::util::shift_keys()`].
+fn shift_keys<T>(map: &mut HashMap<i64, T>, start: i64, delta: i64) {
+    let mut sorted: BTreeMap<i64, T> = map.drain().collect();
+    crate::util::shift_keys(&mut sorted, start, delta);
+    map.extend(sorted);
+}
+
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct IdSpaceBorders {
     pub borders: HashMap<i64, ColumnData<SameValue<CellBorders>>>,
@@ -770,4 +807,27 @@ mod tests {
             "Removed section should have all borders"
         )
     }
+
+    #[test]
+    fn shift_borders() {
+        let mut sheet = Sheet::test();
+        let style = BorderStyle {
+            color: Rgba::color_from_str("#000000").unwrap(),
+            line: CellBorderLine::Line1,
+        };
+        let rect = Rect::single_pos(Pos { x: 2, y: 2 });
+        set_rect_border_selection(&mut sheet, &rect, vec![BorderSelection::All], Some(style));
+        let expected = sheet.borders.get_cell_borders(Pos { x: 2, y: 2 });
+        assert!(expected.is_some());
+
+        sheet.borders.shift(RefAdjust::insert(Axis::X, 1));
+        sheet.borders.shift(RefAdjust::insert(Axis::Y, 0));
+        assert_eq!(sheet.borders.get_cell_borders(Pos { x: 2, y: 2 }), None);
+        assert_eq!(sheet.borders.get_cell_borders(Pos { x: 3, y: 3 }), expected);
+        assert!(sheet.borders.render_lookup.vertical.contains_key(&4));
+        assert!(sheet.borders.render_lookup.horizontal.contains_key(&4));
+
+        sheet.borders.shift(RefAdjust::delete(Axis::X, 3));
+        assert_ne!(sheet.borders.get_cell_borders(Pos { x: 3, y: 3 }), expected);
+    }
 }
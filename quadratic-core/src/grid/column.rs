@@ -93,6 +93,23 @@ impl Column {
             || self.fill_color.get(y).is_some()
     }
 
+    /// Shifts every value and format at or after row `start` by `delta`. If
+    /// `delta` is negative, the rows in `start..start - delta` are removed
+    /// first.
+    pub fn shift_rows(&mut self, start: i64, delta: i64) {
+        crate::util::shift_keys(&mut self.values, start, delta);
+        self.align.shift(start, delta);
+        self.wrap.shift(start, delta);
+        self.numeric_format.shift(start, delta);
+        self.numeric_decimals.shift(start, delta);
+        self.numeric_commas.shift(start, delta);
+        self.bold.shift(start, delta);
+        self.italic.shift(start, delta);
+        self.text_color.shift(start, delta);
+        self.fill_color.shift(start, delta);
+        self.render_size.shift(start, delta);
+    }
+
     /// Gets the Format for a column (which will eventually replace the data structure)
     pub fn format(&self, y: i64) -> Option<Format> {
         let format = Format {
@@ -302,6 +319,24 @@ impl<B: BlockContent> ColumnData<B> {
         to_return
     }
 
+    /// Shifts every value at or after `start` by `delta`. If `delta` is
+    /// negative, the values in `start..start - delta` are removed first.
+    pub fn shift(&mut self, start: i64, delta: i64) {
+        if delta < 0 {
+            self.remove_range(start..start - delta);
+        } else if let Some(block) = self.remove_block_containing(start) {
+            self.add_blocks(block.split(start).into_iter().flatten());
+        }
+        let moved = self.0.split_off(&start);
+        self.0.extend(moved.into_values().map(|mut block| {
+            block.y += delta;
+            (block.y, block)
+        }));
+        if delta < 0 {
+            self.try_merge_at(start);
+        }
+    }
+
     pub fn range(&self) -> Option<Range<i64>> {
         let min = *self.0.first_key_value()?.0;
         let max = self.0.last_key_value()?.1.end();
@@ -410,6 +445,29 @@ mod test {
         }));
     }
 
+    #[test]
+    fn column_data_shift() {
+        let mut cd: ColumnData<SameValue<bool>> = ColumnData::new();
+        cd.set_range(0..4, true);
+
+        // inserting inside a block splits it
+        cd.shift(2, 1);
+        assert_eq!(cd.get(1), Some(true));
+        assert_eq!(cd.get(2), None);
+        assert_eq!(cd.get(3), Some(true));
+        assert_eq!(cd.get(4), Some(true));
+        assert_eq!(cd.get(5), None);
+        assert_eq!(cd.blocks().count(), 2);
+
+        // deleting the gap merges the blocks again
+        cd.shift(2, -1);
+        assert_eq!(cd.range(), Some(0..4));
+        assert_eq!(cd.blocks().count(), 1);
+
+        cd.shift(0, -2);
+        assert_eq!(cd.range(), Some(0..2));
+    }
+
     #[test]
     fn format() {
         let mut cd: Column = Column::new(0);
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Grid, SheetId};
+use crate::{
+    formulas::{adjust_a1_references, RefAdjust},
+    CellValue, Pos, SheetRect,
+};
+
+/// Formula of a name whose range was deleted.
+const REF_ERROR_FORMULA: &str = "#REF!";
+
+/// What a defined name refers to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum NameValue {
+    /// A range of cells. Reading the name reads the cells.
+    Range(SheetRect),
+    /// A fixed value.
+    Constant(#[cfg_attr(feature = "js", ts(type = "any"))] CellValue),
+    /// A formula, which is evaluated wherever the name is used. Cell
+    /// references in it are absolute, and references without a sheet name
+    /// are to the sheet of the cell using the name.
+    Formula(String),
+}
+
+/// A name that can be used in formulas in place of a range, value or
+/// formula. Names are unique within a file and are not case-sensitive.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct DefinedName {
+    pub name: String,
+    pub value: NameValue,
+}
+
+impl Grid {
+    /// Returns all defined names, ordered by name.
+    pub fn defined_names(&self) -> &[DefinedName] {
+        &self.defined_names
+    }
+
+    /// Returns the defined name `name`, ignoring case.
+    pub fn defined_name(&self, name: &str) -> Option<&DefinedName> {
+        self.defined_names
+            .iter()
+            .find(|defined_name| defined_name.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Defines `name`, replacing any existing name that differs only by case,
+    /// or removes it if `value` is `None`. Returns the old value.
+    pub fn set_defined_name(&mut self, name: &str, value: Option<NameValue>) -> Option<NameValue> {
+        let old = self
+            .defined_names
+            .iter()
+            .position(|defined_name| defined_name.name.eq_ignore_ascii_case(name))
+            .map(|index| self.defined_names.remove(index).value);
+        if let Some(value) = value {
+            let index = self.defined_names.partition_point(|defined_name| {
+                defined_name.name.to_ascii_lowercase() < name.to_ascii_lowercase()
+            });
+            self.defined_names.insert(
+                index,
+                DefinedName {
+                    name: name.to_string(),
+                    value,
+                },
+            );
+        }
+        old
+    }
+
+    /// Rewrites defined names after columns or rows are inserted or deleted
+    /// on the sheet `sheet_id`, so that they keep referring to the same
+    /// cells. Ranges that are deleted entirely become `#REF!`. Returns the
+    /// old value of each name that changed.
+    pub fn adjust_defined_names(
+        &mut self,
+        sheet_id: SheetId,
+        adjust: RefAdjust,
+    ) -> Vec<(String, NameValue)> {
+        let Some(sheet_name) = self.try_sheet(sheet_id).map(|sheet| sheet.name.clone()) else {
+            return vec![];
+        };
+        // references without a sheet name are to the sheet using the name,
+        // which may be any sheet, so only named sheets are adjusted
+        let is_affected_sheet = |name: Option<&str>| name == Some(sheet_name.as_str());
+
+        let mut changed = vec![];
+        for defined_name in &mut self.defined_names {
+            let value = match &defined_name.value {
+                NameValue::Range(sheet_rect) if sheet_rect.sheet_id == sheet_id => {
+                    match adjust.adjust_rect((*sheet_rect).into()) {
+                        Some(rect) => NameValue::Range(rect.to_sheet_rect(sheet_id)),
+                        None => NameValue::Formula(REF_ERROR_FORMULA.into()),
+                    }
+                }
+                NameValue::Formula(formula) => NameValue::Formula(adjust_a1_references(
+                    formula,
+                    Pos::ORIGIN,
+                    Pos::ORIGIN,
+                    adjust,
+                    &is_affected_sheet,
+                )),
+                _ => continue,
+            };
+            if value != defined_name.value {
+                let old = std::mem::replace(&mut defined_name.value, value);
+                changed.push((defined_name.name.clone(), old));
+            }
+        }
+        changed
+    }
+
+    /// Replaces defined names that refer to a range on the sheet `sheet_id`,
+    /// which is being deleted, with `#REF!`. Returns the old value of each
+    /// name that changed.
+    pub fn remove_sheet_from_defined_names(
+        &mut self,
+        sheet_id: SheetId,
+    ) -> Vec<(String, NameValue)> {
+        let mut changed = vec![];
+        for defined_name in &mut self.defined_names {
+            if matches!(defined_name.value, NameValue::Range(sheet_rect) if sheet_rect.sheet_id == sheet_id)
+            {
+                let old = std::mem::replace(
+                    &mut defined_name.value,
+                    NameValue::Formula(REF_ERROR_FORMULA.into()),
+                );
+                changed.push((defined_name.name.clone(), old));
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_defined_name() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        let range = NameValue::Range(SheetRect::from_numbers(0, 0, 1, 3, sheet_id));
+        let rate = NameValue::Constant(CellValue::Number(7.into()));
+
+        assert_eq!(grid.set_defined_name("Sales", Some(range.clone())), None);
+        assert_eq!(grid.set_defined_name("rate", Some(rate.clone())), None);
+        assert_eq!(
+            grid.defined_names()
+                .iter()
+                .map(|defined_name| defined_name.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rate", "Sales"],
+        );
+        assert_eq!(grid.defined_name("SALES").unwrap().value, range);
+
+        // redefining a name may change its case
+        let formula = NameValue::Formula("rate * 2".into());
+        assert_eq!(
+            grid.set_defined_name("RATE", Some(formula.clone())),
+            Some(rate)
+        );
+        assert_eq!(grid.defined_name("rate").unwrap().name, "RATE");
+        assert_eq!(grid.set_defined_name("rate", None), Some(formula));
+        assert_eq!(grid.set_defined_name("rate", None), None);
+        assert_eq!(grid.defined_names().len(), 1);
+    }
+}
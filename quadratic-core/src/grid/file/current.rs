@@ -1,15 +1,22 @@
 use crate::color::Rgba;
+use crate::grid::defined_names::{DefinedName, NameValue};
 use crate::grid::formats::format::Format;
+use crate::grid::sheet::comments::{Comment, CommentThread};
+use crate::grid::sheet::conditional_formats::{
+    Comparison, ConditionalFormat, ConditionalFormatRule, ConditionalFormatStyle,
+};
+use crate::grid::sheet::validations::{Validation, ValidationRule};
 use crate::grid::{
     block::SameValue,
-    file::v1_5::schema::{self as current},
+    file::v1_6::schema::{self as current},
     formatting::RenderSize,
     generate_borders, set_rect_borders, BorderSelection, BorderStyle, CellAlign, CellBorderLine,
     CellWrap, CodeCellLanguage, CodeRun, CodeRunResult, Column, ColumnData, Grid, GridBounds,
     NumericFormat, NumericFormatKind, Sheet, SheetBorders, SheetId,
 };
-use crate::sheet_offsets::SheetOffsets;
-use crate::{CellValue, CodeCellValue, Pos, Rect, Value};
+use crate::selection::Selection;
+use crate::sheet_offsets::{offsets::OutlineGroup, SheetOffsets};
+use crate::{CellValue, CodeCellValue, Instant, Pos, Rect, Value};
 
 use anyhow::Result;
 use bigdecimal::BigDecimal;
@@ -19,6 +26,7 @@ use std::{
     collections::{BTreeMap, HashMap},
     str::FromStr,
 };
+use uuid::Uuid;
 
 use super::CURRENT_VERSION;
 
@@ -161,31 +169,7 @@ fn import_column_builder(columns: &[(i64, current::Column)]) -> Result<BTreeMap<
             set_column_format_render_size(&mut col.render_size, &column.render_size);
 
             for (y, value) in column.values.iter() {
-                let cell_value = match value {
-                    current::CellValue::Blank => CellValue::Blank,
-                    current::CellValue::Text(text) => CellValue::Text(text.to_owned()),
-                    current::CellValue::Number(number) => {
-                        CellValue::Number(BigDecimal::from_str(number)?)
-                    }
-                    current::CellValue::Html(html) => CellValue::Html(html.to_owned()),
-                    current::CellValue::Code(code_cell) => CellValue::Code(CodeCellValue {
-                        code: code_cell.code.to_owned(),
-                        language: match code_cell.language {
-                            current::CodeCellLanguage::Python => CodeCellLanguage::Python,
-                            current::CodeCellLanguage::Formula => CodeCellLanguage::Formula,
-                        },
-                    }),
-                    current::CellValue::Logical(logical) => CellValue::Logical(*logical),
-                    current::CellValue::Instant(instant) => {
-                        CellValue::Instant(serde_json::from_str(instant)?)
-                    }
-                    current::CellValue::Duration(duration) => {
-                        CellValue::Duration(serde_json::from_str(duration)?)
-                    }
-                    current::CellValue::Error(error) => {
-                        CellValue::Error(Box::new((*error).clone().into()))
-                    }
-                };
+                let cell_value = import_cell_value(value)?;
                 if let Ok(y) = y.parse::<i64>() {
                     col.values.insert(y, cell_value);
                 }
@@ -231,6 +215,54 @@ fn import_borders_builder(sheet: &mut Sheet, current_sheet: &current::Sheet) {
     });
 }
 
+fn import_cell_value(value: &current::CellValue) -> Result<CellValue> {
+    Ok(match value {
+        current::CellValue::Blank => CellValue::Blank,
+        current::CellValue::Text(text) => CellValue::Text(text.to_owned()),
+        current::CellValue::Number(number) => CellValue::Number(BigDecimal::from_str(number)?),
+        current::CellValue::Html(html) => CellValue::Html(html.to_owned()),
+        current::CellValue::Code(code_cell) => CellValue::Code(CodeCellValue {
+            code: code_cell.code.to_owned(),
+            language: match code_cell.language {
+                current::CodeCellLanguage::Python => CodeCellLanguage::Python,
+                current::CodeCellLanguage::Formula => CodeCellLanguage::Formula,
+            },
+        }),
+        current::CellValue::Logical(logical) => CellValue::Logical(*logical),
+        current::CellValue::Instant(instant) => CellValue::Instant(serde_json::from_str(instant)?),
+        current::CellValue::Duration(duration) => {
+            CellValue::Duration(serde_json::from_str(duration)?)
+        }
+        current::CellValue::Error(error) => CellValue::Error(Box::new((*error).clone().into())),
+    })
+}
+
+fn export_cell_value(value: &CellValue) -> current::CellValue {
+    match value {
+        CellValue::Text(text) => current::CellValue::Text(text.to_owned()),
+        CellValue::Number(number) => current::CellValue::Number(number.to_string()),
+        CellValue::Html(html) => current::CellValue::Html(html.clone()),
+        CellValue::Code(cell_code) => current::CellValue::Code(current::CodeCell {
+            code: cell_code.code.to_owned(),
+            language: match cell_code.language {
+                CodeCellLanguage::Python => current::CodeCellLanguage::Python,
+                CodeCellLanguage::Formula => current::CodeCellLanguage::Formula,
+            },
+        }),
+        CellValue::Logical(logical) => current::CellValue::Logical(*logical),
+        CellValue::Instant(instant) => {
+            current::CellValue::Instant(serde_json::to_string(&instant).unwrap_or_default())
+        }
+        CellValue::Duration(duration) => {
+            current::CellValue::Duration(serde_json::to_string(&duration).unwrap_or_default())
+        }
+        CellValue::Error(error) => {
+            current::CellValue::Error(current::RunError::from_grid_run_error(error))
+        }
+        CellValue::Blank => current::CellValue::Blank,
+    }
+}
+
 fn import_code_cell_output(type_field: &str, value: &str) -> CellValue {
     match type_field.to_lowercase().as_str() {
         "text" => CellValue::Text(value.to_owned()),
@@ -241,10 +273,26 @@ fn import_code_cell_output(type_field: &str, value: &str) -> CellValue {
             "FALSE" => CellValue::Logical(false),
             _ => CellValue::Logical(false),
         },
+        "time instant" => serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Instant),
+        "time duration" => {
+            serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Duration)
+        }
         _ => CellValue::Blank,
     }
 }
 
+fn export_code_cell_output(cell_value: &CellValue) -> current::OutputValueValue {
+    let value = match cell_value {
+        CellValue::Instant(instant) => serde_json::to_string(instant).unwrap_or_default(),
+        CellValue::Duration(duration) => serde_json::to_string(duration).unwrap_or_default(),
+        _ => cell_value.to_string(),
+    };
+    current::OutputValueValue {
+        type_field: cell_value.type_name().into(),
+        value,
+    }
+}
+
 fn import_code_cell_builder(sheet: &current::Sheet) -> Result<IndexMap<Pos, CodeRun>> {
     // davidfig: probably the more idiomatic way is to return the code_runs below. It's above my skill level, though.
     let mut code_runs = IndexMap::new();
@@ -341,6 +389,169 @@ fn import_formats(format: &[(i64, (current::Format, i64))]) -> BTreeMap<i64, (Fo
         .collect()
 }
 
+fn import_selection(selection: &current::Selection) -> Result<Selection> {
+    Ok(Selection {
+        sheet_id: SheetId::from_str(&selection.sheet_id.id)?,
+        x: selection.x,
+        y: selection.y,
+        rects: selection
+            .rects
+            .as_ref()
+            .map(|rects| rects.iter().map(|rect| rect.clone().into()).collect()),
+        rows: selection.rows.clone(),
+        columns: selection.columns.clone(),
+        all: selection.all,
+    })
+}
+
+fn import_validations(validations: &[current::Validation]) -> Result<Vec<Validation>> {
+    validations
+        .iter()
+        .map(|validation| {
+            let rule = match &validation.rule {
+                current::ValidationRule::List { values } => ValidationRule::List {
+                    values: values.clone(),
+                },
+                current::ValidationRule::ListFromRange { range } => ValidationRule::ListFromRange {
+                    range: crate::SheetRect::from(range.clone()),
+                },
+                current::ValidationRule::Number { min, max } => ValidationRule::Number {
+                    min: *min,
+                    max: *max,
+                },
+                current::ValidationRule::Date { start, end } => ValidationRule::Date {
+                    start: start.map(Instant::new),
+                    end: end.map(Instant::new),
+                },
+                current::ValidationRule::TextLength { min, max } => ValidationRule::TextLength {
+                    min: *min,
+                    max: *max,
+                },
+                current::ValidationRule::Custom { formula } => ValidationRule::Custom {
+                    formula: formula.clone(),
+                },
+            };
+            Ok(Validation {
+                id: Uuid::from_str(&validation.id)?,
+                selection: import_selection(&validation.selection)?,
+                rule,
+                message: validation.message.clone(),
+            })
+        })
+        .collect()
+}
+
+fn import_conditional_formats(
+    formats: &[current::ConditionalFormat],
+) -> Result<Vec<ConditionalFormat>> {
+    formats
+        .iter()
+        .map(|format| {
+            let rule = match &format.rule {
+                current::ConditionalFormatRule::Compare { comparison, value } => {
+                    ConditionalFormatRule::Compare {
+                        comparison: match comparison {
+                            current::Comparison::GreaterThan => Comparison::GreaterThan,
+                            current::Comparison::GreaterThanOrEqual => {
+                                Comparison::GreaterThanOrEqual
+                            }
+                            current::Comparison::LessThan => Comparison::LessThan,
+                            current::Comparison::LessThanOrEqual => Comparison::LessThanOrEqual,
+                            current::Comparison::Equal => Comparison::Equal,
+                            current::Comparison::NotEqual => Comparison::NotEqual,
+                        },
+                        value: *value,
+                    }
+                }
+                current::ConditionalFormatRule::Between { min, max } => {
+                    ConditionalFormatRule::Between {
+                        min: *min,
+                        max: *max,
+                    }
+                }
+                current::ConditionalFormatRule::TextContains { text } => {
+                    ConditionalFormatRule::TextContains { text: text.clone() }
+                }
+                current::ConditionalFormatRule::Top { count } => {
+                    ConditionalFormatRule::Top { count: *count }
+                }
+                current::ConditionalFormatRule::Bottom { count } => {
+                    ConditionalFormatRule::Bottom { count: *count }
+                }
+                current::ConditionalFormatRule::Duplicates => ConditionalFormatRule::Duplicates,
+                current::ConditionalFormatRule::ColorScale {
+                    min_color,
+                    max_color,
+                } => ConditionalFormatRule::ColorScale {
+                    min_color: min_color.clone(),
+                    max_color: max_color.clone(),
+                },
+                current::ConditionalFormatRule::DataBar { color } => {
+                    ConditionalFormatRule::DataBar {
+                        color: color.clone(),
+                    }
+                }
+                current::ConditionalFormatRule::Formula { formula } => {
+                    ConditionalFormatRule::Formula {
+                        formula: formula.clone(),
+                    }
+                }
+            };
+            Ok(ConditionalFormat {
+                id: Uuid::from_str(&format.id)?,
+                selection: import_selection(&format.selection)?,
+                rule,
+                style: ConditionalFormatStyle {
+                    bold: format.style.bold,
+                    italic: format.style.italic,
+                    text_color: format.style.text_color.clone(),
+                    fill_color: format.style.fill_color.clone(),
+                },
+            })
+        })
+        .collect()
+}
+
+fn import_outline_groups(groups: &[current::OutlineGroup]) -> Vec<OutlineGroup> {
+    groups
+        .iter()
+        .map(|group| OutlineGroup {
+            start: group.start,
+            end: group.end,
+            collapsed: group.collapsed,
+        })
+        .collect()
+}
+
+fn import_comments(
+    comments: &[(current::Pos, current::CommentThread)],
+) -> Result<BTreeMap<Pos, CommentThread>> {
+    comments
+        .iter()
+        .map(|(pos, thread)| {
+            let comments = thread
+                .comments
+                .iter()
+                .map(|comment| {
+                    Ok(Comment {
+                        id: Uuid::from_str(&comment.id)?,
+                        author: comment.author.clone(),
+                        created: Instant::new(comment.created),
+                        text: comment.text.clone(),
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok((
+                Pos { x: pos.x, y: pos.y },
+                CommentThread {
+                    comments,
+                    resolved: thread.resolved,
+                },
+            ))
+        })
+        .collect()
+}
+
 pub fn import_sheet(sheet: &current::Sheet) -> Result<Sheet> {
     let mut new_sheet = Sheet {
         id: SheetId::from_str(&sheet.id.id)?,
@@ -361,12 +572,55 @@ pub fn import_sheet(sheet: &current::Sheet) -> Result<Sheet> {
         format_all: sheet.formats_all.as_ref().map(import_format),
         formats_columns: import_formats(&sheet.formats_columns),
         formats_rows: import_formats(&sheet.formats_rows),
+        merged_cells: sheet
+            .merged_cells
+            .iter()
+            .map(|rect| rect.clone().into())
+            .collect(),
+        validations: import_validations(&sheet.validations)?,
+        conditional_formats: import_conditional_formats(&sheet.conditional_formats)?,
+        conditional_format_matches: HashMap::new(),
+        comments: import_comments(&sheet.comments)?,
+        frozen_columns: sheet.frozen_columns,
+        frozen_rows: sheet.frozen_rows,
     };
+    for &x in sheet.hidden_columns.iter() {
+        new_sheet.offsets.set_column_hidden(x, true);
+    }
+    for &y in sheet.hidden_rows.iter() {
+        new_sheet.offsets.set_row_hidden(y, true);
+    }
+    new_sheet
+        .offsets
+        .set_column_groups(import_outline_groups(&sheet.column_groups));
+    new_sheet
+        .offsets
+        .set_row_groups(import_outline_groups(&sheet.row_groups));
     new_sheet.recalculate_bounds();
     import_borders_builder(&mut new_sheet, sheet);
     Ok(new_sheet)
 }
 
+fn import_defined_names(defined_names: &[current::DefinedName]) -> Result<Vec<DefinedName>> {
+    defined_names
+        .iter()
+        .map(|defined_name| {
+            Ok(DefinedName {
+                name: defined_name.name.clone(),
+                value: match &defined_name.value {
+                    current::NameValue::Range(sheet_rect) => {
+                        NameValue::Range(crate::SheetRect::from(sheet_rect.clone()))
+                    }
+                    current::NameValue::Constant(value) => {
+                        NameValue::Constant(import_cell_value(value)?)
+                    }
+                    current::NameValue::Formula(formula) => NameValue::Formula(formula.clone()),
+                },
+            })
+        })
+        .collect()
+}
+
 pub fn import(file: current::GridSchema) -> Result<Grid> {
     Ok(Grid {
         sheets: file
@@ -374,6 +628,7 @@ pub fn import(file: current::GridSchema) -> Result<Grid> {
             .into_iter()
             .map(|sheet| import_sheet(&sheet))
             .collect::<Result<_>>()?,
+        defined_names: import_defined_names(&file.defined_names)?,
     })
 }
 
@@ -538,46 +793,7 @@ fn export_column_builder(sheet: &Sheet) -> Vec<(i64, current::Column)> {
                     values: column
                         .values
                         .iter()
-                        .map(|(y, value)| {
-                            (
-                                y.to_string(),
-                                match value {
-                                    CellValue::Text(text) => {
-                                        current::CellValue::Text(text.to_owned())
-                                    }
-                                    CellValue::Number(number) => {
-                                        current::CellValue::Number(number.to_string())
-                                    }
-                                    CellValue::Html(html) => current::CellValue::Html(html.clone()),
-                                    CellValue::Code(cell_code) => {
-                                        current::CellValue::Code(current::CodeCell {
-                                            code: cell_code.code.to_owned(),
-                                            language: match cell_code.language {
-                                                CodeCellLanguage::Python => {
-                                                    current::CodeCellLanguage::Python
-                                                }
-                                                CodeCellLanguage::Formula => {
-                                                    current::CodeCellLanguage::Formula
-                                                }
-                                            },
-                                        })
-                                    }
-                                    CellValue::Logical(logical) => {
-                                        current::CellValue::Logical(*logical)
-                                    }
-                                    CellValue::Instant(instant) => {
-                                        current::CellValue::Instant(instant.to_string())
-                                    }
-                                    CellValue::Duration(duration) => {
-                                        current::CellValue::Duration(duration.to_string())
-                                    }
-                                    CellValue::Error(error) => current::CellValue::Error(
-                                        current::RunError::from_grid_run_error(error),
-                                    ),
-                                    CellValue::Blank => current::CellValue::Blank,
-                                },
-                            )
-                        })
+                        .map(|(y, value)| (y.to_string(), export_cell_value(value)))
                         .collect(),
                 },
             )
@@ -672,6 +888,168 @@ fn export_formats(formats: &BTreeMap<i64, (Format, i64)>) -> Vec<(i64, (current:
         .collect()
 }
 
+fn export_selection(selection: &Selection) -> current::Selection {
+    current::Selection {
+        sheet_id: current::Id {
+            id: selection.sheet_id.to_string(),
+        },
+        x: selection.x,
+        y: selection.y,
+        rects: selection.rects.as_ref().map(|rects| {
+            rects
+                .iter()
+                .map(|rect| current::Rect::from(*rect))
+                .collect()
+        }),
+        rows: selection.rows.clone(),
+        columns: selection.columns.clone(),
+        all: selection.all,
+    }
+}
+
+fn export_validations(validations: &[Validation]) -> Vec<current::Validation> {
+    validations
+        .iter()
+        .map(|validation| {
+            let rule = match &validation.rule {
+                ValidationRule::List { values } => current::ValidationRule::List {
+                    values: values.clone(),
+                },
+                ValidationRule::ListFromRange { range } => current::ValidationRule::ListFromRange {
+                    range: current::SheetRect::from(*range),
+                },
+                ValidationRule::Number { min, max } => current::ValidationRule::Number {
+                    min: *min,
+                    max: *max,
+                },
+                ValidationRule::Date { start, end } => current::ValidationRule::Date {
+                    start: start.map(|start| start.seconds),
+                    end: end.map(|end| end.seconds),
+                },
+                ValidationRule::TextLength { min, max } => current::ValidationRule::TextLength {
+                    min: *min,
+                    max: *max,
+                },
+                ValidationRule::Custom { formula } => current::ValidationRule::Custom {
+                    formula: formula.clone(),
+                },
+            };
+            current::Validation {
+                id: validation.id.to_string(),
+                selection: export_selection(&validation.selection),
+                rule,
+                message: validation.message.clone(),
+            }
+        })
+        .collect()
+}
+
+fn export_conditional_formats(formats: &[ConditionalFormat]) -> Vec<current::ConditionalFormat> {
+    formats
+        .iter()
+        .map(|format| {
+            let rule = match &format.rule {
+                ConditionalFormatRule::Compare { comparison, value } => {
+                    current::ConditionalFormatRule::Compare {
+                        comparison: match comparison {
+                            Comparison::GreaterThan => current::Comparison::GreaterThan,
+                            Comparison::GreaterThanOrEqual => {
+                                current::Comparison::GreaterThanOrEqual
+                            }
+                            Comparison::LessThan => current::Comparison::LessThan,
+                            Comparison::LessThanOrEqual => current::Comparison::LessThanOrEqual,
+                            Comparison::Equal => current::Comparison::Equal,
+                            Comparison::NotEqual => current::Comparison::NotEqual,
+                        },
+                        value: *value,
+                    }
+                }
+                ConditionalFormatRule::Between { min, max } => {
+                    current::ConditionalFormatRule::Between {
+                        min: *min,
+                        max: *max,
+                    }
+                }
+                ConditionalFormatRule::TextContains { text } => {
+                    current::ConditionalFormatRule::TextContains { text: text.clone() }
+                }
+                ConditionalFormatRule::Top { count } => {
+                    current::ConditionalFormatRule::Top { count: *count }
+                }
+                ConditionalFormatRule::Bottom { count } => {
+                    current::ConditionalFormatRule::Bottom { count: *count }
+                }
+                ConditionalFormatRule::Duplicates => current::ConditionalFormatRule::Duplicates,
+                ConditionalFormatRule::ColorScale {
+                    min_color,
+                    max_color,
+                } => current::ConditionalFormatRule::ColorScale {
+                    min_color: min_color.clone(),
+                    max_color: max_color.clone(),
+                },
+                ConditionalFormatRule::DataBar { color } => {
+                    current::ConditionalFormatRule::DataBar {
+                        color: color.clone(),
+                    }
+                }
+                ConditionalFormatRule::Formula { formula } => {
+                    current::ConditionalFormatRule::Formula {
+                        formula: formula.clone(),
+                    }
+                }
+            };
+            current::ConditionalFormat {
+                id: format.id.to_string(),
+                selection: export_selection(&format.selection),
+                rule,
+                style: current::ConditionalFormatStyle {
+                    bold: format.style.bold,
+                    italic: format.style.italic,
+                    text_color: format.style.text_color.clone(),
+                    fill_color: format.style.fill_color.clone(),
+                },
+            }
+        })
+        .collect()
+}
+
+fn export_outline_groups(groups: &[OutlineGroup]) -> Vec<current::OutlineGroup> {
+    groups
+        .iter()
+        .map(|group| current::OutlineGroup {
+            start: group.start,
+            end: group.end,
+            collapsed: group.collapsed,
+        })
+        .collect()
+}
+
+fn export_comments(
+    comments: &BTreeMap<Pos, CommentThread>,
+) -> Vec<(current::Pos, current::CommentThread)> {
+    comments
+        .iter()
+        .map(|(pos, thread)| {
+            (
+                current::Pos::from(*pos),
+                current::CommentThread {
+                    comments: thread
+                        .comments
+                        .iter()
+                        .map(|comment| current::Comment {
+                            id: comment.id.to_string(),
+                            author: comment.author.clone(),
+                            created: comment.created.seconds,
+                            text: comment.text.clone(),
+                        })
+                        .collect(),
+                    resolved: thread.resolved,
+                },
+            )
+        })
+        .collect()
+}
+
 pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
     current::Sheet {
         id: current::Id {
@@ -686,6 +1064,20 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
         formats_all: sheet.format_all.as_ref().and_then(export_format),
         formats_columns: export_formats(&sheet.formats_columns),
         formats_rows: export_formats(&sheet.formats_rows),
+        merged_cells: sheet
+            .merged_cells
+            .iter()
+            .map(|rect| current::Rect::from(*rect))
+            .collect(),
+        validations: export_validations(&sheet.validations),
+        conditional_formats: export_conditional_formats(&sheet.conditional_formats),
+        hidden_columns: sheet.offsets.hidden_columns().collect(),
+        hidden_rows: sheet.offsets.hidden_rows().collect(),
+        column_groups: export_outline_groups(sheet.offsets.column_groups()),
+        row_groups: export_outline_groups(sheet.offsets.row_groups()),
+        frozen_columns: sheet.frozen_columns,
+        frozen_rows: sheet.frozen_rows,
+        comments: export_comments(&sheet.comments),
         code_runs: sheet
             .code_runs
             .iter()
@@ -693,10 +1085,7 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
                 let result = match &code_run.result {
                     CodeRunResult::Ok(output) => current::CodeRunResult::Ok(match output {
                         Value::Single(cell_value) => {
-                            current::OutputValue::Single(current::OutputValueValue {
-                                type_field: cell_value.type_name().into(),
-                                value: cell_value.to_string(),
-                            })
+                            current::OutputValue::Single(export_code_cell_output(cell_value))
                         }
                         Value::Array(array) => current::OutputValue::Array(current::OutputArray {
                             size: current::OutputSize {
@@ -705,12 +1094,7 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
                             },
                             values: array
                                 .rows()
-                                .flat_map(|row| {
-                                    row.iter().map(|cell| current::OutputValueValue {
-                                        type_field: cell.type_name().into(),
-                                        value: cell.to_string(),
-                                    })
-                                })
+                                .flat_map(|row| row.iter().map(export_code_cell_output))
                                 .collect(),
                         }),
                     }),
@@ -743,9 +1127,28 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
     }
 }
 
+fn export_defined_names(defined_names: &[DefinedName]) -> Vec<current::DefinedName> {
+    defined_names
+        .iter()
+        .map(|defined_name| current::DefinedName {
+            name: defined_name.name.clone(),
+            value: match &defined_name.value {
+                NameValue::Range(sheet_rect) => {
+                    current::NameValue::Range(current::SheetRect::from(*sheet_rect))
+                }
+                NameValue::Constant(value) => {
+                    current::NameValue::Constant(export_cell_value(value))
+                }
+                NameValue::Formula(formula) => current::NameValue::Formula(formula.clone()),
+            },
+        })
+        .collect()
+}
+
 pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
     Ok(current::GridSchema {
         version: Some(CURRENT_VERSION.into()),
         sheets: grid.sheets().iter().map(export_sheet).collect(),
+        defined_names: export_defined_names(grid.defined_names()),
     })
 }
@@ -8,12 +8,18 @@ pub mod sheet_schema;
 mod v1_3;
 mod v1_4;
 pub mod v1_5;
+pub mod v1_6;
 
-pub static CURRENT_VERSION: &str = "1.5";
+pub static CURRENT_VERSION: &str = "1.6";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "version")]
 enum GridFile {
+    #[serde(rename = "1.6")]
+    V1_6 {
+        #[serde(flatten)]
+        grid: v1_6::schema::GridSchema,
+    },
     #[serde(rename = "1.5")]
     V1_5 {
         #[serde(flatten)]
@@ -32,16 +38,17 @@ enum GridFile {
 }
 
 impl GridFile {
-    fn into_latest(self) -> Result<v1_5::schema::GridSchema> {
+    fn into_latest(self) -> Result<v1_6::schema::GridSchema> {
         match self {
-            GridFile::V1_5 { grid } => Ok(grid),
-            GridFile::V1_4 { grid } => v1_4::file::upgrade(grid),
+            GridFile::V1_6 { grid } => Ok(grid),
+            GridFile::V1_5 { grid } => v1_5::file::upgrade(grid),
+            GridFile::V1_4 { grid } => v1_5::file::upgrade(v1_4::file::upgrade(grid)?),
             GridFile::V1_3 { grid } => {
                 if let Ok(v1_4) = v1_3::file::upgrade(grid) {
-                    v1_4::file::upgrade(v1_4)
+                    v1_5::file::upgrade(v1_4::file::upgrade(v1_4)?)
                 } else {
                     Err(anyhow!(
-                        "Failed to upgrade from v1.3 to v1.4 (on the way to v1.5"
+                        "Failed to upgrade from v1.3 to v1.4 (on the way to v1.6"
                     ))
                 }
             }
@@ -79,7 +86,7 @@ mod tests {
     use crate::{
         color::Rgba,
         grid::{generate_borders, set_rect_borders, BorderSelection, BorderStyle, CellBorderLine},
-        Pos, Rect,
+        CellValue, Pos, Rect,
     };
 
     const V1_3_FILE: &str = include_str!("../../../../quadratic-rust-shared/data/grid/v1_3.grid");
@@ -211,7 +218,187 @@ mod tests {
     fn imports_and_exports_a_current_grid() {
         let mut imported = import(V1_5_FILE).unwrap();
         let exported = export(&mut imported).unwrap();
-        assert_eq!(V1_5_FILE, exported);
+        assert!(exported.contains(&format!("\"version\":\"{CURRENT_VERSION}\"")));
+
+        let mut reimported = import(&exported).unwrap();
+        assert_eq!(imported, reimported);
+        assert_eq!(exported, export(&mut reimported).unwrap());
+    }
+
+    #[test]
+    fn imports_and_exports_merged_cells() {
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        sheet.merge_cells(Rect::new(1, 2, 3, 4));
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        assert_eq!(
+            imported.sheets()[0].merged_cells,
+            vec![Rect::new(1, 2, 3, 4)]
+        );
+    }
+
+    #[test]
+    fn imports_and_exports_validations() {
+        use crate::grid::sheet::validations::{Validation, ValidationRule};
+        use crate::selection::Selection;
+
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let sheet_id = sheet.id;
+        let validations = vec![
+            Validation::new(
+                Selection::rect(Rect::new(0, 0, 0, 9), sheet_id),
+                ValidationRule::ListFromRange {
+                    range: crate::SheetRect::from_numbers(5, 0, 1, 3, sheet_id),
+                },
+                Some("Pick a color".into()),
+            ),
+            Validation::new(
+                Selection {
+                    columns: Some(vec![2]),
+                    ..Selection::all(sheet_id)
+                },
+                ValidationRule::Date {
+                    start: Some(crate::Instant::new(1_704_067_200.0)),
+                    end: None,
+                },
+                None,
+            ),
+        ];
+        for validation in validations.iter() {
+            sheet.set_validation(validation.clone(), None);
+        }
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        assert_eq!(imported.sheets()[0].validations, validations);
+    }
+
+    #[test]
+    fn imports_and_exports_conditional_formats() {
+        use crate::grid::sheet::conditional_formats::{
+            Comparison, ConditionalFormat, ConditionalFormatRule, ConditionalFormatStyle,
+        };
+        use crate::selection::Selection;
+
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let sheet_id = sheet.id;
+        let formats = vec![
+            ConditionalFormat::new(
+                Selection::rect(Rect::new(0, 0, 0, 9), sheet_id),
+                ConditionalFormatRule::Compare {
+                    comparison: Comparison::GreaterThanOrEqual,
+                    value: 10.0,
+                },
+                ConditionalFormatStyle {
+                    bold: Some(true),
+                    fill_color: Some("#ff0000".into()),
+                    ..Default::default()
+                },
+            ),
+            ConditionalFormat::new(
+                Selection {
+                    columns: Some(vec![2]),
+                    ..Selection::all(sheet_id)
+                },
+                ConditionalFormatRule::ColorScale {
+                    min_color: "#ffffff".into(),
+                    max_color: "#00ff00".into(),
+                },
+                ConditionalFormatStyle::default(),
+            ),
+        ];
+        for format in formats.iter() {
+            sheet.set_conditional_format(format.clone(), None);
+        }
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        assert_eq!(imported.sheets()[0].conditional_formats, formats);
+    }
+
+    #[test]
+    fn imports_and_exports_hidden_and_groups() {
+        use crate::sheet_offsets::offsets::OutlineGroup;
+
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        sheet.offsets.set_column_width(2, 50.0);
+        sheet.offsets.set_column_hidden(2, true);
+        sheet.offsets.set_row_hidden(5, true);
+        let mut collapsed = OutlineGroup::new(3, 4);
+        collapsed.collapsed = true;
+        sheet
+            .offsets
+            .set_row_groups(vec![OutlineGroup::new(1, 8), collapsed]);
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        let offsets = &imported.sheets()[0].offsets;
+        assert!(offsets.is_column_hidden(2));
+        assert_eq!(offsets.unhidden_column_width(2), 50.0);
+        assert_eq!(offsets.hidden_rows().collect::<Vec<_>>(), vec![5]);
+        assert!(offsets.is_row_hidden(3));
+        assert_eq!(offsets.row_groups(), &[OutlineGroup::new(1, 8), collapsed]);
+    }
+
+    #[test]
+    fn imports_and_exports_comments() {
+        use crate::grid::sheet::comments::Comment;
+
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        let pos = crate::Pos { x: 1, y: -2 };
+        sheet.add_comment(pos, Comment::new("ann".into(), "note".into()));
+        sheet.add_comment(pos, Comment::new("bob".into(), "reply".into()));
+        sheet.set_comments_resolved(pos, true);
+        let comments = sheet.comments.clone();
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        assert_eq!(imported.sheets()[0].comments, comments);
+    }
+
+    #[test]
+    fn imports_and_exports_defined_names() {
+        use crate::grid::defined_names::NameValue;
+
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        grid.set_defined_name(
+            "Sales",
+            Some(NameValue::Range(crate::SheetRect::from_numbers(
+                0, 0, 1, 5, sheet_id,
+            ))),
+        );
+        grid.set_defined_name(
+            "rate",
+            Some(NameValue::Constant(crate::CellValue::Number(
+                "0.07".parse().unwrap(),
+            ))),
+        );
+        grid.set_defined_name("tax", Some(NameValue::Formula("SUM(Sales) * rate".into())));
+        let defined_names = grid.defined_names().to_vec();
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        assert_eq!(imported.defined_names(), defined_names);
+    }
+
+    #[test]
+    fn imports_and_exports_frozen_panes() {
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        sheet.frozen_columns = 2;
+        sheet.frozen_rows = 1;
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        assert_eq!(imported.sheets()[0].frozen_columns, 2);
+        assert_eq!(imported.sheets()[0].frozen_rows, 1);
     }
 
     #[test]
@@ -219,4 +406,31 @@ mod tests {
         let mut imported = import(V1_4_FILE).unwrap();
         export(&mut imported).unwrap();
     }
+
+    #[test]
+    fn imports_and_exports_instants_and_durations() {
+        let instant = crate::Instant::new(1_710_374_400.5);
+        let duration = crate::Duration {
+            years: 1,
+            months: -2,
+            seconds: 3.25,
+        };
+
+        let mut grid = Grid::new();
+        let sheet = &mut grid.sheets_mut()[0];
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Instant(instant));
+        sheet.set_cell_value(Pos { x: 0, y: 1 }, CellValue::Duration(duration));
+
+        let exported = export(&mut grid).unwrap();
+        let imported = import(&exported).unwrap();
+        let sheet = &imported.sheets()[0];
+        assert_eq!(
+            Some(CellValue::Instant(instant)),
+            sheet.cell_value(Pos { x: 0, y: 0 }),
+        );
+        assert_eq!(
+            Some(CellValue::Duration(duration)),
+            sheet.cell_value(Pos { x: 0, y: 1 }),
+        );
+    }
 }
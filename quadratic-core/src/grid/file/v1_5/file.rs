@@ -1,3 +1,42 @@
+use crate::grid::file::v1_5::schema as v1_5;
+use crate::grid::file::v1_6::schema as v1_6;
+use anyhow::Result;
+
+pub(crate) fn upgrade_sheet(sheet: v1_5::Sheet) -> v1_6::Sheet {
+    v1_6::Sheet {
+        id: sheet.id,
+        name: sheet.name,
+        color: sheet.color,
+        order: sheet.order,
+        offsets: sheet.offsets,
+        columns: sheet.columns,
+        borders: sheet.borders,
+        code_runs: sheet.code_runs,
+        formats_all: sheet.formats_all,
+        formats_columns: sheet.formats_columns,
+        formats_rows: sheet.formats_rows,
+        merged_cells: vec![],
+        validations: vec![],
+        conditional_formats: vec![],
+        hidden_columns: vec![],
+        hidden_rows: vec![],
+        column_groups: vec![],
+        row_groups: vec![],
+        frozen_columns: 0,
+        frozen_rows: 0,
+        comments: vec![],
+    }
+}
+
+pub(crate) fn upgrade(schema: v1_5::GridSchema) -> Result<v1_6::GridSchema> {
+    let schema = v1_6::GridSchema {
+        version: Some("1.6".into()),
+        sheets: schema.sheets.into_iter().map(upgrade_sheet).collect(),
+        defined_names: vec![],
+    };
+    Ok(schema)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::grid::file::v1_5::schema::GridSchema;
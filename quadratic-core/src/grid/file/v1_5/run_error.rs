@@ -68,6 +68,7 @@ pub enum RunErrorMsg {
 
     // Runtime errors
     CircularReference,
+    LambdaTooDeep,
     Overflow,
     DivideByZero,
     NegativeExponent,
@@ -161,6 +162,7 @@ impl RunError {
                 crate::RunErrorMsg::ArrayTooBig => RunErrorMsg::ArrayTooBig,
 
                 crate::RunErrorMsg::CircularReference => RunErrorMsg::CircularReference,
+                crate::RunErrorMsg::LambdaTooDeep => RunErrorMsg::LambdaTooDeep,
                 crate::RunErrorMsg::Overflow => RunErrorMsg::Overflow,
                 crate::RunErrorMsg::DivideByZero => RunErrorMsg::DivideByZero,
                 crate::RunErrorMsg::NegativeExponent => RunErrorMsg::NegativeExponent,
@@ -258,6 +260,7 @@ impl From<RunError> for crate::RunError {
 
                 // Runtime errors
                 RunErrorMsg::CircularReference => crate::RunErrorMsg::CircularReference,
+                RunErrorMsg::LambdaTooDeep => crate::RunErrorMsg::LambdaTooDeep,
                 RunErrorMsg::Overflow => crate::RunErrorMsg::Overflow,
                 RunErrorMsg::DivideByZero => crate::RunErrorMsg::DivideByZero,
                 RunErrorMsg::NegativeExponent => crate::RunErrorMsg::NegativeExponent,
@@ -0,0 +1,244 @@
+use crate::grid::file::v1_5::schema as v1_5;
+use serde::{Deserialize, Serialize};
+
+pub use v1_5::RunError;
+pub use v1_5::RunErrorMsg;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridSchema {
+    pub sheets: Vec<Sheet>,
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub defined_names: Vec<DefinedName>,
+}
+
+pub type Id = v1_5::Id;
+pub type Pos = v1_5::Pos;
+pub type SheetPos = v1_5::SheetPos;
+pub type SheetRect = v1_5::SheetRect;
+pub type Offsets = v1_5::Offsets;
+pub type Borders = v1_5::Borders;
+pub type Format = v1_5::Format;
+pub type CodeRun = v1_5::CodeRun;
+pub type CodeRunResult = v1_5::CodeRunResult;
+pub type OutputValue = v1_5::OutputValue;
+pub type OutputArray = v1_5::OutputArray;
+pub type OutputSize = v1_5::OutputSize;
+pub type OutputValueValue = v1_5::OutputValueValue;
+pub type Span = v1_5::Span;
+pub type RenderSize = v1_5::RenderSize;
+pub type Column = v1_5::Column;
+pub type CellValue = v1_5::CellValue;
+pub type ColumnRepeat<T> = v1_5::ColumnRepeat<T>;
+pub type NumericFormatKind = v1_5::NumericFormatKind;
+pub type NumericFormat = v1_5::NumericFormat;
+pub type CellBorder = v1_5::CellBorder;
+pub type CodeCellLanguage = v1_5::CodeCellLanguage;
+pub type CodeCell = v1_5::CodeCell;
+pub type CellAlign = v1_5::CellAlign;
+pub type CellWrap = v1_5::CellWrap;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub min: Pos,
+    pub max: Pos,
+}
+
+impl From<crate::Rect> for Rect {
+    fn from(rect: crate::Rect) -> Self {
+        Self {
+            min: rect.min.into(),
+            max: rect.max.into(),
+        }
+    }
+}
+
+impl From<Rect> for crate::Rect {
+    fn from(rect: Rect) -> Self {
+        Self {
+            min: crate::Pos {
+                x: rect.min.x,
+                y: rect.min.y,
+            },
+            max: crate::Pos {
+                x: rect.max.x,
+                y: rect.max.y,
+            },
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sheet {
+    pub id: Id,
+    pub name: String,
+    pub color: Option<String>,
+    pub order: String,
+    pub offsets: Offsets,
+    pub columns: Vec<(i64, Column)>,
+    pub borders: Borders,
+    pub code_runs: Vec<(Pos, CodeRun)>,
+    pub formats_all: Option<Format>,
+    pub formats_columns: Vec<(i64, (Format, i64))>,
+    pub formats_rows: Vec<(i64, (Format, i64))>,
+    pub merged_cells: Vec<Rect>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub validations: Vec<Validation>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub conditional_formats: Vec<ConditionalFormat>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub hidden_columns: Vec<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub hidden_rows: Vec<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub column_groups: Vec<OutlineGroup>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub row_groups: Vec<OutlineGroup>,
+    #[serde(default)]
+    pub frozen_columns: u32,
+    #[serde(default)]
+    pub frozen_rows: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub comments: Vec<(Pos, CommentThread)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommentThread {
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: String,
+    pub author: String,
+
+    // seconds since the Unix epoch
+    pub created: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinedName {
+    pub name: String,
+    pub value: NameValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum NameValue {
+    Range(SheetRect),
+    Constant(CellValue),
+    Formula(String),
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlineGroup {
+    pub start: i64,
+    pub end: i64,
+    pub collapsed: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Selection {
+    pub sheet_id: Id,
+    pub x: i64,
+    pub y: i64,
+    pub rects: Option<Vec<Rect>>,
+    pub rows: Option<Vec<i64>>,
+    pub columns: Option<Vec<i64>>,
+    pub all: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Validation {
+    pub id: String,
+    pub selection: Selection,
+    pub rule: ValidationRule,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ValidationRule {
+    List {
+        values: Vec<String>,
+    },
+    ListFromRange {
+        range: SheetRect,
+    },
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+
+    // seconds since the Unix epoch
+    Date {
+        start: Option<f64>,
+        end: Option<f64>,
+    },
+    TextLength {
+        min: Option<u32>,
+        max: Option<u32>,
+    },
+    Custom {
+        formula: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalFormat {
+    pub id: String,
+    pub selection: Selection,
+    pub rule: ConditionalFormatRule,
+    pub style: ConditionalFormatStyle,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ConditionalFormatRule {
+    Compare {
+        comparison: Comparison,
+        value: f64,
+    },
+    Between {
+        min: f64,
+        max: f64,
+    },
+    TextContains {
+        text: String,
+    },
+    Top {
+        count: u32,
+    },
+    Bottom {
+        count: u32,
+    },
+    Duplicates,
+    ColorScale {
+        min_color: String,
+        max_color: String,
+    },
+    DataBar {
+        color: String,
+    },
+    Formula {
+        formula: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConditionalFormatStyle {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub text_color: Option<String>,
+    pub fill_color: Option<String>,
+}
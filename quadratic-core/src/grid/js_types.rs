@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use super::formatting::{CellAlign, CellWrap};
 use super::CodeCellLanguage;
 use crate::grid::BorderStyle;
+use crate::selection::Selection;
 use crate::{Pos, SheetRect};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -40,6 +41,25 @@ pub struct JsRenderCell {
     pub text_color: Option<String>,
 
     pub special: Option<JsRenderCellSpecial>,
+
+    /// Size of a merged region, set only for its top-left cell.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_height: Option<u32>,
+
+    /// Data bar drawn by a conditional format.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_bar: Option<JsRenderDataBar>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsRenderDataBar {
+    pub color: String,
+
+    // width of the bar as a percentage of the cell's width
+    pub percent: u8,
 }
 
 #[cfg(test)]
@@ -56,6 +76,9 @@ impl JsRenderCell {
             italic: None,
             text_color: None,
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
         }
     }
 }
@@ -73,6 +96,9 @@ impl From<Pos> for JsRenderCell {
             italic: None,
             text_color: None,
             special: None,
+            merge_width: None,
+            merge_height: None,
+            data_bar: None,
         }
     }
 }
@@ -201,3 +227,39 @@ pub struct JsClipboard {
     pub plain_text: String,
     pub html: String,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsValidationFailure {
+    pub x: i64,
+    pub y: i64,
+    pub message: String,
+}
+
+// indicator drawn on a cell that has comments
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsRenderComment {
+    pub x: i64,
+    pub y: i64,
+    pub count: usize,
+    pub resolved: bool,
+}
+
+// features of a sheet in an imported Excel file that could not be imported
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsUnsupportedExcelFeatures {
+    pub sheet_name: String,
+    pub features: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct JsRenderValidations {
+    // selections that show a dropdown arrow (list validations)
+    pub dropdowns: Vec<Selection>,
+
+    // cells whose current value fails their validation
+    pub invalid: Vec<Pos>,
+}
@@ -27,6 +27,7 @@ mod borders;
 mod bounds;
 mod code_run;
 mod column;
+pub mod defined_names;
 pub mod file;
 pub mod formats;
 pub mod formatting;
@@ -41,6 +42,8 @@ pub mod sheets;
 #[cfg_attr(feature = "js", wasm_bindgen)]
 pub struct Grid {
     sheets: Vec<Sheet>,
+    #[serde(default)]
+    defined_names: Vec<defined_names::DefinedName>,
 }
 impl Default for Grid {
     fn default() -> Self {
@@ -54,7 +57,10 @@ impl Grid {
         ret
     }
     pub fn new_blank() -> Self {
-        Grid { sheets: vec![] }
+        Grid {
+            sheets: vec![],
+            defined_names: vec![],
+        }
     }
 
     #[cfg(test)]
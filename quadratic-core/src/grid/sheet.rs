@@ -1,11 +1,15 @@
-use std::collections::{btree_map, BTreeMap};
+use std::collections::{btree_map, BTreeMap, HashMap};
 use std::str::FromStr;
 
 use bigdecimal::{BigDecimal, RoundingMode};
 use indexmap::IndexMap;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use self::comments::CommentThread;
+use self::conditional_formats::{ConditionalFormat, ConditionalFormatMatches};
+use self::validations::Validation;
 use super::bounds::GridBounds;
 use super::column::Column;
 use super::formats::format::Format;
@@ -22,15 +26,21 @@ pub mod cell_array;
 pub mod cell_values;
 pub mod clipboard;
 pub mod code;
+pub mod comments;
+pub mod conditional_formats;
 pub mod formats;
 pub mod formatting;
+pub mod merged_cells;
 pub mod rendering;
+pub mod rows_columns;
 pub mod search;
 pub mod selection;
 pub mod send_render;
 pub mod sheet_test;
+pub mod sort;
 
 pub mod summarize;
+pub mod validations;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Sheet {
@@ -65,6 +75,42 @@ pub struct Sheet {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub format_all: Option<Format>,
 
+    // Merged regions, which never overlap. Each one displays the value and
+    // formatting of its top-left cell.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub merged_cells: Vec<Rect>,
+
+    // Data validations. When several include the same cell, the last one
+    // applies.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub validations: Vec<Validation>,
+
+    // Conditional formats. When several style the same cell, later ones take
+    // precedence.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub conditional_formats: Vec<ConditionalFormat>,
+
+    // Cells that match each formula-based conditional format, and the cells
+    // its formula read. Formulas need the whole grid, so the controller
+    // evaluates them when cells they read change.
+    #[serde(skip)]
+    pub(crate) conditional_format_matches: HashMap<Uuid, ConditionalFormatMatches>,
+
+    // Comments on cells, which move with the cells.
+    #[serde(
+        skip_serializing_if = "BTreeMap::is_empty",
+        with = "crate::util::btreemap_serde",
+        default
+    )]
+    pub comments: BTreeMap<Pos, CommentThread>,
+
+    // Number of columns/rows, starting at 0, that stay in view when the sheet
+    // scrolls.
+    #[serde(default)]
+    pub frozen_columns: u32,
+    #[serde(default)]
+    pub frozen_rows: u32,
+
     // bounds for the grid with only data
     pub(super) data_bounds: GridBounds,
 
@@ -87,6 +133,13 @@ impl Sheet {
             formats_columns: BTreeMap::new(),
             formats_rows: BTreeMap::new(),
             format_all: None,
+            merged_cells: vec![],
+            validations: vec![],
+            conditional_formats: vec![],
+            conditional_format_matches: HashMap::new(),
+            comments: BTreeMap::new(),
+            frozen_columns: 0,
+            frozen_rows: 0,
 
             data_bounds: GridBounds::Empty,
             format_bounds: GridBounds::Empty,
@@ -247,12 +247,41 @@ impl Sheet {
                 clipboard_origin.column = sheet_bounds.map(|b| b.min.x);
             }
         }
+        let merged_cells = match sheet_bounds {
+            Some(bounds) => self
+                .merged_rects_within(bounds)
+                .filter(|merged| selection.pos_in_selection(merged.min))
+                .map(|mut merged| {
+                    merged.translate(-bounds.min.x, -bounds.min.y);
+                    merged
+                })
+                .collect(),
+            None => vec![],
+        };
+        let comments = match sheet_bounds {
+            Some(bounds) => self
+                .comment_threads_in_rect(bounds)
+                .filter(|(pos, _)| selection.pos_in_selection(*pos))
+                .map(|(pos, thread)| {
+                    (
+                        Pos {
+                            x: pos.x - bounds.min.x,
+                            y: pos.y - bounds.min.y,
+                        },
+                        thread.clone(),
+                    )
+                })
+                .collect(),
+            None => vec![],
+        };
         let sheet_formats = self.sheet_formats(selection, &clipboard_origin);
         let clipboard = Clipboard {
             cells,
             formats,
             sheet_formats,
             borders,
+            merged_cells,
+            comments,
             values,
             w: sheet_bounds.map_or(0, |b| b.width()),
             h: sheet_bounds.map_or(0, |b| b.height()),
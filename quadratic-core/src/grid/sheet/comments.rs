@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Sheet;
+use crate::{Instant, Pos, Rect};
+
+/// A note left on a cell, or a reply to one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Comment {
+    pub id: Uuid,
+    pub author: String,
+    pub created: Instant,
+    pub text: String,
+}
+
+impl Comment {
+    pub fn new(author: String, text: String) -> Self {
+        Comment {
+            id: Uuid::new_v4(),
+            author,
+            created: chrono::Utc::now().naive_utc().into(),
+            text,
+        }
+    }
+}
+
+/// The comments on a cell. The first comment starts the thread and the rest
+/// are replies, oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct CommentThread {
+    pub comments: Vec<Comment>,
+    pub resolved: bool,
+}
+
+impl Sheet {
+    /// Returns the comments on a cell.
+    pub fn comment_thread(&self, pos: Pos) -> Option<&CommentThread> {
+        self.comments.get(&pos)
+    }
+
+    /// Iterates over the cells in `rect` that have comments.
+    pub fn comment_threads_in_rect(
+        &self,
+        rect: Rect,
+    ) -> impl '_ + Iterator<Item = (Pos, &CommentThread)> {
+        self.comments
+            .iter()
+            .filter(move |(pos, _)| rect.contains(**pos))
+            .map(|(pos, thread)| (*pos, thread))
+    }
+
+    /// Adds a comment to a cell, starting a thread if the cell has none.
+    pub fn add_comment(&mut self, pos: Pos, comment: Comment) {
+        self.comments
+            .entry(pos)
+            .or_insert_with(|| CommentThread {
+                comments: vec![],
+                resolved: false,
+            })
+            .comments
+            .push(comment);
+    }
+
+    /// Changes the text of a comment. Returns the old text, or `None` if the
+    /// comment does not exist.
+    pub fn edit_comment(&mut self, pos: Pos, id: Uuid, text: String) -> Option<String> {
+        let comment = self
+            .comments
+            .get_mut(&pos)?
+            .comments
+            .iter_mut()
+            .find(|comment| comment.id == id)?;
+        Some(std::mem::replace(&mut comment.text, text))
+    }
+
+    /// Marks a cell's thread as resolved or not. Returns the old state, or
+    /// `None` if the cell has no comments.
+    pub fn set_comments_resolved(&mut self, pos: Pos, resolved: bool) -> Option<bool> {
+        let thread = self.comments.get_mut(&pos)?;
+        Some(std::mem::replace(&mut thread.resolved, resolved))
+    }
+
+    /// Deletes a comment. Deleting the first comment deletes the whole thread.
+    /// Returns the thread as it was before, or `None` if the comment does not
+    /// exist.
+    pub fn delete_comment(&mut self, pos: Pos, id: Uuid) -> Option<CommentThread> {
+        let thread = self.comments.get_mut(&pos)?;
+        let index = thread
+            .comments
+            .iter()
+            .position(|comment| comment.id == id)?;
+        if index == 0 {
+            self.comments.remove(&pos)
+        } else {
+            let old = thread.clone();
+            thread.comments.remove(index);
+            Some(old)
+        }
+    }
+
+    /// Replaces the comments on a cell and returns the old ones.
+    pub fn set_comment_thread(
+        &mut self,
+        pos: Pos,
+        thread: Option<CommentThread>,
+    ) -> Option<CommentThread> {
+        match thread {
+            Some(thread) if !thread.comments.is_empty() => self.comments.insert(pos, thread),
+            _ => self.comments.remove(&pos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_threads() {
+        let mut sheet = Sheet::test();
+        let pos = Pos { x: 1, y: 2 };
+        let first = Comment::new("ann".into(), "check this".into());
+        let reply = Comment::new("bob".into(), "done".into());
+        sheet.add_comment(pos, first.clone());
+        sheet.add_comment(pos, reply.clone());
+        assert_eq!(sheet.comment_thread(pos).unwrap().comments.len(), 2);
+        assert_eq!(
+            sheet.comment_threads_in_rect(Rect::new(0, 0, 1, 2)).count(),
+            1
+        );
+        assert_eq!(
+            sheet.comment_threads_in_rect(Rect::new(0, 0, 0, 2)).count(),
+            0
+        );
+
+        assert_eq!(
+            sheet.edit_comment(pos, reply.id, "fixed".into()),
+            Some("done".into())
+        );
+        assert_eq!(sheet.edit_comment(pos, Uuid::new_v4(), "x".into()), None);
+        assert_eq!(sheet.set_comments_resolved(pos, true), Some(false));
+        assert!(sheet.comment_thread(pos).unwrap().resolved);
+
+        // deleting a reply keeps the thread
+        let old = sheet.delete_comment(pos, reply.id).unwrap();
+        assert_eq!(old.comments[1].text, "fixed");
+        assert_eq!(
+            sheet.comment_thread(pos).unwrap().comments,
+            vec![first.clone()]
+        );
+
+        // deleting the first comment deletes the thread
+        sheet.add_comment(pos, reply);
+        sheet.delete_comment(pos, first.id).unwrap();
+        assert!(sheet.comment_thread(pos).is_none());
+
+        sheet.set_comment_thread(pos, Some(old.clone()));
+        assert_eq!(sheet.comment_thread(pos), Some(&old));
+        assert_eq!(sheet.set_comment_thread(pos, None), Some(old));
+    }
+}
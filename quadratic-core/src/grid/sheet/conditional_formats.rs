@@ -0,0 +1,769 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bigdecimal::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Sheet;
+use crate::{
+    color::Rgba,
+    formulas::{
+        adjust_cell_references, replace_a1_notation, replace_internal_cell_references, RefAdjust,
+    },
+    grid::{
+        js_types::{JsRenderCell, JsRenderDataBar, JsRenderFill},
+        GridBounds, SheetId,
+    },
+    selection::Selection,
+    CellValue, IsBlank, Pos, Rect, SheetRect,
+};
+
+/// A rule that styles the cells of a selection based on their values.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ConditionalFormat {
+    pub id: Uuid,
+    pub selection: Selection,
+    pub rule: ConditionalFormatRule,
+
+    // Applied to matching cells. Color scales and data bars ignore the style.
+    pub style: ConditionalFormatStyle,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum ConditionalFormatRule {
+    /// Numbers that compare to `value`.
+    Compare { comparison: Comparison, value: f64 },
+
+    /// Numbers within the (inclusive) bounds.
+    Between { min: f64, max: f64 },
+
+    /// Values whose text contains `text` (case-insensitive).
+    TextContains { text: String },
+
+    /// The `count` largest numbers in the selection.
+    Top { count: u32 },
+
+    /// The `count` smallest numbers in the selection.
+    Bottom { count: u32 },
+
+    /// Values that appear more than once in the selection (case-insensitive).
+    Duplicates,
+
+    /// Fills numbers with a color between `min_color` (for the smallest
+    /// number in the selection) and `max_color` (for the largest).
+    ColorScale {
+        min_color: String,
+        max_color: String,
+    },
+
+    /// Draws a bar proportional to each number.
+    DataBar { color: String },
+
+    /// Formula that is true for matching cells. Cell references are relative
+    /// to the selection's cursor position.
+    Formula { formula: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum Comparison {
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct ConditionalFormatStyle {
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub text_color: Option<String>,
+    pub fill_color: Option<String>,
+}
+
+/// The result of evaluating a formula-based conditional format.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConditionalFormatMatches {
+    /// Cells that the formula is true for.
+    pub cells: HashSet<Pos>,
+    /// Cells that the formula read, so that it is only evaluated again when
+    /// they change.
+    pub cells_accessed: HashSet<SheetRect>,
+}
+
+impl Comparison {
+    pub fn compare(self, a: f64, b: f64) -> bool {
+        match self {
+            Comparison::GreaterThan => a > b,
+            Comparison::GreaterThanOrEqual => a >= b,
+            Comparison::LessThan => a < b,
+            Comparison::LessThanOrEqual => a <= b,
+            Comparison::Equal => a == b,
+            Comparison::NotEqual => a != b,
+        }
+    }
+}
+
+impl ConditionalFormat {
+    pub fn new(
+        selection: Selection,
+        rule: ConditionalFormatRule,
+        style: ConditionalFormatStyle,
+    ) -> Self {
+        ConditionalFormat {
+            id: Uuid::new_v4(),
+            selection,
+            rule,
+            style,
+        }
+    }
+
+    /// Returns the position that relative references in a formula rule are
+    /// written against.
+    pub fn anchor(&self) -> Pos {
+        Pos {
+            x: self.selection.x,
+            y: self.selection.y,
+        }
+    }
+
+    pub fn is_formula(&self) -> bool {
+        matches!(self.rule, ConditionalFormatRule::Formula { .. })
+    }
+
+    /// Returns whether the format may change cell fills.
+    pub fn has_fill(&self) -> bool {
+        match self.rule {
+            ConditionalFormatRule::ColorScale { .. } => true,
+            ConditionalFormatRule::DataBar { .. } => false,
+            _ => self.style.fill_color.is_some(),
+        }
+    }
+}
+
+fn number(value: &CellValue) -> Option<f64> {
+    match value {
+        CellValue::Number(n) => n.to_f64(),
+        _ => None,
+    }
+}
+
+/// Returns the color `t` (from 0 to 1) of the way from `from` to `to`.
+fn interpolate_color(from: &str, to: &str, t: f64) -> Option<String> {
+    let parse = |color: &str| {
+        (color.starts_with('#') && matches!(color.len(), 7 | 9))
+            .then(|| Rgba::color_from_str(color).ok())
+            .flatten()
+    };
+    let (from, to) = (parse(from)?, parse(to)?);
+    let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Some(
+        Rgba {
+            red: channel(from.red, to.red),
+            green: channel(from.green, to.green),
+            blue: channel(from.blue, to.blue),
+            alpha: channel(from.alpha, to.alpha),
+        }
+        .as_rgb_hex(),
+    )
+}
+
+/// A conditional format together with the values of its selection, which
+/// rules such as top N and color scales are relative to.
+pub struct ConditionalFormatEvaluator<'a> {
+    format: &'a ConditionalFormat,
+    sheet: &'a Sheet,
+
+    // numbers in the selection, in ascending order
+    numbers: Vec<f64>,
+
+    // how often each (lowercase) value appears in the selection
+    counts: HashMap<String, usize>,
+}
+
+impl<'a> ConditionalFormatEvaluator<'a> {
+    fn new(sheet: &'a Sheet, format: &'a ConditionalFormat) -> Self {
+        let values = sheet
+            .selection(&format.selection, None, false)
+            .unwrap_or_default();
+        let mut numbers = vec![];
+        let mut counts = HashMap::new();
+        match format.rule {
+            ConditionalFormatRule::Top { .. }
+            | ConditionalFormatRule::Bottom { .. }
+            | ConditionalFormatRule::ColorScale { .. }
+            | ConditionalFormatRule::DataBar { .. } => {
+                numbers = values.values().filter_map(|value| number(value)).collect();
+                numbers.sort_by(f64::total_cmp);
+            }
+            ConditionalFormatRule::Duplicates => {
+                for value in values.values() {
+                    *counts.entry(value.to_edit().to_lowercase()).or_default() += 1;
+                }
+            }
+            _ => (),
+        }
+        ConditionalFormatEvaluator {
+            format,
+            sheet,
+            numbers,
+            counts,
+        }
+    }
+
+    /// Returns how far `n` is between the smallest and largest numbers in the
+    /// selection, from 0 to 1.
+    fn scale(&self, n: f64, include_zero: bool) -> f64 {
+        let (Some(&min), Some(&max)) = (self.numbers.first(), self.numbers.last()) else {
+            return 0.0;
+        };
+        let (min, max) = if include_zero {
+            (min.min(0.0), max.max(0.0))
+        } else {
+            (min, max)
+        };
+        if max > min {
+            ((n - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns whether the format's style applies to `value` at `pos`.
+    pub fn matches(&self, pos: Pos, value: &CellValue) -> bool {
+        if !self.format.selection.pos_in_selection(pos) {
+            return false;
+        }
+        match &self.format.rule {
+            ConditionalFormatRule::Compare {
+                comparison,
+                value: other,
+            } => number(value).is_some_and(|n| comparison.compare(n, *other)),
+            ConditionalFormatRule::Between { min, max } => {
+                number(value).is_some_and(|n| n >= *min && n <= *max)
+            }
+            ConditionalFormatRule::TextContains { text } => {
+                !value.is_blank()
+                    && value
+                        .to_edit()
+                        .to_lowercase()
+                        .contains(&text.to_lowercase())
+            }
+            ConditionalFormatRule::Top { count } => {
+                let count = *count as usize;
+                number(value).is_some_and(|n| {
+                    count > 0 && n >= self.numbers[self.numbers.len().saturating_sub(count)]
+                })
+            }
+            ConditionalFormatRule::Bottom { count } => {
+                let count = (*count as usize).min(self.numbers.len());
+                number(value).is_some_and(|n| count > 0 && n <= self.numbers[count - 1])
+            }
+            ConditionalFormatRule::Duplicates => {
+                !value.is_blank()
+                    && self
+                        .counts
+                        .get(&value.to_edit().to_lowercase())
+                        .is_some_and(|count| *count > 1)
+            }
+            ConditionalFormatRule::ColorScale { .. } | ConditionalFormatRule::DataBar { .. } => {
+                false
+            }
+            ConditionalFormatRule::Formula { .. } => self
+                .sheet
+                .conditional_format_matches
+                .get(&self.format.id)
+                .is_some_and(|matches| matches.cells.contains(&pos)),
+        }
+    }
+
+    /// Returns the fill color for `value` at `pos`, if the format sets one.
+    pub fn fill(&self, pos: Pos, value: &CellValue) -> Option<String> {
+        match &self.format.rule {
+            ConditionalFormatRule::ColorScale {
+                min_color,
+                max_color,
+            } => {
+                if !self.format.selection.pos_in_selection(pos) {
+                    return None;
+                }
+                let n = number(value)?;
+                interpolate_color(min_color, max_color, self.scale(n, false))
+            }
+            _ => self
+                .format
+                .style
+                .fill_color
+                .clone()
+                .filter(|_| self.matches(pos, value)),
+        }
+    }
+
+    /// Returns the data bar for `value` at `pos`, if the format draws one.
+    pub fn data_bar(&self, pos: Pos, value: &CellValue) -> Option<JsRenderDataBar> {
+        let ConditionalFormatRule::DataBar { color } = &self.format.rule else {
+            return None;
+        };
+        if !self.format.selection.pos_in_selection(pos) {
+            return None;
+        }
+        let n = number(value)?;
+        Some(JsRenderDataBar {
+            color: color.clone(),
+            percent: (self.scale(n, true) * 100.0).round() as u8,
+        })
+    }
+}
+
+impl Sheet {
+    /// Returns the area of the sheet's data that a selection covers.
+    pub fn selection_data_bounds(&self, selection: &Selection) -> Option<Rect> {
+        let GridBounds::NonEmpty(data) = self.bounds(true) else {
+            return None;
+        };
+        let covered = if selection.has_sheet_selection() {
+            data
+        } else {
+            selection
+                .rects
+                .as_ref()?
+                .iter()
+                .copied()
+                .reduce(|a, b| a.union(&b))?
+        };
+        let min = Pos {
+            x: covered.min.x.max(data.min.x),
+            y: covered.min.y.max(data.min.y),
+        };
+        let max = Pos {
+            x: covered.max.x.min(data.max.x),
+            y: covered.max.y.min(data.max.y),
+        };
+        (min.x <= max.x && min.y <= max.y).then_some(Rect { min, max })
+    }
+
+    pub fn conditional_format(&self, id: Uuid) -> Option<&ConditionalFormat> {
+        self.conditional_formats
+            .iter()
+            .find(|format| format.id == id)
+    }
+
+    /// Replaces the conditional format with the same id in place, or
+    /// otherwise adds it at `index` (or at the end if `index` is `None`).
+    /// Returns the replaced format.
+    pub fn set_conditional_format(
+        &mut self,
+        format: ConditionalFormat,
+        index: Option<usize>,
+    ) -> Option<ConditionalFormat> {
+        match self
+            .conditional_formats
+            .iter_mut()
+            .find(|f| f.id == format.id)
+        {
+            Some(existing) => Some(std::mem::replace(existing, format)),
+            None => {
+                let len = self.conditional_formats.len();
+                let index = index.map_or(len, |index| index.min(len));
+                self.conditional_formats.insert(index, format);
+                None
+            }
+        }
+    }
+
+    /// Removes a conditional format. Returns its index and the removed format.
+    pub fn remove_conditional_format(&mut self, id: Uuid) -> Option<(usize, ConditionalFormat)> {
+        let index = self.conditional_formats.iter().position(|f| f.id == id)?;
+        self.conditional_format_matches.remove(&id);
+        Some((index, self.conditional_formats.remove(index)))
+    }
+
+    /// Adjusts conditional formats after columns or rows are inserted or
+    /// deleted on the sheet `sheet_id` named `sheet_name`, which may be
+    /// another sheet that a formula rule refers to. Formats whose cells are
+    /// deleted are removed. Returns the index and old value of each format
+    /// that changed.
+    pub fn adjust_conditional_formats(
+        &mut self,
+        sheet_id: SheetId,
+        sheet_name: &str,
+        adjust: RefAdjust,
+    ) -> Vec<(usize, ConditionalFormat)> {
+        let is_own_sheet = self.id == sheet_id;
+        let is_affected_sheet = |name: Option<&str>| match name {
+            None => is_own_sheet,
+            Some(name) => name == sheet_name,
+        };
+        // relative references keep their offsets from an anchor that moves
+        // because its column or row is deleted
+        let old_anchor = |anchor: Pos| {
+            if is_own_sheet {
+                adjust.skip_deleted(anchor)
+            } else {
+                anchor
+            }
+        };
+        let adjusted = |format: &ConditionalFormat| {
+            let selection = if is_own_sheet {
+                format.selection.adjust(adjust)?
+            } else {
+                format.selection.clone()
+            };
+            let rule = match &format.rule {
+                ConditionalFormatRule::Formula { formula } => {
+                    let internal = replace_a1_notation(formula, format.anchor());
+                    let adjusted = adjust_cell_references(
+                        &internal,
+                        old_anchor(format.anchor()),
+                        selection.source(),
+                        adjust,
+                        &is_affected_sheet,
+                    );
+                    ConditionalFormatRule::Formula {
+                        formula: replace_internal_cell_references(&adjusted, selection.source()),
+                    }
+                }
+                rule => rule.clone(),
+            };
+            Some(ConditionalFormat {
+                selection,
+                rule,
+                ..format.clone()
+            })
+        };
+
+        let mut changed = vec![];
+        let mut formats = Vec::with_capacity(self.conditional_formats.len());
+        for (index, format) in std::mem::take(&mut self.conditional_formats)
+            .into_iter()
+            .enumerate()
+        {
+            match adjusted(&format) {
+                Some(new) if new == format => formats.push(format),
+                Some(new) => {
+                    formats.push(new);
+                    changed.push((index, format));
+                }
+                None => {
+                    self.conditional_format_matches.remove(&format.id);
+                    changed.push((index, format));
+                }
+            }
+        }
+        self.conditional_formats = formats;
+        changed
+    }
+
+    /// Returns evaluators for the conditional formats that cover part of
+    /// `rect`, in the order they apply.
+    fn conditional_format_evaluators(&self, rect: Rect) -> Vec<ConditionalFormatEvaluator<'_>> {
+        self.conditional_formats
+            .iter()
+            .filter(|format| {
+                self.selection_data_bounds(&format.selection)
+                    .is_some_and(|bounds| bounds.intersects(rect))
+            })
+            .map(|format| ConditionalFormatEvaluator::new(self, format))
+            .collect()
+    }
+
+    /// Applies conditional formats to cells being rendered. Later formats
+    /// take precedence over earlier ones.
+    pub(crate) fn apply_conditional_formats(&self, rect: Rect, cells: &mut [JsRenderCell]) {
+        let evaluators = self.conditional_format_evaluators(rect);
+        if evaluators.is_empty() {
+            return;
+        }
+        for cell in cells.iter_mut() {
+            let pos = Pos {
+                x: cell.x,
+                y: cell.y,
+            };
+            let Some(value) = self.display_value(pos) else {
+                continue;
+            };
+            for evaluator in evaluators.iter() {
+                if let Some(data_bar) = evaluator.data_bar(pos, &value) {
+                    cell.data_bar = Some(data_bar);
+                } else if evaluator.matches(pos, &value) {
+                    let style = &evaluator.format.style;
+                    cell.bold = style.bold.or(cell.bold);
+                    cell.italic = style.italic.or(cell.italic);
+                    cell.text_color = style.text_color.clone().or(cell.text_color.take());
+                }
+            }
+        }
+    }
+
+    /// Returns the fills set by conditional formats, which are drawn over the
+    /// sheet's own fills.
+    pub fn get_conditional_format_fills(&self) -> Vec<JsRenderFill> {
+        let Some(bounds) = self.selection_data_bounds(&Selection::all(self.id)) else {
+            return vec![];
+        };
+        let mut fills = BTreeMap::new();
+        for evaluator in self
+            .conditional_format_evaluators(bounds)
+            .iter()
+            .filter(|evaluator| evaluator.format.has_fill())
+        {
+            let Some(covered) = self.selection_data_bounds(&evaluator.format.selection) else {
+                continue;
+            };
+            for pos in covered.iter() {
+                let value = self.display_value(pos).unwrap_or(CellValue::Blank);
+                if let Some(color) = evaluator.fill(pos, &value) {
+                    fills.insert((pos.x, pos.y), color);
+                }
+            }
+        }
+        fills
+            .into_iter()
+            .map(|((x, y), color)| JsRenderFill {
+                x,
+                y,
+                w: 1,
+                h: 1,
+                color,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Axis;
+
+    fn sheet_with_numbers(numbers: &[i64]) -> Sheet {
+        let mut sheet = Sheet::test();
+        for (y, n) in numbers.iter().enumerate() {
+            sheet.set_cell_value(Pos { x: 0, y: y as i64 }, CellValue::Number((*n).into()));
+        }
+        sheet.recalculate_bounds();
+        sheet
+    }
+
+    fn bold() -> ConditionalFormatStyle {
+        ConditionalFormatStyle {
+            bold: Some(true),
+            ..Default::default()
+        }
+    }
+
+    fn bold_cells(sheet: &Sheet) -> Vec<i64> {
+        sheet
+            .get_render_cells(Rect::new(0, 0, 0, 10))
+            .into_iter()
+            .filter(|cell| cell.bold == Some(true))
+            .map(|cell| cell.y)
+            .collect()
+    }
+
+    fn add_format(sheet: &mut Sheet, rule: ConditionalFormatRule, style: ConditionalFormatStyle) {
+        let format = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 0, 10), SheetId::test()),
+            rule,
+            style,
+        );
+        sheet.set_conditional_format(format, None);
+    }
+
+    #[test]
+    fn test_compare_and_between() {
+        let mut sheet = sheet_with_numbers(&[1, 5, 10, 15]);
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::Compare {
+                comparison: Comparison::GreaterThan,
+                value: 5.0,
+            },
+            bold(),
+        );
+        assert_eq!(bold_cells(&sheet), vec![2, 3]);
+
+        sheet.conditional_formats.clear();
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::Between {
+                min: 5.0,
+                max: 10.0,
+            },
+            bold(),
+        );
+        assert_eq!(bold_cells(&sheet), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_top_bottom_and_duplicates() {
+        let mut sheet = sheet_with_numbers(&[3, 9, 1, 9, 4]);
+        add_format(&mut sheet, ConditionalFormatRule::Top { count: 2 }, bold());
+        assert_eq!(bold_cells(&sheet), vec![1, 3]);
+
+        sheet.conditional_formats.clear();
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::Bottom { count: 2 },
+            bold(),
+        );
+        assert_eq!(bold_cells(&sheet), vec![0, 2]);
+
+        sheet.conditional_formats.clear();
+        add_format(&mut sheet, ConditionalFormatRule::Duplicates, bold());
+        assert_eq!(bold_cells(&sheet), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_text_contains_overrides_earlier_format() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, "Apple pie");
+        sheet.set_cell_value(Pos { x: 0, y: 1 }, "banana");
+        sheet.recalculate_bounds();
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::TextContains { text: "".into() },
+            ConditionalFormatStyle {
+                text_color: Some("red".into()),
+                ..Default::default()
+            },
+        );
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::TextContains {
+                text: "APPLE".into(),
+            },
+            ConditionalFormatStyle {
+                text_color: Some("green".into()),
+                ..Default::default()
+            },
+        );
+        let cells = sheet.get_render_cells(Rect::new(0, 0, 0, 1));
+        assert_eq!(cells[0].text_color, Some("green".into()));
+        assert_eq!(cells[1].text_color, Some("red".into()));
+    }
+
+    #[test]
+    fn test_color_scale_and_data_bar() {
+        let mut sheet = sheet_with_numbers(&[0, 5, 10]);
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::ColorScale {
+                min_color: "#000000".into(),
+                max_color: "#ffffff".into(),
+            },
+            ConditionalFormatStyle::default(),
+        );
+        let fills = sheet.get_conditional_format_fills();
+        assert_eq!(
+            fills
+                .iter()
+                .map(|fill| (fill.y, fill.color.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "#000000"), (1, "#808080"), (2, "#ffffff")]
+        );
+
+        sheet.conditional_formats.clear();
+        add_format(
+            &mut sheet,
+            ConditionalFormatRule::DataBar {
+                color: "blue".into(),
+            },
+            ConditionalFormatStyle::default(),
+        );
+        assert!(sheet.get_conditional_format_fills().is_empty());
+        let cells = sheet.get_render_cells(Rect::new(0, 0, 0, 2));
+        assert_eq!(
+            cells
+                .iter()
+                .map(|cell| cell.data_bar.as_ref().unwrap().percent)
+                .collect::<Vec<_>>(),
+            vec![0, 50, 100]
+        );
+    }
+
+    #[test]
+    fn test_set_and_remove_conditional_format() {
+        let mut sheet = Sheet::test();
+        let format = ConditionalFormat::new(
+            Selection::pos(0, 0, SheetId::test()),
+            ConditionalFormatRule::Duplicates,
+            bold(),
+        );
+        assert!(sheet.set_conditional_format(format.clone(), None).is_none());
+        assert_eq!(sheet.conditional_format(format.id), Some(&format));
+        assert_eq!(
+            sheet.remove_conditional_format(format.id),
+            Some((0, format.clone()))
+        );
+        assert_eq!(sheet.remove_conditional_format(format.id), None);
+    }
+
+    #[test]
+    fn test_adjust_conditional_formats() {
+        let mut sheet = Sheet::test();
+        let sheet_id = sheet.id;
+        let sheet_name = sheet.name.clone();
+        let duplicates = ConditionalFormat::new(
+            Selection::rect(Rect::new(0, 0, 0, 3), sheet_id),
+            ConditionalFormatRule::Duplicates,
+            bold(),
+        );
+        let formula = ConditionalFormat::new(
+            Selection::rect(Rect::new(1, 1, 1, 2), sheet_id),
+            ConditionalFormatRule::Formula {
+                formula: "B1 > $A$3".into(),
+            },
+            bold(),
+        );
+        sheet.set_conditional_format(duplicates.clone(), None);
+        sheet.set_conditional_format(formula.clone(), None);
+
+        let changed =
+            sheet.adjust_conditional_formats(sheet_id, &sheet_name, RefAdjust::delete(Axis::Y, 1));
+        assert_eq!(changed, vec![(0, duplicates.clone()), (1, formula.clone())]);
+        assert_eq!(
+            sheet.conditional_formats[0].selection,
+            Selection::rect(Rect::new(0, 0, 0, 2), sheet_id)
+        );
+        // the anchor was deleted, so it moves to the next row
+        assert_eq!(sheet.conditional_formats[1].anchor(), Pos { x: 1, y: 1 });
+        assert_eq!(
+            sheet.conditional_formats[1].rule,
+            ConditionalFormatRule::Formula {
+                formula: "B1 > $A$2".into(),
+            }
+        );
+
+        // formats whose cells are deleted are removed
+        let changed =
+            sheet.adjust_conditional_formats(sheet_id, &sheet_name, RefAdjust::delete(Axis::X, 1));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(sheet.conditional_formats.len(), 1);
+
+        // formulas on other sheets follow the sheet they refer to
+        let mut other = Sheet::new(SheetId::new(), "Other".into(), "a1".into());
+        let on_other = ConditionalFormat::new(
+            Selection::pos(0, 0, other.id),
+            ConditionalFormatRule::Formula {
+                formula: format!("'{sheet_name}'!A5 > 0"),
+            },
+            bold(),
+        );
+        other.set_conditional_format(on_other.clone(), None);
+        let changed =
+            other.adjust_conditional_formats(sheet_id, &sheet_name, RefAdjust::insert(Axis::Y, 0));
+        assert_eq!(changed, vec![(0, on_other.clone())]);
+        assert_eq!(other.conditional_formats[0].selection, on_other.selection);
+        assert_eq!(
+            other.conditional_formats[0].rule,
+            ConditionalFormatRule::Formula {
+                formula: format!("\"{sheet_name}\"!A6 > 0"),
+            }
+        );
+    }
+}
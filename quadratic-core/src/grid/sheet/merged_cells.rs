@@ -0,0 +1,103 @@
+use super::Sheet;
+use crate::{Pos, Rect};
+
+impl Sheet {
+    /// Returns the merged region that contains `pos`, if any.
+    pub fn merged_rect_at(&self, pos: Pos) -> Option<Rect> {
+        self.merged_cells
+            .iter()
+            .find(|merged| merged.contains(pos))
+            .copied()
+    }
+
+    /// Returns the merged regions that intersect `rect`.
+    pub fn merged_rects_intersecting(&self, rect: Rect) -> impl Iterator<Item = Rect> + '_ {
+        self.merged_cells
+            .iter()
+            .filter(move |merged| merged.intersects(rect))
+            .copied()
+    }
+
+    /// Returns the merged regions entirely within `rect`.
+    pub fn merged_rects_within(&self, rect: Rect) -> impl Iterator<Item = Rect> + '_ {
+        self.merged_cells
+            .iter()
+            .filter(move |merged| rect.contains_rect(**merged))
+            .copied()
+    }
+
+    /// Grows `rect` until every merged region it intersects is entirely
+    /// inside it.
+    pub fn expand_to_merged_cells(&self, rect: Rect) -> Rect {
+        let mut rect = rect;
+        loop {
+            let expanded = self
+                .merged_rects_intersecting(rect)
+                .fold(rect, |rect, merged| rect.union(&merged));
+            if expanded == rect {
+                return rect;
+            }
+            rect = expanded;
+        }
+    }
+
+    /// Merges the cells in `rect`. Returns `false` and does nothing if `rect`
+    /// is a single cell or overlaps an existing merged region.
+    pub fn merge_cells(&mut self, rect: Rect) -> bool {
+        if rect.len() < 2 || self.merged_rects_intersecting(rect).next().is_some() {
+            return false;
+        }
+        self.merged_cells.push(rect);
+        true
+    }
+
+    /// Unmerges the merged region `rect`. Returns `false` if there is no
+    /// merged region with exactly those bounds.
+    pub fn unmerge_cells(&mut self, rect: Rect) -> bool {
+        let len = self.merged_cells.len();
+        self.merged_cells.retain(|merged| *merged != rect);
+        self.merged_cells.len() != len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_cells() {
+        let mut sheet = Sheet::test();
+        let rect = Rect::new(1, 1, 2, 3);
+        assert!(sheet.merge_cells(rect));
+        assert!(!sheet.merge_cells(Rect::new(2, 3, 4, 4)));
+        assert!(!sheet.merge_cells(Rect::single_pos(Pos { x: 5, y: 5 })));
+
+        assert_eq!(sheet.merged_rect_at(Pos { x: 2, y: 2 }), Some(rect));
+        assert_eq!(sheet.merged_rect_at(Pos { x: 3, y: 2 }), None);
+        assert_eq!(
+            sheet.expand_to_merged_cells(Rect::new(0, 0, 1, 1)),
+            Rect::new(0, 0, 2, 3)
+        );
+        assert_eq!(sheet.merged_rects_within(Rect::new(0, 0, 2, 2)).count(), 0);
+        assert_eq!(sheet.merged_rects_within(Rect::new(0, 0, 2, 3)).count(), 1);
+
+        assert!(!sheet.unmerge_cells(Rect::new(1, 1, 2, 2)));
+        assert!(sheet.unmerge_cells(rect));
+        assert!(sheet.merged_cells.is_empty());
+    }
+
+    #[test]
+    fn test_expand_to_chained_merged_cells() {
+        let mut sheet = Sheet::test();
+        sheet.merge_cells(Rect::new(0, 0, 1, 1));
+        sheet.merge_cells(Rect::new(2, 1, 3, 2));
+        assert_eq!(
+            sheet.expand_to_merged_cells(Rect::new(1, 0, 1, 0)),
+            Rect::new(0, 0, 1, 1)
+        );
+        assert_eq!(
+            sheet.expand_to_merged_cells(Rect::new(1, 1, 2, 1)),
+            Rect::new(0, 0, 3, 2)
+        );
+    }
+}
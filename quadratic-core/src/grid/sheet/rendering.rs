@@ -7,7 +7,7 @@ use crate::{
         formats::format::Format,
         js_types::{
             JsHtmlOutput, JsRenderBorders, JsRenderCell, JsRenderCellSpecial, JsRenderCodeCell,
-            JsRenderCodeCellState, JsRenderFill, JsSheetFill,
+            JsRenderCodeCellState, JsRenderComment, JsRenderFill, JsSheetFill,
         },
         CellAlign, CodeCellLanguage, CodeRun, Column, NumericFormatKind,
     },
@@ -48,6 +48,9 @@ impl Sheet {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::Chart),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             };
         } else if let CellValue::Error(error) = value {
             let spill_error = matches!(error.msg, RunErrorMsg::Spill);
@@ -66,6 +69,9 @@ impl Sheet {
                 } else {
                     JsRenderCellSpecial::RunError
                 }),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             };
         } else if let CellValue::Logical(logical) = value {
             return JsRenderCell {
@@ -83,6 +89,9 @@ impl Sheet {
                 } else {
                     JsRenderCellSpecial::False
                 }),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             };
         }
 
@@ -111,6 +120,9 @@ impl Sheet {
                     italic: format.italic,
                     text_color: format.text_color,
                     special: None,
+                    merge_width: None,
+                    merge_height: None,
+                    data_bar: None,
                 }
             }
             Some(column) => {
@@ -153,6 +165,9 @@ impl Sheet {
                     italic,
                     text_color,
                     special: None,
+                    merge_width: None,
+                    merge_height: None,
+                    data_bar: None,
                 }
             }
         }
@@ -267,6 +282,34 @@ impl Sheet {
                     render_cells.extend(self.get_code_cells(&code, code_run, &rect, &code_rect));
                 }
             });
+
+        if !self.conditional_formats.is_empty() {
+            self.apply_conditional_formats(rect, &mut render_cells);
+        }
+
+        // A merged region renders as its top-left cell, spanning the region.
+        if !self.merged_cells.is_empty() {
+            render_cells.retain_mut(|cell| {
+                match self.merged_rect_at(Pos {
+                    x: cell.x,
+                    y: cell.y,
+                }) {
+                    Some(merged) if merged.min.x == cell.x && merged.min.y == cell.y => {
+                        cell.merge_width = Some(merged.width());
+                        cell.merge_height = Some(merged.height());
+                        true
+                    }
+                    Some(_) => false,
+                    None => true,
+                }
+            });
+        }
+
+        if self.offsets.has_hidden() {
+            render_cells.retain(|cell| {
+                !self.offsets.is_column_hidden(cell.x) && !self.offsets.is_row_hidden(cell.y)
+            });
+        }
         render_cells
     }
 
@@ -330,9 +373,27 @@ impl Sheet {
                 });
             }
         }
+        ret.extend(self.get_conditional_format_fills());
         ret
     }
 
+    /// Returns the comment indicators for the sheet. Cells in hidden columns
+    /// or rows are skipped.
+    pub fn get_render_comments(&self) -> Vec<JsRenderComment> {
+        self.comments
+            .iter()
+            .filter(|(pos, _)| {
+                !self.offsets.is_column_hidden(pos.x) && !self.offsets.is_row_hidden(pos.y)
+            })
+            .map(|(pos, thread)| JsRenderComment {
+                x: pos.x,
+                y: pos.y,
+                count: thread.comments.len(),
+                resolved: thread.resolved,
+            })
+            .collect()
+    }
+
     /// Returns all fills for the rows, columns, and sheet. This does not return
     /// individual cell formats.
     pub fn get_sheet_fills(&self) -> JsSheetFill {
@@ -579,6 +640,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: None,
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         );
         assert_eq!(
@@ -594,6 +658,9 @@ mod tests {
                 italic: Some(true),
                 text_color: None,
                 special: None,
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         );
         assert_eq!(
@@ -609,6 +676,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::Chart),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         );
         assert_eq!(
@@ -624,6 +694,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::True),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         );
         assert_eq!(
@@ -639,6 +712,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::SpillError),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         );
         assert_eq!(
@@ -654,6 +730,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::RunError),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         );
     }
@@ -817,6 +896,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: None,
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             }]
         );
     }
@@ -847,6 +929,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn render_merged_cells() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 1, y: 1 }, "merged");
+        sheet.set_cell_value(Pos { x: 2, y: 2 }, "hidden");
+        sheet.set_cell_value(Pos { x: 4, y: 1 }, "outside");
+        sheet.merge_cells(Rect::new(1, 1, 3, 2));
+
+        let cells = sheet.get_render_cells(Rect::new(0, 0, 5, 5));
+        assert_eq!(cells.len(), 2);
+        let merged = cells
+            .iter()
+            .find(|cell| cell.x == 1 && cell.y == 1)
+            .unwrap();
+        assert_eq!(merged.value, "merged");
+        assert_eq!(merged.merge_width, Some(3));
+        assert_eq!(merged.merge_height, Some(2));
+        let outside = cells.iter().find(|cell| cell.x == 4).unwrap();
+        assert_eq!(outside.merge_width, None);
+    }
+
     #[test]
     fn render_code_cell() {
         let mut gc = GridController::test();
@@ -910,6 +1013,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::True),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
             JsRenderCell {
                 x: 1,
@@ -922,6 +1028,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::False),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
             JsRenderCell {
                 x: 2,
@@ -934,6 +1043,9 @@ mod tests {
                 italic: None,
                 text_color: None,
                 special: Some(JsRenderCellSpecial::True),
+                merge_width: None,
+                merge_height: None,
+                data_bar: None,
             },
         ];
         let cells_string = serde_json::to_string(&cells).unwrap();
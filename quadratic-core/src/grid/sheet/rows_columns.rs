@@ -0,0 +1,184 @@
+use super::Sheet;
+use crate::{formulas::RefAdjust, util::shift_keys, Axis, Rect};
+
+impl Sheet {
+    /// Returns the rectangle covering the values, formats and borders in a
+    /// column or row, or `None` if there are none.
+    pub fn line_bounds(&self, axis: Axis, index: i64) -> Option<Rect> {
+        match axis {
+            Axis::X => {
+                let values_and_formats = self
+                    .columns
+                    .get(&index)
+                    .and_then(|column| column.range(false));
+                let borders = self
+                    .borders
+                    .per_cell
+                    .borders
+                    .get(&index)
+                    .and_then(|column| column.range());
+                let range = crate::util::union_ranges([values_and_formats, borders])?;
+                Some(Rect::new(index, range.start, index, range.end - 1))
+            }
+            Axis::Y => {
+                let with_contents = self
+                    .columns
+                    .iter()
+                    .filter(|(_, column)| column.has_anything_in_row(index))
+                    .map(|(&x, _)| x);
+                let with_borders = self
+                    .borders
+                    .per_cell
+                    .borders
+                    .iter()
+                    .filter(|(_, column)| column.get(index).is_some())
+                    .map(|(&x, _)| x);
+                let (min, max) =
+                    with_contents
+                        .chain(with_borders)
+                        .fold(None, |bounds, x| match bounds {
+                            None => Some((x, x)),
+                            Some((min, max)) => {
+                                Some((std::cmp::min(min, x), std::cmp::max(max, x)))
+                            }
+                        })?;
+                Some(Rect::new(min, index, max, index))
+            }
+        }
+    }
+
+    /// Shifts the contents of the sheet after columns or rows are inserted or
+    /// deleted. Anything in deleted columns or rows is removed.
+    ///
+    /// Cell references in formulas are not changed here because they may be
+    /// on other sheets.
+    pub fn shift_cells(&mut self, adjust: RefAdjust) {
+        let RefAdjust { axis, start, delta } = adjust;
+        match axis {
+            Axis::X => {
+                shift_keys(&mut self.columns, start, delta);
+                for (&x, column) in self.columns.range_mut(start..) {
+                    column.x = x;
+                }
+                shift_keys(&mut self.formats_columns, start, delta);
+                self.offsets.shift_columns(start, delta);
+            }
+            Axis::Y => {
+                for column in self.columns.values_mut() {
+                    column.shift_rows(start, delta);
+                }
+                shift_keys(&mut self.formats_rows, start, delta);
+                self.offsets.shift_rows(start, delta);
+            }
+        }
+        self.borders.shift(adjust);
+
+        // Keep the order of the code runs, which determines which one wins
+        // when their outputs overlap.
+        self.code_runs = std::mem::take(&mut self.code_runs)
+            .into_iter()
+            .filter_map(|(pos, code_run)| Some((adjust.adjust_pos(pos)?, code_run)))
+            .collect();
+
+        self.merged_cells = std::mem::take(&mut self.merged_cells)
+            .into_iter()
+            .filter_map(|merged| adjust.adjust_rect(merged))
+            .collect();
+
+        self.comments = std::mem::take(&mut self.comments)
+            .into_iter()
+            .filter_map(|(pos, thread)| Some((adjust.adjust_pos(pos)?, thread)))
+            .collect();
+
+        self.recalculate_bounds();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grid::{
+            formats::{format_update::FormatUpdate, Formats},
+            CodeCellLanguage,
+        },
+        selection::Selection,
+        CellValue, Pos,
+    };
+
+    #[test]
+    fn test_shift_cells() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, "a");
+        sheet.set_cell_value(Pos { x: 1, y: 0 }, "b");
+        sheet.set_cell_value(Pos { x: 2, y: 2 }, "c");
+        sheet.test_set_code_run_number(3, 3, "2");
+        sheet.offsets.set_column_width(2, 150.0);
+        sheet.set_formats_selection(
+            &Selection {
+                columns: Some(vec![2]),
+                ..Default::default()
+            },
+            &Formats::repeat(
+                FormatUpdate {
+                    bold: Some(Some(true)),
+                    ..Default::default()
+                },
+                1,
+            ),
+        );
+
+        sheet.shift_cells(RefAdjust::insert(Axis::X, 1));
+        assert_eq!(sheet.cell_value(Pos { x: 0, y: 0 }), Some("a".into()));
+        assert_eq!(sheet.cell_value(Pos { x: 1, y: 0 }), None);
+        assert_eq!(sheet.cell_value(Pos { x: 2, y: 0 }), Some("b".into()));
+        assert_eq!(sheet.cell_value(Pos { x: 3, y: 2 }), Some("c".into()));
+        assert!(sheet.code_run(Pos { x: 4, y: 3 }).is_some());
+        assert_eq!(sheet.columns[&3].x, 3);
+        assert_eq!(sheet.offsets.column_width(3), 150.0);
+        assert_eq!(sheet.format_column(3).bold, Some(true));
+        assert_eq!(sheet.format_column(2).bold, None);
+
+        sheet.shift_cells(RefAdjust::delete(Axis::Y, 2));
+        assert_eq!(sheet.cell_value(Pos { x: 3, y: 2 }), None);
+        assert!(sheet.code_run(Pos { x: 4, y: 2 }).is_some());
+        assert!(matches!(
+            sheet.cell_value(Pos { x: 4, y: 2 }),
+            Some(CellValue::Code(code)) if code.language == CodeCellLanguage::Formula,
+        ));
+
+        sheet.shift_cells(RefAdjust::delete(Axis::X, 4));
+        assert!(sheet.code_runs.is_empty());
+    }
+
+    #[test]
+    fn test_shift_merged_cells() {
+        let mut sheet = Sheet::test();
+        sheet.merge_cells(Rect::new(1, 1, 3, 2));
+        sheet.merge_cells(Rect::new(5, 0, 5, 1));
+
+        sheet.shift_cells(RefAdjust::insert(Axis::X, 2));
+        assert_eq!(
+            sheet.merged_cells,
+            vec![Rect::new(1, 1, 4, 2), Rect::new(6, 0, 6, 1)]
+        );
+
+        sheet.shift_cells(RefAdjust::delete(Axis::X, 6));
+        assert_eq!(sheet.merged_cells, vec![Rect::new(1, 1, 4, 2)]);
+
+        sheet.shift_cells(RefAdjust::delete(Axis::Y, 1));
+        assert_eq!(sheet.merged_cells, vec![Rect::new(1, 1, 4, 1)]);
+    }
+
+    #[test]
+    fn test_line_bounds() {
+        let mut sheet = Sheet::test();
+        assert_eq!(sheet.line_bounds(Axis::X, 1), None);
+        sheet.set_cell_value(Pos { x: 1, y: 2 }, "a");
+        sheet.set_cell_value(Pos { x: 1, y: 5 }, "b");
+        sheet.set_cell_value(Pos { x: 3, y: 5 }, "c");
+        assert_eq!(sheet.line_bounds(Axis::X, 1), Some(Rect::new(1, 2, 1, 5)));
+        assert_eq!(sheet.line_bounds(Axis::Y, 5), Some(Rect::new(1, 5, 3, 5)));
+        assert_eq!(sheet.line_bounds(Axis::Y, 4), None);
+    }
+}
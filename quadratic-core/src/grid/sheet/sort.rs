@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::Sheet;
+use crate::{
+    grid::{
+        generate_borders, get_cell_borders_in_rect, set_rect_borders, BorderSelection, SheetBorders,
+    },
+    CellValue, IsBlank, Pos, Rect,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ts_rs::TS)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// A column to sort rows by. When sorting by several keys, earlier keys take
+/// precedence over later ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ts_rs::TS)]
+pub struct SortKey {
+    /// Column of the sheet (not relative to the sorted range).
+    pub column: i64,
+    pub direction: SortDirection,
+
+    // Text that differs only by case is ordered lowercase first. Otherwise
+    // it is treated as equal.
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+impl SortKey {
+    /// Compares two values for this key. Blanks always sort last, regardless
+    /// of the direction.
+    pub fn compare(&self, a: Option<&CellValue>, b: Option<&CellValue>) -> Ordering {
+        let a = a.filter(|a| !a.is_blank());
+        let b = b.filter(|b| !b.is_blank());
+        let (a, b) = match (a, b) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Greater,
+            (Some(_), None) => return Ordering::Less,
+            (Some(a), Some(b)) => (a, b),
+        };
+        let ordering = match (a, b) {
+            (CellValue::Text(a), CellValue::Text(b)) if self.case_sensitive => {
+                a.to_uppercase().cmp(&b.to_uppercase()).then_with(|| {
+                    a.chars()
+                        .map(char::is_uppercase)
+                        .cmp(b.chars().map(char::is_uppercase))
+                })
+            }
+            // `cmp` only fails for errors, which sort after other values
+            _ => a.cmp(b).unwrap_or_else(|_| {
+                let is_error = |value: &CellValue| matches!(value, CellValue::Error(_));
+                is_error(a).cmp(&is_error(b))
+            }),
+        };
+        match self.direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+}
+
+impl Sheet {
+    /// Returns the rows of `rect` in sorted order, as offsets from the top of
+    /// `rect`. Rows that compare equal keep their original order.
+    pub fn sort_order(&self, rect: Rect, keys: &[SortKey]) -> Vec<usize> {
+        let values: Vec<Vec<Option<CellValue>>> = rect
+            .y_range()
+            .map(|y| {
+                keys.iter()
+                    .map(|key| self.display_value(Pos { x: key.column, y }))
+                    .collect()
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&a, &b| {
+            keys.iter()
+                .enumerate()
+                .map(|(i, key)| key.compare(values[a][i].as_ref(), values[b][i].as_ref()))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        order
+    }
+
+    /// Returns the borders of `rect` with its rows rearranged into `order`
+    /// (as returned by [`Sheet::sort_order`]), or `None` if `rect` has no
+    /// borders.
+    pub fn sorted_borders(&self, rect: Rect, order: &[usize]) -> Option<SheetBorders> {
+        let cell_borders = get_cell_borders_in_rect(self, rect, None);
+        if cell_borders.iter().all(|(_, _, borders)| borders.is_none()) {
+            return None;
+        }
+
+        // borders are shared between neighboring cells, so they are rebuilt
+        // one cell at a time in a scratch sheet
+        let mut scratch = Sheet::new(self.id, String::new(), String::new());
+        scratch.borders = self.borders.clone();
+        let cleared = generate_borders(&scratch, &rect, vec![BorderSelection::All], None);
+        set_rect_borders(&mut scratch, &rect, cleared);
+
+        let mut new_rows = vec![0; order.len()];
+        for (new_row, old_row) in order.iter().enumerate() {
+            new_rows[*old_row] = new_row;
+        }
+        for (x, y, borders) in cell_borders {
+            let Some(borders) = borders else {
+                continue;
+            };
+            let cell = Rect::single_pos(Pos {
+                x: rect.min.x + x,
+                y: rect.min.y + new_rows[y as usize] as i64,
+            });
+            for (side, style) in borders.borders.iter().enumerate() {
+                let Some(style) = style else {
+                    continue;
+                };
+                let selection = match side {
+                    0 => BorderSelection::Left,
+                    1 => BorderSelection::Top,
+                    2 => BorderSelection::Right,
+                    _ => BorderSelection::Bottom,
+                };
+                let borders = generate_borders(&scratch, &cell, vec![selection], Some(*style));
+                set_rect_borders(&mut scratch, &cell, borders);
+            }
+        }
+        Some(scratch.get_rect_borders(rect))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{BorderStyle, CellBorderLine};
+
+    fn key(column: i64, direction: SortDirection) -> SortKey {
+        SortKey {
+            column,
+            direction,
+            case_sensitive: false,
+        }
+    }
+
+    #[test]
+    fn test_sort_key_compare() {
+        let ascending = key(0, SortDirection::Ascending);
+        let descending = key(0, SortDirection::Descending);
+        let one = CellValue::Number(1.into());
+        let two = CellValue::Number(2.into());
+        let text = CellValue::from("a");
+
+        assert_eq!(ascending.compare(Some(&one), Some(&two)), Ordering::Less);
+        assert_eq!(
+            descending.compare(Some(&one), Some(&two)),
+            Ordering::Greater
+        );
+
+        // numbers before text
+        assert_eq!(ascending.compare(Some(&one), Some(&text)), Ordering::Less);
+
+        // blanks are last in both directions
+        assert_eq!(ascending.compare(None, Some(&one)), Ordering::Greater);
+        assert_eq!(descending.compare(None, Some(&one)), Ordering::Greater);
+        assert_eq!(
+            descending.compare(Some(&CellValue::Blank), None),
+            Ordering::Equal
+        );
+
+        let upper = CellValue::from("A");
+        assert_eq!(
+            ascending.compare(Some(&text), Some(&upper)),
+            Ordering::Equal
+        );
+        let case_sensitive = SortKey {
+            case_sensitive: true,
+            ..ascending
+        };
+        assert_eq!(
+            case_sensitive.compare(Some(&text), Some(&upper)),
+            Ordering::Less
+        );
+        assert_eq!(
+            case_sensitive.compare(Some(&upper), Some(&CellValue::from("b"))),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let mut sheet = Sheet::test();
+        sheet.test_set_values(
+            0,
+            0,
+            2,
+            5,
+            vec!["b", "1", "a", "2", "b", "0", "", "9", "a", "3"],
+        );
+
+        let rect = Rect::new(0, 0, 1, 4);
+        assert_eq!(
+            sheet.sort_order(rect, &[key(0, SortDirection::Ascending)]),
+            vec![1, 4, 0, 2, 3]
+        );
+        assert_eq!(
+            sheet.sort_order(
+                rect,
+                &[
+                    key(0, SortDirection::Descending),
+                    key(1, SortDirection::Ascending)
+                ]
+            ),
+            vec![2, 0, 1, 4, 3]
+        );
+    }
+
+    #[test]
+    fn test_sorted_borders() {
+        let mut sheet = Sheet::test();
+        let rect = Rect::new(0, 0, 0, 2);
+        assert_eq!(sheet.sorted_borders(rect, &[2, 1, 0]), None);
+
+        let style = BorderStyle {
+            color: crate::color::Rgba::default(),
+            line: CellBorderLine::Line1,
+        };
+        let borders = generate_borders(
+            &sheet,
+            &Rect::single_pos(Pos { x: 0, y: 0 }),
+            vec![BorderSelection::Left],
+            Some(style),
+        );
+        set_rect_borders(&mut sheet, &Rect::single_pos(Pos { x: 0, y: 0 }), borders);
+
+        let sorted = sheet.sorted_borders(rect, &[2, 1, 0]).unwrap();
+        sheet.set_region_borders(&rect, sorted);
+        let borders = get_cell_borders_in_rect(&sheet, rect, None);
+        assert!(borders[0].2.is_none());
+        assert_eq!(borders[2].2.as_ref().unwrap().borders[0], Some(style));
+    }
+}
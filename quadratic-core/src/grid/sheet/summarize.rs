@@ -1,36 +1,41 @@
 use super::Sheet;
 use crate::{
     selection::Selection, util::round,
-    wasm_bindings::controller::summarize::SummarizeSelectionResult, CellValue,
+    wasm_bindings::controller::summarize::SummarizeSelectionResult, CellValue, Pos,
 };
 use bigdecimal::{BigDecimal, ToPrimitive, Zero};
 
 const MAX_SUMMARIZE_SELECTION_SIZE: i64 = 50000;
 
 impl Sheet {
-    /// Returns the summary of values in the Grid. If there is there is less
-    /// than two values, then returns None.
+    /// Returns the summary of values in the Grid, ignoring hidden columns and
+    /// rows. If there is there is less than two values, then returns None.
     pub fn summarize_selection(
         &self,
         selection: Selection,
         max_decimals: i64,
     ) -> Option<SummarizeSelectionResult> {
-        // sum and count
+        // sum and count of the visible cells
         let mut count: i64 = 0;
         let mut sum = BigDecimal::zero();
 
         let values = self.selection(&selection, Some(MAX_SUMMARIZE_SELECTION_SIZE), false)?;
-        values.iter().for_each(|(_pos, value)| match value {
-            CellValue::Number(n) => {
-                sum += n;
-                count += 1;
-            }
-            CellValue::Blank => {}
-            CellValue::Code(_) => {}
-            _ => {
-                count += 1;
-            }
-        });
+        let hidden =
+            |pos: &Pos| self.offsets.is_column_hidden(pos.x) || self.offsets.is_row_hidden(pos.y);
+        values
+            .iter()
+            .filter(|(pos, _)| !hidden(pos))
+            .for_each(|(_pos, value)| match value {
+                CellValue::Number(n) => {
+                    sum += n;
+                    count += 1;
+                }
+                CellValue::Blank => {}
+                CellValue::Code(_) => {}
+                _ => {
+                    count += 1;
+                }
+            });
 
         if count <= 1 {
             return None;
@@ -92,6 +97,19 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn summarize_skips_hidden() {
+        let mut sheet = Sheet::test();
+        sheet.test_set_value_number(1, 1, "1");
+        sheet.test_set_value_number(1, 2, "2");
+        sheet.test_set_value_number(1, 3, "3");
+        sheet.offsets.set_row_hidden(2, true);
+        let selection = Selection::rect(Rect::new(1, 1, 1, 3), sheet.id);
+        let result = sheet.summarize_selection(selection, 9).unwrap();
+        assert_eq!(result.count, 2);
+        assert_eq!(result.sum, Some(4.0));
+    }
+
     #[test]
     fn summarize_rounding() {
         let mut sheet = Sheet::test();
@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Sheet;
+use crate::{
+    formulas::{
+        adjust_cell_references, replace_a1_notation, replace_internal_cell_references, RefAdjust,
+    },
+    grid::SheetId,
+    selection::Selection,
+    Instant, Pos, SheetRect,
+};
+
+/// A rule that constrains what users may enter into the cells of a selection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct Validation {
+    pub id: Uuid,
+    pub selection: Selection,
+    pub rule: ValidationRule,
+
+    // Shown to the user when input is rejected. A default message is used if
+    // this is `None`.
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub enum ValidationRule {
+    /// Value must be one of `values` (case-insensitive).
+    List { values: Vec<String> },
+
+    /// Value must match (case-insensitive) one of the values in `range`.
+    ListFromRange { range: SheetRect },
+
+    /// Value must be a number within the (inclusive) bounds.
+    Number { min: Option<f64>, max: Option<f64> },
+
+    /// Value must be a date within the (inclusive) bounds. Only the date part
+    /// of each instant is compared.
+    Date {
+        start: Option<Instant>,
+        end: Option<Instant>,
+    },
+
+    /// Value's text must have a length within the (inclusive) bounds.
+    TextLength { min: Option<u32>, max: Option<u32> },
+
+    /// Formula that must evaluate to true. Cell references are relative to
+    /// the selection's cursor position, so `A0 > 0` on a selection with its
+    /// cursor at A0 checks each cell against itself.
+    Custom { formula: String },
+}
+
+impl Validation {
+    pub fn new(selection: Selection, rule: ValidationRule, message: Option<String>) -> Self {
+        Validation {
+            id: Uuid::new_v4(),
+            selection,
+            rule,
+            message,
+        }
+    }
+
+    /// Returns the position that relative references in a custom formula are
+    /// written against.
+    pub fn anchor(&self) -> Pos {
+        Pos {
+            x: self.selection.x,
+            y: self.selection.y,
+        }
+    }
+
+    /// Returns whether the client should show a dropdown for this validation.
+    pub fn has_dropdown(&self) -> bool {
+        matches!(
+            self.rule,
+            ValidationRule::List { .. } | ValidationRule::ListFromRange { .. }
+        )
+    }
+
+    /// Returns the message shown when a value is rejected.
+    pub fn failure_message(&self) -> String {
+        if let Some(message) = &self.message {
+            return message.clone();
+        }
+        match &self.rule {
+            ValidationRule::List { .. } | ValidationRule::ListFromRange { .. } => {
+                "Value must be one of the listed options".to_string()
+            }
+            ValidationRule::Number { min, max } => match (min, max) {
+                (Some(min), Some(max)) => format!("Value must be a number between {min} and {max}"),
+                (Some(min), None) => {
+                    format!("Value must be a number greater than or equal to {min}")
+                }
+                (None, Some(max)) => format!("Value must be a number less than or equal to {max}"),
+                (None, None) => "Value must be a number".to_string(),
+            },
+            ValidationRule::Date { .. } => "Value must be a date in the allowed range".to_string(),
+            ValidationRule::TextLength { min, max } => match (min, max) {
+                (Some(min), Some(max)) => {
+                    format!("Text must be between {min} and {max} characters long")
+                }
+                (Some(min), None) => format!("Text must be at least {min} characters long"),
+                (None, Some(max)) => format!("Text must be at most {max} characters long"),
+                (None, None) => "Value must be text".to_string(),
+            },
+            ValidationRule::Custom { .. } => "Value is not allowed in this cell".to_string(),
+        }
+    }
+}
+
+impl Sheet {
+    /// Returns the validation that applies to `pos`. If several validations
+    /// include `pos`, the most recently added one wins.
+    pub fn validation_at(&self, pos: Pos) -> Option<&Validation> {
+        self.validations
+            .iter()
+            .rev()
+            .find(|validation| validation.selection.pos_in_selection(pos))
+    }
+
+    pub fn validation(&self, id: Uuid) -> Option<&Validation> {
+        self.validations
+            .iter()
+            .find(|validation| validation.id == id)
+    }
+
+    /// Replaces the validation with the same id in place, or otherwise adds
+    /// it at `index` (or at the end if `index` is `None`). Returns the
+    /// replaced validation.
+    pub fn set_validation(
+        &mut self,
+        validation: Validation,
+        index: Option<usize>,
+    ) -> Option<Validation> {
+        match self.validations.iter_mut().find(|v| v.id == validation.id) {
+            Some(existing) => Some(std::mem::replace(existing, validation)),
+            None => {
+                let index = index.map_or(self.validations.len(), |index| {
+                    index.min(self.validations.len())
+                });
+                self.validations.insert(index, validation);
+                None
+            }
+        }
+    }
+
+    /// Removes a validation. Returns its index and the removed validation.
+    pub fn remove_validation(&mut self, id: Uuid) -> Option<(usize, Validation)> {
+        let index = self.validations.iter().position(|v| v.id == id)?;
+        Some((index, self.validations.remove(index)))
+    }
+
+    /// Adjusts validations after columns or rows are inserted or deleted on
+    /// the sheet `sheet_id` named `sheet_name`, which may be another sheet
+    /// that a list or formula refers to. Validations whose cells or list are
+    /// deleted are removed. Returns the index and old value of each
+    /// validation that changed.
+    pub fn adjust_validations(
+        &mut self,
+        sheet_id: SheetId,
+        sheet_name: &str,
+        adjust: RefAdjust,
+    ) -> Vec<(usize, Validation)> {
+        let is_own_sheet = self.id == sheet_id;
+        let is_affected_sheet = |name: Option<&str>| match name {
+            None => is_own_sheet,
+            Some(name) => name == sheet_name,
+        };
+        // relative references keep their offsets from an anchor that moves
+        // because its column or row is deleted
+        let old_anchor = |anchor: Pos| {
+            if is_own_sheet {
+                adjust.skip_deleted(anchor)
+            } else {
+                anchor
+            }
+        };
+        let adjusted = |validation: &Validation| {
+            let selection = if is_own_sheet {
+                validation.selection.adjust(adjust)?
+            } else {
+                validation.selection.clone()
+            };
+            let rule = match &validation.rule {
+                ValidationRule::ListFromRange { range } if range.sheet_id == sheet_id => {
+                    ValidationRule::ListFromRange {
+                        range: adjust.adjust_rect((*range).into())?.to_sheet_rect(sheet_id),
+                    }
+                }
+                ValidationRule::Custom { formula } => {
+                    let internal = replace_a1_notation(formula, validation.anchor());
+                    let adjusted = adjust_cell_references(
+                        &internal,
+                        old_anchor(validation.anchor()),
+                        selection.source(),
+                        adjust,
+                        &is_affected_sheet,
+                    );
+                    ValidationRule::Custom {
+                        formula: replace_internal_cell_references(&adjusted, selection.source()),
+                    }
+                }
+                rule => rule.clone(),
+            };
+            Some(Validation {
+                selection,
+                rule,
+                ..validation.clone()
+            })
+        };
+
+        let mut changed = vec![];
+        let mut validations = Vec::with_capacity(self.validations.len());
+        for (index, validation) in std::mem::take(&mut self.validations)
+            .into_iter()
+            .enumerate()
+        {
+            match adjusted(&validation) {
+                Some(new) if new == validation => validations.push(validation),
+                Some(new) => {
+                    validations.push(new);
+                    changed.push((index, validation));
+                }
+                None => changed.push((index, validation)),
+            }
+        }
+        self.validations = validations;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Axis, Rect};
+
+    #[test]
+    fn test_validation_at() {
+        let mut sheet = Sheet::test();
+        let first = Validation::new(
+            Selection::rect(Rect::new(0, 0, 2, 2), SheetId::test()),
+            ValidationRule::TextLength {
+                min: None,
+                max: Some(3),
+            },
+            None,
+        );
+        let second = Validation::new(
+            Selection::pos(1, 1, SheetId::test()),
+            ValidationRule::List {
+                values: vec!["a".into()],
+            },
+            Some("pick a".into()),
+        );
+        assert!(sheet.set_validation(first.clone(), None).is_none());
+        assert!(sheet.set_validation(second.clone(), None).is_none());
+
+        assert_eq!(sheet.validation_at(Pos { x: 0, y: 0 }), Some(&first));
+        assert_eq!(sheet.validation_at(Pos { x: 1, y: 1 }), Some(&second));
+        assert_eq!(sheet.validation_at(Pos { x: 3, y: 3 }), None);
+        assert!(second.has_dropdown());
+        assert!(!first.has_dropdown());
+        assert_eq!(second.failure_message(), "pick a");
+        assert_eq!(
+            first.failure_message(),
+            "Text must be at most 3 characters long"
+        );
+
+        // replacing keeps the position in the list
+        let mut replaced = first.clone();
+        replaced.message = Some("short".into());
+        assert_eq!(sheet.set_validation(replaced.clone(), None), Some(first));
+        assert_eq!(sheet.validations, vec![replaced.clone(), second.clone()]);
+
+        assert_eq!(
+            sheet.remove_validation(replaced.id),
+            Some((0, replaced.clone()))
+        );
+        assert_eq!(sheet.remove_validation(Uuid::new_v4()), None);
+        assert_eq!(sheet.validations, vec![second.clone()]);
+
+        // restoring at the old index keeps the order
+        sheet.set_validation(replaced.clone(), Some(0));
+        assert_eq!(sheet.validations, vec![replaced, second]);
+    }
+
+    #[test]
+    fn test_adjust_validations() {
+        let mut sheet = Sheet::test();
+        let sheet_id = sheet.id;
+        let list = Validation::new(
+            Selection::rect(Rect::new(0, 2, 0, 5), sheet_id),
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(3, 0, 1, 3, sheet_id),
+            },
+            None,
+        );
+        let custom = Validation::new(
+            Selection::rect(Rect::new(1, 1, 1, 1), sheet_id),
+            ValidationRule::Custom {
+                formula: "B1 < $D$3".into(),
+            },
+            None,
+        );
+        sheet.set_validation(list.clone(), None);
+        sheet.set_validation(custom.clone(), None);
+        let sheet_name = sheet.name.clone();
+
+        let changed =
+            sheet.adjust_validations(sheet_id, &sheet_name, RefAdjust::insert(Axis::Y, 1));
+        assert_eq!(changed, vec![(0, list.clone()), (1, custom.clone())]);
+        assert_eq!(
+            sheet.validations[0].selection,
+            Selection::rect(Rect::new(0, 3, 0, 6), sheet_id)
+        );
+        assert_eq!(
+            sheet.validations[0].rule,
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(3, 0, 1, 4, sheet_id),
+            }
+        );
+        assert_eq!(sheet.validations[1].anchor(), Pos { x: 1, y: 2 });
+        assert_eq!(
+            sheet.validations[1].rule,
+            ValidationRule::Custom {
+                formula: "B2 < $D$4".into(),
+            }
+        );
+
+        // validations whose cells are deleted are removed
+        let changed =
+            sheet.adjust_validations(sheet_id, &sheet_name, RefAdjust::delete(Axis::X, 1));
+        assert_eq!(
+            changed.iter().map(|(index, _)| *index).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(sheet.validations.len(), 1);
+        assert_eq!(
+            sheet.validations[0].rule,
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(2, 0, 1, 4, sheet_id),
+            }
+        );
+
+        // lists on other sheets follow the sheet they refer to
+        let mut other = Sheet::new(SheetId::new(), "Other".into(), "a1".into());
+        let mut on_other = list.clone();
+        on_other.selection.sheet_id = other.id;
+        other.set_validation(on_other.clone(), None);
+        let changed =
+            other.adjust_validations(sheet_id, &sheet_name, RefAdjust::delete(Axis::X, 2));
+        assert_eq!(changed, vec![(0, on_other.clone())]);
+        assert_eq!(other.validations[0].selection, on_other.selection);
+        assert_eq!(
+            other.validations[0].rule,
+            ValidationRule::ListFromRange {
+                range: SheetRect::from_numbers(2, 0, 1, 3, sheet_id),
+            }
+        );
+    }
+}
@@ -188,7 +188,13 @@ impl Rect {
 
     /// Returns whether a position is contained within the rectangle.
     pub fn contains(&self, pos: Pos) -> bool {
-        self.x_range().contains(&pos.x) && self.y_range().contains(&pos.y)
+        (self.min.x..=self.max.x).contains(&pos.x) && (self.min.y..=self.max.y).contains(&pos.y)
+    }
+
+    /// Returns whether another rectangle is entirely contained within the
+    /// rectangle.
+    pub fn contains_rect(&self, other: Rect) -> bool {
+        self.contains(other.min) && self.contains(other.max)
     }
 
     /// Returns whether a rectangle intersects with the rectangle.
@@ -401,8 +407,8 @@ impl SheetRect {
     /// Returns whether a position is contained within the rectangle.
     pub fn contains(self, sheet_pos: SheetPos) -> bool {
         self.sheet_id == sheet_pos.sheet_id
-            && self.x_range().contains(&sheet_pos.x)
-            && self.y_range().contains(&sheet_pos.y)
+            && (self.min.x..=self.max.x).contains(&sheet_pos.x)
+            && (self.min.y..=self.max.y).contains(&sheet_pos.y)
     }
     /// Returns whether a rectangle intersects with the rectangle.
     pub fn intersects(self, other: SheetRect) -> bool {
@@ -702,6 +708,15 @@ mod test {
         assert!(!rect.contains(Pos { x: 1, y: 5 }));
     }
 
+    #[test]
+    fn test_contains_rect() {
+        let rect = Rect::from_ranges(1..4, 2..5);
+        assert!(rect.contains_rect(rect));
+        assert!(rect.contains_rect(Rect::from_ranges(2..3, 3..5)));
+        assert!(!rect.contains_rect(Rect::from_ranges(0..3, 3..5)));
+        assert!(!rect.contains_rect(Rect::from_ranges(2..3, 3..6)));
+    }
+
     #[test]
     fn test_intersects() {
         let rect = Rect::from_ranges(1..5, 2..6);
@@ -1,6 +1,6 @@
 use std::str::FromStr;
 
-use crate::{grid::SheetId, Pos, Rect, SheetPos, SheetRect};
+use crate::{formulas::RefAdjust, grid::SheetId, Axis, Pos, Rect, SheetPos, SheetRect};
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -194,6 +194,42 @@ impl Selection {
             ..self.clone()
         }
     }
+
+    /// Returns the selection after columns or rows are inserted or deleted,
+    /// or `None` if all of it was deleted. A cursor in a deleted column or
+    /// row moves to the one after it.
+    pub fn adjust(&self, adjust: RefAdjust) -> Option<Selection> {
+        let adjust_lines = |lines: &Option<Vec<i64>>, axis: Axis| {
+            let lines = lines.as_ref()?;
+            if axis != adjust.axis {
+                return Some(lines.clone());
+            }
+            let lines: Vec<i64> = lines.iter().filter_map(|&i| adjust.adjust(i)).collect();
+            (!lines.is_empty()).then_some(lines)
+        };
+        let rects = self.rects.as_ref().and_then(|rects| {
+            let rects: Vec<Rect> = rects
+                .iter()
+                .filter_map(|&rect| adjust.adjust_rect(rect))
+                .collect();
+            (!rects.is_empty()).then_some(rects)
+        });
+        let Pos { x, y } = adjust.adjust_pos(adjust.skip_deleted(self.source()))?;
+        let selection = Selection {
+            sheet_id: self.sheet_id,
+            x,
+            y,
+            rects,
+            rows: adjust_lines(&self.rows, Axis::Y),
+            columns: adjust_lines(&self.columns, Axis::X),
+            all: self.all,
+        };
+        (selection.all
+            || selection.rects.is_some()
+            || selection.rows.is_some()
+            || selection.columns.is_some())
+        .then_some(selection)
+    }
 }
 
 impl FromStr for Selection {
@@ -407,6 +443,53 @@ mod test {
         assert!(selection.pos_in_selection(Pos { x: 5, y: 5 }));
     }
 
+    #[test]
+    fn adjust() {
+        let sheet_id = SheetId::test();
+        let selection = Selection {
+            sheet_id,
+            x: 1,
+            y: 2,
+            rects: Some(vec![Rect::new(1, 2, 3, 4), Rect::new(5, 5, 5, 5)]),
+            rows: Some(vec![10]),
+            columns: Some(vec![2, 4]),
+            all: false,
+        };
+        assert_eq!(
+            selection.adjust(RefAdjust::insert(Axis::X, 2)),
+            Some(Selection {
+                rects: Some(vec![Rect::new(1, 2, 4, 4), Rect::new(6, 5, 6, 5)]),
+                columns: Some(vec![3, 5]),
+                ..selection.clone()
+            })
+        );
+        assert_eq!(
+            selection.adjust(RefAdjust::delete(Axis::Y, 2)),
+            Some(Selection {
+                rects: Some(vec![Rect::new(1, 2, 3, 3), Rect::new(5, 4, 5, 4)]),
+                rows: Some(vec![9]),
+                ..selection.clone()
+            })
+        );
+
+        // the cursor moves to the next column when its column is deleted
+        let selection = Selection::rect(Rect::new(1, 1, 2, 2), sheet_id);
+        assert_eq!(
+            selection.adjust(RefAdjust::delete(Axis::X, 1)),
+            Some(Selection::rect(Rect::new(1, 1, 1, 2), sheet_id))
+        );
+
+        let selection = Selection {
+            sheet_id,
+            rows: Some(vec![3]),
+            ..Default::default()
+        };
+        assert_eq!(selection.adjust(RefAdjust::delete(Axis::Y, 3)), None);
+        assert!(Selection::all(sheet_id)
+            .adjust(RefAdjust::delete(Axis::Y, 0))
+            .is_some());
+    }
+
     #[test]
     fn origin() {
         let sheet_id = SheetId::test();
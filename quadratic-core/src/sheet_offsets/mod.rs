@@ -3,7 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use self::{offsets::Offsets, resize_transient::TransientResize};
+use self::{
+    offsets::{Offsets, OutlineGroup},
+    resize_transient::TransientResize,
+};
 
 pub mod offsets;
 pub mod resize_transient;
@@ -96,19 +99,119 @@ impl SheetOffsets {
         old
     }
 
+    /// Shifts the widths of columns at or after `x` by `delta`, for inserting
+    /// or deleting columns.
+    pub fn shift_columns(&mut self, x: i64, delta: i64) {
+        self.column_widths.shift(x, delta);
+        self.calculate_thumbnail();
+    }
+    /// Shifts the heights of rows at or after `y` by `delta`, for inserting
+    /// or deleting rows.
+    pub fn shift_rows(&mut self, y: i64, delta: i64) {
+        self.row_heights.shift(y, delta);
+        self.calculate_thumbnail();
+    }
+
+    /// Returns the width of a column, which is zero if it is hidden.
     pub fn column_width(&self, x: i64) -> f64 {
         self.column_widths.get_size(x)
     }
 
+    /// Returns the height of a row, which is zero if it is hidden.
     pub fn row_height(&self, y: i64) -> f64 {
         self.row_heights.get_size(y)
     }
 
-    /// gets the column index from an x-coordinate on the screen
+    /// Returns the width a column has when it is not hidden.
+    pub fn unhidden_column_width(&self, x: i64) -> f64 {
+        self.column_widths.get_unhidden_size(x)
+    }
+
+    /// Returns the height a row has when it is not hidden.
+    pub fn unhidden_row_height(&self, y: i64) -> f64 {
+        self.row_heights.get_unhidden_size(y)
+    }
+
+    /// Returns whether a column is hidden, either directly or because it is in
+    /// a collapsed group.
+    pub fn is_column_hidden(&self, x: i64) -> bool {
+        self.column_widths.is_hidden(x)
+    }
+
+    /// Returns whether a row is hidden, either directly or because it is in a
+    /// collapsed group.
+    pub fn is_row_hidden(&self, y: i64) -> bool {
+        self.row_heights.is_hidden(y)
+    }
+
+    /// Returns whether any column or row is hidden.
+    pub fn has_hidden(&self) -> bool {
+        self.column_widths.has_hidden() || self.row_heights.has_hidden()
+    }
+
+    /// Hides or shows a column. Returns whether it was hidden before.
+    pub fn set_column_hidden(&mut self, x: i64, hidden: bool) -> bool {
+        let old = self.column_widths.set_hidden(x, hidden);
+        self.calculate_thumbnail();
+        old
+    }
+
+    /// Hides or shows a row. Returns whether it was hidden before.
+    pub fn set_row_hidden(&mut self, y: i64, hidden: bool) -> bool {
+        let old = self.row_heights.set_hidden(y, hidden);
+        self.calculate_thumbnail();
+        old
+    }
+
+    /// Iterates over the columns that are hidden directly.
+    pub fn hidden_columns(&self) -> impl '_ + Iterator<Item = i64> {
+        self.column_widths.iter_hidden()
+    }
+
+    /// Iterates over the rows that are hidden directly.
+    pub fn hidden_rows(&self) -> impl '_ + Iterator<Item = i64> {
+        self.row_heights.iter_hidden()
+    }
+
+    pub fn column_groups(&self) -> &[OutlineGroup] {
+        self.column_widths.groups()
+    }
+
+    pub fn row_groups(&self) -> &[OutlineGroup] {
+        self.row_heights.groups()
+    }
+
+    /// Replaces the column groups and returns the old ones.
+    pub fn set_column_groups(&mut self, groups: Vec<OutlineGroup>) -> Vec<OutlineGroup> {
+        let old = self.column_widths.set_groups(groups);
+        self.calculate_thumbnail();
+        old
+    }
+
+    /// Replaces the row groups and returns the old ones.
+    pub fn set_row_groups(&mut self, groups: Vec<OutlineGroup>) -> Vec<OutlineGroup> {
+        let old = self.row_heights.set_groups(groups);
+        self.calculate_thumbnail();
+        old
+    }
+
+    /// Returns the number of column groups that contain a column.
+    pub fn column_outline_level(&self, x: i64) -> usize {
+        self.column_widths.outline_level(x)
+    }
+
+    /// Returns the number of row groups that contain a row.
+    pub fn row_outline_level(&self, y: i64) -> usize {
+        self.row_heights.outline_level(y)
+    }
+
+    /// gets the column index from an x-coordinate on the screen, skipping
+    /// hidden columns
     pub fn column_from_x(&self, x: f64) -> (i64, f64) {
         self.column_widths.find_offset(x)
     }
-    /// gets the column index from an x-coordinate on the screen
+    /// gets the row index from a y-coordinate on the screen, skipping hidden
+    /// rows
     pub fn row_from_y(&self, y: f64) -> (i64, f64) {
         self.row_heights.find_offset(y)
     }
@@ -261,6 +364,28 @@ mod test {
         assert_eq!(screen_rect.h, 20.0 * 2.0);
     }
 
+    #[test]
+    fn hidden_columns_and_rows() {
+        let mut sheet = super::SheetOffsets::default();
+        sheet.set_column_hidden(1, true);
+        sheet.set_row_groups(vec![super::OutlineGroup {
+            start: 0,
+            end: 1,
+            collapsed: true,
+        }]);
+        assert!(sheet.is_column_hidden(1));
+        assert!(sheet.is_row_hidden(0));
+        assert_eq!(sheet.column_width(1), 0.0);
+        assert_eq!(sheet.unhidden_column_width(1), 100.0);
+
+        // the screen position skips over hidden columns and rows
+        assert_eq!(sheet.column_from_x(150.0), (2, 100.0));
+        assert_eq!(sheet.row_from_y(10.0), (2, 0.0));
+        let screen_rect = sheet.screen_rect_cell_offsets(super::Rect::from_numbers(0, 0, 3, 3));
+        assert_eq!(screen_rect.w, 200.0);
+        assert_eq!(screen_rect.h, 20.0);
+    }
+
     #[test]
     fn rect_cell_offsets() {
         let sheet = super::SheetOffsets::default();
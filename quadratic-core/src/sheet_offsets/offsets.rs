@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ops::Range;
 
 #[cfg(feature = "js")]
@@ -7,14 +7,55 @@ use wasm_bindgen::prelude::wasm_bindgen;
 
 use itertools::Itertools;
 
+/// The deepest that outline groups may be nested.
+pub const MAX_OUTLINE_LEVEL: usize = 7;
+
+/// A group of columns/rows that can be collapsed (hidden) together.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ts_rs::TS)]
+pub struct OutlineGroup {
+    pub start: i64,
+    pub end: i64,
+    pub collapsed: bool,
+}
+
+impl OutlineGroup {
+    pub fn new(start: i64, end: i64) -> Self {
+        OutlineGroup {
+            start: start.min(end),
+            end: start.max(end),
+            collapsed: false,
+        }
+    }
+
+    pub fn contains(&self, index: i64) -> bool {
+        (self.start..=self.end).contains(&index)
+    }
+
+    /// Returns whether the groups may both exist, which is when they are
+    /// disjoint or one is nested inside the other.
+    pub fn nests_with(&self, other: &OutlineGroup) -> bool {
+        self.end < other.start
+            || other.end < self.start
+            || (self.start <= other.start && other.end <= self.end)
+            || (other.start <= self.start && self.end <= other.end)
+    }
+}
+
 /// Data structure that tracks column widths or row heights in pixel units,
 /// optimized for converting between column/row indices and pixel units.
+///
+/// Hidden columns/rows (including those in collapsed groups) keep their size
+/// but take up no space.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "js", wasm_bindgen)]
 pub struct Offsets {
     default: f64,
     #[serde(with = "crate::util::btreemap_serde")]
     sizes: BTreeMap<i64, f64>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty", default)]
+    hidden: BTreeSet<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    groups: Vec<OutlineGroup>,
 }
 impl Offsets {
     /// Constructs an empty `Offsets` structure.
@@ -22,6 +63,8 @@ impl Offsets {
         Offsets {
             default,
             sizes: BTreeMap::new(),
+            hidden: BTreeSet::new(),
+            groups: vec![],
         }
     }
 
@@ -30,6 +73,8 @@ impl Offsets {
         Offsets {
             default,
             sizes: iter.into_iter().collect(),
+            hidden: BTreeSet::new(),
+            groups: vec![],
         }
     }
 
@@ -54,10 +99,150 @@ impl Offsets {
         }
     }
 
-    /// Returns the width/height of a column/row.
+    /// Shifts the columns/rows at or after `index` by `delta`. If `delta` is
+    /// negative, the columns/rows in `index..index - delta` are removed first.
+    /// Groups containing `index` grow (or shrink) to match.
+    pub fn shift(&mut self, index: i64, delta: i64) {
+        crate::util::shift_keys(&mut self.sizes, index, delta);
+
+        let shift = |i: i64| if i >= index { i + delta } else { i };
+        self.hidden = std::mem::take(&mut self.hidden)
+            .into_iter()
+            .filter(|&i| delta > 0 || !(index..index - delta).contains(&i))
+            .map(shift)
+            .collect();
+
+        for group in self.groups.iter_mut() {
+            if delta > 0 {
+                // a group grows if the insertion is inside it
+                if group.start >= index {
+                    group.start += delta;
+                }
+                if group.end >= index {
+                    group.end += delta;
+                }
+            } else {
+                let removed = index..index - delta;
+                group.start = if removed.contains(&group.start) {
+                    index
+                } else {
+                    shift(group.start)
+                };
+                group.end = if removed.contains(&group.end) {
+                    index - 1
+                } else {
+                    shift(group.end)
+                };
+            }
+        }
+        self.groups.retain(|group| group.start <= group.end);
+    }
+
+    /// Returns the width/height of a column/row, which is zero if it is
+    /// hidden.
     pub fn get_size(&self, index: i64) -> f64 {
+        if self.is_hidden(index) {
+            0.0
+        } else {
+            self.get_unhidden_size(index)
+        }
+    }
+    /// Returns the width/height that a column/row has when it is not hidden.
+    pub fn get_unhidden_size(&self, index: i64) -> f64 {
         *self.sizes.get(&index).unwrap_or(&self.default)
     }
+
+    /// Returns whether a column/row is hidden, either directly or because it
+    /// is in a collapsed group.
+    pub fn is_hidden(&self, index: i64) -> bool {
+        self.hidden.contains(&index)
+            || self
+                .groups
+                .iter()
+                .any(|group| group.collapsed && group.contains(index))
+    }
+    /// Returns whether any column/row is hidden.
+    pub fn has_hidden(&self) -> bool {
+        !self.hidden.is_empty() || self.groups.iter().any(|group| group.collapsed)
+    }
+    /// Hides or shows a column/row. Returns whether it was hidden before.
+    /// Columns/rows in collapsed groups stay hidden.
+    pub fn set_hidden(&mut self, index: i64, hidden: bool) -> bool {
+        if hidden {
+            !self.hidden.insert(index)
+        } else {
+            self.hidden.remove(&index)
+        }
+    }
+    /// Iterates over the columns/rows that are hidden directly (not because
+    /// they are in a collapsed group).
+    pub fn iter_hidden(&self) -> impl '_ + Iterator<Item = i64> {
+        self.hidden.iter().copied()
+    }
+
+    /// Returns the outline groups, ordered by start and then outermost first.
+    pub fn groups(&self) -> &[OutlineGroup] {
+        &self.groups
+    }
+    /// Replaces the outline groups and returns the old ones.
+    pub fn set_groups(&mut self, mut groups: Vec<OutlineGroup>) -> Vec<OutlineGroup> {
+        groups.sort_by_key(|group| (group.start, std::cmp::Reverse(group.end)));
+        std::mem::replace(&mut self.groups, groups)
+    }
+    /// Returns the number of groups that contain a column/row.
+    pub fn outline_level(&self, index: i64) -> usize {
+        self.groups
+            .iter()
+            .filter(|group| group.contains(index))
+            .count()
+    }
+
+    /// Returns the hidden columns/rows in `range` as sorted, disjoint ranges.
+    fn hidden_ranges(&self, range: Range<i64>) -> Vec<Range<i64>> {
+        // collapsed groups may be large, so they are merged as ranges instead
+        // of visiting each column/row
+        let collapsed = self
+            .groups
+            .iter()
+            .filter(|group| group.collapsed)
+            .map(|group| group.start..group.end.saturating_add(1));
+        let hidden = self
+            .hidden
+            .range(range.clone())
+            .map(|&index| index..index + 1);
+        let clipped = collapsed
+            .chain(hidden)
+            .map(|hidden_range| {
+                hidden_range.start.max(range.start)..hidden_range.end.min(range.end)
+            })
+            .filter(|hidden_range| !hidden_range.is_empty())
+            .sorted_by_key(|hidden_range| hidden_range.start);
+
+        let mut merged: Vec<Range<i64>> = vec![];
+        for hidden_range in clipped {
+            match merged.last_mut() {
+                Some(last) if hidden_range.start <= last.end => {
+                    last.end = last.end.max(hidden_range.end);
+                }
+                _ => merged.push(hidden_range),
+            }
+        }
+        merged
+    }
+    /// Returns the total size of the hidden columns/rows in `range`.
+    fn hidden_size(&self, range: Range<i64>) -> f64 {
+        self.hidden_ranges(range)
+            .into_iter()
+            .map(|hidden_range| {
+                self.default * (hidden_range.end - hidden_range.start) as f64
+                    + self
+                        .sizes
+                        .range(hidden_range)
+                        .map(|(_, size)| size - self.default)
+                        .sum::<f64>()
+            })
+            .sum()
+    }
     /// Sets the width/height of a column/row.
     pub fn set_size(&mut self, index: i64, value: f64) -> f64 {
         if value == self.default {
@@ -82,6 +267,7 @@ impl Offsets {
                     .range(start..0)
                     .map(|(_k, v)| v - self.default)
                     .sum::<f64>()
+                + self.hidden_size(start..0)
         } else {
             self.default * start as f64
                 + self
@@ -89,6 +275,7 @@ impl Offsets {
                     .range(0..start)
                     .map(|(_k, v)| v - self.default)
                     .sum::<f64>()
+                - self.hidden_size(0..start)
         };
         index_range.map(move |index| {
             let ret = current_position;
@@ -98,23 +285,49 @@ impl Offsets {
     }
 
     /// returns the entry index and screen position for a screen coordinate
+    ///
+    /// Runs of hidden columns/rows are skipped as a whole. If everything past
+    /// the coordinate is hidden, the first hidden column/row is returned.
     pub fn find_offset(&self, pixel: f64) -> (i64, f64) {
         if pixel >= 0.0 {
+            let hidden_ranges = self.hidden_ranges(0..i64::MAX);
+            let mut hidden_ranges = hidden_ranges.iter().peekable();
             let mut index = 0;
             let mut position = 0.0;
-            let mut next_width = self.get_size(index);
-            while position + next_width <= pixel {
+            loop {
+                if let Some(hidden_range) = hidden_ranges.next_if(|r| r.start <= index) {
+                    if hidden_range.end == i64::MAX {
+                        break;
+                    }
+                    index = hidden_range.end;
+                    continue;
+                }
+                let next_width = self.get_unhidden_size(index);
+                if position + next_width > pixel {
+                    break;
+                }
                 position += next_width;
                 index += 1;
-                next_width = self.get_size(index);
             }
             (index, position)
         } else {
+            let hidden_ranges = self.hidden_ranges(i64::MIN..0);
+            let mut hidden_ranges = hidden_ranges.iter().rev().peekable();
             let mut index = -1;
-            let mut position = -self.get_size(-1);
-            while position > pixel {
+            let mut position = 0.0;
+            loop {
+                if let Some(hidden_range) = hidden_ranges.next_if(|r| r.end > index) {
+                    if hidden_range.start == i64::MIN {
+                        break;
+                    }
+                    index = hidden_range.start - 1;
+                    continue;
+                }
+                position -= self.get_unhidden_size(index);
+                if position <= pixel {
+                    break;
+                }
                 index -= 1;
-                position -= self.get_size(index);
             }
             (index, position)
         }
@@ -281,4 +494,115 @@ mod tests {
             vec![(-1, -10.0), (0, -10.0), (10, -40.0), (1, 20.0), (20, 30.0)]
         );
     }
+
+    #[test]
+    fn test_hidden_offsets() {
+        let mut offsets = Offsets::new(10.0);
+        offsets.set_size(2, 30.0);
+        assert!(!offsets.set_hidden(1, true));
+        assert!(offsets.set_hidden(1, true));
+
+        assert_eq!(offsets.get_size(1), 0.0);
+        assert_eq!(offsets.get_unhidden_size(1), 10.0);
+        assert_eq!(
+            offsets.iter_offsets(0..4).collect_vec(),
+            vec![0.0, 10.0, 10.0, 40.0],
+        );
+        assert_eq!(offsets.iter_offsets(3..4).collect_vec(), vec![40.0]);
+        assert_eq!(offsets.find_offset(15.0), (2, 10.0));
+
+        offsets.set_hidden(-2, true);
+        assert_eq!(
+            offsets.iter_offsets(-3..0).collect_vec(),
+            vec![-20.0, -10.0, -10.0],
+        );
+        assert_eq!(offsets.find_offset(-15.0), (-3, -20.0));
+
+        assert!(offsets.set_hidden(1, false));
+        assert_eq!(offsets.find_offset(15.0), (1, 10.0));
+    }
+
+    #[test]
+    fn test_outline_groups() {
+        let mut offsets = Offsets::new(10.0);
+        offsets.set_groups(vec![OutlineGroup::new(4, 5), OutlineGroup::new(2, 6)]);
+        assert_eq!(
+            offsets.groups(),
+            &[OutlineGroup::new(2, 6), OutlineGroup::new(4, 5)]
+        );
+        assert_eq!(offsets.outline_level(1), 0);
+        assert_eq!(offsets.outline_level(3), 1);
+        assert_eq!(offsets.outline_level(4), 2);
+        assert!(!offsets.has_hidden());
+
+        let mut groups = offsets.groups().to_vec();
+        groups[1].collapsed = true;
+        offsets.set_groups(groups);
+        assert!(offsets.is_hidden(4));
+        assert!(!offsets.is_hidden(6));
+        assert_eq!(offsets.size(0, 7), 40.0);
+
+        // columns/rows hidden both directly and by overlapping groups are
+        // only subtracted once
+        offsets.set_hidden(5, true);
+        offsets.set_hidden(7, true);
+        offsets.set_size(5, 30.0);
+        let mut groups = offsets.groups().to_vec();
+        groups[0].collapsed = true;
+        groups.push(OutlineGroup {
+            start: 20,
+            end: i64::MAX,
+            collapsed: true,
+        });
+        groups.push(OutlineGroup {
+            start: i64::MIN,
+            end: -5,
+            collapsed: true,
+        });
+        offsets.set_groups(groups);
+        assert_eq!(offsets.size(0, 9), 20.0);
+        assert_eq!(
+            offsets.iter_offsets(1_000_000..1_000_001).next(),
+            Some(140.0)
+        );
+
+        // hidden runs are skipped, including ones that never end
+        assert_eq!(offsets.find_offset(25.0), (8, 20.0));
+        assert_eq!(offsets.find_offset(135.0), (19, 130.0));
+        assert_eq!(offsets.find_offset(1e12), (20, 140.0));
+        assert_eq!(offsets.find_offset(-35.0), (-4, -40.0));
+        assert_eq!(offsets.find_offset(-1e12), (-5, -40.0));
+
+        assert!(OutlineGroup::new(2, 6).nests_with(&OutlineGroup::new(3, 4)));
+        assert!(OutlineGroup::new(2, 6).nests_with(&OutlineGroup::new(7, 8)));
+        assert!(!OutlineGroup::new(2, 6).nests_with(&OutlineGroup::new(5, 8)));
+    }
+
+    #[test]
+    fn test_shift_hidden_and_groups() {
+        let mut offsets = Offsets::new(10.0);
+        offsets.set_hidden(3, true);
+        offsets.set_hidden(6, true);
+        offsets.set_groups(vec![OutlineGroup::new(2, 4), OutlineGroup::new(8, 9)]);
+
+        // inserting inside a group grows it
+        offsets.shift(3, 1);
+        assert_eq!(offsets.iter_hidden().collect_vec(), vec![4, 7]);
+        assert_eq!(
+            offsets.groups(),
+            &[OutlineGroup::new(2, 5), OutlineGroup::new(9, 10)]
+        );
+
+        // deleting removes hidden entries and shrinks groups
+        offsets.shift(4, -1);
+        assert_eq!(offsets.iter_hidden().collect_vec(), vec![6]);
+        assert_eq!(
+            offsets.groups(),
+            &[OutlineGroup::new(2, 4), OutlineGroup::new(8, 9)]
+        );
+
+        // deleting a whole group removes it
+        offsets.shift(8, -2);
+        assert_eq!(offsets.groups(), &[OutlineGroup::new(2, 4)]);
+    }
 }
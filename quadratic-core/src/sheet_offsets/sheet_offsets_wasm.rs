@@ -45,6 +45,25 @@ impl SheetOffsets {
         self.column_width(x as i64) as f32
     }
 
+    /// Returns whether a column is hidden, including by a collapsed group.
+    #[wasm_bindgen(js_name = "isColumnHidden")]
+    pub fn js_is_column_hidden(&self, x: i32) -> bool {
+        self.is_column_hidden(x as i64)
+    }
+
+    /// Returns whether a row is hidden, including by a collapsed group.
+    #[wasm_bindgen(js_name = "isRowHidden")]
+    pub fn js_is_row_hidden(&self, y: i32) -> bool {
+        self.is_row_hidden(y as i64)
+    }
+
+    /// Returns the column and row outline groups as a JSON
+    /// `[OutlineGroup[], OutlineGroup[]]`.
+    #[wasm_bindgen(js_name = "getOutlineGroups")]
+    pub fn js_get_outline_groups(&self) -> String {
+        serde_json::to_string(&(self.column_groups(), self.row_groups())).unwrap_or_default()
+    }
+
     /// Sets the column width. Returns the old width.
     #[wasm_bindgen(js_name = "setColumnWidth")]
     pub fn js_set_column_width(&mut self, x: i32, width: f64) -> f64 {
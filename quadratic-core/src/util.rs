@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::ops::Range;
 
@@ -269,6 +270,20 @@ pub(crate) fn assert_f64_approx_eq(expected: f64, actual: &str) {
         "expected {expected} but got {actual}",
     );
 }
+/// Shifts every key at or after `start` by `delta`, which is used when
+/// inserting or deleting columns or rows. If `delta` is negative, the entries
+/// with keys in `start..start - delta` are removed first and returned.
+pub fn shift_keys<T>(map: &mut BTreeMap<i64, T>, start: i64, delta: i64) -> Vec<(i64, T)> {
+    let mut after = map.split_off(&start);
+    let mut removed = vec![];
+    if delta < 0 {
+        let rest = after.split_off(&(start - delta));
+        removed = std::mem::replace(&mut after, rest).into_iter().collect();
+    }
+    map.extend(after.into_iter().map(|(k, v)| (k + delta, v)));
+    removed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -434,4 +449,16 @@ mod tests {
         let used = ["Sheet 2", "Sheet 3"];
         assert_eq!(unused_name("Sheet", &used), "Sheet 4");
     }
+
+    #[test]
+    fn test_shift_keys() {
+        let mut map = BTreeMap::from([(1, 'a'), (2, 'b'), (3, 'c'), (5, 'd')]);
+        assert!(shift_keys(&mut map, 2, 1).is_empty());
+        assert_eq!(
+            map,
+            BTreeMap::from([(1, 'a'), (3, 'b'), (4, 'c'), (6, 'd')])
+        );
+        assert_eq!(shift_keys(&mut map, 3, -2), vec![(3, 'b'), (4, 'c')]);
+        assert_eq!(map, BTreeMap::from([(1, 'a'), (4, 'd')]));
+    }
 }
@@ -1,11 +1,18 @@
-use arrow_array::{cast::AsArray, Array, ArrayRef};
+use std::sync::Arc;
+
+use arrow_array::{
+    cast::AsArray, Array, ArrayRef, BooleanArray, DurationNanosecondArray, Float64Array,
+    Int64Array, StringArray, TimestampNanosecondArray,
+};
 use arrow_buffer::ArrowNativeType;
 use arrow_data::ArrayData;
 use arrow_schema::{DataType, TimeUnit};
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, ToPrimitive};
 use chrono::{LocalResult, TimeZone, Utc};
 
-use crate::{cell_values::CellValues, CellValue};
+use crate::{cell_values::CellValues, CellValue, IsBlank};
+
+const NANOS_PER_SECOND: f64 = 1e9;
 
 impl From<&ArrayRef> for CellValues {
     fn from(array: &ArrayRef) -> Self {
@@ -198,3 +205,153 @@ fn arryow_timestamp_to_cell_values(array_data: ArrayData) -> Vec<CellValue> {
 
     values
 }
+
+/// Converts a column of cells to an Arrow array, inferring its type from the
+/// values. A column with only integers, numbers, logicals, instants or
+/// durations (not counting blanks and errors, which are null) gets that type.
+/// Any other column is text.
+pub fn cell_values_to_arrow_array(values: &[Option<CellValue>]) -> ArrayRef {
+    let present = || {
+        values
+            .iter()
+            .flatten()
+            .filter(|value| !value.is_blank() && !matches!(value, CellValue::Error(_)))
+    };
+    let all = |f: fn(&CellValue) -> bool| present().next().is_some() && present().all(f);
+
+    if all(|value| matches!(value, CellValue::Number(n) if n.is_integer() && n.to_i64().is_some()))
+    {
+        Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(CellValue::Number(n)) => n.to_i64(),
+                    _ => None,
+                })
+                .collect::<Int64Array>(),
+        )
+    } else if all(|value| matches!(value, CellValue::Number(_))) {
+        Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(CellValue::Number(n)) => n.to_f64(),
+                    _ => None,
+                })
+                .collect::<Float64Array>(),
+        )
+    } else if all(|value| matches!(value, CellValue::Logical(_))) {
+        Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(CellValue::Logical(b)) => Some(*b),
+                    _ => None,
+                })
+                .collect::<BooleanArray>(),
+        )
+    } else if all(|value| matches!(value, CellValue::Instant(_))) {
+        Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(CellValue::Instant(instant)) => seconds_to_nanos(instant.seconds),
+                    _ => None,
+                })
+                .collect::<TimestampNanosecondArray>(),
+        )
+    } else if all(|value| {
+        // months and years have no fixed length
+        matches!(value, CellValue::Duration(duration) if duration.total_months() == Some(0))
+    }) {
+        Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(CellValue::Duration(duration)) => seconds_to_nanos(duration.seconds),
+                    _ => None,
+                })
+                .collect::<DurationNanosecondArray>(),
+        )
+    } else {
+        Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(value) if !value.is_blank() && !matches!(value, CellValue::Error(_)) => {
+                        Some(value.to_string())
+                    }
+                    _ => None,
+                })
+                .collect::<StringArray>(),
+        )
+    }
+}
+
+/// Returns `None` if the time is out of range for nanoseconds in an `i64`.
+fn seconds_to_nanos(seconds: f64) -> Option<i64> {
+    let nanos = (seconds * NANOS_PER_SECOND).round();
+    (nanos.is_finite() && nanos >= i64::MIN as f64 && nanos < i64::MAX as f64)
+        .then_some(nanos as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Duration, Instant};
+
+    #[test]
+    fn test_cell_values_to_arrow_array() {
+        let number = |n: &str| Some(CellValue::Number(n.parse().unwrap()));
+        let array = cell_values_to_arrow_array(&[number("1"), None, number("-3")]);
+        assert_eq!(array.data_type(), &DataType::Int64);
+        assert_eq!(array.null_count(), 1);
+
+        let array = cell_values_to_arrow_array(&[number("1"), number("2.5")]);
+        assert_eq!(
+            array
+                .as_primitive::<arrow_array::types::Float64Type>()
+                .value(1),
+            2.5
+        );
+
+        let error = Some(CellValue::Error(Box::new(
+            crate::RunErrorMsg::DivideByZero.without_span(),
+        )));
+        let array = cell_values_to_arrow_array(&[Some(CellValue::Logical(true)), error]);
+        assert_eq!(array.data_type(), &DataType::Boolean);
+        assert!(array.is_null(1));
+
+        let array = cell_values_to_arrow_array(&[Some(CellValue::Instant(Instant::new(1.5)))]);
+        assert_eq!(
+            array.data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, None)
+        );
+        assert_eq!(
+            array
+                .as_primitive::<arrow_array::types::TimestampNanosecondType>()
+                .value(0),
+            1_500_000_000
+        );
+
+        let array =
+            cell_values_to_arrow_array(&[Some(CellValue::Duration(Duration::from_seconds(2.0)))]);
+        assert_eq!(array.data_type(), &DataType::Duration(TimeUnit::Nanosecond));
+
+        // mixed values and durations with months are text
+        let array = cell_values_to_arrow_array(&[
+            number("1"),
+            Some(CellValue::Text("a".into())),
+            Some(CellValue::Duration(Duration::from_months(2))),
+            Some(CellValue::Blank),
+        ]);
+        let strings = array.as_string::<i32>();
+        assert_eq!(strings.value(0), "1");
+        assert_eq!(strings.value(1), "a");
+        assert_eq!(strings.value(2), Duration::from_months(2).to_string());
+        assert!(strings.is_null(3));
+
+        let array = cell_values_to_arrow_array(&[None, Some(CellValue::Blank)]);
+        assert_eq!(array.data_type(), &DataType::Utf8);
+    }
+}
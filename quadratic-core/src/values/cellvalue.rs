@@ -100,8 +100,8 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "TRUE".to_string(),
             CellValue::Logical(false) => "FALSE".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => format!("{:?}", i.to_string()),
+            CellValue::Duration(d) => format!("{:?}", d.to_string()),
             CellValue::Error(_) => "[error]".to_string(),
             CellValue::Html(s) => s.clone(),
             CellValue::Code(_) => todo!("repr of python"),
@@ -209,8 +209,8 @@ impl CellValue {
             }
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
 
             // this should not render
@@ -226,8 +226,8 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
 
             // this should not be editable
@@ -1,10 +1,16 @@
 use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use chrono::{NaiveDate, NaiveDateTime};
 
-use super::{CellValue, IsBlank, Value};
+use super::{CellValue, Duration, Instant, IsBlank, Value};
 use crate::{CodeResult, CodeResultExt, RunErrorMsg, Span, Spanned, Unspan};
 
 const CURRENCY_PREFIXES: &[char] = &['$', '¥', '£', '€'];
 
+/// Date and time formats accepted when coercing text to an instant.
+const DATE_TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"];
+/// Date formats accepted when coercing text to an instant.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%m/%d/%Y"];
+
 /*
  * CONVERSIONS (specific type -> Value)
  */
@@ -36,6 +42,11 @@ impl From<&str> for CellValue {
     }
 }
 // todo: this might be wrong for formulas
+impl From<BigDecimal> for CellValue {
+    fn from(value: BigDecimal) -> Self {
+        CellValue::Number(value)
+    }
+}
 impl From<f64> for CellValue {
     fn from(value: f64) -> Self {
         BigDecimal::try_from(value)
@@ -62,6 +73,16 @@ impl From<bool> for CellValue {
         CellValue::Logical(value)
     }
 }
+impl From<Instant> for CellValue {
+    fn from(value: Instant) -> Self {
+        CellValue::Instant(value)
+    }
+}
+impl From<Duration> for CellValue {
+    fn from(value: Duration) -> Self {
+        CellValue::Duration(value)
+    }
+}
 impl<T> From<CodeResult<T>> for CellValue
 where
     CellValue: From<T>,
@@ -164,6 +185,53 @@ impl<'a> TryFrom<&'a CellValue> for bool {
     }
 }
 
+impl<'a> TryFrom<&'a CellValue> for Instant {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        // TODO: remove string conversions once we have a stricter type system
+        match value {
+            CellValue::Instant(i) => Ok(*i),
+            CellValue::Text(s) => {
+                let s = s.trim();
+                DATE_TIME_FORMATS
+                    .iter()
+                    .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+                    .map(Instant::from)
+                    .or_else(|| {
+                        DATE_FORMATS
+                            .iter()
+                            .find_map(|fmt| NaiveDate::parse_from_str(s, fmt).ok())
+                            .map(Instant::from)
+                    })
+                    .ok_or_else(|| RunErrorMsg::Expected {
+                        expected: "time instant".into(),
+                        got: Some(value.type_name().into()),
+                    })
+            }
+            CellValue::Error(e) => Err(e.msg.clone()),
+            _ => Err(RunErrorMsg::Expected {
+                expected: "time instant".into(),
+                got: Some(value.type_name().into()),
+            }),
+        }
+    }
+}
+impl<'a> TryFrom<&'a CellValue> for Duration {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        match value {
+            CellValue::Duration(d) => Ok(*d),
+            CellValue::Error(e) => Err(e.msg.clone()),
+            _ => Err(RunErrorMsg::Expected {
+                expected: "time duration".into(),
+                got: Some(value.type_name().into()),
+            }),
+        }
+    }
+}
+
 impl TryFrom<CellValue> for String {
     type Error = RunErrorMsg;
 
@@ -189,6 +257,8 @@ macro_rules! impl_try_from_cell_value_for {
 impl_try_from_cell_value_for!(f64);
 impl_try_from_cell_value_for!(i64);
 impl_try_from_cell_value_for!(bool);
+impl_try_from_cell_value_for!(Instant);
+impl_try_from_cell_value_for!(Duration);
 
 impl<'a> TryFrom<&'a Value> for &'a CellValue {
     type Error = RunErrorMsg;
@@ -227,6 +297,8 @@ impl_try_from_value_for!(String);
 impl_try_from_value_for!(f64);
 impl_try_from_value_for!(i64);
 impl_try_from_value_for!(bool);
+impl_try_from_value_for!(Instant);
+impl_try_from_value_for!(Duration);
 
 /// Coercion from `Value` or `CellValue` into a particular Rust type.
 pub trait CoerceInto: Sized + Unspan
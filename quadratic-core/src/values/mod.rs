@@ -14,6 +14,7 @@ mod time;
 
 pub use array::Array;
 pub use array_size::{ArraySize, Axis};
+pub use arrow::cell_values_to_arrow_array;
 pub use cellvalue::CellValue;
 pub use cellvalue::CodeCellValue;
 pub use convert::CoerceInto;
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Months, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 use crate::CellValue;
@@ -17,6 +17,61 @@ impl Instant {
     pub fn new(seconds: f64) -> Self {
         Self { seconds }
     }
+
+    /// Constructs an instant at midnight UTC on `date`.
+    pub fn from_naive_date(date: NaiveDate) -> Self {
+        date.and_hms_opt(0, 0, 0).unwrap_or_default().into()
+    }
+
+    /// Returns the instant as a UTC date and time, or `None` if it is out of
+    /// range.
+    pub fn to_naive_date_time(self) -> Option<NaiveDateTime> {
+        if !self.seconds.is_finite() {
+            return None;
+        }
+        let whole_seconds = self.seconds.floor();
+        let nanos = ((self.seconds - whole_seconds) * 1e9).round() as u32;
+        DateTime::from_timestamp(whole_seconds as i64, nanos.min(999_999_999))
+            .map(|datetime| datetime.naive_utc())
+    }
+
+    /// Returns the UTC date of the instant, or `None` if it is out of range.
+    pub fn to_naive_date(self) -> Option<NaiveDate> {
+        self.to_naive_date_time().map(|datetime| datetime.date())
+    }
+
+    /// Adds a duration to the instant. Years and months are added first, using
+    /// the last day of the month if the day does not exist in the resulting
+    /// month, and then seconds are added. Returns `None` if the result is out
+    /// of range.
+    pub fn checked_add(self, duration: Duration) -> Option<Instant> {
+        let months = duration.total_months()?;
+        let datetime = self.to_naive_date_time()?;
+        let datetime = if months >= 0 {
+            datetime.checked_add_months(Months::new(months.unsigned_abs()))?
+        } else {
+            datetime.checked_sub_months(Months::new(months.unsigned_abs()))?
+        };
+        let subsec = self.seconds - self.seconds.floor();
+        let seconds = Instant::from(datetime).seconds + subsec + duration.seconds;
+        seconds.is_finite().then_some(Instant { seconds })
+    }
+
+    /// Subtracts a duration from the instant. See [`Instant::checked_add()`].
+    pub fn checked_sub(self, duration: Duration) -> Option<Instant> {
+        self.checked_add(-duration)
+    }
+
+    /// Returns the duration from `earlier` to `self`, in seconds.
+    pub fn duration_since(self, earlier: Instant) -> Duration {
+        Duration::from_seconds(self.seconds - earlier.seconds)
+    }
+}
+
+impl From<NaiveDate> for Instant {
+    fn from(date: NaiveDate) -> Self {
+        Self::from_naive_date(date)
+    }
 }
 
 impl From<NaiveDateTime> for Instant {
@@ -65,6 +120,58 @@ pub struct Duration {
     pub seconds: f64,
 }
 
+impl Duration {
+    /// Constructs a duration consisting only of seconds.
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self {
+            years: 0,
+            months: 0,
+            seconds,
+        }
+    }
+
+    /// Constructs a duration consisting only of months.
+    pub fn from_months(months: i32) -> Self {
+        Self {
+            years: 0,
+            months,
+            seconds: 0.0,
+        }
+    }
+
+    /// Returns the total number of months in the duration, including years, or
+    /// `None` if it overflows.
+    pub fn total_months(self) -> Option<i32> {
+        self.years.checked_mul(12)?.checked_add(self.months)
+    }
+
+    /// Adds two durations component-wise, returning `None` on overflow.
+    pub fn checked_add(self, other: Duration) -> Option<Duration> {
+        Some(Duration {
+            years: self.years.checked_add(other.years)?,
+            months: self.months.checked_add(other.months)?,
+            seconds: self.seconds + other.seconds,
+        })
+    }
+
+    /// Subtracts two durations component-wise, returning `None` on overflow.
+    pub fn checked_sub(self, other: Duration) -> Option<Duration> {
+        self.checked_add(-other)
+    }
+}
+
+impl std::ops::Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Self::Output {
+        Duration {
+            years: self.years.wrapping_neg(),
+            months: self.months.wrapping_neg(),
+            seconds: -self.seconds,
+        }
+    }
+}
+
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -0,0 +1,98 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds a comment to a cell, starting a thread or replying to the existing
+    /// one. Returns the id of the new comment.
+    #[wasm_bindgen(js_name = "addComment")]
+    pub fn js_add_comment(
+        &mut self,
+        sheet_id: String,
+        x: i32,
+        y: i32,
+        author: String,
+        text: String,
+        cursor: Option<String>,
+    ) -> Result<String, JsValue> {
+        let sheet_pos = js_sheet_pos(&sheet_id, x, y)?;
+        Ok(self
+            .add_comment(sheet_pos, author, text, cursor)
+            .to_string())
+    }
+
+    #[wasm_bindgen(js_name = "editComment")]
+    pub fn js_edit_comment(
+        &mut self,
+        sheet_id: String,
+        x: i32,
+        y: i32,
+        comment_id: String,
+        text: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_pos = js_sheet_pos(&sheet_id, x, y)?;
+        let comment_id = uuid::Uuid::from_str(&comment_id).map_err(|_| JsValue::UNDEFINED)?;
+        self.edit_comment(sheet_pos, comment_id, text, cursor);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "resolveComments")]
+    pub fn js_resolve_comments(
+        &mut self,
+        sheet_id: String,
+        x: i32,
+        y: i32,
+        resolved: bool,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_pos = js_sheet_pos(&sheet_id, x, y)?;
+        self.resolve_comments(sheet_pos, resolved, cursor);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "deleteComment")]
+    pub fn js_delete_comment(
+        &mut self,
+        sheet_id: String,
+        x: i32,
+        y: i32,
+        comment_id: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_pos = js_sheet_pos(&sheet_id, x, y)?;
+        let comment_id = uuid::Uuid::from_str(&comment_id).map_err(|_| JsValue::UNDEFINED)?;
+        self.delete_comment(sheet_pos, comment_id, cursor);
+        Ok(())
+    }
+
+    /// Returns the [`CommentThread`] on a cell as JSON, or `undefined` if the
+    /// cell has no comments.
+    #[wasm_bindgen(js_name = "getCommentThread")]
+    pub fn js_get_comment_thread(&self, sheet_id: String, x: i32, y: i32) -> Option<String> {
+        let sheet = self.try_sheet_from_string_id(sheet_id)?;
+        let thread = sheet.comment_thread(Pos {
+            x: x as i64,
+            y: y as i64,
+        })?;
+        serde_json::to_string(thread).ok()
+    }
+
+    /// Returns a sheet's comment indicators as a JSON `JsRenderComment[]`.
+    #[wasm_bindgen(js_name = "getRenderComments")]
+    pub fn js_get_render_comments(&self, sheet_id: String) -> Result<String, JsValue> {
+        let sheet = self
+            .try_sheet_from_string_id(sheet_id)
+            .ok_or(JsValue::UNDEFINED)?;
+        serde_json::to_string(&sheet.get_render_comments())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+fn js_sheet_pos(sheet_id: &str, x: i32, y: i32) -> Result<SheetPos, JsValue> {
+    let sheet_id = SheetId::from_str(sheet_id).map_err(|_| JsValue::UNDEFINED)?;
+    Ok(Pos {
+        x: x as i64,
+        y: y as i64,
+    }
+    .to_sheet_pos(sheet_id))
+}
@@ -0,0 +1,43 @@
+use super::*;
+use crate::grid::sheet::conditional_formats::ConditionalFormat;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds a conditional format, or replaces the conditional format with the
+    /// same id. `conditional_format` is a JSON [`ConditionalFormat`].
+    #[wasm_bindgen(js_name = "addConditionalFormat")]
+    pub fn js_add_conditional_format(
+        &mut self,
+        conditional_format: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let conditional_format: ConditionalFormat =
+            serde_json::from_str(&conditional_format).map_err(|_| JsValue::UNDEFINED)?;
+        self.add_conditional_format(conditional_format, cursor);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "removeConditionalFormat")]
+    pub fn js_remove_conditional_format(
+        &mut self,
+        sheet_id: String,
+        conditional_format_id: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|_| JsValue::UNDEFINED)?;
+        let conditional_format_id =
+            uuid::Uuid::from_str(&conditional_format_id).map_err(|_| JsValue::UNDEFINED)?;
+        self.remove_conditional_format(sheet_id, conditional_format_id, cursor);
+        Ok(())
+    }
+
+    /// Returns a JSON array of a sheet's [`ConditionalFormat`]s.
+    #[wasm_bindgen(js_name = "getConditionalFormats")]
+    pub fn js_get_conditional_formats(&self, sheet_id: String) -> Result<String, JsValue> {
+        let sheet = self
+            .try_sheet_from_string_id(sheet_id)
+            .ok_or(JsValue::UNDEFINED)?;
+        serde_json::to_string(&sheet.conditional_formats)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
@@ -0,0 +1,40 @@
+use super::*;
+use crate::formulas::lsp::provide_completion_items_for_grid;
+use crate::grid::defined_names::NameValue;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Defines a name for the whole file. `value` is a JSON [`NameValue`], or
+    /// `undefined` to remove the name.
+    #[wasm_bindgen(js_name = "setDefinedName")]
+    pub fn js_set_defined_name(
+        &mut self,
+        name: String,
+        value: Option<String>,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let value: Option<NameValue> = match value {
+            Some(value) => Some(serde_json::from_str(&value).map_err(|e| e.to_string())?),
+            None => None,
+        };
+        self.set_defined_name(name, value, cursor)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Returns the file's defined names as a JSON array of [`DefinedName`]s.
+    #[wasm_bindgen(js_name = "getDefinedNames")]
+    pub fn js_get_defined_names(&self) -> Result<String, JsValue> {
+        Ok(serde_json::to_string(self.grid().defined_names()).map_err(|e| e.to_string())?)
+    }
+
+    /// Returns the formula editor's completions, including the file's defined
+    /// names, as a JSON `CompletionList`.
+    #[wasm_bindgen(js_name = "provideFormulaCompletionItems")]
+    pub fn js_provide_formula_completion_items(&self) -> Result<String, JsValue> {
+        Ok(
+            serde_json::to_string(&provide_completion_items_for_grid(self.grid()))
+                .map_err(|e| e.to_string())?,
+        )
+    }
+}
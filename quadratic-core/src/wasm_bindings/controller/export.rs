@@ -7,10 +7,59 @@ use crate::{controller::GridController, selection::Selection};
 impl GridController {
     /// Returns [`TransactionSummary`]
     #[wasm_bindgen(js_name = "exportCsvSelection")]
-    pub fn js_export_csv_selection(&self, selection: String) -> Result<String, JsValue> {
+    pub fn js_export_csv_selection(
+        &self,
+        selection: String,
+        skip_hidden: bool,
+    ) -> Result<String, JsValue> {
         let selection = Selection::from_str(&selection).map_err(|e| e.to_string())?;
         let output = self
-            .export_csv_selection(selection)
+            .export_csv_selection(selection, skip_hidden)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    /// Returns the contents of an XLSX file with every sheet
+    #[wasm_bindgen(js_name = "exportXlsx")]
+    pub fn js_export_xlsx(&self) -> Result<Vec<u8>, JsValue> {
+        let output = self.export_xlsx().map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    /// Returns the contents of an XLSX file with the cells in a selection
+    #[wasm_bindgen(js_name = "exportXlsxSelection")]
+    pub fn js_export_xlsx_selection(&self, selection: String) -> Result<Vec<u8>, JsValue> {
+        let selection = Selection::from_str(&selection).map_err(|e| e.to_string())?;
+        let output = self
+            .export_xlsx_selection(&selection)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    /// Returns the contents of a Parquet file with the values in a selection
+    #[wasm_bindgen(js_name = "exportParquetSelection")]
+    pub fn js_export_parquet_selection(
+        &self,
+        selection: String,
+        first_row_as_headers: bool,
+    ) -> Result<Vec<u8>, JsValue> {
+        let selection = Selection::from_str(&selection).map_err(|e| e.to_string())?;
+        let output = self
+            .export_parquet_selection(&selection, first_row_as_headers)
+            .map_err(|e| e.to_string())?;
+        Ok(output)
+    }
+
+    /// Returns the contents of an Arrow IPC file with the values in a selection
+    #[wasm_bindgen(js_name = "exportArrowSelection")]
+    pub fn js_export_arrow_selection(
+        &self,
+        selection: String,
+        first_row_as_headers: bool,
+    ) -> Result<Vec<u8>, JsValue> {
+        let selection = Selection::from_str(&selection).map_err(|e| e.to_string())?;
+        let output = self
+            .export_arrow_selection(&selection, first_row_as_headers)
             .map_err(|e| e.to_string())?;
         Ok(output)
     }
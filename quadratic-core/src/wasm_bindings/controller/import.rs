@@ -2,7 +2,7 @@ use std::str::FromStr;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 use crate::{
-    controller::GridController,
+    controller::{operations::import::CsvImportOptions, GridController},
     grid::{Grid, SheetId},
     Pos,
 };
@@ -17,14 +17,39 @@ impl GridController {
         file_name: &str,
         insert_at: &str,
         cursor: Option<String>,
+        options: JsValue,
     ) -> Result<(), JsValue> {
         let insert_at = serde_json::from_str::<Pos>(insert_at).map_err(|e| e.to_string())?;
         let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
-        self.import_csv(sheet_id, file, file_name, insert_at, cursor)
-            .map_err(|e| e.to_string())?;
+        let options: Option<CsvImportOptions> = serde_wasm_bindgen::from_value(options)?;
+        self.import_csv(
+            sheet_id,
+            file,
+            file_name,
+            insert_at,
+            &options.unwrap_or_default(),
+            cursor,
+        )
+        .map_err(|e| e.to_string())?;
 
         Ok(())
     }
+
+    /// Returns the first rows of a CSV file as they would be imported with
+    /// `options`, and the delimiter and encoding used.
+    #[wasm_bindgen(js_name = "previewCsv")]
+    pub fn js_preview_csv(
+        file: &[u8],
+        options: JsValue,
+        max_rows: u32,
+    ) -> Result<JsValue, JsValue> {
+        let options: Option<CsvImportOptions> = serde_wasm_bindgen::from_value(options)?;
+        let preview = options
+            .unwrap_or_default()
+            .preview(file, max_rows as usize)
+            .map_err(|e| e.to_string())?;
+        Ok(serde_wasm_bindgen::to_value(&preview)?)
+    }
 }
 
 #[wasm_bindgen]
@@ -41,6 +66,26 @@ impl GridController {
     }
 }
 
+#[wasm_bindgen]
+impl GridController {
+    #[wasm_bindgen(js_name = "importJson")]
+    pub fn js_import_json(
+        &mut self,
+        sheet_id: &str,
+        file: &[u8],
+        file_name: &str,
+        insert_at: &str,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let insert_at = serde_json::from_str::<Pos>(insert_at).map_err(|e| e.to_string())?;
+        let sheet_id = SheetId::from_str(sheet_id).map_err(|e| e.to_string())?;
+        self.import_json(sheet_id, file, file_name, insert_at, cursor)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
+
 #[wasm_bindgen]
 impl GridController {
     #[wasm_bindgen(js_name = "importParquet")]
@@ -0,0 +1,32 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Merges the cells in a rectangle.
+    #[wasm_bindgen(js_name = "mergeCells")]
+    pub fn js_merge_cells(
+        &mut self,
+        sheet_id: String,
+        rect: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let rect: Rect = serde_json::from_str(&rect).map_err(|_| JsValue::UNDEFINED)?;
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|_| JsValue::UNDEFINED)?;
+        self.merge_cells(rect.to_sheet_rect(sheet_id), cursor);
+        Ok(())
+    }
+
+    /// Unmerges every merged region that intersects a rectangle.
+    #[wasm_bindgen(js_name = "unmergeCells")]
+    pub fn js_unmerge_cells(
+        &mut self,
+        sheet_id: String,
+        rect: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let rect: Rect = serde_json::from_str(&rect).map_err(|_| JsValue::UNDEFINED)?;
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|_| JsValue::UNDEFINED)?;
+        self.unmerge_cells(rect.to_sheet_rect(sheet_id), cursor);
+        Ok(())
+    }
+}
@@ -9,16 +9,23 @@ pub mod bounds;
 pub mod cells;
 pub mod clipboard;
 pub mod code;
+pub mod comments;
+pub mod conditional_formats;
+pub mod defined_names;
 pub mod export;
 pub mod formatting;
 pub mod import;
+pub mod merge_cells;
 pub mod render;
+pub mod rows_columns;
 pub mod search;
 pub mod sheet_info;
 pub mod sheet_offsets;
 pub mod sheets;
+pub mod sort;
 pub mod summarize;
 pub mod transactions;
+pub mod validations;
 pub mod worker;
 
 #[wasm_bindgen]
@@ -51,6 +58,7 @@ impl GridController {
                     if let Ok(sheets_info) = serde_json::to_string(&sheets_info) {
                         crate::wasm_bindings::js::jsSheetInfo(sheets_info);
                     }
+                    grid.send_defined_names();
                     if !html.is_empty() {
                         if let Ok(html) = serde_json::to_string(&html) {
                             crate::wasm_bindings::js::jsHtmlOutput(html);
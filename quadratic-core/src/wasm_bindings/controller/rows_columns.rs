@@ -0,0 +1,152 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Inserts an empty column before `column`.
+    #[wasm_bindgen(js_name = "insertColumn")]
+    pub fn js_insert_column(
+        &mut self,
+        sheet_id: String,
+        column: i32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.insert_column(sheet_id, column as i64, cursor);
+        Ok(())
+    }
+
+    /// Deletes a column.
+    #[wasm_bindgen(js_name = "deleteColumn")]
+    pub fn js_delete_column(
+        &mut self,
+        sheet_id: String,
+        column: i32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.delete_column(sheet_id, column as i64, cursor);
+        Ok(())
+    }
+
+    /// Inserts an empty row before `row`.
+    #[wasm_bindgen(js_name = "insertRow")]
+    pub fn js_insert_row(
+        &mut self,
+        sheet_id: String,
+        row: i32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.insert_row(sheet_id, row as i64, cursor);
+        Ok(())
+    }
+
+    /// Deletes a row.
+    #[wasm_bindgen(js_name = "deleteRow")]
+    pub fn js_delete_row(
+        &mut self,
+        sheet_id: String,
+        row: i32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.delete_row(sheet_id, row as i64, cursor);
+        Ok(())
+    }
+
+    /// Hides or shows columns. `columns` is a JSON `number[]`.
+    #[wasm_bindgen(js_name = "setColumnsHidden")]
+    pub fn js_set_columns_hidden(
+        &mut self,
+        sheet_id: String,
+        columns: String,
+        hidden: bool,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let columns = serde_json::from_str(&columns).map_err(|e| e.to_string())?;
+        self.set_columns_hidden(sheet_id, columns, hidden, cursor);
+        Ok(())
+    }
+
+    /// Hides or shows rows. `rows` is a JSON `number[]`.
+    #[wasm_bindgen(js_name = "setRowsHidden")]
+    pub fn js_set_rows_hidden(
+        &mut self,
+        sheet_id: String,
+        rows: String,
+        hidden: bool,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let rows = serde_json::from_str(&rows).map_err(|e| e.to_string())?;
+        self.set_rows_hidden(sheet_id, rows, hidden, cursor);
+        Ok(())
+    }
+
+    /// Groups the columns (`columns` is true) or rows in `start..=end`.
+    #[wasm_bindgen(js_name = "group")]
+    pub fn js_group(
+        &mut self,
+        sheet_id: String,
+        columns: bool,
+        start: i32,
+        end: i32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.group(sheet_id, axis(columns), start as i64, end as i64, cursor)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Removes the group of columns (`columns` is true) or rows that spans
+    /// exactly `start..=end`.
+    #[wasm_bindgen(js_name = "ungroup")]
+    pub fn js_ungroup(
+        &mut self,
+        sheet_id: String,
+        columns: bool,
+        start: i32,
+        end: i32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.ungroup(sheet_id, axis(columns), start as i64, end as i64, cursor)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Collapses or expands the group of columns (`columns` is true) or rows
+    /// that spans exactly `start..=end`.
+    #[wasm_bindgen(js_name = "setGroupCollapsed")]
+    pub fn js_set_group_collapsed(
+        &mut self,
+        sheet_id: String,
+        columns: bool,
+        start: i32,
+        end: i32,
+        collapsed: bool,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.set_group_collapsed(
+            sheet_id,
+            axis(columns),
+            start as i64,
+            end as i64,
+            collapsed,
+            cursor,
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+fn axis(columns: bool) -> crate::Axis {
+    if columns {
+        crate::Axis::X
+    } else {
+        crate::Axis::Y
+    }
+}
@@ -10,6 +10,8 @@ pub struct SheetInfo {
     pub order: String,
     pub color: Option<String>,
     pub offsets: String,
+    pub frozen_columns: u32,
+    pub frozen_rows: u32,
     pub bounds: GridBounds,
     pub bounds_without_formatting: GridBounds,
 }
@@ -23,6 +25,8 @@ impl From<&Sheet> for SheetInfo {
             order: sheet.order.clone(),
             color: sheet.color.clone(),
             offsets,
+            frozen_columns: sheet.frozen_columns,
+            frozen_rows: sheet.frozen_rows,
             bounds: sheet.bounds(false),
             bounds_without_formatting: sheet.bounds(true),
         }
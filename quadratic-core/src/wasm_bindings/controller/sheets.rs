@@ -106,4 +106,17 @@ impl GridController {
             &self.set_sheet_color(sheet_id, color, cursor),
         )?)
     }
+
+    #[wasm_bindgen(js_name = "setSheetFrozenPanes")]
+    pub fn js_set_sheet_frozen_panes(
+        &mut self,
+        sheet_id: String,
+        frozen_columns: u32,
+        frozen_rows: u32,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        self.set_sheet_frozen_panes(sheet_id, frozen_columns, frozen_rows, cursor);
+        Ok(())
+    }
 }
@@ -0,0 +1,22 @@
+use super::*;
+use crate::grid::sheet::sort::SortKey;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Sorts the rows of a rectangle. `keys` is a JSON array of [`SortKey`]s.
+    #[wasm_bindgen(js_name = "sortRange")]
+    pub fn js_sort_range(
+        &mut self,
+        sheet_id: String,
+        rect: String,
+        keys: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let rect: Rect = serde_json::from_str(&rect).map_err(|e| e.to_string())?;
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|e| e.to_string())?;
+        let keys: Vec<SortKey> = serde_json::from_str(&keys).map_err(|e| e.to_string())?;
+        self.sort_range(rect.to_sheet_rect(sheet_id), keys, cursor)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use super::*;
+use crate::grid::sheet::validations::Validation;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Adds a validation, or replaces the validation with the same id.
+    /// `validation` is a JSON [`Validation`].
+    #[wasm_bindgen(js_name = "addValidation")]
+    pub fn js_add_validation(
+        &mut self,
+        validation: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let validation: Validation =
+            serde_json::from_str(&validation).map_err(|_| JsValue::UNDEFINED)?;
+        self.add_validation(validation, cursor);
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "removeValidation")]
+    pub fn js_remove_validation(
+        &mut self,
+        sheet_id: String,
+        validation_id: String,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|_| JsValue::UNDEFINED)?;
+        let validation_id = uuid::Uuid::from_str(&validation_id).map_err(|_| JsValue::UNDEFINED)?;
+        self.remove_validation(sheet_id, validation_id, cursor);
+        Ok(())
+    }
+
+    /// Returns a JSON array of a sheet's [`Validation`]s.
+    #[wasm_bindgen(js_name = "getValidations")]
+    pub fn js_get_validations(&self, sheet_id: String) -> Result<String, JsValue> {
+        let sheet = self
+            .try_sheet_from_string_id(sheet_id)
+            .ok_or(JsValue::UNDEFINED)?;
+        serde_json::to_string(&sheet.validations).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns a JSON array of the values allowed by the list validation at a
+    /// cell, or `undefined` if the cell has no list validation.
+    #[wasm_bindgen(js_name = "getValidationList")]
+    pub fn js_get_validation_list(&self, sheet_id: String, x: i32, y: i32) -> Option<String> {
+        let sheet_id = SheetId::from_str(&sheet_id).ok()?;
+        let sheet_pos = Pos {
+            x: x as i64,
+            y: y as i64,
+        }
+        .to_sheet_pos(sheet_id);
+        serde_json::to_string(&self.validation_list(sheet_pos)?).ok()
+    }
+
+    /// Returns the [`JsRenderValidations`] for a sheet as JSON.
+    #[wasm_bindgen(js_name = "getRenderValidations")]
+    pub fn js_get_render_validations(&self, sheet_id: String) -> Result<String, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).map_err(|_| JsValue::UNDEFINED)?;
+        serde_json::to_string(&self.render_validations(sheet_id))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
@@ -46,6 +46,10 @@ extern "C" {
 
     pub fn jsSheetMetaFills(sheet_id: String, fills: String /* JsSheetFill */);
 
+    pub fn jsSheetValidations(sheet_id: String, validations: String /* JsRenderValidations */);
+    pub fn jsSheetComments(sheet_id: String, comments: String /* Vec<JsRenderComment> */);
+    pub fn jsDefinedNames(names: String /* Vec<DefinedName> */);
+
     pub fn jsAddSheet(sheetInfo: String /*SheetInfo*/, user: bool);
     pub fn jsDeleteSheet(sheetId: String, user: bool);
     pub fn jsRequestTransactions(sequence_num: u64);
@@ -76,6 +80,10 @@ extern "C" {
         w: u32,
         h: u32,
     );
+    pub fn jsExcelImportReport(
+        file_name: &str,
+        report: String, /* Vec<JsUnsupportedExcelFeatures> */
+    );
     pub fn jsTransactionStart(transaction_id: String, name: String);
     pub fn addUnsentTransaction(transaction_id: String, transaction: String, operations: u32);
     pub fn jsSendTransaction(transaction_id: String, transaction: String);
@@ -264,6 +272,33 @@ pub fn jsSheetMetaFills(sheet_id: String, fills: String /* JsSheetFill */) {
     ));
 }
 
+#[cfg(test)]
+#[allow(non_snake_case)]
+pub fn jsSheetValidations(sheet_id: String, validations: String /* JsRenderValidations */) {
+    TEST_ARRAY.lock().unwrap().push(TestFunction::new(
+        "jsSheetValidations",
+        format!("{},{}", sheet_id, validations),
+    ));
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+pub fn jsSheetComments(sheet_id: String, comments: String /* Vec<JsRenderComment> */) {
+    TEST_ARRAY.lock().unwrap().push(TestFunction::new(
+        "jsSheetComments",
+        format!("{},{}", sheet_id, comments),
+    ));
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+pub fn jsDefinedNames(names: String /* Vec<DefinedName> */) {
+    TEST_ARRAY
+        .lock()
+        .unwrap()
+        .push(TestFunction::new("jsDefinedNames", names));
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 pub fn jsAddSheet(sheetInfo: String /*SheetInfo*/, user: bool) {
@@ -411,6 +446,18 @@ pub fn jsImportProgress(file_name: &str, current: u32, total: u32, x: i64, y: i6
     ));
 }
 
+#[cfg(test)]
+#[allow(non_snake_case)]
+pub fn jsExcelImportReport(
+    file_name: &str,
+    report: String, /* Vec<JsUnsupportedExcelFeatures> */
+) {
+    TEST_ARRAY.lock().unwrap().push(TestFunction::new(
+        "jsExcelImportReport",
+        format!("{},{}", file_name, report),
+    ));
+}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 pub fn jsTransactionStart(transaction_id: String, name: String) {